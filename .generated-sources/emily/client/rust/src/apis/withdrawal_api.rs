@@ -20,6 +20,7 @@ pub enum CreateWithdrawalError {
     Status400(models::ErrorResponse),
     Status404(models::ErrorResponse),
     Status405(models::ErrorResponse),
+    Status422(models::ErrorResponse),
     Status500(models::ErrorResponse),
     UnknownValue(serde_json::Value),
 }
@@ -149,6 +150,7 @@ pub async fn get_withdrawals(
     status: models::Status,
     next_token: Option<&str>,
     page_size: Option<i32>,
+    direction: Option<models::SortDirection>,
 ) -> Result<models::GetWithdrawalsResponse, Error<GetWithdrawalsError>> {
     let local_var_configuration = configuration;
 
@@ -167,6 +169,10 @@ pub async fn get_withdrawals(
         local_var_req_builder =
             local_var_req_builder.query(&[("pageSize", &local_var_str.to_string())]);
     }
+    if let Some(ref local_var_str) = direction {
+        local_var_req_builder =
+            local_var_req_builder.query(&[("direction", &local_var_str.to_string())]);
+    }
     if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
         local_var_req_builder =
             local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
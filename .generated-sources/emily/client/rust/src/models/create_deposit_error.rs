@@ -0,0 +1,46 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateDepositError : A deposit that could not be created as part of a create deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateDepositError {
+    /// Output index on the bitcoin transaction of the deposit.
+    #[serde(rename = "bitcoinTxOutputIndex")]
+    pub bitcoin_tx_output_index: u32,
+    /// Bitcoin transaction id of the deposit.
+    #[serde(rename = "bitcoinTxid")]
+    pub bitcoin_txid: String,
+    /// Why the deposit could not be created.
+    #[serde(rename = "message")]
+    pub message: String,
+    /// The http status code that creating the deposit on its own would have failed with.
+    #[serde(rename = "statusCode")]
+    pub status_code: u32,
+}
+
+impl CreateDepositError {
+    /// A deposit that could not be created as part of a create deposits request.
+    pub fn new(
+        bitcoin_tx_output_index: u32,
+        bitcoin_txid: String,
+        message: String,
+        status_code: u32,
+    ) -> CreateDepositError {
+        CreateDepositError {
+            bitcoin_tx_output_index,
+            bitcoin_txid,
+            message,
+            status_code,
+        }
+    }
+}
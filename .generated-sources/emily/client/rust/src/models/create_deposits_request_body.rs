@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateDepositsRequestBody : Request structure for the create deposits request, which creates many deposits at once.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateDepositsRequestBody {
+    /// The deposits to create.
+    #[serde(rename = "deposits")]
+    pub deposits: Vec<models::CreateDepositRequestBody>,
+}
+
+impl CreateDepositsRequestBody {
+    /// Request structure for the create deposits request, which creates many deposits at once.
+    pub fn new(deposits: Vec<models::CreateDepositRequestBody>) -> CreateDepositsRequestBody {
+        CreateDepositsRequestBody { deposits }
+    }
+}
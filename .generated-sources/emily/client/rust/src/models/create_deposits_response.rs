@@ -0,0 +1,33 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// CreateDepositsResponse : Response to create deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateDepositsResponse {
+    /// The deposits that were created, in the order they were requested.
+    #[serde(rename = "deposits")]
+    pub deposits: Vec<models::Deposit>,
+    /// The deposits that could not be created, in the order they were requested.
+    #[serde(rename = "errors")]
+    pub errors: Vec<models::CreateDepositError>,
+}
+
+impl CreateDepositsResponse {
+    /// Response to create deposits request.
+    pub fn new(
+        deposits: Vec<models::Deposit>,
+        errors: Vec<models::CreateDepositError>,
+    ) -> CreateDepositsResponse {
+        CreateDepositsResponse { deposits, errors }
+    }
+}
@@ -26,6 +26,14 @@ pub struct Deposit {
     /// Raw deposit script binary in hex.
     #[serde(rename = "depositScript")]
     pub deposit_script: String,
+    /// Why the deposit failed. Only present when the status is failed.
+    #[serde(
+        rename = "failureReason",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub failure_reason: Option<Option<String>>,
     #[serde(
         rename = "fulfillment",
         default,
@@ -74,6 +82,7 @@ impl Deposit {
             bitcoin_tx_output_index,
             bitcoin_txid,
             deposit_script,
+            failure_reason: None,
             fulfillment: None,
             last_update_block_hash,
             last_update_height,
@@ -0,0 +1,42 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// DepositHistogramBucket : A bucket of the deposit amount histogram.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositHistogramBucket {
+    /// The number of recently swept deposits in the bucket.
+    #[serde(rename = "count")]
+    pub count: u64,
+    /// The deposit amount, in satoshis, that all deposits in the bucket are below. There is no upper bound for the last bucket.
+    #[serde(
+        rename = "maxAmount",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_amount: Option<Option<u64>>,
+    /// The smallest deposit amount in the bucket, in satoshis.
+    #[serde(rename = "minAmount")]
+    pub min_amount: u64,
+}
+
+impl DepositHistogramBucket {
+    /// A bucket of the deposit amount histogram.
+    pub fn new(count: u64, min_amount: u64) -> DepositHistogramBucket {
+        DepositHistogramBucket {
+            count,
+            max_amount: None,
+            min_amount,
+        }
+    }
+}
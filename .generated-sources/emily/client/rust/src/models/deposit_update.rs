@@ -20,6 +20,14 @@ pub struct DepositUpdate {
     /// Bitcoin transaction id.
     #[serde(rename = "bitcoinTxid")]
     pub bitcoin_txid: String,
+    /// Why the deposit failed. Only accepted when the status is failed.
+    #[serde(
+        rename = "failureReason",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub failure_reason: Option<Option<String>>,
     #[serde(
         rename = "fulfillment",
         default,
@@ -53,6 +61,7 @@ impl DepositUpdate {
         DepositUpdate {
             bitcoin_tx_output_index,
             bitcoin_txid,
+            failure_reason: None,
             fulfillment: None,
             last_update_block_hash,
             last_update_height,
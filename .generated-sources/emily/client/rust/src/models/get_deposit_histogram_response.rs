@@ -0,0 +1,27 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetDepositHistogramResponse : Response to get deposit histogram request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDepositHistogramResponse {
+    /// The buckets of the histogram, ordered by amount.
+    #[serde(rename = "buckets")]
+    pub buckets: Vec<models::DepositHistogramBucket>,
+}
+
+impl GetDepositHistogramResponse {
+    /// Response to get deposit histogram request.
+    pub fn new(buckets: Vec<models::DepositHistogramBucket>) -> GetDepositHistogramResponse {
+        GetDepositHistogramResponse { buckets }
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// GetDepositSlaResponse : Response to get deposit SLA request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDepositSlaResponse {
+    /// The number of recently swept deposits that the percentiles are computed over.
+    #[serde(rename = "count")]
+    pub count: u64,
+    /// The median number of seconds from a deposit being first seen to it being swept.
+    #[serde(
+        rename = "p50Seconds",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub p50_seconds: Option<Option<u64>>,
+    /// The 95th percentile of the number of seconds from a deposit being first seen to it being swept.
+    #[serde(
+        rename = "p95Seconds",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub p95_seconds: Option<Option<u64>>,
+}
+
+impl GetDepositSlaResponse {
+    /// Response to get deposit SLA request.
+    pub fn new(count: u64) -> GetDepositSlaResponse {
+        GetDepositSlaResponse {
+            count,
+            p50_seconds: None,
+            p95_seconds: None,
+        }
+    }
+}
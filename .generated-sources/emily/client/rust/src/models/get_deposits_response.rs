@@ -25,11 +25,32 @@ pub struct GetDepositsResponse {
         skip_serializing_if = "Option::is_none"
     )]
     pub next_token: Option<Option<String>>,
+    /// Token for the page before this one. Searching with it as the next token, in the opposite direction, walks back through the results. Not present for the first page.
+    #[serde(
+        rename = "prevToken",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub prev_token: Option<Option<String>>,
+    /// The total number of deposits matching the search. Only present in the response to the first page, when the total was asked for.
+    #[serde(
+        rename = "total",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub total: Option<Option<u64>>,
 }
 
 impl GetDepositsResponse {
     /// Response to get deposits request.
     pub fn new(deposits: Vec<models::DepositInfo>) -> GetDepositsResponse {
-        GetDepositsResponse { deposits, next_token: None }
+        GetDepositsResponse {
+            deposits,
+            next_token: None,
+            prev_token: None,
+            total: None,
+        }
     }
 }
@@ -22,6 +22,14 @@ pub struct GetWithdrawalsResponse {
         skip_serializing_if = "Option::is_none"
     )]
     pub next_token: Option<Option<String>>,
+    /// Token for the page before this one. Searching with it as the next token, in the opposite direction, walks back through the results. Not present for the first page.
+    #[serde(
+        rename = "prevToken",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub prev_token: Option<Option<String>>,
     /// Withdrawal infos: withdrawals with a little less data.
     #[serde(rename = "withdrawals")]
     pub withdrawals: Vec<models::WithdrawalInfo>,
@@ -30,6 +38,10 @@ pub struct GetWithdrawalsResponse {
 impl GetWithdrawalsResponse {
     /// Response to get withdrawals request.
     pub fn new(withdrawals: Vec<models::WithdrawalInfo>) -> GetWithdrawalsResponse {
-        GetWithdrawalsResponse { next_token: None, withdrawals }
+        GetWithdrawalsResponse {
+            next_token: None,
+            prev_token: None,
+            withdrawals,
+        }
     }
 }
@@ -0,0 +1,32 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// MinConfirmations : The minimum number of confirmations that a deposit needs before the signers process it.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MinConfirmations {
+    /// The minimum number of confirmations. When not set, the signers process deposits as soon as they are confirmed.
+    #[serde(
+        rename = "minConfirmations",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub min_confirmations: Option<Option<u64>>,
+}
+
+impl MinConfirmations {
+    /// The minimum number of confirmations that a deposit needs before the signers process it.
+    pub fn new() -> MinConfirmations {
+        MinConfirmations { min_confirmations: None }
+    }
+}
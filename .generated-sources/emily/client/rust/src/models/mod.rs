@@ -2,12 +2,20 @@ pub mod account_limits;
 pub use self::account_limits::AccountLimits;
 pub mod chainstate;
 pub use self::chainstate::Chainstate;
+pub mod create_deposit_error;
+pub use self::create_deposit_error::CreateDepositError;
 pub mod create_deposit_request_body;
 pub use self::create_deposit_request_body::CreateDepositRequestBody;
+pub mod create_deposits_request_body;
+pub use self::create_deposits_request_body::CreateDepositsRequestBody;
+pub mod create_deposits_response;
+pub use self::create_deposits_response::CreateDepositsResponse;
 pub mod create_withdrawal_request_body;
 pub use self::create_withdrawal_request_body::CreateWithdrawalRequestBody;
 pub mod deposit;
 pub use self::deposit::Deposit;
+pub mod deposit_histogram_bucket;
+pub use self::deposit_histogram_bucket::DepositHistogramBucket;
 pub mod deposit_info;
 pub use self::deposit_info::DepositInfo;
 pub mod deposit_parameters;
@@ -18,6 +26,10 @@ pub mod error_response;
 pub use self::error_response::ErrorResponse;
 pub mod fulfillment;
 pub use self::fulfillment::Fulfillment;
+pub mod get_deposit_histogram_response;
+pub use self::get_deposit_histogram_response::GetDepositHistogramResponse;
+pub mod get_deposit_sla_response;
+pub use self::get_deposit_sla_response::GetDepositSlaResponse;
 pub mod get_deposits_for_transaction_response;
 pub use self::get_deposits_for_transaction_response::GetDepositsForTransactionResponse;
 pub mod get_deposits_response;
@@ -28,8 +40,16 @@ pub mod health_data;
 pub use self::health_data::HealthData;
 pub mod limits;
 pub use self::limits::Limits;
+pub mod min_confirmations;
+pub use self::min_confirmations::MinConfirmations;
+pub mod register_webhook_request_body;
+pub use self::register_webhook_request_body::RegisterWebhookRequestBody;
+pub mod sort_direction;
+pub use self::sort_direction::SortDirection;
 pub mod status;
 pub use self::status::Status;
+pub mod update_deposit_error;
+pub use self::update_deposit_error::UpdateDepositError;
 pub mod update_deposits_request_body;
 pub use self::update_deposits_request_body::UpdateDepositsRequestBody;
 pub mod update_deposits_response;
@@ -38,6 +58,8 @@ pub mod update_withdrawals_request_body;
 pub use self::update_withdrawals_request_body::UpdateWithdrawalsRequestBody;
 pub mod update_withdrawals_response;
 pub use self::update_withdrawals_response::UpdateWithdrawalsResponse;
+pub mod webhook;
+pub use self::webhook::Webhook;
 pub mod withdrawal;
 pub use self::withdrawal::Withdrawal;
 pub mod withdrawal_info;
@@ -0,0 +1,37 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// RegisterWebhookRequestBody : Request structure for the register webhook request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RegisterWebhookRequestBody {
+    /// The secret used to sign the notification payloads.
+    #[serde(rename = "secret")]
+    pub secret: String,
+    /// The statuses to notify about. A notification is sent whenever a deposit is updated to one of these statuses.
+    #[serde(rename = "statuses")]
+    pub statuses: Vec<models::Status>,
+    /// The URL that deposit status change notifications are posted to.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+impl RegisterWebhookRequestBody {
+    /// Request structure for the register webhook request.
+    pub fn new(
+        secret: String,
+        statuses: Vec<models::Status>,
+        url: String,
+    ) -> RegisterWebhookRequestBody {
+        RegisterWebhookRequestBody { secret, statuses, url }
+    }
+}
@@ -0,0 +1,37 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// SortDirection : The order in which a list endpoint returns its items, by the height at which they were last updated.
+/// The order in which a list endpoint returns its items, by the height at which they were last updated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub enum SortDirection {
+    #[serde(rename = "desc")]
+    Desc,
+    #[serde(rename = "asc")]
+    Asc,
+}
+
+impl std::fmt::Display for SortDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Desc => write!(f, "desc"),
+            Self::Asc => write!(f, "asc"),
+        }
+    }
+}
+
+impl Default for SortDirection {
+    fn default() -> SortDirection {
+        Self::Desc
+    }
+}
@@ -25,6 +25,12 @@ pub enum Status {
     Confirmed,
     #[serde(rename = "failed")]
     Failed,
+    #[serde(rename = "reclaimed")]
+    Reclaimed,
+    #[serde(rename = "expired")]
+    Expired,
+    #[serde(rename = "rejectedwrongkey")]
+    RejectedWrongKey,
 }
 
 impl std::fmt::Display for Status {
@@ -35,6 +41,9 @@ impl std::fmt::Display for Status {
             Self::Accepted => write!(f, "accepted"),
             Self::Confirmed => write!(f, "confirmed"),
             Self::Failed => write!(f, "failed"),
+            Self::Reclaimed => write!(f, "reclaimed"),
+            Self::Expired => write!(f, "expired"),
+            Self::RejectedWrongKey => write!(f, "rejectedwrongkey"),
         }
     }
 }
@@ -0,0 +1,46 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// UpdateDepositError : A deposit update that was rejected as part of an update deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpdateDepositError {
+    /// Output index on the bitcoin transaction of the deposit.
+    #[serde(rename = "bitcoinTxOutputIndex")]
+    pub bitcoin_tx_output_index: u32,
+    /// Bitcoin transaction id of the deposit.
+    #[serde(rename = "bitcoinTxid")]
+    pub bitcoin_txid: String,
+    /// Why the update was rejected.
+    #[serde(rename = "message")]
+    pub message: String,
+    /// The http status code that the update on its own would have failed with.
+    #[serde(rename = "statusCode")]
+    pub status_code: u32,
+}
+
+impl UpdateDepositError {
+    /// A deposit update that was rejected as part of an update deposits request.
+    pub fn new(
+        bitcoin_tx_output_index: u32,
+        bitcoin_txid: String,
+        message: String,
+        status_code: u32,
+    ) -> UpdateDepositError {
+        UpdateDepositError {
+            bitcoin_tx_output_index,
+            bitcoin_txid,
+            message,
+            status_code,
+        }
+    }
+}
@@ -17,11 +17,14 @@ pub struct UpdateDepositsResponse {
     /// Deposit infos: deposits with a little less data.
     #[serde(rename = "deposits")]
     pub deposits: Vec<models::Deposit>,
+    /// The updates that were rejected because the deposit cannot move to the requested status, in the order they were requested.
+    #[serde(rename = "errors", skip_serializing_if = "Option::is_none")]
+    pub errors: Option<Vec<models::UpdateDepositError>>,
 }
 
 impl UpdateDepositsResponse {
     /// Response to update deposits request.
     pub fn new(deposits: Vec<models::Deposit>) -> UpdateDepositsResponse {
-        UpdateDepositsResponse { deposits }
+        UpdateDepositsResponse { deposits, errors: None }
     }
 }
@@ -0,0 +1,30 @@
+/*
+ * emily-openapi-spec
+ *
+ * No description provided (generated by Openapi Generator https://github.com/openapitools/openapi-generator)
+ *
+ * The version of the OpenAPI document: 0.1.0
+ *
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+/// Webhook : A registered webhook.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    /// The statuses to notify about.
+    #[serde(rename = "statuses")]
+    pub statuses: Vec<models::Status>,
+    /// The URL that deposit status change notifications are posted to.
+    #[serde(rename = "url")]
+    pub url: String,
+}
+
+impl Webhook {
+    /// A registered webhook.
+    pub fn new(statuses: Vec<models::Status>, url: String) -> Webhook {
+        Webhook { statuses, url }
+    }
+}
@@ -38,6 +38,9 @@ pub struct Withdrawal {
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
     #[serde(rename = "requestId")]
     pub request_id: u64,
+    /// The number of signer signatures collected so far for the withdrawal.
+    #[serde(rename = "signaturesCollected")]
+    pub signatures_collected: u32,
     /// The stacks block hash in which this request id was initiated.
     #[serde(rename = "stacksBlockHash")]
     pub stacks_block_hash: String,
@@ -49,6 +52,9 @@ pub struct Withdrawal {
     /// The status message of the withdrawal.
     #[serde(rename = "statusMessage")]
     pub status_message: String,
+    /// The number of signer signatures required for the withdrawal.
+    #[serde(rename = "threshold")]
+    pub threshold: u32,
 }
 
 impl Withdrawal {
@@ -60,10 +66,12 @@ impl Withdrawal {
         parameters: models::WithdrawalParameters,
         recipient: String,
         request_id: u64,
+        signatures_collected: u32,
         stacks_block_hash: String,
         stacks_block_height: u64,
         status: models::Status,
         status_message: String,
+        threshold: u32,
     ) -> Withdrawal {
         Withdrawal {
             amount,
@@ -73,10 +81,12 @@ impl Withdrawal {
             parameters: Box::new(parameters),
             recipient,
             request_id,
+            signatures_collected,
             stacks_block_hash,
             stacks_block_height,
             status,
             status_message,
+            threshold,
         }
     }
 }
@@ -30,11 +30,27 @@ pub struct WithdrawalUpdate {
     /// The id of the Stacks withdrawal request that initiated the sBTC operation.
     #[serde(rename = "requestId")]
     pub request_id: u64,
+    /// The number of signer signatures collected so far for the withdrawal. The current count is left unchanged if this is not present. The count cannot be more than the threshold.
+    #[serde(
+        rename = "signaturesCollected",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub signatures_collected: Option<Option<u32>>,
     #[serde(rename = "status")]
     pub status: models::Status,
     /// The status message of the withdrawal.
     #[serde(rename = "statusMessage")]
     pub status_message: String,
+    /// The number of signer signatures required for the withdrawal. The current threshold is left unchanged if this is not present.
+    #[serde(
+        rename = "threshold",
+        default,
+        with = "::serde_with::rust::double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub threshold: Option<Option<u32>>,
 }
 
 impl WithdrawalUpdate {
@@ -51,8 +67,10 @@ impl WithdrawalUpdate {
             last_update_block_hash,
             last_update_height,
             request_id,
+            signatures_collected: None,
             status,
             status_message,
+            threshold: None,
         }
     }
 }
@@ -26,8 +26,12 @@ fn main() {
         api::handlers::deposit::get_deposit,
         api::handlers::deposit::get_deposits_for_transaction,
         api::handlers::deposit::get_deposits,
+        api::handlers::deposit::get_deposit_histogram,
+        api::handlers::deposit::get_deposit_sla,
         api::handlers::deposit::create_deposit,
+        api::handlers::deposit::create_deposits,
         api::handlers::deposit::update_deposits,
+        api::handlers::deposit::delete_deposit,
         // Withdrawal endpoints.
         api::handlers::withdrawal::get_withdrawal,
         api::handlers::withdrawal::get_withdrawals,
@@ -37,7 +41,10 @@ fn main() {
         api::handlers::chainstate::get_chain_tip,
         api::handlers::chainstate::get_chainstate_at_height,
         api::handlers::chainstate::set_chainstate,
+        api::handlers::chainstate::set_chainstate_batch,
         api::handlers::chainstate::update_chainstate,
+        api::handlers::chainstate::get_min_confirmations,
+        api::handlers::chainstate::set_min_confirmations,
         // Limit endpoints.
         api::handlers::limits::get_limits,
         api::handlers::limits::set_limits,
@@ -45,20 +52,29 @@ fn main() {
         api::handlers::limits::set_limits_for_account,
         // Testing endpoints.
         api::handlers::testing::wipe_databases,
+        api::handlers::testing::register_webhook,
     ),
     // Components to be included in the OpenAPI specification.
     components(schemas(
         // Chainstate models.
         api::models::chainstate::Chainstate,
+        api::models::chainstate::MinConfirmations,
         // Deposit models.
         api::models::deposit::Deposit,
         api::models::deposit::DepositParameters,
         api::models::deposit::DepositInfo,
         api::models::deposit::requests::CreateDepositRequestBody,
+        api::models::deposit::requests::CreateDepositsRequestBody,
         api::models::deposit::requests::DepositUpdate,
         api::models::deposit::requests::UpdateDepositsRequestBody,
         api::models::deposit::responses::GetDepositsForTransactionResponse,
         api::models::deposit::responses::GetDepositsResponse,
+        api::models::deposit::responses::DepositHistogramBucket,
+        api::models::deposit::responses::GetDepositHistogramResponse,
+        api::models::deposit::responses::GetDepositSlaResponse,
+        api::models::deposit::responses::CreateDepositError,
+        api::models::deposit::responses::CreateDepositsResponse,
+        api::models::deposit::responses::UpdateDepositError,
         api::models::deposit::responses::UpdateDepositsResponse,
         // Withdrawal Models.
         api::models::withdrawal::Withdrawal,
@@ -69,6 +85,9 @@ fn main() {
         api::models::withdrawal::requests::UpdateWithdrawalsRequestBody,
         api::models::withdrawal::responses::GetWithdrawalsResponse,
         api::models::withdrawal::responses::UpdateWithdrawalsResponse,
+        // Webhook models.
+        api::models::webhook::RegisterWebhookRequestBody,
+        api::models::webhook::Webhook,
         // Health check datatypes.
         api::models::health::responses::HealthData,
         // Limit models.
@@ -76,6 +95,7 @@ fn main() {
         api::models::limits::AccountLimits,
         // Common models.
         api::models::common::Status,
+        api::models::common::SortDirection,
         api::models::common::Fulfillment,
         // Errors.
         common::error::ErrorResponse,
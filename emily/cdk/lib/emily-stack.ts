@@ -145,6 +145,8 @@ export class EmilyStack extends cdk.Stack {
                 "LastUpdateBlockHash",
                 "ReclaimScript",
                 "DepositScript",
+                "FirstSeenTime",
+                "SweptTime",
             ]
         });
 
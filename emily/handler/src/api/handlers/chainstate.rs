@@ -2,11 +2,12 @@
 use crate::{
     api::{
         handlers::internal::{execute_reorg_handler, ExecuteReorgRequest},
-        models::chainstate::Chainstate,
+        models::chainstate::{Chainstate, MinConfirmations},
+        models::common::requests::validate_batch_size,
     },
     common::error::{Error, Inconsistency},
     context::EmilyContext,
-    database::{accessors, entries::chainstate::ChainstateEntry},
+    database::{accessors, entries::chainstate::ChainstateEntry, store::Store},
 };
 use tracing::{debug, info, instrument, warn};
 use warp::http::StatusCode;
@@ -44,6 +45,72 @@ pub async fn get_chain_tip(context: EmilyContext) -> impl warp::reply::Reply {
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Get minimum confirmations handler.
+#[utoipa::path(
+    get,
+    operation_id = "getMinConfirmations",
+    path = "/chainstate/min-confirmations",
+    tag = "chainstate",
+    responses(
+        (status = 200, description = "Minimum confirmations retrieved successfully", body = MinConfirmations),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_min_confirmations(context: EmilyContext) -> impl warp::reply::Reply {
+    debug!("Attempting to get the minimum confirmations");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(context: EmilyContext) -> Result<impl warp::reply::Reply, Error> {
+        let min_confirmations = accessors::get_min_confirmations(&context).await?;
+        Ok(with_status(
+            json(&MinConfirmations { min_confirmations }),
+            StatusCode::OK,
+        ))
+    }
+    // Handle and respond.
+    handler(context)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Set minimum confirmations handler.
+#[utoipa::path(
+    post,
+    operation_id = "setMinConfirmations",
+    path = "/chainstate/min-confirmations",
+    tag = "chainstate",
+    request_body = MinConfirmations,
+    responses(
+        (status = 200, description = "Minimum confirmations updated successfully", body = MinConfirmations),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn set_min_confirmations(
+    context: EmilyContext,
+    // The API key itself is checked by the API gateway.
+    _api_key: String,
+    body: MinConfirmations,
+) -> impl warp::reply::Reply {
+    debug!("Attempting to set the minimum confirmations: {body:?}");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: MinConfirmations,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        accessors::set_min_confirmations(&context, body.min_confirmations).await?;
+        Ok(with_status(json(&body), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
 /// Get chainstate handler.
 #[utoipa::path(
     get,
@@ -69,16 +136,14 @@ pub async fn get_chainstate_at_height(
 ) -> impl warp::reply::Reply {
     debug!("Attempting to get chainstate at height: {height:?}");
     // Internal handler so `?` can be used correctly while still returning a reply.
-    async fn handler(context: EmilyContext, height: u64) -> Result<impl warp::reply::Reply, Error> {
+    async fn handler(store: &impl Store, height: u64) -> Result<impl warp::reply::Reply, Error> {
         // Get chainstate at height.
-        let chainstate: Chainstate = accessors::get_chainstate_entry_at_height(&context, &height)
-            .await?
-            .into();
+        let chainstate: Chainstate = store.get_chainstate_entry_at_height(&height).await?.into();
         // Respond.
         Ok(with_status(json(&chainstate), StatusCode::OK))
     }
     // Handle and respond.
-    handler(context, height)
+    handler(&context, height)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -173,6 +238,116 @@ pub async fn update_chainstate(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Set chainstate batch handler.
+#[utoipa::path(
+    post,
+    operation_id = "setChainstateBatch",
+    path = "/chainstate/batch",
+    tag = "chainstate",
+    request_body = Vec<Chainstate>,
+    responses(
+        (status = 201, description = "Chainstates updated successfully", body = Vec<Chainstate>),
+        (status = 400, description = "Invalid request body or too many chainstates", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 409, description = "Chainstates in the batch conflict", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn set_chainstate_batch(
+    context: EmilyContext,
+    api_key: String,
+    body: Vec<Chainstate>,
+) -> impl warp::reply::Reply {
+    debug!("Attempting to set chainstate batch: {body:?}");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        api_key: String,
+        body: Vec<Chainstate>,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        validate_batch_size(body.len())?;
+        let can_reorg = context.settings.trusted_reorg_api_key == api_key;
+        // Check the whole batch before writing anything, so that a batch
+        // that conflicts with itself or with the stored chainstates is
+        // rejected before any of it is written. The chainstates are then
+        // written one at a time rather than in a single transaction, so if
+        // a write fails partway through, the chainstates before it stay
+        // written. Retrying the batch is safe since writing a chainstate
+        // that is already stored changes nothing.
+        validate_chainstate_batch(&context, can_reorg, &body).await?;
+        for chainstate in body.iter() {
+            add_chainstate_entry_or_reorg(&context, can_reorg, chainstate).await?;
+        }
+        // Respond.
+        Ok(with_status(json(&body), StatusCode::CREATED))
+    }
+    // Handle and respond.
+    handler(context, api_key, body)
+        .await
+        .map_err(|error| {
+            warn!("Failed to set chainstate batch with error: {}", error);
+            error
+        })
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Checks that the chainstates of a batch are in ascending order of
+/// height and don't conflict with each other. Unless the caller is allowed
+/// to reorg the API, they must not conflict with the stored chainstates
+/// either.
+///
+/// A caller that is allowed to reorg the API does so by conflicting with
+/// the stored chainstates, but the batch must still be a single chain that
+/// forks off the stored chain at most once. So once a chainstate of the
+/// batch conflicts with the stored chainstates, none of the chainstates
+/// after it may match a stored chainstate.
+async fn validate_chainstate_batch(
+    context: &EmilyContext,
+    can_reorg: bool,
+    chainstates: &[Chainstate],
+) -> Result<(), Error> {
+    for pair in chainstates.windows(2) {
+        let (previous, next) = (&pair[0], &pair[1]);
+        if previous.stacks_block_height == next.stacks_block_height {
+            warn!("Conflicting chainstates in batch: {previous:?} | {next:?}");
+            return Err(Error::Conflict);
+        }
+        if previous.stacks_block_height > next.stacks_block_height {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                "chainstates must be in ascending order of height".to_string(),
+            ));
+        }
+    }
+    let mut forked = false;
+    for chainstate in chainstates {
+        let entry: ChainstateEntry = chainstate.clone().into();
+        let matches =
+            match accessors::get_chainstate_entry_at_height(context, &entry.key.height).await {
+                Ok(existing_entry) => Some(existing_entry == entry),
+                Err(Error::InconsistentState(_)) => Some(false),
+                Err(Error::NotFound) => None,
+                Err(error) => return Err(error),
+            };
+        match matches {
+            Some(false) if !can_reorg => {
+                warn!("Chainstate in batch conflicts with the stored chainstates: {chainstate:?}");
+                return Err(Error::Conflict);
+            }
+            Some(false) => forked = true,
+            Some(true) if forked => {
+                warn!("Chainstate batch forks off the stored chainstates twice: {chainstate:?}");
+                return Err(Error::Conflict);
+            }
+            Some(true) | None => {}
+        }
+    }
+    Ok(())
+}
+
 /// Adds the chainstate to the table, and reorganizes the API if there's a
 /// conflict that suggests it needs a reorg in order for this entry to be
 /// consistent.
@@ -1,31 +1,38 @@
 //! Handlers for Deposit endpoints.
-use crate::api::models::common::Status;
+use crate::api::models::common::requests::{validate_batch_size, validate_page_size};
+use crate::api::models::common::{SortDirection, Status};
 use crate::api::models::deposit::responses::{
-    GetDepositsForTransactionResponse, UpdateDepositsResponse,
+    CreateDepositError, CreateDepositsResponse, DepositHistogramBucket,
+    GetDepositHistogramResponse, GetDepositsForTransactionResponse, UpdateDepositError,
+    UpdateDepositsResponse,
 };
 use crate::database::entries::StatusEntry;
 use stacks_common::codec::StacksMessageCodec as _;
 use tracing::{debug, instrument};
 use warp::reply::{json, with_status, Reply};
 
+use std::time::SystemTime;
+
 use bitcoin::ScriptBuf;
 use warp::http::StatusCode;
 
 use crate::api::models::deposit::{Deposit, DepositInfo};
 use crate::api::models::{
     deposit::requests::{
-        CreateDepositRequestBody, GetDepositsForTransactionQuery, GetDepositsQuery,
-        UpdateDepositsRequestBody,
+        CreateDepositRequestBody, CreateDepositsRequestBody, GetDepositsForTransactionQuery,
+        GetDepositsQuery, UpdateDepositsRequestBody,
     },
-    deposit::responses::GetDepositsResponse,
+    deposit::responses::{GetDepositSlaResponse, GetDepositsResponse},
 };
 use crate::common::error::Error;
 use crate::context::EmilyContext;
 use crate::database::accessors;
+use crate::database::entries::chainstate::ChainstateEntry;
 use crate::database::entries::deposit::{
-    DepositEntry, DepositEntryKey, DepositEvent, DepositParametersEntry,
-    ValidatedUpdateDepositsRequest,
+    unix_timestamp, DepositEntry, DepositEntryKey, DepositEvent, DepositInfoEntry,
+    DepositParametersEntry, ValidatedUpdateDepositsRequest,
 };
+use crate::database::store::Store;
 
 /// Get deposit handler.
 #[utoipa::path(
@@ -55,7 +62,7 @@ pub async fn get_deposit(
     debug!("In get deposit");
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
-        context: EmilyContext,
+        store: &impl Store,
         bitcoin_txid: String,
         bitcoin_tx_output_index: u32,
     ) -> Result<impl warp::reply::Reply, Error> {
@@ -65,7 +72,59 @@ pub async fn get_deposit(
             bitcoin_tx_output_index,
         };
         // Get deposit.
-        let deposit: Deposit = accessors::get_deposit_entry(&context, &key)
+        let deposit: Deposit = store.get_deposit_entry(&key).await?.try_into()?;
+
+        // Respond.
+        Ok(with_status(json(&deposit), StatusCode::OK))
+    }
+
+    // Handle and respond.
+    handler(&context, bitcoin_txid, bitcoin_tx_output_index)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Delete deposit handler.
+#[utoipa::path(
+    delete,
+    operation_id = "deleteDeposit",
+    path = "/deposit/{txid}/{index}",
+    params(
+        ("txid" = String, Path, description = "txid associated with the Deposit."),
+        ("index" = String, Path, description = "output index associated with the Deposit."),
+    ),
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposit deleted successfully", body = Deposit),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Deposit not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn delete_deposit(
+    context: EmilyContext,
+    bitcoin_txid: String,
+    bitcoin_tx_output_index: u32,
+    // The API key itself is checked by the API gateway.
+    _api_key: String,
+) -> impl warp::reply::Reply {
+    debug!("In delete deposit");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        bitcoin_txid: String,
+        bitcoin_tx_output_index: u32,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        // Make key.
+        let key = DepositEntryKey {
+            bitcoin_txid,
+            bitcoin_tx_output_index,
+        };
+        // Delete deposit.
+        let deposit: Deposit = accessors::delete_deposit_entry(&context, &key)
             .await?
             .try_into()?;
 
@@ -87,7 +146,7 @@ pub async fn get_deposit(
     params(
         ("txid" = String, Path, description = "txid associated with the Deposit."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
-        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list.")
+        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list, between 1 and 1000.")
     ),
     tag = "deposit",
     responses(
@@ -111,8 +170,8 @@ pub async fn get_deposits_for_transaction(
         bitcoin_txid: String,
         query: GetDepositsForTransactionQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
-        // TODO(506): Reverse this order of deposits so that the transactions are returned
-        // in ascending index order.
+        validate_page_size(query.page_size)?;
+        // The deposits are returned in ascending order of output index.
         let (entries, next_token) = accessors::get_deposit_entries_for_transaction(
             &context,
             &bitcoin_txid,
@@ -144,7 +203,9 @@ pub async fn get_deposits_for_transaction(
     params(
         ("status" = Status, Query, description = "the status to search by when getting all deposits."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
-        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list.")
+        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list, between 1 and 1000."),
+        ("direction" = Option<SortDirection>, Query, description = "the order to return the deposits in, most recently updated first by default. Deposits updated at the same height are only ordered by txid and output index within a page."),
+        ("includeTotal" = Option<bool>, Query, description = "whether to count the deposits matching the search with the first page, false by default.")
     ),
     tag = "deposit",
     responses(
@@ -166,18 +227,34 @@ pub async fn get_deposits(
         context: EmilyContext,
         query: GetDepositsQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
+        validate_page_size(query.page_size)?;
+        // Counting reads every matching entry, so only count the matching
+        // deposits when asked to, and only for the first page, so that
+        // paginating clients pay for the count once.
+        let total = match query.next_token {
+            None if query.include_total => {
+                Some(accessors::count_deposit_entries(&context, &query.status).await?)
+            }
+            _ => None,
+        };
         // Deserialize next token into the exclusive start key if present/
-        let (entries, next_token) = accessors::get_deposit_entries(
+        let (entries, next_token, prev_token) = accessors::get_deposit_entries(
             &context,
             &query.status,
             query.next_token,
             query.page_size,
+            query.direction,
         )
         .await?;
         // Convert data into resource types.
         let deposits: Vec<DepositInfo> = entries.into_iter().map(|entry| entry.into()).collect();
         // Create response.
-        let response = GetDepositsResponse { deposits, next_token };
+        let response = GetDepositsResponse {
+            deposits,
+            next_token,
+            prev_token,
+            total,
+        };
         // Respond.
         Ok(with_status(json(&response), StatusCode::OK))
     }
@@ -187,6 +264,155 @@ pub async fn get_deposits(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// The amounts, in satoshis, that separate the buckets of the deposit
+/// amount histogram: 0.01, 0.1 and 1 BTC.
+const DEPOSIT_HISTOGRAM_BOUNDARIES: [u64; 3] = [1_000_000, 10_000_000, 100_000_000];
+
+/// The number of Stacks blocks back from the chaintip whose confirmed
+/// deposits are counted by the deposit histogram handler.
+const DEPOSIT_HISTOGRAM_WINDOW_BLOCKS: u64 = 20_000;
+
+/// Get deposit histogram handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositHistogram",
+    path = "/deposit/histogram",
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposit histogram retrieved successfully", body = GetDepositHistogramResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposit_histogram(context: EmilyContext) -> impl warp::reply::Reply {
+    debug!("In get deposit histogram");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(context: EmilyContext) -> Result<impl warp::reply::Reply, Error> {
+        let api_state = accessors::get_api_state(&context).await?;
+        let minimum_height = api_state
+            .chaintip()
+            .key
+            .height
+            .saturating_sub(DEPOSIT_HISTOGRAM_WINDOW_BLOCKS);
+        // Only count the confirmed deposits that were updated within the
+        // window, so that the scan stays bounded as deposits accumulate.
+        let entries = accessors::get_all_deposit_entries_modified_from_height_with_status(
+            &context,
+            &Status::Confirmed,
+            minimum_height,
+            None,
+        )
+        .await?;
+        let buckets = deposit_histogram(entries.iter().map(|entry| entry.amount));
+        // Create response.
+        let response = GetDepositHistogramResponse { buckets };
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Count the given deposit amounts into the buckets separated by
+/// [`DEPOSIT_HISTOGRAM_BOUNDARIES`].
+fn deposit_histogram(amounts: impl IntoIterator<Item = u64>) -> Vec<DepositHistogramBucket> {
+    let mut buckets: Vec<DepositHistogramBucket> = std::iter::once(0)
+        .chain(DEPOSIT_HISTOGRAM_BOUNDARIES)
+        .zip(
+            DEPOSIT_HISTOGRAM_BOUNDARIES
+                .map(Some)
+                .into_iter()
+                .chain([None]),
+        )
+        .map(|(min_amount, max_amount)| DepositHistogramBucket {
+            min_amount,
+            max_amount,
+            count: 0,
+        })
+        .collect();
+
+    for amount in amounts {
+        let index = DEPOSIT_HISTOGRAM_BOUNDARIES.partition_point(|boundary| *boundary <= amount);
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+/// The number of Stacks blocks back from the chaintip whose confirmed
+/// deposits are counted by the deposit SLA handler.
+const DEPOSIT_SLA_WINDOW_BLOCKS: u64 = 20_000;
+
+/// Get deposit SLA handler.
+#[utoipa::path(
+    get,
+    operation_id = "getDepositSla",
+    path = "/deposit/sla",
+    tag = "deposit",
+    responses(
+        (status = 200, description = "Deposit SLA retrieved successfully", body = GetDepositSlaResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn get_deposit_sla(context: EmilyContext) -> impl warp::reply::Reply {
+    debug!("In get deposit sla");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(context: EmilyContext) -> Result<impl warp::reply::Reply, Error> {
+        let api_state = accessors::get_api_state(&context).await?;
+        let minimum_height = api_state
+            .chaintip()
+            .key
+            .height
+            .saturating_sub(DEPOSIT_SLA_WINDOW_BLOCKS);
+        // Only swept deposits have a sweep time, so only scan the confirmed
+        // deposits that were updated within the window.
+        let entries = accessors::get_all_deposit_entries_modified_from_height_with_status(
+            &context,
+            &Status::Confirmed,
+            minimum_height,
+            None,
+        )
+        .await?;
+        let response = deposit_sla_seconds(
+            entries
+                .iter()
+                .filter_map(DepositInfoEntry::seconds_to_sweep),
+        );
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::OK))
+    }
+    // Handle and respond.
+    handler(context)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
+
+/// Compute the median and 95th percentile of the given number of seconds
+/// that deposits took to be swept, using the nearest-rank method.
+fn deposit_sla_seconds(durations: impl IntoIterator<Item = u64>) -> GetDepositSlaResponse {
+    let mut durations: Vec<u64> = durations.into_iter().collect();
+    durations.sort_unstable();
+
+    let percentile = |p: usize| -> Option<u64> {
+        let rank = (p * durations.len()).div_ceil(100);
+        durations.get(rank.checked_sub(1)?).copied()
+    };
+
+    GetDepositSlaResponse {
+        count: durations.len() as u64,
+        p50_seconds: percentile(50),
+        p95_seconds: percentile(95),
+    }
+}
+
 /// Create deposit handler.
 #[utoipa::path(
     post,
@@ -199,6 +425,7 @@ pub async fn get_deposits(
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 422, description = "Reclaim lock time too large or field too long", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
@@ -216,48 +443,67 @@ pub async fn create_deposit(
         // Set variables.
         let api_state = accessors::get_api_state(&context).await?;
         api_state.error_if_reorganizing()?;
+        // Create the deposit.
+        let response = create_deposit_entry(&context, &api_state.chaintip(), body).await?;
+        // Respond.
+        Ok(with_status(json(&response), StatusCode::CREATED))
+    }
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
 
+/// Create deposits handler.
+#[utoipa::path(
+    post,
+    operation_id = "createDeposits",
+    path = "/deposit/batch",
+    tag = "deposit",
+    request_body = CreateDepositsRequestBody,
+    responses(
+        (status = 200, description = "Deposits processed, see the response for any that failed", body = CreateDepositsResponse),
+        (status = 400, description = "Invalid request body", body = ErrorResponse),
+        (status = 404, description = "Address not found", body = ErrorResponse),
+        (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[instrument(skip(context))]
+pub async fn create_deposits(
+    context: EmilyContext,
+    body: CreateDepositsRequestBody,
+) -> impl warp::reply::Reply {
+    debug!("In create deposits");
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: CreateDepositsRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        validate_batch_size(body.deposits.len())?;
+        // Set variables. All the deposits are created at the same chain tip.
+        let api_state = accessors::get_api_state(&context).await?;
+        api_state.error_if_reorganizing()?;
         let chaintip = api_state.chaintip();
-        let stacks_block_hash: String = chaintip.key.hash;
-        let stacks_block_height: u64 = chaintip.key.height;
-        let status = Status::Pending;
-
-        // Get parameters from scripts.
-        let script_parameters =
-            scripts_to_resource_parameters(&body.deposit_script, &body.reclaim_script)?;
-
-        // Make table entry.
-        let deposit_entry: DepositEntry = DepositEntry {
-            key: DepositEntryKey {
-                bitcoin_txid: body.bitcoin_txid,
-                bitcoin_tx_output_index: body.bitcoin_tx_output_index,
-            },
-            recipient: script_parameters.recipient,
-            parameters: DepositParametersEntry {
-                max_fee: script_parameters.max_fee,
-                lock_time: script_parameters.lock_time,
-            },
-            history: vec![DepositEvent {
-                status: StatusEntry::Pending,
-                message: "Just received deposit".to_string(),
-                stacks_block_hash: stacks_block_hash.clone(),
-                stacks_block_height,
-            }],
-            status,
-            last_update_block_hash: stacks_block_hash,
-            last_update_height: stacks_block_height,
-            amount: script_parameters.amount,
-            reclaim_script: body.reclaim_script,
-            deposit_script: body.deposit_script,
-            ..Default::default()
-        };
-        // Validate deposit entry.
-        deposit_entry.validate()?;
-        // Add entry to the table.
-        accessors::add_deposit_entry(&context, &deposit_entry).await?;
+
+        // Create each deposit, reporting the ones that fail individually
+        // so that one bad deposit doesn't fail the whole batch.
+        let mut response = CreateDepositsResponse::default();
+        for request in body.deposits {
+            let bitcoin_txid = request.bitcoin_txid.clone();
+            let bitcoin_tx_output_index = request.bitcoin_tx_output_index;
+            match create_deposit_entry(&context, &chaintip, request).await {
+                Ok(deposit) => response.deposits.push(deposit),
+                Err(error) => response.errors.push(CreateDepositError {
+                    bitcoin_txid,
+                    bitcoin_tx_output_index,
+                    status_code: error.status_code().as_u16(),
+                    message: error.to_string(),
+                }),
+            }
+        }
         // Respond.
-        let response: Deposit = deposit_entry.try_into()?;
-        Ok(with_status(json(&response), StatusCode::CREATED))
+        Ok(with_status(json(&response), StatusCode::OK))
     }
     // Handle and respond.
     handler(context, body)
@@ -265,6 +511,70 @@ pub async fn create_deposit(
         .map_or_else(Reply::into_response, Reply::into_response)
 }
 
+/// Validate the given create deposit request and add the deposit to the
+/// table as pending at the given chain tip.
+async fn create_deposit_entry(
+    context: &EmilyContext,
+    chaintip: &ChainstateEntry,
+    body: CreateDepositRequestBody,
+) -> Result<Deposit, Error> {
+    // Reject oversized fields before doing anything else.
+    let max_deposit_script_length = context.settings.max_deposit_script_length;
+    body.validate(max_deposit_script_length)?;
+    // Set variables.
+    let stacks_block_hash: String = chaintip.key.hash.clone();
+    let stacks_block_height: u64 = chaintip.key.height;
+    let status = Status::Pending;
+
+    // Get parameters from scripts.
+    let script_parameters = scripts_to_resource_parameters(
+        &body.deposit_script,
+        &body.reclaim_script,
+        max_deposit_script_length,
+    )?;
+    // Reject deposits that the signers would not accept anyway.
+    let max_lock_time = context.settings.max_reclaim_lock_time;
+    if script_parameters.lock_time > max_lock_time {
+        return Err(Error::ReclaimLockTimeTooLarge {
+            lock_time: script_parameters.lock_time,
+            max_lock_time,
+        });
+    }
+
+    // Make table entry.
+    let deposit_entry: DepositEntry = DepositEntry {
+        key: DepositEntryKey {
+            bitcoin_txid: body.bitcoin_txid,
+            bitcoin_tx_output_index: body.bitcoin_tx_output_index,
+        },
+        recipient: script_parameters.recipient,
+        parameters: DepositParametersEntry {
+            max_fee: script_parameters.max_fee,
+            lock_time: script_parameters.lock_time,
+        },
+        history: vec![DepositEvent {
+            status: StatusEntry::Pending,
+            message: "Just received deposit".to_string(),
+            stacks_block_hash: stacks_block_hash.clone(),
+            stacks_block_height,
+            failure_reason: None,
+        }],
+        status,
+        last_update_block_hash: stacks_block_hash,
+        last_update_height: stacks_block_height,
+        amount: script_parameters.amount,
+        reclaim_script: body.reclaim_script,
+        deposit_script: body.deposit_script,
+        first_seen_time: Some(unix_timestamp(SystemTime::now())),
+        ..Default::default()
+    };
+    // Validate deposit entry.
+    deposit_entry.validate()?;
+    // Add entry to the table.
+    context.add_deposit_entry(&deposit_entry).await?;
+    deposit_entry.try_into()
+}
+
 /// Parameters from the deposit and reclaim scripts.
 struct ScriptParameters {
     amount: u64,
@@ -280,9 +590,13 @@ struct ScriptParameters {
 fn scripts_to_resource_parameters(
     deposit_script: &str,
     reclaim_script: &str,
+    max_deposit_script_length: usize,
 ) -> Result<ScriptParameters, Error> {
     let deposit_script_buf = ScriptBuf::from_hex(deposit_script)?;
-    let deposit_script_inputs = sbtc::deposits::DepositScriptInputs::parse(&deposit_script_buf)?;
+    let deposit_script_inputs = sbtc::deposits::DepositScriptInputs::parse_with_max_length(
+        &deposit_script_buf,
+        max_deposit_script_length,
+    )?;
 
     let reclaim_script_buf = ScriptBuf::from_hex(reclaim_script)?;
     let reclaim_script_inputs = sbtc::deposits::ReclaimScriptInputs::parse(&reclaim_script_buf)?;
@@ -307,7 +621,7 @@ fn scripts_to_resource_parameters(
     tag = "deposit",
     request_body = UpdateDepositsRequestBody,
     responses(
-        (status = 201, description = "Deposits updated successfully", body = UpdateDepositsResponse),
+        (status = 201, description = "Deposits updated, see the response for any updates that were rejected", body = UpdateDepositsResponse),
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
@@ -353,23 +667,44 @@ pub async fn update_deposits(
             .await?;
         }
 
-        // Create aggregator.
+        // Create aggregators.
         let mut updated_deposits: Vec<(usize, Deposit)> =
             Vec::with_capacity(validated_request.deposits.len());
+        let mut rejected_updates: Vec<(usize, UpdateDepositError)> = Vec::new();
 
-        // Loop through all updates and execute.
+        // Loop through all updates and execute. Updates that would move a
+        // deposit to a status that cannot follow its current one are
+        // reported individually so that they don't fail the whole request.
         for (index, update) in validated_request.deposits {
-            let updated_deposit =
-                accessors::pull_and_update_deposit_with_retry(&context, update, 15).await?;
-            updated_deposits.push((index, updated_deposit.try_into()?));
+            let bitcoin_txid = update.key.bitcoin_txid.clone();
+            let bitcoin_tx_output_index = update.key.bitcoin_tx_output_index;
+            match accessors::pull_and_update_deposit_with_retry(&context, update, 15).await {
+                Ok(updated_deposit) => updated_deposits.push((index, updated_deposit.try_into()?)),
+                Err(error @ Error::InvalidStatusTransition { .. }) => rejected_updates.push((
+                    index,
+                    UpdateDepositError {
+                        bitcoin_txid,
+                        bitcoin_tx_output_index,
+                        status_code: error.status_code().as_u16(),
+                        message: error.to_string(),
+                    },
+                )),
+                Err(error) => return Err(error),
+            }
         }
 
         updated_deposits.sort_by_key(|(index, _)| *index);
-        let deposits = updated_deposits
+        rejected_updates.sort_by_key(|(index, _)| *index);
+        let deposits: Vec<Deposit> = updated_deposits
             .into_iter()
             .map(|(_, deposit)| deposit)
             .collect();
-        let response = UpdateDepositsResponse { deposits };
+        let errors: Vec<UpdateDepositError> = rejected_updates
+            .into_iter()
+            .map(|(_, error)| error)
+            .collect();
+        context.webhooks.notify_deposit_updates(&deposits).await;
+        let response = UpdateDepositsResponse { deposits, errors };
         Ok(with_status(json(&response), StatusCode::CREATED))
     }
     // Handle and respond.
@@ -396,8 +731,12 @@ mod tests {
         let deposit_script = setup.deposit.deposit_script().to_hex_string();
         let reclaim_script = setup.reclaim.reclaim_script().to_hex_string();
 
-        let script_parameters: ScriptParameters =
-            scripts_to_resource_parameters(&deposit_script, &reclaim_script).unwrap();
+        let script_parameters: ScriptParameters = scripts_to_resource_parameters(
+            &deposit_script,
+            &reclaim_script,
+            sbtc::deposits::MAX_DEPOSIT_SCRIPT_LENGTH,
+        )
+        .unwrap();
 
         assert_eq!(script_parameters.max_fee, max_fee);
         assert_eq!(script_parameters.lock_time, lock_time);
@@ -405,4 +744,78 @@ mod tests {
         // TODO: Test the recipient with an input value.
         assert!(script_parameters.recipient.len() > 0);
     }
+
+    #[test]
+    fn scripts_to_resource_parameters_respects_the_max_deposit_script_length() {
+        let setup: TxSetup = testing::deposits::tx_setup(150, 15000, 500_000);
+
+        let deposit_script = setup.deposit.deposit_script();
+        let reclaim_script = setup.reclaim.reclaim_script().to_hex_string();
+
+        let result = scripts_to_resource_parameters(
+            &deposit_script.to_hex_string(),
+            &reclaim_script,
+            deposit_script.len() - 1,
+        );
+        assert!(result.is_err());
+
+        let result = scripts_to_resource_parameters(
+            &deposit_script.to_hex_string(),
+            &reclaim_script,
+            deposit_script.len(),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn deposit_histogram_counts_each_bucket() {
+        let amounts = [
+            0,
+            999_999,
+            1_000_000,
+            5_000_000,
+            9_999_999,
+            10_000_000,
+            100_000_000,
+            250_000_000,
+            2_100_000_000_000_000,
+        ];
+
+        let buckets = deposit_histogram(amounts);
+
+        let counts: Vec<u64> = buckets.iter().map(|bucket| bucket.count).collect();
+        assert_eq!(counts, vec![2, 3, 1, 3]);
+
+        let bounds: Vec<(u64, Option<u64>)> = buckets
+            .iter()
+            .map(|bucket| (bucket.min_amount, bucket.max_amount))
+            .collect();
+        let expected = vec![
+            (0, Some(1_000_000)),
+            (1_000_000, Some(10_000_000)),
+            (10_000_000, Some(100_000_000)),
+            (100_000_000, None),
+        ];
+        assert_eq!(bounds, expected);
+    }
+
+    #[test]
+    fn deposit_sla_seconds_computes_percentiles() {
+        // Twenty deposits swept 60, 120, ..., 1200 seconds after being
+        // first seen, given out of order.
+        let durations = (1..=20).rev().map(|minutes| minutes * 60);
+
+        let sla = deposit_sla_seconds(durations);
+
+        assert_eq!(sla.count, 20);
+        assert_eq!(sla.p50_seconds, Some(600));
+        assert_eq!(sla.p95_seconds, Some(1140));
+    }
+
+    #[test]
+    fn deposit_sla_seconds_is_empty_without_swept_deposits() {
+        let sla = deposit_sla_seconds([]);
+
+        assert_eq!(sla, GetDepositSlaResponse::default());
+    }
 }
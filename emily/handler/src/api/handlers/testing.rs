@@ -2,8 +2,9 @@
 
 use reqwest::StatusCode;
 use tracing::instrument;
-use warp::reply::Reply;
+use warp::reply::{json, with_status, Reply};
 
+use crate::api::models::webhook::{RegisterWebhookRequestBody, Webhook};
 use crate::common::error::Error;
 use crate::context::EmilyContext;
 use crate::database::accessors;
@@ -40,3 +41,45 @@ pub async fn wipe_databases(context: EmilyContext) -> impl warp::reply::Reply {
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
+
+/// Register webhook handler. Registrations are held in memory and are
+/// lost when the API restarts.
+#[utoipa::path(
+    post,
+    operation_id = "registerWebhook",
+    path = "/testing/webhooks",
+    tag = "testing",
+    request_body = RegisterWebhookRequestBody,
+    responses(
+        (status = 201, description = "Webhook registered successfully", body = Webhook),
+        (status = 400, description = "Invalid request body"),
+        (status = 405, description = "Method not allowed"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("ApiGatewayKey" = []))
+)]
+#[instrument(skip(context))]
+pub async fn register_webhook(
+    context: EmilyContext,
+    body: RegisterWebhookRequestBody,
+) -> impl warp::reply::Reply {
+    // Internal handler so `?` can be used correctly while still returning a reply.
+    async fn handler(
+        context: EmilyContext,
+        body: RegisterWebhookRequestBody,
+    ) -> Result<impl warp::reply::Reply, Error> {
+        if reqwest::Url::parse(&body.url).is_err() {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!("invalid webhook url: {}", body.url),
+            ));
+        }
+        let webhook: Webhook = context.webhooks.register(body);
+        Ok(with_status(json(&webhook), StatusCode::CREATED))
+    }
+
+    // Handle and respond.
+    handler(context, body)
+        .await
+        .map_or_else(Reply::into_response, Reply::into_response)
+}
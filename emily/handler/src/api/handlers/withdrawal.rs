@@ -2,7 +2,8 @@
 use tracing::instrument;
 use warp::reply::{json, with_status, Reply};
 
-use crate::api::models::common::Status;
+use crate::api::models::common::requests::validate_page_size;
+use crate::api::models::common::{SortDirection, Status};
 use crate::api::models::withdrawal::{
     requests::{CreateWithdrawalRequestBody, GetWithdrawalsQuery, UpdateWithdrawalsRequestBody},
     responses::{GetWithdrawalsResponse, UpdateWithdrawalsResponse},
@@ -16,6 +17,7 @@ use crate::database::entries::withdrawal::{
     WithdrawalParametersEntry,
 };
 use crate::database::entries::StatusEntry;
+use crate::database::store::Store;
 use warp::http::StatusCode;
 
 /// Get withdrawal handler.
@@ -40,19 +42,17 @@ use warp::http::StatusCode;
 pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp::reply::Reply {
     // Internal handler so `?` can be used correctly while still returning a reply.
     async fn handler(
-        context: EmilyContext,
+        store: &impl Store,
         request_id: u64,
     ) -> Result<impl warp::reply::Reply, Error> {
         // Get withdrawal.
-        let withdrawal: Withdrawal = accessors::get_withdrawal_entry(&context, &request_id)
-            .await?
-            .try_into()?;
+        let withdrawal: Withdrawal = store.get_withdrawal_entry(&request_id).await?.try_into()?;
 
         // Respond.
         Ok(with_status(json(&withdrawal), StatusCode::OK))
     }
     // Handle and respond.
-    handler(context, request_id)
+    handler(&context, request_id)
         .await
         .map_or_else(Reply::into_response, Reply::into_response)
 }
@@ -65,7 +65,8 @@ pub async fn get_withdrawal(context: EmilyContext, request_id: u64) -> impl warp
     params(
         ("status" = Status, Query, description = "the status to search by when getting all deposits."),
         ("nextToken" = Option<String>, Query, description = "the next token value from the previous return of this api call."),
-        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list.")
+        ("pageSize" = Option<i32>, Query, description = "the maximum number of items in the response list, between 1 and 1000."),
+        ("direction" = Option<SortDirection>, Query, description = "the order to return the withdrawals in, most recently updated first by default.")
     ),
     tag = "withdrawal",
     responses(
@@ -87,19 +88,25 @@ pub async fn get_withdrawals(
         context: EmilyContext,
         query: GetWithdrawalsQuery,
     ) -> Result<impl warp::reply::Reply, Error> {
+        validate_page_size(query.page_size)?;
         // Deserialize next token into the exclusive start key if present.
-        let (entries, next_token) = accessors::get_withdrawal_entries(
+        let (entries, next_token, prev_token) = accessors::get_withdrawal_entries(
             &context,
             &query.status,
             query.next_token,
             query.page_size,
+            query.direction,
         )
         .await?;
         // Convert data into resource types.
         let withdrawals: Vec<WithdrawalInfo> =
             entries.into_iter().map(|entry| entry.into()).collect();
         // Create response.
-        let response = GetWithdrawalsResponse { withdrawals, next_token };
+        let response = GetWithdrawalsResponse {
+            withdrawals,
+            next_token,
+            prev_token,
+        };
         // Respond.
         Ok(with_status(json(&response), StatusCode::OK))
     }
@@ -122,6 +129,7 @@ pub async fn get_withdrawals(
         (status = 400, description = "Invalid request body", body = ErrorResponse),
         (status = 404, description = "Address not found", body = ErrorResponse),
         (status = 405, description = "Method not allowed", body = ErrorResponse),
+        (status = 422, description = "Field too long", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     security(("ApiGatewayKey" = []))
@@ -136,6 +144,8 @@ pub async fn create_withdrawal(
         context: EmilyContext,
         body: CreateWithdrawalRequestBody,
     ) -> Result<impl warp::reply::Reply, Error> {
+        // Reject oversized fields before doing anything else.
+        body.validate()?;
         // Get the api state and error if the api state is claimed by a reorg.
         //
         // Note: This may not be necessary due to the implied order of events
@@ -179,7 +189,7 @@ pub async fn create_withdrawal(
         // Validate withdrawal entry.
         withdrawal_entry.validate()?;
         // Add entry to the table.
-        accessors::add_withdrawal_entry(&context, &withdrawal_entry).await?;
+        context.add_withdrawal_entry(&withdrawal_entry).await?;
         // Respond.
         let response: Withdrawal = withdrawal_entry.try_into()?;
         Ok(with_status(json(&response), StatusCode::CREATED))
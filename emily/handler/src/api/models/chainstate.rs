@@ -25,3 +25,15 @@ pub struct Chainstate {
     /// Stacks block hash at the height.
     pub stacks_block_hash: String,
 }
+
+/// The minimum number of confirmations that a deposit needs before the
+/// signers process it.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct MinConfirmations {
+    /// The minimum number of confirmations. When not set, the signers
+    /// process deposits as soon as they are confirmed.
+    pub min_confirmations: Option<u64>,
+}
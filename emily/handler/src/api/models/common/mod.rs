@@ -48,6 +48,28 @@ pub enum Status {
     Confirmed,
     /// The operation was not fulfilled.
     Failed,
+    /// The deposit was reclaimed by the depositor on the Bitcoin blockchain,
+    /// so it can no longer be fulfilled. This only applies to deposits.
+    Reclaimed,
+    /// The withdrawal was rejected by the signers because it was still
+    /// pending at the end of their expiry window, so it is refunded. This
+    /// only applies to withdrawals.
+    Expired,
+    /// The deposit is locked to a key that the signers no longer use, so
+    /// it will not be processed. This only applies to deposits.
+    RejectedWrongKey,
+}
+
+/// The order in which a list endpoint returns its items, by the height at
+/// which they were last updated.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    /// Most recently updated first.
+    #[default]
+    Desc,
+    /// Least recently updated first.
+    Asc,
 }
 
 /// Data about the fulfillment of an sBTC Operation.
@@ -4,6 +4,17 @@ use utoipa::ToSchema;
 
 use serde::{Deserialize, Serialize};
 
+use crate::common::error::Error;
+
+/// The smallest page size that list requests accept.
+pub const MIN_PAGE_SIZE: i32 = 1;
+
+/// The largest page size that list requests accept.
+pub const MAX_PAGE_SIZE: i32 = 1000;
+
+/// The largest number of items that batch requests accept.
+pub const MAX_BATCH_SIZE: usize = 100;
+
 /// Generic paginated query representation.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -15,3 +26,42 @@ pub struct PaginatedQuery<T> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<i32>,
 }
+
+/// Check that the given request field is no longer than the maximum
+/// length, returning an error naming the field if it is.
+pub fn validate_field_length(field: &str, value: &str, max_length: usize) -> Result<(), Error> {
+    if value.len() > max_length {
+        return Err(Error::FieldTooLong {
+            field: field.to_string(),
+            length: value.len(),
+            max_length,
+        });
+    }
+    Ok(())
+}
+
+/// Check that the given page size of a list request, if any, is between
+/// [`MIN_PAGE_SIZE`] and [`MAX_PAGE_SIZE`].
+pub fn validate_page_size(page_size: Option<i32>) -> Result<(), Error> {
+    match page_size {
+        Some(page_size) if !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&page_size) => {
+            Err(Error::InvalidPageSize {
+                page_size,
+                min_page_size: MIN_PAGE_SIZE,
+                max_page_size: MAX_PAGE_SIZE,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check that a batch request holds no more than [`MAX_BATCH_SIZE`] items.
+pub fn validate_batch_size(batch_size: usize) -> Result<(), Error> {
+    if batch_size > MAX_BATCH_SIZE {
+        return Err(Error::BatchTooLarge {
+            batch_size,
+            max_batch_size: MAX_BATCH_SIZE,
+        });
+    }
+    Ok(())
+}
@@ -1,9 +1,12 @@
 //! Request structures for deposit api calls.
 
+use bitcoin::{OutPoint, ScriptBuf};
+use sbtc::deposits::CreateDepositRequest;
 use serde::{Deserialize, Serialize};
 use utoipa::{ToResponse, ToSchema};
 
 use crate::api::models::common::{Fulfillment, Status};
+use crate::common::error::Error;
 
 /// Requests.
 pub mod requests;
@@ -56,6 +59,23 @@ pub struct Deposit {
     /// Details about the on chain artifacts that fulfilled the deposit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// Why the deposit failed. Only present when the status is failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+impl TryFrom<&Deposit> for CreateDepositRequest {
+    type Error = Error;
+    fn try_from(deposit: &Deposit) -> Result<Self, Self::Error> {
+        Ok(CreateDepositRequest {
+            outpoint: OutPoint {
+                txid: deposit.bitcoin_txid.parse()?,
+                vout: deposit.bitcoin_tx_output_index,
+            },
+            reclaim_script: ScriptBuf::from_hex(&deposit.reclaim_script)?,
+            deposit_script: ScriptBuf::from_hex(&deposit.deposit_script)?,
+        })
+    }
 }
 
 /// Deposit parameters.
@@ -1,9 +1,20 @@
 //! Request structures for deposit api calls.
 
+use bitcoin::{OutPoint, ScriptBuf};
+use sbtc::deposits::CreateDepositRequest;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::api::models::common::{Fulfillment, Status};
+use crate::api::models::common::requests::validate_field_length;
+use crate::api::models::common::{Fulfillment, SortDirection, Status};
+use crate::common::error::Error;
+
+/// The maximum length of a hex encoded bitcoin txid.
+const MAX_BITCOIN_TXID_LENGTH: usize = 64;
+
+/// The maximum length of a hex encoded reclaim script. Reclaim scripts
+/// used in practice are far smaller than one kilobyte.
+const MAX_RECLAIM_SCRIPT_LENGTH: usize = 2 * 1024;
 
 /// Query structure for the GetDepositsQuery struct.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
@@ -29,6 +40,13 @@ pub struct GetDepositsQuery {
     /// Maximum number of results to show.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<i32>,
+    /// The order to return the results in.
+    #[serde(default)]
+    pub direction: SortDirection,
+    /// Whether to count the deposits matching the search. Only honored
+    /// for the first page.
+    #[serde(default)]
+    pub include_total: bool,
 }
 
 /// Request structure for create deposit request.
@@ -45,6 +63,61 @@ pub struct CreateDepositRequestBody {
     pub deposit_script: String,
 }
 
+impl CreateDepositRequestBody {
+    /// Check that the fields of the request are within their length
+    /// bounds, where deposit scripts can be at most
+    /// `max_deposit_script_length` bytes long.
+    pub fn validate(&self, max_deposit_script_length: usize) -> Result<(), Error> {
+        validate_field_length("bitcoinTxid", &self.bitcoin_txid, MAX_BITCOIN_TXID_LENGTH)?;
+        validate_field_length(
+            "reclaimScript",
+            &self.reclaim_script,
+            MAX_RECLAIM_SCRIPT_LENGTH,
+        )?;
+        // The deposit script is hex encoded, so it takes two characters
+        // per byte.
+        validate_field_length(
+            "depositScript",
+            &self.deposit_script,
+            2 * max_deposit_script_length,
+        )
+    }
+}
+
+impl TryFrom<&CreateDepositRequestBody> for CreateDepositRequest {
+    type Error = Error;
+    fn try_from(body: &CreateDepositRequestBody) -> Result<Self, Self::Error> {
+        Ok(CreateDepositRequest {
+            outpoint: OutPoint {
+                txid: body.bitcoin_txid.parse()?,
+                vout: body.bitcoin_tx_output_index,
+            },
+            reclaim_script: ScriptBuf::from_hex(&body.reclaim_script)?,
+            deposit_script: ScriptBuf::from_hex(&body.deposit_script)?,
+        })
+    }
+}
+
+impl From<&CreateDepositRequest> for CreateDepositRequestBody {
+    fn from(request: &CreateDepositRequest) -> Self {
+        CreateDepositRequestBody {
+            bitcoin_txid: request.outpoint.txid.to_string(),
+            bitcoin_tx_output_index: request.outpoint.vout,
+            reclaim_script: request.reclaim_script.to_hex_string(),
+            deposit_script: request.deposit_script.to_hex_string(),
+        }
+    }
+}
+
+/// Request structure for the create deposits request, which creates many
+/// deposits at once.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDepositsRequestBody {
+    /// The deposits to create.
+    pub deposits: Vec<CreateDepositRequestBody>,
+}
+
 /// A singlular Deposit update that contains only the fields pertinent
 /// to updating the status of a deposit. This includes the key related
 /// data in addition to status history related data.
@@ -70,6 +143,9 @@ pub struct DepositUpdate {
     /// Details about the on chain artifacts that fulfilled the deposit.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// Why the deposit failed. Only accepted when the status is failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
 }
 
 /// Request structure for update deposit request.
@@ -79,3 +155,47 @@ pub struct UpdateDepositsRequestBody {
     /// Bitcoin transaction id.
     pub deposits: Vec<DepositUpdate>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::api::models::deposit::Deposit;
+
+    #[test]
+    fn create_deposit_request_conversions_round_trip() {
+        let setup = sbtc::testing::deposits::tx_setup(12345, 30, 1000);
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            reclaim_script: setup.reclaim.reclaim_script(),
+            deposit_script: setup.deposit.deposit_script(),
+        };
+
+        let body = CreateDepositRequestBody::from(&request);
+        let from_body = CreateDepositRequest::try_from(&body).unwrap();
+
+        let deposit = Deposit {
+            bitcoin_txid: body.bitcoin_txid.clone(),
+            bitcoin_tx_output_index: body.bitcoin_tx_output_index,
+            reclaim_script: body.reclaim_script.clone(),
+            deposit_script: body.deposit_script.clone(),
+            ..Default::default()
+        };
+        let from_deposit = CreateDepositRequest::try_from(&deposit).unwrap();
+
+        for converted in [from_body, from_deposit] {
+            assert_eq!(converted.outpoint, request.outpoint);
+            assert_eq!(converted.reclaim_script, request.reclaim_script);
+            assert_eq!(converted.deposit_script, request.deposit_script);
+        }
+    }
+
+    #[test]
+    fn create_deposit_request_conversion_rejects_invalid_txids() {
+        let body = CreateDepositRequestBody {
+            bitcoin_txid: "not a txid".to_string(),
+            ..Default::default()
+        };
+        assert!(CreateDepositRequest::try_from(&body).is_err());
+    }
+}
@@ -21,8 +21,94 @@ pub struct GetDepositsForTransactionResponse {
 pub struct GetDepositsResponse {
     /// Next token for the search.
     pub next_token: Option<String>,
+    /// Token for the page before this one. Searching with it as the next
+    /// token, in the opposite direction, walks back through the results.
+    /// Not present for the first page.
+    pub prev_token: Option<String>,
     /// Deposit infos: deposits with a little less data.
     pub deposits: Vec<DepositInfo>,
+    /// The total number of deposits matching the search. Only present in
+    /// the response to the first page, when the total was asked for.
+    pub total: Option<u64>,
+}
+
+/// A bucket of the deposit amount histogram.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositHistogramBucket {
+    /// The smallest deposit amount in the bucket, in satoshis.
+    pub min_amount: u64,
+    /// The deposit amount, in satoshis, that all deposits in the bucket are
+    /// below. There is no upper bound for the last bucket.
+    pub max_amount: Option<u64>,
+    /// The number of recently swept deposits in the bucket.
+    pub count: u64,
+}
+
+/// Response to get deposit histogram request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositHistogramResponse {
+    /// The buckets of the histogram, ordered by amount.
+    pub buckets: Vec<DepositHistogramBucket>,
+}
+
+/// Response to get deposit SLA request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDepositSlaResponse {
+    /// The number of recently swept deposits that the percentiles are
+    /// computed over.
+    pub count: u64,
+    /// The median number of seconds from a deposit being first seen to it
+    /// being swept.
+    pub p50_seconds: Option<u64>,
+    /// The 95th percentile of the number of seconds from a deposit being
+    /// first seen to it being swept.
+    pub p95_seconds: Option<u64>,
+}
+
+/// A deposit that could not be created as part of a create deposits
+/// request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDepositError {
+    /// Bitcoin transaction id of the deposit.
+    pub bitcoin_txid: String,
+    /// Output index on the bitcoin transaction of the deposit.
+    pub bitcoin_tx_output_index: u32,
+    /// The http status code that creating the deposit on its own would
+    /// have failed with.
+    pub status_code: u16,
+    /// Why the deposit could not be created.
+    pub message: String,
+}
+
+/// Response to create deposits request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDepositsResponse {
+    /// The deposits that were created, in the order they were requested.
+    pub deposits: Vec<Deposit>,
+    /// The deposits that could not be created, in the order they were
+    /// requested.
+    pub errors: Vec<CreateDepositError>,
+}
+
+/// A deposit update that was rejected as part of an update deposits
+/// request.
+#[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDepositError {
+    /// Bitcoin transaction id of the deposit.
+    pub bitcoin_txid: String,
+    /// Output index on the bitcoin transaction of the deposit.
+    pub bitcoin_tx_output_index: u32,
+    /// The http status code that the update on its own would have failed
+    /// with.
+    pub status_code: u16,
+    /// Why the update was rejected.
+    pub message: String,
 }
 
 /// Response to update deposits request.
@@ -31,4 +117,8 @@ pub struct GetDepositsResponse {
 pub struct UpdateDepositsResponse {
     /// Deposit infos: deposits with a little less data.
     pub deposits: Vec<Deposit>,
+    /// The updates that were rejected because the deposit cannot move to
+    /// the requested status, in the order they were requested.
+    #[serde(default)]
+    pub errors: Vec<UpdateDepositError>,
 }
@@ -10,5 +10,7 @@ pub mod deposit;
 pub mod health;
 /// Api structures for limits.
 pub mod limits;
+/// Api structures for webhooks.
+pub mod webhook;
 /// Api structures for withdrawals.
 pub mod withdrawal;
@@ -0,0 +1,40 @@
+//! Request and response structures for webhook api calls.
+
+use serde::{Deserialize, Serialize};
+use utoipa::{ToResponse, ToSchema};
+
+use crate::api::models::common::Status;
+
+/// Request structure for the register webhook request.
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterWebhookRequestBody {
+    /// The URL that deposit status change notifications are posted to.
+    pub url: String,
+    /// The statuses to notify about. A notification is sent whenever a
+    /// deposit is updated to one of these statuses.
+    pub statuses: Vec<Status>,
+    /// The secret used to sign the notification payloads.
+    pub secret: String,
+}
+
+/// A registered webhook.
+#[derive(
+    Clone, Default, Debug, Eq, PartialEq, Hash, Serialize, Deserialize, ToSchema, ToResponse,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// The URL that deposit status change notifications are posted to.
+    pub url: String,
+    /// The statuses to notify about.
+    pub statuses: Vec<Status>,
+}
+
+impl From<RegisterWebhookRequestBody> for Webhook {
+    fn from(request: RegisterWebhookRequestBody) -> Self {
+        Webhook {
+            url: request.url,
+            statuses: request.statuses,
+        }
+    }
+}
@@ -54,6 +54,10 @@ pub struct Withdrawal {
     /// Details about the on chain artifacts that fulfilled the withdrawal.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// The number of signer signatures collected so far for the withdrawal.
+    pub signatures_collected: u32,
+    /// The number of signer signatures required for the withdrawal.
+    pub threshold: u32,
 }
 
 /// Withdrawal parameters.
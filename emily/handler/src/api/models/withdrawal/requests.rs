@@ -3,8 +3,17 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::api::models::common::{Fulfillment, Status};
+use crate::api::models::common::requests::validate_field_length;
+use crate::api::models::common::{Fulfillment, SortDirection, Status};
 use crate::api::models::withdrawal::WithdrawalParameters;
+use crate::common::error::Error;
+
+/// The maximum length of a hex encoded stacks block hash.
+const MAX_STACKS_BLOCK_HASH_LENGTH: usize = 64;
+
+/// The maximum length of a recipient bitcoin address. Bech32 encoded
+/// addresses are at most 90 characters.
+const MAX_RECIPIENT_LENGTH: usize = 90;
 
 /// Query structure for the get withdrawals request.
 #[derive(Clone, Default, Debug, PartialEq, Hash, Serialize, Deserialize, ToSchema)]
@@ -18,6 +27,9 @@ pub struct GetWithdrawalsQuery {
     /// Maximum number of results to show.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page_size: Option<i32>,
+    /// The order to return the results in.
+    #[serde(default)]
+    pub direction: SortDirection,
 }
 
 /// Request structure for the create withdrawal request.
@@ -38,6 +50,19 @@ pub struct CreateWithdrawalRequestBody {
     pub parameters: WithdrawalParameters,
 }
 
+impl CreateWithdrawalRequestBody {
+    /// Check that the fields of the request are within their length
+    /// bounds.
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_field_length(
+            "stacksBlockHash",
+            &self.stacks_block_hash,
+            MAX_STACKS_BLOCK_HASH_LENGTH,
+        )?;
+        validate_field_length("recipient", &self.recipient, MAX_RECIPIENT_LENGTH)
+    }
+}
+
 /// A singlular Withdrawal update that contains only the fields pertinent
 /// to updating the status of a withdrawal. This includes the key related
 /// data in addition to status history related data.
@@ -61,6 +86,15 @@ pub struct WithdrawalUpdate {
     /// Details about the on chain artifacts that fulfilled the withdrawal.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fulfillment: Option<Fulfillment>,
+    /// The number of signer signatures collected so far for the withdrawal.
+    /// The current count is left unchanged if this is not present. The
+    /// count cannot be more than the threshold.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signatures_collected: Option<u32>,
+    /// The number of signer signatures required for the withdrawal. The
+    /// current threshold is left unchanged if this is not present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<u32>,
 }
 
 /// Request structure for the create withdrawal request.
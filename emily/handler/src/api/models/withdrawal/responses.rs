@@ -11,6 +11,10 @@ use crate::api::models::withdrawal::{Withdrawal, WithdrawalInfo};
 pub struct GetWithdrawalsResponse {
     /// Next token for the search.
     pub next_token: Option<String>,
+    /// Token for the page before this one. Searching with it as the next
+    /// token, in the opposite direction, walks back through the results.
+    /// Not present for the first page.
+    pub prev_token: Option<String>,
     /// Withdrawal infos: withdrawals with a little less data.
     pub withdrawals: Vec<WithdrawalInfo>,
 }
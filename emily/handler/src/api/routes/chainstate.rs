@@ -10,8 +10,11 @@ use super::handlers;
 pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    get_chainstate_at_height(context.clone())
+    get_min_confirmations(context.clone())
+        .or(set_min_confirmations(context.clone()))
+        .or(get_chainstate_at_height(context.clone()))
         .or(set_chainstate(context.clone()))
+        .or(set_chainstate_batch(context.clone()))
         .or(update_chainstate(context.clone()))
         .or(get_chain_tip(context))
 }
@@ -27,6 +30,30 @@ fn get_chain_tip(
         .then(handlers::chainstate::get_chain_tip)
 }
 
+/// Get minimum confirmations endpoint.
+fn get_min_confirmations(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("chainstate" / "min-confirmations"))
+        .and(warp::get())
+        .then(handlers::chainstate::get_min_confirmations)
+}
+
+/// Set minimum confirmations endpoint.
+fn set_min_confirmations(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("chainstate" / "min-confirmations"))
+        .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
+        .and(warp::body::json())
+        .then(handlers::chainstate::set_min_confirmations)
+}
+
 /// Get chainstate at height endpoint.
 fn get_chainstate_at_height(
     context: EmilyContext,
@@ -51,6 +78,19 @@ fn set_chainstate(
         .then(handlers::chainstate::set_chainstate)
 }
 
+/// Set chainstate batch endpoint.
+fn set_chainstate_batch(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("chainstate" / "batch"))
+        .and(warp::post())
+        .and(warp::header::<String>("x-api-key"))
+        .and(warp::body::json())
+        .then(handlers::chainstate::set_chainstate_batch)
+}
+
 /// Update chainstate endpoint.
 fn update_chainstate(
     context: EmilyContext,
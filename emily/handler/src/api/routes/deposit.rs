@@ -10,10 +10,14 @@ pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     get_deposit(context.clone())
+        .or(get_deposit_histogram(context.clone()))
+        .or(get_deposit_sla(context.clone()))
         .or(get_deposits_for_transaction(context.clone()))
         .or(get_deposits(context.clone()))
         .or(create_deposit(context.clone()))
-        .or(update_deposits(context))
+        .or(create_deposits(context.clone()))
+        .or(update_deposits(context.clone()))
+        .or(delete_deposit(context))
 }
 
 /// Get deposit endpoint.
@@ -27,6 +31,31 @@ fn get_deposit(
         .then(handlers::deposit::get_deposit)
 }
 
+/// Get deposit histogram endpoint. This needs to come before the get
+/// deposits for transaction endpoint, which would otherwise treat
+/// `histogram` as a txid.
+fn get_deposit_histogram(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "histogram"))
+        .and(warp::get())
+        .then(handlers::deposit::get_deposit_histogram)
+}
+
+/// Get deposit SLA endpoint. Like the histogram, this needs to come before
+/// the get deposits for transaction endpoint.
+fn get_deposit_sla(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "sla"))
+        .and(warp::get())
+        .then(handlers::deposit::get_deposit_sla)
+}
+
 /// Get deposits for transaction endpoint.
 fn get_deposits_for_transaction(
     context: EmilyContext,
@@ -63,6 +92,18 @@ fn create_deposit(
         .then(handlers::deposit::create_deposit)
 }
 
+/// Create deposits endpoint.
+fn create_deposits(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / "batch"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(handlers::deposit::create_deposits)
+}
+
 /// Update deposits endpoint.
 fn update_deposits(
     context: EmilyContext,
@@ -76,4 +117,16 @@ fn update_deposits(
         .then(handlers::deposit::update_deposits)
 }
 
+/// Delete deposit endpoint.
+fn delete_deposit(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("deposit" / String / u32))
+        .and(warp::delete())
+        .and(warp::header::<String>("x-api-key"))
+        .then(handlers::deposit::delete_deposit)
+}
+
 // TODO(387): Add route unit tests.
@@ -9,7 +9,7 @@ use super::handlers;
 pub fn routes(
     context: EmilyContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    wipe_databases(context)
+    wipe_databases(context.clone()).or(register_webhook(context))
 }
 
 /// Wipe databases
@@ -22,3 +22,15 @@ fn wipe_databases(
         .and(warp::post())
         .then(handlers::testing::wipe_databases)
 }
+
+/// Register webhook
+fn register_webhook(
+    context: EmilyContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::any()
+        .map(move || context.clone())
+        .and(warp::path!("testing" / "webhooks"))
+        .and(warp::post())
+        .and(warp::body::json())
+        .then(handlers::testing::register_webhook)
+}
@@ -10,13 +10,16 @@ use aws_sdk_dynamodb::{
         update_item::UpdateItemError,
     },
 };
-use bitcoin::hex::HexToBytesError;
+use bitcoin::hex::{HexToArrayError, HexToBytesError};
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use warp::{reject::Reject, reply::Reply};
 
-use crate::{api::models::chainstate::Chainstate, database::entries::chainstate::ChainstateEntry};
+use crate::{
+    api::models::{chainstate::Chainstate, common::Status},
+    database::entries::chainstate::ChainstateEntry,
+};
 
 /// State inconsistency representations.
 #[derive(Debug)]
@@ -106,6 +109,57 @@ pub enum Error {
     /// in an update not being performed.
     #[error("Version conflict")]
     VersionConflict,
+
+    /// A field in the request body is longer than its maximum length.
+    #[error("Field {field} has length {length} which exceeds the maximum of {max_length}")]
+    FieldTooLong {
+        /// The name of the field.
+        field: String,
+        /// The length of the field.
+        length: usize,
+        /// The maximum length of the field.
+        max_length: usize,
+    },
+
+    /// The page size of a list request is outside of the allowed range.
+    #[error("Page size {page_size} is not between {min_page_size} and {max_page_size}")]
+    InvalidPageSize {
+        /// The requested page size.
+        page_size: i32,
+        /// The smallest allowed page size.
+        min_page_size: i32,
+        /// The largest allowed page size.
+        max_page_size: i32,
+    },
+
+    /// A batch request contains more items than the maximum batch size.
+    #[error("Batch size {batch_size} exceeds the maximum of {max_batch_size}")]
+    BatchTooLarge {
+        /// The number of items in the batch.
+        batch_size: usize,
+        /// The largest allowed batch size.
+        max_batch_size: usize,
+    },
+
+    /// The lock time in the reclaim script of a deposit is larger than
+    /// the maximum allowed lock time.
+    #[error("Reclaim lock time {lock_time} exceeds the maximum of {max_lock_time}")]
+    ReclaimLockTimeTooLarge {
+        /// The lock time in the reclaim script.
+        lock_time: u32,
+        /// The maximum allowed lock time.
+        max_lock_time: u32,
+    },
+
+    /// An update would move a resource from its current status to a
+    /// status that cannot follow it.
+    #[error("Cannot update status from {from:?} to {to:?}")]
+    InvalidStatusTransition {
+        /// The current status of the resource.
+        from: Status,
+        /// The status that the update would set.
+        to: Status,
+    },
 }
 
 /// Error implementation.
@@ -130,6 +184,11 @@ impl Error {
             Error::InconsistentState(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::Reorganzing(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::VersionConflict => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::FieldTooLong { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::ReclaimLockTimeTooLarge { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::InvalidPageSize { .. } => StatusCode::BAD_REQUEST,
+            Error::BatchTooLarge { .. } => StatusCode::BAD_REQUEST,
+            Error::InvalidStatusTransition { .. } => StatusCode::CONFLICT,
         }
     }
     /// Converts the error into a warp response.
@@ -258,6 +317,11 @@ impl From<HexToBytesError> for Error {
         Error::Debug(format!("HexToBytesError - {err:?}"))
     }
 }
+impl From<HexToArrayError> for Error {
+    fn from(err: HexToArrayError) -> Self {
+        Error::Debug(format!("HexToArrayError - {err:?}"))
+    }
+}
 impl From<sbtc::error::Error> for Error {
     fn from(err: sbtc::error::Error) -> Self {
         Error::Debug(format!("sbtc::error::Error - {err:?}"))
@@ -2,3 +2,5 @@
 
 /// Api errors.
 pub mod error;
+/// Deposit status change webhooks.
+pub mod webhooks;
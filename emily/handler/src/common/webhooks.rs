@@ -0,0 +1,210 @@
+//! Delivery of deposit status change notifications to registered webhooks.
+//!
+//! Each notification is the JSON encoded deposit, posted to the webhook
+//! URL with a HMAC-SHA256 signature of the body, keyed with the secret
+//! given at registration, in the [`SIGNATURE_HEADER`] header.
+//!
+//! Registrations are only held in memory, so they are lost whenever the
+//! lambda cold starts and are not shared between concurrent instances.
+//! Consumers must re-register, and should poll the API rather than rely
+//! on every status change being delivered.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use reqwest::header::CONTENT_TYPE;
+use tracing::{debug, warn};
+
+use crate::api::models::deposit::Deposit;
+use crate::api::models::webhook::{RegisterWebhookRequestBody, Webhook};
+use crate::common::error::Error;
+
+/// The header holding the hex encoded signature of the notification body.
+pub const SIGNATURE_HEADER: &str = "X-Emily-Signature";
+
+/// The maximum number of times we attempt to deliver a notification.
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// How long to wait between delivery attempts.
+const DELIVERY_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// How long a single delivery attempt may take before it is abandoned.
+/// Deliveries happen while the update request is being handled, so this
+/// bounds how long a slow webhook can hold up the response.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The webhooks registered for deposit status changes.
+#[derive(Clone, Debug)]
+pub struct WebhookRegistry {
+    webhooks: Arc<RwLock<Vec<RegisterWebhookRequestBody>>>,
+    client: reqwest::Client,
+}
+
+impl Default for WebhookRegistry {
+    fn default() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .expect("Failed to build the webhook client.");
+
+        Self {
+            webhooks: Arc::default(),
+            client,
+        }
+    }
+}
+
+impl WebhookRegistry {
+    /// Register a webhook.
+    pub fn register(&self, request: RegisterWebhookRequestBody) -> Webhook {
+        self.webhooks
+            .write()
+            .expect("Failed to acquire the webhook registry lock.")
+            .push(request.clone());
+        request.into()
+    }
+
+    /// Notify the registered webhooks that the given deposits have been
+    /// updated. Each webhook is only notified about the deposits whose new
+    /// status is one of the statuses it registered for.
+    ///
+    /// Notifications are best effort; failed deliveries are logged. Each
+    /// delivery attempt is bounded by [`DELIVERY_TIMEOUT`].
+    pub async fn notify_deposit_updates(&self, deposits: &[Deposit]) {
+        let webhooks = self
+            .webhooks
+            .read()
+            .expect("Failed to acquire the webhook registry lock.")
+            .clone();
+
+        for deposit in deposits {
+            let watchers = webhooks
+                .iter()
+                .filter(|webhook| webhook.statuses.contains(&deposit.status));
+
+            for webhook in watchers {
+                if let Err(error) = self.notify(webhook, deposit).await {
+                    warn!(%error, url = %webhook.url, "Failed to deliver deposit webhook notification.");
+                }
+            }
+        }
+    }
+
+    /// Post the deposit to the webhook, retrying a bounded number of times.
+    async fn notify(
+        &self,
+        webhook: &RegisterWebhookRequestBody,
+        deposit: &Deposit,
+    ) -> Result<(), Error> {
+        let body =
+            serde_json::to_vec(deposit).map_err(|error| Error::Serialization(error.to_string()))?;
+        let signature = sign_payload(&webhook.secret, &body)?;
+
+        let mut attempt = 1;
+        loop {
+            let response = self
+                .client
+                .post(&webhook.url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .body(body.clone())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+
+            match response {
+                Ok(_) => return Ok(()),
+                Err(error) if attempt >= MAX_DELIVERY_ATTEMPTS => return Err(error.into()),
+                Err(error) => {
+                    debug!(%error, attempt, url = %webhook.url, "Retrying webhook notification.");
+                    attempt += 1;
+                    tokio::time::sleep(DELIVERY_RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+}
+
+/// Compute the hex encoded HMAC-SHA256 signature of the payload.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> Result<String, Error> {
+    let sign = || {
+        let key = PKey::hmac(secret.as_bytes())?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(payload)?;
+        signer.sign_to_vec()
+    };
+    sign()
+        .map(hex::encode)
+        .map_err(|error| Error::Debug(format!("Failed to sign webhook payload: {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::models::common::Status;
+
+    fn webhook(url: String, statuses: Vec<Status>) -> RegisterWebhookRequestBody {
+        RegisterWebhookRequestBody {
+            url,
+            statuses,
+            secret: "test-secret".to_string(),
+        }
+    }
+
+    fn deposit(status: Status) -> Deposit {
+        Deposit {
+            bitcoin_txid: "bitcoin_txid".to_string(),
+            bitcoin_tx_output_index: 1,
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn watched_status_change_is_delivered_signed() {
+        let confirmed = deposit(Status::Confirmed);
+        let body = serde_json::to_vec(&confirmed).unwrap();
+        let signature = sign_payload("test-secret", &body).unwrap();
+
+        let mock = mockito::mock("POST", "/watched")
+            .match_header(SIGNATURE_HEADER, signature.as_str())
+            .match_body(mockito::Matcher::Json(
+                serde_json::to_value(&confirmed).unwrap(),
+            ))
+            .with_status(200)
+            .expect(1)
+            .create();
+
+        let registry = WebhookRegistry::default();
+        let url = format!("{}/watched", mockito::server_url());
+        registry.register(webhook(url, vec![Status::Confirmed]));
+
+        // Only the update to a watched status triggers a notification.
+        registry
+            .notify_deposit_updates(&[deposit(Status::Accepted), confirmed])
+            .await;
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn failed_deliveries_are_retried_a_bounded_number_of_times() {
+        let mock = mockito::mock("POST", "/failing")
+            .with_status(500)
+            .expect(MAX_DELIVERY_ATTEMPTS as usize)
+            .create();
+
+        let registry = WebhookRegistry::default();
+        let url = format!("{}/failing", mockito::server_url());
+        registry.register(webhook(url, vec![Status::Failed]));
+
+        registry
+            .notify_deposit_updates(&[deposit(Status::Failed)])
+            .await;
+
+        mock.assert();
+    }
+}
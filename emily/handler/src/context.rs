@@ -10,11 +10,17 @@ use std::fmt;
 
 use aws_config::BehaviorVersion;
 use aws_sdk_dynamodb::Client;
+use sbtc::deposits::MAX_DEPOSIT_SCRIPT_LENGTH;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::api::models::limits::AccountLimits;
 use crate::common::error::Error;
+use crate::common::webhooks::WebhookRegistry;
+
+/// The default maximum lock time, in blocks, of the reclaim script of a
+/// deposit. This is roughly one year worth of bitcoin blocks.
+pub const DEFAULT_MAX_RECLAIM_LOCK_TIME: u32 = 52_560;
 
 /// Emily lambda settings.
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
@@ -33,6 +39,12 @@ pub struct Settings {
     pub default_limits: AccountLimits,
     /// The API key for the Bitcoin Layer 2 API.
     pub trusted_reorg_api_key: String,
+    /// The maximum lock time, in blocks, of the reclaim script of a
+    /// deposit. Deposits with a larger lock time are rejected on creation.
+    pub max_reclaim_lock_time: u32,
+    /// The maximum length, in bytes, of the deposit script of a deposit.
+    /// Deposits with a longer deposit script are rejected on creation.
+    pub max_deposit_script_length: usize,
 }
 
 /// Emily Context
@@ -43,6 +55,9 @@ pub struct EmilyContext {
     /// DynamoDB Client.
     #[serde(skip_serializing)]
     pub dynamodb_client: Client,
+    /// Webhooks registered for deposit status changes.
+    #[serde(skip_serializing)]
+    pub webhooks: WebhookRegistry,
 }
 
 /// Implement debug print for the context struct.
@@ -84,6 +99,16 @@ impl Settings {
                     .transpose()?,
             },
             trusted_reorg_api_key: env::var("TRUSTED_REORG_API_KEY")?,
+            max_reclaim_lock_time: env::var("MAX_RECLAIM_LOCK_TIME")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?
+                .unwrap_or(DEFAULT_MAX_RECLAIM_LOCK_TIME),
+            max_deposit_script_length: env::var("MAX_DEPOSIT_SCRIPT_LENGTH")
+                .ok()
+                .map(|v| v.parse())
+                .transpose()?
+                .unwrap_or(MAX_DEPOSIT_SCRIPT_LENGTH),
         })
     }
 }
@@ -109,6 +134,7 @@ impl EmilyContext {
         Ok(EmilyContext {
             settings,
             dynamodb_client: Client::new(&config),
+            webhooks: WebhookRegistry::default(),
         })
     }
     /// Create a local testing instance.
@@ -170,8 +196,11 @@ impl EmilyContext {
                     .to_string(),
                 default_limits: AccountLimits::default(),
                 trusted_reorg_api_key: "testApiKey".to_string(),
+                max_reclaim_lock_time: DEFAULT_MAX_RECLAIM_LOCK_TIME,
+                max_deposit_script_length: MAX_DEPOSIT_SCRIPT_LENGTH,
             },
             dynamodb_client,
+            webhooks: WebhookRegistry::default(),
         })
     }
 }
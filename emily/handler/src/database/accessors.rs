@@ -1,6 +1,7 @@
 //! Accessors.
 
 use std::collections::HashMap;
+use std::time::SystemTime;
 
 use aws_sdk_dynamodb::types::AttributeValue;
 use serde_dynamo::Item;
@@ -10,7 +11,10 @@ use tracing::{debug, warn};
 use crate::api::models::limits::{AccountLimits, Limits};
 use crate::common::error::{Error, Inconsistency};
 
-use crate::{api::models::common::Status, context::EmilyContext};
+use crate::{
+    api::models::common::{SortDirection, Status},
+    context::EmilyContext,
+};
 
 use super::entries::deposit::ValidatedDepositUpdate;
 use super::entries::limits::{
@@ -61,20 +65,51 @@ pub async fn get_deposit_entry(
     Ok(entry)
 }
 
-/// Get deposit entries.
+/// Delete deposit entry, returning the entry that was deleted.
+pub async fn delete_deposit_entry(
+    context: &EmilyContext,
+    key: &DepositEntryKey,
+) -> Result<DepositEntry, Error> {
+    delete_entry_returning_old::<DepositTablePrimaryIndex>(context, key).await
+}
+
+/// Get deposit entries, along with the next and previous page tokens.
+///
+/// Entries that share an update height are ordered by bitcoin txid and
+/// then output index, see [`DepositInfoEntry::listing_order`], so that
+/// each page comes back in the same order on every fetch. Pages walked in
+/// ascending order come back in the reverse order.
+///
+/// The secondary index only sorts on the update height, so the tie break
+/// is applied to each page after it is queried. Entries that share an
+/// update height but land on different pages are in the order of the
+/// index, not necessarily the order of the tie break.
 pub async fn get_deposit_entries(
     context: &EmilyContext,
     status: &Status,
     maybe_next_token: Option<String>,
     maybe_page_size: Option<i32>,
-) -> Result<(Vec<DepositInfoEntry>, Option<String>), Error> {
-    query_with_partition_key::<DepositTableSecondaryIndex>(
-        context,
-        status,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
+    direction: SortDirection,
+) -> Result<(Vec<DepositInfoEntry>, Option<String>, Option<String>), Error> {
+    let (mut entries, next_token, prev_token) =
+        query_with_partition_key_in_direction::<DepositTableSecondaryIndex>(
+            context,
+            status,
+            maybe_next_token,
+            maybe_page_size,
+            direction,
+        )
+        .await?;
+    match direction {
+        SortDirection::Desc => entries.sort_by(DepositInfoEntry::listing_order),
+        SortDirection::Asc => entries.sort_by(|a, b| b.listing_order(a)),
+    }
+    Ok((entries, next_token, prev_token))
+}
+
+/// Counts the deposit entries with the given status.
+pub async fn count_deposit_entries(context: &EmilyContext, status: &Status) -> Result<u64, Error> {
+    count_with_partition_key::<DepositTableSecondaryIndex>(context, status).await
 }
 
 /// Hacky exhasutive list of all statuses that we will iterate over in order to
@@ -85,6 +120,9 @@ const ALL_STATUSES: &[Status] = &[
     Status::Failed,
     Status::Pending,
     Status::Reprocessing,
+    Status::Reclaimed,
+    Status::Expired,
+    Status::RejectedWrongKey,
 ];
 
 /// Gets all deposit entries modified from (on or after) a given height.
@@ -126,7 +164,8 @@ pub async fn get_all_deposit_entries_modified_from_height_with_status(
     .await
 }
 
-/// Get deposit entries for a given transaction.
+/// Get deposit entries for a given transaction, in ascending order of
+/// output index.
 #[allow(clippy::ptr_arg)]
 pub async fn get_deposit_entries_for_transaction(
     context: &EmilyContext,
@@ -134,13 +173,16 @@ pub async fn get_deposit_entries_for_transaction(
     maybe_next_token: Option<String>,
     maybe_page_size: Option<i32>,
 ) -> Result<(Vec<DepositEntry>, Option<String>), Error> {
-    query_with_partition_key::<DepositTablePrimaryIndex>(
-        context,
-        bitcoin_txid,
-        maybe_next_token,
-        maybe_page_size,
-    )
-    .await
+    let (entries, next_token, _) =
+        query_with_partition_key_in_direction::<DepositTablePrimaryIndex>(
+            context,
+            bitcoin_txid,
+            maybe_next_token,
+            maybe_page_size,
+            SortDirection::Asc,
+        )
+        .await?;
+    Ok((entries, next_token))
 }
 
 /// Pulls in a deposit entry and then updates it, retrying the specified number
@@ -161,7 +203,7 @@ pub async fn pull_and_update_deposit_with_retry(
         }
         // Make the update package.
         let update_package: DepositUpdatePackage =
-            DepositUpdatePackage::try_from(&deposit_entry, update.clone())?;
+            DepositUpdatePackage::try_from(&deposit_entry, update.clone(), SystemTime::now())?;
         // Attempt to update the deposit.
         match update_deposit(context, &update_package).await {
             Err(Error::VersionConflict) => {
@@ -188,7 +230,8 @@ pub async fn update_deposit(
         Version = Version + :one,
         OpStatus = :new_op_status,
         LastUpdateHeight = :new_height,
-        LastUpdateBlockHash = :new_hash
+        LastUpdateBlockHash = :new_hash,
+        SweptTime = :swept_time
     ";
     // Ensure the version field is what we expect it to be.
     let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
@@ -215,6 +258,10 @@ pub async fn update_deposit(
             ":new_event",
             serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
         )
+        .expression_attribute_values(
+            ":swept_time",
+            serde_dynamo::to_attribute_value(update.swept_time)?,
+        )
         .expression_attribute_values(
             ":expected_version",
             serde_dynamo::to_attribute_value(update.version)?,
@@ -284,18 +331,20 @@ pub async fn get_withdrawal_entry(
     }
 }
 
-/// Get withdrawal entries.
+/// Get withdrawal entries, along with the next and previous page tokens.
 pub async fn get_withdrawal_entries(
     context: &EmilyContext,
     status: &Status,
     maybe_next_token: Option<String>,
     maybe_page_size: Option<i32>,
-) -> Result<(Vec<WithdrawalInfoEntry>, Option<String>), Error> {
-    query_with_partition_key::<WithdrawalTableSecondaryIndex>(
+    direction: SortDirection,
+) -> Result<(Vec<WithdrawalInfoEntry>, Option<String>, Option<String>), Error> {
+    query_with_partition_key_in_direction::<WithdrawalTableSecondaryIndex>(
         context,
         status,
         maybe_next_token,
         maybe_page_size,
+        direction,
     )
     .await
 }
@@ -383,7 +432,9 @@ pub async fn update_withdrawal(
         Version = Version + :one,
         OpStatus = :new_op_status,
         LastUpdateHeight = :new_height,
-        LastUpdateBlockHash = :new_hash
+        LastUpdateBlockHash = :new_hash,
+        SignaturesCollected = :signatures_collected,
+        Threshold = :threshold
     ";
     // Ensure the version field is what we expect it to be.
     let condition_expression = "attribute_exists(Version) AND Version = :expected_version";
@@ -410,6 +461,14 @@ pub async fn update_withdrawal(
             ":new_event",
             serde_dynamo::to_attribute_value(vec![update.event.clone()])?,
         )
+        .expression_attribute_values(
+            ":signatures_collected",
+            serde_dynamo::to_attribute_value(update.signatures_collected)?,
+        )
+        .expression_attribute_values(
+            ":threshold",
+            serde_dynamo::to_attribute_value(update.threshold)?,
+        )
         .expression_attribute_values(
             ":expected_version",
             serde_dynamo::to_attribute_value(update.version)?,
@@ -454,18 +513,9 @@ pub async fn add_chainstate_entry(
     context: &EmilyContext,
     entry: &ChainstateEntry,
 ) -> Result<(), Error> {
-    // Get the current api state and give up if reorging.
+    // Get the current api state.
     let mut api_state = get_api_state(context).await?;
     debug!("Adding chainstate entry, current api state: {api_state:?}");
-    if let ApiStatus::Reorg(reorg_chaintip) = &api_state.api_status {
-        if reorg_chaintip != entry {
-            warn!("Attempting to update chainstate during a reorg [ new entry {entry:?} | reorg chaintip {reorg_chaintip:?} ]");
-            return Err(Error::InconsistentState(Inconsistency::ItemUpdate(
-                "Attempting to update chainstate during a reorg.".to_string(),
-            )));
-        }
-    }
-
     // Get the existing chainstate entry for height. If there's a conflict
     // then propagate it back to the caller.
     let current_chainstate_entry_result =
@@ -481,6 +531,26 @@ pub async fn add_chainstate_entry(
                 }
             });
 
+    // Re-submitting a chainstate entry that is already in the table and
+    // isn't ahead of the api chaintip changes nothing, so there is nothing
+    // to write.
+    if current_chainstate_entry_result.is_ok()
+        && api_state.chaintip().key.height >= entry.key.height
+    {
+        debug!("Chainstate entry is already stored: {entry:?}");
+        return Ok(());
+    }
+
+    // Give up if reorging.
+    if let ApiStatus::Reorg(reorg_chaintip) = &api_state.api_status {
+        if reorg_chaintip != entry {
+            warn!("Attempting to update chainstate during a reorg [ new entry {entry:?} | reorg chaintip {reorg_chaintip:?} ]");
+            return Err(Error::InconsistentState(Inconsistency::ItemUpdate(
+                "Attempting to update chainstate during a reorg.".to_string(),
+            )));
+        }
+    }
+
     match current_chainstate_entry_result {
         // Fall through if there is no existing entry..
         Err(Error::NotFound) => (),
@@ -610,6 +680,23 @@ pub async fn set_api_state(context: &EmilyContext, api_state: &ApiStateEntry) ->
     put_entry_with_version::<SpecialApiStateIndex>(context, &mut api_state.clone()).await
 }
 
+/// Gets the minimum number of confirmations that a deposit needs before
+/// the signers process it, if it has been set.
+pub async fn get_min_confirmations(context: &EmilyContext) -> Result<Option<u64>, Error> {
+    Ok(get_api_state(context).await?.min_confirmations)
+}
+
+/// Sets the minimum number of confirmations that a deposit needs before the
+/// signers process it. The value is kept alongside the API state.
+pub async fn set_min_confirmations(
+    context: &EmilyContext,
+    min_confirmations: Option<u64>,
+) -> Result<(), Error> {
+    let mut api_state = get_api_state(context).await?;
+    api_state.min_confirmations = min_confirmations;
+    set_api_state(context, &api_state).await
+}
+
 // Limits ----------------------------------------------------------------------
 
 /// Note, this function provides the direct output structure for the api call
@@ -781,6 +868,18 @@ async fn delete_entry<T: TableIndexTrait>(
     <T as TableIndexTrait>::delete_entry(&context.dynamodb_client, &context.settings, key).await
 }
 
+async fn delete_entry_returning_old<T: TableIndexTrait>(
+    context: &EmilyContext,
+    key: &<<T as TableIndexTrait>::Entry as EntryTrait>::Key,
+) -> Result<<T as TableIndexTrait>::Entry, Error> {
+    <T as TableIndexTrait>::delete_entry_returning_old(
+        &context.dynamodb_client,
+        &context.settings,
+        key,
+    )
+    .await
+}
+
 async fn query_with_partition_key<T: TableIndexTrait>(
     context: &EmilyContext,
     parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
@@ -797,6 +896,43 @@ async fn query_with_partition_key<T: TableIndexTrait>(
     .await
 }
 
+async fn query_with_partition_key_in_direction<T: TableIndexTrait>(
+    context: &EmilyContext,
+    parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    maybe_next_token: Option<String>,
+    maybe_page_size: Option<i32>,
+    direction: SortDirection,
+) -> Result<
+    (
+        Vec<<T as TableIndexTrait>::Entry>,
+        Option<String>,
+        Option<String>,
+    ),
+    Error,
+> {
+    <T as TableIndexTrait>::query_with_partition_key_in_direction(
+        &context.dynamodb_client,
+        &context.settings,
+        parition_key,
+        maybe_next_token,
+        maybe_page_size,
+        direction,
+    )
+    .await
+}
+
+async fn count_with_partition_key<T: TableIndexTrait>(
+    context: &EmilyContext,
+    parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+) -> Result<u64, Error> {
+    <T as TableIndexTrait>::count_with_partition_key(
+        &context.dynamodb_client,
+        &context.settings,
+        parition_key,
+    )
+    .await
+}
+
 async fn query_all_with_partition_and_sort_key<T: TableIndexTrait>(
     context: &EmilyContext,
     parition_key: &<<<T as TableIndexTrait>::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
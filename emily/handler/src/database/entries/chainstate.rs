@@ -158,6 +158,10 @@ pub struct ApiStateEntry {
     pub version: u64,
     /// Api Status.
     pub api_status: ApiStatus,
+    /// The minimum number of confirmations that a deposit needs before the
+    /// signers process it, if it has been set.
+    #[serde(default)]
+    pub min_confirmations: Option<u64>,
 }
 
 /// Api state entry implementation.
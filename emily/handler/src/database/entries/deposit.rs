@@ -1,8 +1,11 @@
 //! Entries into the deposit table.
 
+use std::cmp::Ordering;
 use std::collections::HashSet;
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
+use warp::http::StatusCode;
 
 use crate::{
     api::models::{
@@ -69,6 +72,13 @@ pub struct DepositEntry {
     pub fulfillment: Option<Fulfillment>,
     /// History of this deposit transaction.
     pub history: Vec<DepositEvent>,
+    /// Unix timestamp, in seconds, of when the API first received the deposit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_time: Option<u64>,
+    /// Unix timestamp, in seconds, of when the API first saw the deposit
+    /// confirmed as swept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swept_time: Option<u64>,
 }
 
 /// Implements versioned entry trait for the deposit entry.
@@ -152,6 +162,12 @@ impl DepositEntry {
         Ok(())
     }
 
+    /// The number of seconds between the API first receiving the deposit
+    /// and seeing it swept, if both are known.
+    pub fn seconds_to_sweep(&self) -> Option<u64> {
+        Some(self.swept_time?.saturating_sub(self.first_seen_time?))
+    }
+
     /// Gets the latest event.
     pub fn latest_event(&self) -> Result<&DepositEvent, Error> {
         self.history.last().ok_or(Error::Debug(format!(
@@ -178,6 +194,7 @@ impl DepositEntry {
                 message: "Reprocessing deposit status after reorg.".to_string(),
                 stacks_block_height: chainstate.stacks_block_height,
                 stacks_block_hash: chainstate.stacks_block_hash.clone(),
+                failure_reason: None,
             }]
         }
         // Synchronize self with the new history.
@@ -227,6 +244,7 @@ impl TryFrom<DepositEntry> for Deposit {
             StatusEntry::Confirmed(fulfillment) => Some(fulfillment.clone()),
             _ => None,
         };
+        let failure_reason = latest_event.failure_reason.clone();
 
         // Create deposit from table entry.
         Ok(Deposit {
@@ -245,6 +263,7 @@ impl TryFrom<DepositEntry> for Deposit {
             reclaim_script: deposit_entry.reclaim_script,
             deposit_script: deposit_entry.deposit_script,
             fulfillment,
+            failure_reason,
         })
     }
 }
@@ -273,6 +292,9 @@ pub struct DepositEvent {
     pub stacks_block_height: u64,
     /// Stacks block hash associated with the height of this update.
     pub stacks_block_hash: String,
+    /// Why the deposit failed, if this event marks it as failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
 }
 
 /// Implementation of deposit event.
@@ -351,6 +373,13 @@ pub struct DepositInfoEntry {
     /// updated. If the most recent update is tied to an artifact on the Stacks blockchain
     /// then this hash is the Stacks block hash that contains that artifact.
     pub last_update_block_hash: String,
+    /// Unix timestamp, in seconds, of when the API first received the deposit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen_time: Option<u64>,
+    /// Unix timestamp, in seconds, of when the API first saw the deposit
+    /// confirmed as swept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swept_time: Option<u64>,
 }
 
 /// Implements the key trait for the deposit entry key.
@@ -378,6 +407,34 @@ impl EntryTrait for DepositInfoEntry {
     }
 }
 
+impl DepositInfoEntry {
+    /// The number of seconds between the API first receiving the deposit
+    /// and seeing it swept, if both are known.
+    pub fn seconds_to_sweep(&self) -> Option<u64> {
+        Some(self.swept_time?.saturating_sub(self.first_seen_time?))
+    }
+
+    /// Compares two deposit info entries in the order they are listed:
+    /// most recently updated first, with ties on the update height broken
+    /// by the bitcoin txid and then the output index, both ascending.
+    pub fn listing_order(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .last_update_height
+            .cmp(&self.key.last_update_height)
+            .then_with(|| {
+                self.primary_index_key
+                    .bitcoin_txid
+                    .cmp(&other.primary_index_key.bitcoin_txid)
+            })
+            .then_with(|| {
+                self.primary_index_key
+                    .bitcoin_tx_output_index
+                    .cmp(&other.primary_index_key.bitcoin_tx_output_index)
+            })
+    }
+}
+
 /// Primary index struct.
 pub struct DepositTableSecondaryIndexInner;
 /// Deposit table primary index type.
@@ -495,13 +552,33 @@ impl TryFrom<DepositUpdate> for ValidatedDepositUpdate {
             Status::Pending => StatusEntry::Pending,
             Status::Reprocessing => StatusEntry::Reprocessing,
             Status::Failed => StatusEntry::Failed,
+            Status::Reclaimed => StatusEntry::Reclaimed,
+            Status::RejectedWrongKey => StatusEntry::RejectedWrongKey,
+            Status::Expired => {
+                return Err(Error::HttpRequest(
+                    StatusCode::BAD_REQUEST,
+                    "deposits cannot expire".to_string(),
+                ))
+            }
         };
+        // Only failed deposits have a reason for failing.
+        let failure_reason = update.failure_reason.filter(|reason| !reason.is_empty());
+        if failure_reason.is_some() && status_entry != StatusEntry::Failed {
+            return Err(Error::HttpRequest(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "A failure reason was given for deposit {}:{} with status {:?}, but it is only accepted for failed deposits",
+                    key.bitcoin_txid, key.bitcoin_tx_output_index, update.status,
+                ),
+            ));
+        }
         // Make the new event.
         let event = DepositEvent {
             status: status_entry,
             message: update.status_message,
             stacks_block_height: update.last_update_height,
             stacks_block_hash: update.last_update_block_hash,
+            failure_reason,
         };
         // Return the validated update.
         Ok(ValidatedDepositUpdate { key, event })
@@ -520,6 +597,39 @@ impl ValidatedDepositUpdate {
     }
 }
 
+/// Returns true if a deposit with the `from` status can be updated to
+/// have the `to` status.
+///
+/// Deposits move from `Pending` through `Accepted` to `Confirmed`, and
+/// can fail, be reclaimed or be rejected for a wrong key along the way.
+/// Confirmed deposits can only go back to `Accepted` or `Reprocessing`
+/// when the signers see a conflicting chainstate, and failed or rejected
+/// deposits can only be reprocessed. Deposits being reprocessed can take
+/// any status, while reclaimed deposits keep theirs. Keeping the same
+/// status is always allowed, and `Expired` only applies to withdrawals.
+pub fn can_transition(from: &Status, to: &Status) -> bool {
+    match (from, to) {
+        (Status::Expired, _) | (_, Status::Expired) => false,
+        (from, to) if from == to => true,
+        (Status::Reprocessing, _) => true,
+        (
+            Status::Pending,
+            Status::Accepted
+            | Status::Confirmed
+            | Status::Failed
+            | Status::Reclaimed
+            | Status::RejectedWrongKey,
+        ) => true,
+        (
+            Status::Accepted,
+            Status::Reprocessing | Status::Confirmed | Status::Failed | Status::Reclaimed,
+        ) => true,
+        (Status::Confirmed, Status::Reprocessing | Status::Accepted) => true,
+        (Status::Failed | Status::RejectedWrongKey, Status::Reprocessing) => true,
+        _ => false,
+    }
+}
+
 /// Packaged deposit update.
 pub struct DepositUpdatePackage {
     /// Key.
@@ -528,12 +638,20 @@ pub struct DepositUpdatePackage {
     pub version: u64,
     /// Deposit event.
     pub event: DepositEvent,
+    /// Unix timestamp, in seconds, of when the deposit was first seen
+    /// swept, after the update.
+    pub swept_time: Option<u64>,
 }
 
 /// Implementation of deposit update package.
 impl DepositUpdatePackage {
-    /// Implements from.
-    pub fn try_from(entry: &DepositEntry, update: ValidatedDepositUpdate) -> Result<Self, Error> {
+    /// Implements from, recording the given time as the sweep time if the
+    /// update is the first to confirm the deposit.
+    pub fn try_from(
+        entry: &DepositEntry,
+        update: ValidatedDepositUpdate,
+        now: SystemTime,
+    ) -> Result<Self, Error> {
         // Ensure the keys are equal.
         if update.key != entry.key {
             return Err(Error::Debug(
@@ -544,15 +662,37 @@ impl DepositUpdatePackage {
         entry
             .latest_event()?
             .ensure_following_event_is_valid(&update.event)?;
+        // Ensure that the deposit can move to the new status.
+        let status: Status = (&update.event.status).into();
+        if !can_transition(&entry.status, &status) {
+            return Err(Error::InvalidStatusTransition {
+                from: entry.status.clone(),
+                to: status,
+            });
+        }
+        // Record when the deposit was first swept.
+        let swept_time = match (&update.event.status, entry.swept_time) {
+            (StatusEntry::Confirmed(_), None) => Some(unix_timestamp(now)),
+            (_, swept_time) => swept_time,
+        };
         // Create the deposit update package.
         Ok(DepositUpdatePackage {
             key: entry.key.clone(),
             version: entry.version,
             event: update.event,
+            swept_time,
         })
     }
 }
 
+/// Convert the given time into a unix timestamp in seconds.
+pub fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        // It's impossible for this to fail.
+        .expect("Error making timestamp for deposit entry.")
+        .as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -564,6 +704,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 0,
             stacks_block_hash: "".to_string(),
+            failure_reason: None,
         };
 
         let accepted = DepositEvent {
@@ -571,6 +712,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "".to_string(),
+            failure_reason: None,
         };
 
         let deposit = DepositEntry {
@@ -586,6 +728,8 @@ mod tests {
             last_update_block_hash: "".to_string(),
             fulfillment: None,
             history: vec![pending, accepted.clone()],
+            first_seen_time: None,
+            swept_time: None,
         };
 
         let update = ValidatedDepositUpdate {
@@ -603,6 +747,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 0,
             stacks_block_hash: "".to_string(),
+            failure_reason: None,
         };
 
         let accepted = DepositEvent {
@@ -610,6 +755,7 @@ mod tests {
             message: "".to_string(),
             stacks_block_height: 1,
             stacks_block_hash: "".to_string(),
+            failure_reason: None,
         };
 
         let deposit = DepositEntry {
@@ -625,6 +771,8 @@ mod tests {
             last_update_block_hash: "".to_string(),
             fulfillment: None,
             history: vec![pending.clone()],
+            first_seen_time: None,
+            swept_time: None,
         };
 
         let update = ValidatedDepositUpdate {
@@ -634,4 +782,159 @@ mod tests {
 
         assert!(!update.is_unnecessary(&deposit));
     }
+
+    #[test]
+    fn listing_order_breaks_height_ties_on_txid_then_output_index() {
+        let info =
+            |bitcoin_txid: &str, bitcoin_tx_output_index: u32, height: u64| DepositInfoEntry {
+                key: DepositInfoEntryKey {
+                    status: Status::Pending,
+                    last_update_height: height,
+                },
+                primary_index_key: DepositEntryKey {
+                    bitcoin_txid: bitcoin_txid.to_string(),
+                    bitcoin_tx_output_index,
+                },
+                ..Default::default()
+            };
+
+        let mut entries = vec![
+            info("txid_b", 0, 5),
+            info("txid_a", 1, 5),
+            info("txid_c", 0, 6),
+            info("txid_a", 0, 5),
+        ];
+        entries.sort_by(DepositInfoEntry::listing_order);
+
+        let order: Vec<(&str, u32)> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.primary_index_key.bitcoin_txid.as_str(),
+                    entry.primary_index_key.bitcoin_tx_output_index,
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![("txid_c", 0), ("txid_a", 0), ("txid_a", 1), ("txid_b", 0)]
+        );
+    }
+
+    #[test]
+    fn update_package_records_the_first_sweep_time() {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+        let pending = DepositEvent {
+            status: StatusEntry::Pending,
+            stacks_block_height: 1,
+            ..Default::default()
+        };
+        let confirmed = DepositEvent {
+            status: StatusEntry::Confirmed(Fulfillment::default()),
+            stacks_block_height: 2,
+            ..Default::default()
+        };
+        let mut deposit = DepositEntry {
+            history: vec![pending],
+            first_seen_time: Some(400),
+            ..Default::default()
+        };
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            event: confirmed,
+        };
+
+        let package = DepositUpdatePackage::try_from(&deposit, update.clone(), now).unwrap();
+        assert_eq!(package.swept_time, Some(1_000));
+
+        // A later confirmation keeps the time of the first one.
+        deposit.swept_time = package.swept_time;
+        let later = now + std::time::Duration::from_secs(60);
+        let package = DepositUpdatePackage::try_from(&deposit, update, later).unwrap();
+        assert_eq!(package.swept_time, Some(1_000));
+        assert_eq!(deposit.seconds_to_sweep(), Some(600));
+    }
+
+    const ALL_STATUSES: [Status; 8] = [
+        Status::Pending,
+        Status::Reprocessing,
+        Status::Accepted,
+        Status::Confirmed,
+        Status::Failed,
+        Status::Reclaimed,
+        Status::Expired,
+        Status::RejectedWrongKey,
+    ];
+
+    #[test]
+    fn can_transition_covers_every_pair_of_statuses() {
+        let allowed = [
+            (Status::Pending, Status::Accepted),
+            (Status::Pending, Status::Confirmed),
+            (Status::Pending, Status::Failed),
+            (Status::Pending, Status::Reclaimed),
+            (Status::Pending, Status::RejectedWrongKey),
+            (Status::Reprocessing, Status::Pending),
+            (Status::Reprocessing, Status::Accepted),
+            (Status::Reprocessing, Status::Confirmed),
+            (Status::Reprocessing, Status::Failed),
+            (Status::Reprocessing, Status::Reclaimed),
+            (Status::Reprocessing, Status::RejectedWrongKey),
+            (Status::Accepted, Status::Reprocessing),
+            (Status::Accepted, Status::Confirmed),
+            (Status::Accepted, Status::Failed),
+            (Status::Accepted, Status::Reclaimed),
+            (Status::Confirmed, Status::Reprocessing),
+            (Status::Confirmed, Status::Accepted),
+            (Status::Failed, Status::Reprocessing),
+            (Status::RejectedWrongKey, Status::Reprocessing),
+        ];
+
+        for from in ALL_STATUSES.iter() {
+            for to in ALL_STATUSES.iter() {
+                let expected = (from == to && from != &Status::Expired)
+                    || allowed.contains(&(from.clone(), to.clone()));
+                assert_eq!(
+                    can_transition(from, to),
+                    expected,
+                    "unexpected result for {from:?} -> {to:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn update_package_rejects_invalid_status_transitions() {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+        let confirmed = DepositEvent {
+            status: StatusEntry::Confirmed(Fulfillment::default()),
+            stacks_block_height: 1,
+            ..Default::default()
+        };
+        let pending = DepositEvent {
+            status: StatusEntry::Pending,
+            stacks_block_height: 2,
+            ..Default::default()
+        };
+        let deposit = DepositEntry {
+            status: Status::Confirmed,
+            history: vec![confirmed],
+            ..Default::default()
+        };
+        let update = ValidatedDepositUpdate {
+            key: Default::default(),
+            event: pending,
+        };
+
+        let error = DepositUpdatePackage::try_from(&deposit, update, now)
+            .err()
+            .expect("a confirmed deposit was moved back to pending");
+        assert!(matches!(
+            error,
+            Error::InvalidStatusTransition {
+                from: Status::Confirmed,
+                to: Status::Pending,
+            }
+        ));
+    }
 }
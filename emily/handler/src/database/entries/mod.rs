@@ -47,6 +47,8 @@
 use std::{collections::HashMap, fmt::Debug};
 
 use aws_sdk_dynamodb::types::AttributeValue;
+use aws_sdk_dynamodb::types::ReturnValue;
+use aws_sdk_dynamodb::types::Select;
 #[cfg(feature = "testing")]
 use aws_sdk_dynamodb::types::{DeleteRequest, WriteRequest};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -54,7 +56,7 @@ use serde::{Deserialize, Serialize};
 use serde_dynamo::Item;
 
 use crate::{
-    api::models::common::{Fulfillment, Status},
+    api::models::common::{Fulfillment, SortDirection, Status},
     common::error::Error,
     context::Settings,
 };
@@ -98,6 +100,16 @@ pub enum StatusEntry {
     Confirmed(Fulfillment),
     /// The operation was not fulfilled.
     Failed,
+    /// The deposit was reclaimed by the depositor on the Bitcoin blockchain,
+    /// so it can no longer be fulfilled. This only applies to deposits.
+    Reclaimed,
+    /// The withdrawal was rejected by the signers because it was still
+    /// pending at the end of their expiry window, so it is refunded. This
+    /// only applies to withdrawals.
+    Expired,
+    /// The deposit is locked to a key that the signers no longer use, so
+    /// it will not be processed. This only applies to deposits.
+    RejectedWrongKey,
 }
 
 impl From<&StatusEntry> for Status {
@@ -108,6 +120,9 @@ impl From<&StatusEntry> for Status {
             StatusEntry::Accepted => Status::Accepted,
             StatusEntry::Confirmed(_) => Status::Confirmed,
             StatusEntry::Failed => Status::Failed,
+            StatusEntry::Reclaimed => Status::Reclaimed,
+            StatusEntry::Expired => Status::Expired,
+            StatusEntry::RejectedWrongKey => Status::RejectedWrongKey,
         }
     }
 }
@@ -243,6 +258,35 @@ pub(crate) trait TableIndexTrait {
         maybe_next_token: Option<String>,
         maybe_page_size: Option<i32>,
     ) -> Result<(Vec<Self::Entry>, Option<String>), Error> {
+        let (entries, next_token, _) = Self::query_with_partition_key_in_direction(
+            dynamodb_client,
+            settings,
+            partition_key,
+            maybe_next_token,
+            maybe_page_size,
+            SortDirection::Desc,
+        )
+        .await?;
+        Ok((entries, next_token))
+    }
+
+    /// Generic table query for all attributes with a given primary key,
+    /// walking the sort key in the given direction.
+    ///
+    /// Along with the next token, this returns a previous token when the
+    /// query did not start at the beginning of the results. Querying with
+    /// the previous token in the opposite direction returns the entries
+    /// that precede this page.
+    async fn query_with_partition_key_in_direction(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        partition_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+        maybe_next_token: Option<String>,
+        maybe_page_size: Option<i32>,
+        direction: SortDirection,
+    ) -> Result<(Vec<Self::Entry>, Option<String>, Option<String>), Error> {
+        // Only pages that don't start at the beginning have a previous page.
+        let is_first_page = maybe_next_token.is_none();
         // Convert inputs into the types needed for querying.
         let exclusive_start_key =
             maybe_exclusive_start_key_from_next_token::<Self::SearchToken>(maybe_next_token)?;
@@ -259,17 +303,55 @@ pub(crate) trait TableIndexTrait {
                 <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
             )
             .expression_attribute_values(":v", serde_dynamo::to_attribute_value(partition_key)?)
-            .scan_index_forward(false)
+            .scan_index_forward(direction == SortDirection::Asc)
             .send()
             .await?;
+        let items = query_output.items.unwrap_or_default();
+        // The first item of the page is where the previous page ends.
+        let first_item = items.first().filter(|_| !is_first_page).cloned();
+        let prev_token = maybe_next_token_from_last_evaluated_key::<Self::SearchToken>(first_item)?;
         // Convert data into output format.
-        let entries: Vec<Self::Entry> =
-            serde_dynamo::from_items(query_output.items.unwrap_or_default())?;
+        let entries: Vec<Self::Entry> = serde_dynamo::from_items(items)?;
         let next_token = maybe_next_token_from_last_evaluated_key::<Self::SearchToken>(
             query_output.last_evaluated_key,
         )?;
         // Return.
-        Ok((entries, next_token))
+        Ok((entries, next_token, prev_token))
+    }
+
+    /// Generic table query counting all entries with a given primary key.
+    async fn count_with_partition_key(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        partition_key: &<<Self::Entry as EntryTrait>::Key as KeyTrait>::PartitionKey,
+    ) -> Result<u64, Error> {
+        let mut total: u64 = 0;
+        let mut exclusive_start_key: Option<HashMap<String, AttributeValue>> = None;
+        // Count the entries page by page, since a single query only counts
+        // up to one megabyte of data.
+        loop {
+            let query_output = dynamodb_client
+                .query()
+                .table_name(Self::table_name(settings))
+                .set_index_name(Self::INDEX_NAME_IF_GSI.map(|s| s.to_string()))
+                .set_exclusive_start_key(exclusive_start_key)
+                .select(Select::Count)
+                .key_condition_expression("#pk = :v")
+                .expression_attribute_names(
+                    "#pk",
+                    <<Self::Entry as EntryTrait>::Key as KeyTrait>::PARTITION_KEY_NAME,
+                )
+                .expression_attribute_values(":v", serde_dynamo::to_attribute_value(partition_key)?)
+                .send()
+                .await?;
+            total += query_output.count() as u64;
+            exclusive_start_key = query_output.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+        // Return.
+        Ok(total)
     }
 
     /// Generic table query for all attributes with a given primary key.
@@ -392,6 +474,34 @@ pub(crate) trait TableIndexTrait {
         Ok(())
     }
 
+    /// Generic delete of the entry with the given key, returning the entry
+    /// that was deleted. The entry is read and deleted in a single request,
+    /// so a concurrent write cannot land between the two.
+    async fn delete_entry_returning_old(
+        dynamodb_client: &aws_sdk_dynamodb::Client,
+        settings: &Settings,
+        key: &<Self::Entry as EntryTrait>::Key,
+    ) -> Result<Self::Entry, Error> {
+        // Get table name.
+        let table_name = Self::table_name(settings);
+        // Convert Entry into the type needed for querying.
+        let key_item: Item = serde_dynamo::to_item(key)?;
+        // Delete from the database, asking for the deleted item back.
+        let delete_item_output = dynamodb_client
+            .delete_item()
+            .table_name(table_name)
+            .set_key(Some(key_item.into()))
+            .return_values(ReturnValue::AllOld)
+            .send()
+            .await?;
+        // There are no old attributes when there was no entry to delete.
+        let item = delete_item_output.attributes.ok_or(Error::NotFound)?;
+        // Convert item into entry.
+        let entry = serde_dynamo::from_item(item)?;
+        // Return.
+        Ok(entry)
+    }
+
     /// Deletes every entry in a table with the specified keys.
     #[cfg(feature = "testing")]
     async fn delete_entries(
@@ -64,6 +64,12 @@ pub struct WithdrawalEntry {
     pub last_update_block_hash: String,
     /// History of this withdrawal transaction.
     pub history: Vec<WithdrawalEvent>,
+    /// The number of signer signatures collected so far for the withdrawal.
+    #[serde(default)]
+    pub signatures_collected: u32,
+    /// The number of signer signatures required for the withdrawal.
+    #[serde(default)]
+    pub threshold: u32,
 }
 
 /// Implements versioned entry trait for the withdrawal entry.
@@ -201,6 +207,8 @@ impl TryFrom<WithdrawalEntry> for Withdrawal {
                 max_fee: withdrawal_entry.parameters.max_fee,
             },
             fulfillment,
+            signatures_collected: withdrawal_entry.signatures_collected,
+            threshold: withdrawal_entry.threshold,
         })
     }
 }
@@ -250,6 +258,14 @@ impl WithdrawalEvent {
                     .into(),
             )));
         }
+        // Only withdrawals that the signers have not started fulfilling can
+        // expire, an accepted withdrawal may already be getting swept.
+        if next_event.status == StatusEntry::Expired && self.status != StatusEntry::Pending {
+            return Err(Error::HttpRequest(
+                reqwest::StatusCode::BAD_REQUEST,
+                "only pending withdrawals can expire".to_string(),
+            ));
+        }
 
         Ok(())
     }
@@ -462,11 +478,22 @@ pub struct ValidatedWithdrawalUpdate {
     pub request_id: u64,
     /// Withdrawal event.
     pub event: WithdrawalEvent,
+    /// The new number of signer signatures collected, if it changed.
+    pub signatures_collected: Option<u32>,
+    /// The new number of signer signatures required, if it changed.
+    pub threshold: Option<u32>,
 }
 
 impl TryFrom<WithdrawalUpdate> for ValidatedWithdrawalUpdate {
     type Error = Error;
     fn try_from(update: WithdrawalUpdate) -> Result<Self, Self::Error> {
+        // Fulfillment data only describes confirmed withdrawals.
+        if update.status != Status::Confirmed && update.fulfillment.is_some() {
+            return Err(Error::HttpRequest(
+                reqwest::StatusCode::BAD_REQUEST,
+                "only confirmed withdrawals can have fulfillment data".to_string(),
+            ));
+        }
         // Make status entry.
         let status_entry: StatusEntry = match update.status {
             Status::Confirmed => {
@@ -477,6 +504,19 @@ impl TryFrom<WithdrawalUpdate> for ValidatedWithdrawalUpdate {
             Status::Pending => StatusEntry::Pending,
             Status::Reprocessing => StatusEntry::Reprocessing,
             Status::Failed => StatusEntry::Failed,
+            Status::Reclaimed => {
+                return Err(Error::HttpRequest(
+                    reqwest::StatusCode::BAD_REQUEST,
+                    "withdrawals cannot be reclaimed".to_string(),
+                ))
+            }
+            Status::Expired => StatusEntry::Expired,
+            Status::RejectedWrongKey => {
+                return Err(Error::HttpRequest(
+                    reqwest::StatusCode::BAD_REQUEST,
+                    "withdrawals cannot be rejected for a wrong key".to_string(),
+                ))
+            }
         };
         // Make the new event.
         let event = WithdrawalEvent {
@@ -489,6 +529,8 @@ impl TryFrom<WithdrawalUpdate> for ValidatedWithdrawalUpdate {
         Ok(ValidatedWithdrawalUpdate {
             request_id: update.request_id,
             event,
+            signatures_collected: update.signatures_collected,
+            threshold: update.threshold,
         })
     }
 }
@@ -496,12 +538,20 @@ impl TryFrom<WithdrawalUpdate> for ValidatedWithdrawalUpdate {
 impl ValidatedWithdrawalUpdate {
     /// Returns true if the update is not necessary.
     pub fn is_unnecessary(&self, entry: &WithdrawalEntry) -> bool {
-        entry
-            .history
-            .iter()
-            .rev()
-            .take_while(|event| event.stacks_block_height >= self.event.stacks_block_height)
-            .any(|event| event == &self.event)
+        let signatures_unchanged = self
+            .signatures_collected
+            .map_or(true, |count| count == entry.signatures_collected)
+            && self
+                .threshold
+                .map_or(true, |threshold| threshold == entry.threshold);
+
+        signatures_unchanged
+            && entry
+                .history
+                .iter()
+                .rev()
+                .take_while(|event| event.stacks_block_height >= self.event.stacks_block_height)
+                .any(|event| event == &self.event)
     }
 }
 
@@ -513,6 +563,10 @@ pub struct WithdrawalUpdatePackage {
     pub version: u64,
     /// Withdrawal event.
     pub event: WithdrawalEvent,
+    /// The number of signer signatures collected after the update.
+    pub signatures_collected: u32,
+    /// The number of signer signatures required after the update.
+    pub threshold: u32,
 }
 
 /// Implementation of withdrawal update package.
@@ -532,23 +586,42 @@ impl WithdrawalUpdatePackage {
         entry
             .latest_event()?
             .ensure_following_event_is_valid(&update.event)?;
+        // Signatures are collected toward the threshold, so there can
+        // never be more of them than the threshold asks for.
+        let signatures_collected = update
+            .signatures_collected
+            .unwrap_or(entry.signatures_collected);
+        let threshold = update.threshold.unwrap_or(entry.threshold);
+        if signatures_collected > threshold {
+            return Err(Error::HttpRequest(
+                reqwest::StatusCode::BAD_REQUEST,
+                format!(
+                    "a withdrawal cannot have {signatures_collected} signatures collected toward a threshold of {threshold}"
+                ),
+            ));
+        }
         // Create the withdrawal update package.
         Ok(WithdrawalUpdatePackage {
             key: entry.key.clone(),
             version: entry.version,
             event: update.event,
+            signatures_collected,
+            threshold,
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
     use crate::database::entries::StatusEntry;
     use crate::{
-        api::models::common::Status,
+        api::models::common::{Fulfillment, Status},
+        api::models::withdrawal::requests::WithdrawalUpdate,
         database::entries::withdrawal::{
             ValidatedWithdrawalUpdate, WithdrawalEntry, WithdrawalEntryKey, WithdrawalEvent,
-            WithdrawalParametersEntry,
+            WithdrawalParametersEntry, WithdrawalUpdatePackage,
         },
     };
 
@@ -583,9 +656,16 @@ mod tests {
             last_update_height: 1,
             last_update_block_hash: "hash".to_string(),
             history: vec![pending, failed.clone()],
+            signatures_collected: 0,
+            threshold: 0,
         };
 
-        let withdrawal_update = ValidatedWithdrawalUpdate { request_id: 1, event: failed };
+        let withdrawal_update = ValidatedWithdrawalUpdate {
+            request_id: 1,
+            event: failed,
+            signatures_collected: None,
+            threshold: None,
+        };
 
         // Act
         let is_unnecessary = withdrawal_update.is_unnecessary(&withdrawal_entry);
@@ -625,9 +705,16 @@ mod tests {
             last_update_height: 1,
             last_update_block_hash: "hash".to_string(),
             history: vec![pending.clone()],
+            signatures_collected: 0,
+            threshold: 0,
         };
 
-        let withdrawal_update = ValidatedWithdrawalUpdate { request_id: 1, event: failed };
+        let withdrawal_update = ValidatedWithdrawalUpdate {
+            request_id: 1,
+            event: failed,
+            signatures_collected: None,
+            threshold: None,
+        };
 
         // Act
         let is_unnecessary = withdrawal_update.is_unnecessary(&withdrawal_entry);
@@ -635,4 +722,96 @@ mod tests {
         // Assert
         assert!(!is_unnecessary);
     }
+
+    #[test_case(StatusEntry::Pending, true; "pending")]
+    #[test_case(StatusEntry::Accepted, false; "accepted")]
+    #[test_case(StatusEntry::Reprocessing, false; "reprocessing")]
+    #[test_case(StatusEntry::Failed, false; "failed")]
+    fn only_pending_withdrawals_can_expire(status: StatusEntry, can_expire: bool) {
+        // Arrange
+        let current = WithdrawalEvent {
+            status,
+            message: "message".to_string(),
+            stacks_block_height: 1,
+            stacks_block_hash: "hash".to_string(),
+        };
+
+        let expired = WithdrawalEvent {
+            status: StatusEntry::Expired,
+            message: "message".to_string(),
+            stacks_block_height: 2,
+            stacks_block_hash: "hash".to_string(),
+        };
+
+        // Act
+        let result = current.ensure_following_event_is_valid(&expired);
+
+        // Assert
+        assert_eq!(result.is_ok(), can_expire);
+    }
+
+    #[test_case(Status::Confirmed, true; "confirmed")]
+    #[test_case(Status::Accepted, false; "accepted")]
+    #[test_case(Status::Pending, false; "pending")]
+    #[test_case(Status::Failed, false; "failed")]
+    fn only_confirmed_withdrawal_updates_accept_fulfillment(status: Status, is_valid: bool) {
+        // Arrange
+        let update = WithdrawalUpdate {
+            request_id: 1,
+            status,
+            fulfillment: Some(Fulfillment::default()),
+            ..Default::default()
+        };
+
+        // Act
+        let result = ValidatedWithdrawalUpdate::try_from(update);
+
+        // Assert
+        assert_eq!(result.is_ok(), is_valid);
+    }
+
+    #[test_case(Some(3), None, true; "up to the threshold")]
+    #[test_case(Some(4), None, false; "past the threshold")]
+    #[test_case(None, Some(0), false; "threshold below the collected signatures")]
+    #[test_case(Some(4), Some(5), true; "raised threshold")]
+    fn withdrawal_updates_cannot_collect_more_signatures_than_the_threshold(
+        signatures_collected: Option<u32>,
+        threshold: Option<u32>,
+        is_valid: bool,
+    ) {
+        // Arrange
+        let pending = WithdrawalEvent {
+            status: StatusEntry::Pending,
+            message: "message".to_string(),
+            stacks_block_height: 1,
+            stacks_block_hash: "hash".to_string(),
+        };
+        let entry = WithdrawalEntry {
+            key: WithdrawalEntryKey {
+                request_id: 1,
+                stacks_block_hash: "hash".to_string(),
+            },
+            history: vec![pending],
+            signatures_collected: 1,
+            threshold: 3,
+            ..Default::default()
+        };
+        let update = ValidatedWithdrawalUpdate {
+            request_id: 1,
+            event: WithdrawalEvent {
+                status: StatusEntry::Accepted,
+                message: "message".to_string(),
+                stacks_block_height: 2,
+                stacks_block_hash: "hash".to_string(),
+            },
+            signatures_collected,
+            threshold,
+        };
+
+        // Act
+        let result = WithdrawalUpdatePackage::try_from(&entry, update);
+
+        // Assert
+        assert_eq!(result.is_ok(), is_valid);
+    }
 }
@@ -0,0 +1,203 @@
+//! A storage abstraction over the basic deposit, withdrawal and
+//! chainstate operations.
+//!
+//! The [`Store`] trait only covers adding and getting single entries, and
+//! the handlers that only need those go through it. The queries, paginated
+//! listings and conditional updates are DynamoDB specific, so the rest of
+//! the handlers still use the [`accessors`] module directly, and
+//! [`EmilyContext`] is not generic over the store. Code that only needs
+//! the operations here should take a [`Store`], so that it can be tested
+//! against an [`InMemoryStore`] without a running database.
+
+#[cfg(feature = "testing")]
+use std::collections::HashMap;
+use std::future::Future;
+#[cfg(feature = "testing")]
+use std::sync::Mutex;
+
+use crate::common::error::Error;
+use crate::context::EmilyContext;
+use crate::database::accessors;
+use crate::database::entries::chainstate::ChainstateEntry;
+use crate::database::entries::deposit::{DepositEntry, DepositEntryKey};
+use crate::database::entries::withdrawal::WithdrawalEntry;
+
+/// The deposit, withdrawal and chainstate operations of a storage backend.
+pub trait Store: Send + Sync {
+    /// Adds a deposit entry.
+    fn add_deposit_entry(
+        &self,
+        entry: &DepositEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Gets a deposit entry.
+    fn get_deposit_entry(
+        &self,
+        key: &DepositEntryKey,
+    ) -> impl Future<Output = Result<DepositEntry, Error>> + Send;
+
+    /// Adds a withdrawal entry.
+    fn add_withdrawal_entry(
+        &self,
+        entry: &WithdrawalEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Gets a withdrawal entry by its request id.
+    fn get_withdrawal_entry(
+        &self,
+        request_id: &u64,
+    ) -> impl Future<Output = Result<WithdrawalEntry, Error>> + Send;
+
+    /// Adds a chainstate entry.
+    fn add_chainstate_entry(
+        &self,
+        entry: &ChainstateEntry,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Gets the chainstate entry at the given height.
+    fn get_chainstate_entry_at_height(
+        &self,
+        height: &u64,
+    ) -> impl Future<Output = Result<ChainstateEntry, Error>> + Send;
+}
+
+/// The DynamoDB backed store.
+impl Store for EmilyContext {
+    async fn add_deposit_entry(&self, entry: &DepositEntry) -> Result<(), Error> {
+        accessors::add_deposit_entry(self, entry).await
+    }
+
+    async fn get_deposit_entry(&self, key: &DepositEntryKey) -> Result<DepositEntry, Error> {
+        accessors::get_deposit_entry(self, key).await
+    }
+
+    async fn add_withdrawal_entry(&self, entry: &WithdrawalEntry) -> Result<(), Error> {
+        accessors::add_withdrawal_entry(self, entry).await
+    }
+
+    async fn get_withdrawal_entry(&self, request_id: &u64) -> Result<WithdrawalEntry, Error> {
+        accessors::get_withdrawal_entry(self, request_id).await
+    }
+
+    async fn add_chainstate_entry(&self, entry: &ChainstateEntry) -> Result<(), Error> {
+        accessors::add_chainstate_entry(self, entry).await
+    }
+
+    async fn get_chainstate_entry_at_height(&self, height: &u64) -> Result<ChainstateEntry, Error> {
+        accessors::get_chainstate_entry_at_height(self, height).await
+    }
+}
+
+/// A store that keeps its entries in memory, for tests.
+#[cfg(feature = "testing")]
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    deposits: Mutex<HashMap<DepositEntryKey, DepositEntry>>,
+    withdrawals: Mutex<HashMap<u64, WithdrawalEntry>>,
+    chainstates: Mutex<HashMap<u64, ChainstateEntry>>,
+}
+
+#[cfg(feature = "testing")]
+impl Store for InMemoryStore {
+    async fn add_deposit_entry(&self, entry: &DepositEntry) -> Result<(), Error> {
+        let mut deposits = self
+            .deposits
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        deposits.insert(entry.key.clone(), entry.clone());
+        Ok(())
+    }
+
+    async fn get_deposit_entry(&self, key: &DepositEntryKey) -> Result<DepositEntry, Error> {
+        let deposits = self
+            .deposits
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        deposits.get(key).cloned().ok_or(Error::NotFound)
+    }
+
+    async fn add_withdrawal_entry(&self, entry: &WithdrawalEntry) -> Result<(), Error> {
+        let mut withdrawals = self
+            .withdrawals
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        withdrawals.insert(entry.key.request_id, entry.clone());
+        Ok(())
+    }
+
+    async fn get_withdrawal_entry(&self, request_id: &u64) -> Result<WithdrawalEntry, Error> {
+        let withdrawals = self
+            .withdrawals
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        withdrawals.get(request_id).cloned().ok_or(Error::NotFound)
+    }
+
+    async fn add_chainstate_entry(&self, entry: &ChainstateEntry) -> Result<(), Error> {
+        let mut chainstates = self
+            .chainstates
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        chainstates.insert(entry.key.height, entry.clone());
+        Ok(())
+    }
+
+    async fn get_chainstate_entry_at_height(&self, height: &u64) -> Result<ChainstateEntry, Error> {
+        let chainstates = self
+            .chainstates
+            .lock()
+            .expect("Failed to acquire the in-memory store lock.");
+        chainstates.get(height).cloned().ok_or(Error::NotFound)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use crate::database::entries::chainstate::ChainstateEntryKey;
+    use crate::database::entries::withdrawal::WithdrawalEntryKey;
+
+    use super::*;
+
+    /// Write one entry of each kind to the store and read them back.
+    async fn round_trip<S: Store>(store: &S) {
+        let deposit = DepositEntry {
+            key: DepositEntryKey {
+                bitcoin_txid: "bitcoin_txid".to_string(),
+                bitcoin_tx_output_index: 1,
+            },
+            ..Default::default()
+        };
+        store.add_deposit_entry(&deposit).await.unwrap();
+        let stored = store.get_deposit_entry(&deposit.key).await.unwrap();
+        assert_eq!(stored, deposit);
+
+        let withdrawal = WithdrawalEntry {
+            key: WithdrawalEntryKey {
+                request_id: 2,
+                stacks_block_hash: "stacks_block_hash".to_string(),
+            },
+            ..Default::default()
+        };
+        store.add_withdrawal_entry(&withdrawal).await.unwrap();
+        let stored = store.get_withdrawal_entry(&2).await.unwrap();
+        assert_eq!(stored, withdrawal);
+
+        let chainstate = ChainstateEntry {
+            key: ChainstateEntryKey {
+                hash: "stacks_block_hash".to_string(),
+                height: 3,
+            },
+        };
+        store.add_chainstate_entry(&chainstate).await.unwrap();
+        let stored = store.get_chainstate_entry_at_height(&3).await.unwrap();
+        assert_eq!(stored, chainstate);
+
+        let missing = store.get_chainstate_entry_at_height(&4).await;
+        assert!(matches!(missing, Err(Error::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_implements_the_store_operations() {
+        round_trip(&InMemoryStore::default()).await;
+    }
+}
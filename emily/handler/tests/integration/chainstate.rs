@@ -3,8 +3,9 @@ use std::cmp::Ordering;
 use emily_client::apis;
 use emily_client::apis::configuration::Configuration;
 use emily_client::models::Chainstate;
+use emily_client::models::MinConfirmations;
 
-use crate::common::clean_setup;
+use crate::common::{clean_setup, is_canonical_chainstate, StandardError, TestContextBuilder};
 use test_case::test_case;
 
 /// Make a test chainstate.
@@ -177,3 +178,253 @@ async fn create_and_replay_does_not_initiate_reorg(min_height: u64, max_height:
     assert_eq!(expected_chainstates, gotten_chainstates);
     assert_eq!(expected_chaintip, gotten_chaintip)
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn stored_chainstates_are_canonical() {
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (1123..1128)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+    let context = TestContextBuilder::new()
+        .with_chainstates(chainstates.clone())
+        .build()
+        .await;
+
+    let matching = new_test_chainstate(1125, 0);
+    let conflicting = new_test_chainstate(1125, 1);
+    let unknown = new_test_chainstate(2000, 0);
+
+    // Act & Assert.
+    // -------------
+    assert!(is_canonical_chainstate(&context.configuration, &matching).await);
+    assert!(!is_canonical_chainstate(&context.configuration, &conflicting).await);
+    assert!(!is_canonical_chainstate(&context.configuration, &unknown).await);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn resubmitting_a_chainstate_is_a_no_op() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (1123..1128)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+    batch_set_chainstates(&configuration, chainstates.clone()).await;
+
+    let resubmitted = new_test_chainstate(1127, 0);
+    let conflicting = new_test_chainstate(1127, 1);
+
+    // Act.
+    // --------
+    let first = apis::chainstate_api::update_chainstate(&configuration, resubmitted.clone())
+        .await
+        .expect("Received an error after resubmitting a stored chainstate.");
+    let second = apis::chainstate_api::update_chainstate(&configuration, resubmitted.clone())
+        .await
+        .expect("Received an error after resubmitting a stored chainstate.");
+
+    // Getting the chainstate at a height fails if there is more than one
+    // entry at that height, so this also checks for duplicates.
+    let gotten_chainstate = apis::chainstate_api::get_chainstate_at_height(&configuration, 1127)
+        .await
+        .expect("Received an error after making a valid get chainstate at height api call.");
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    apis::chainstate_api::update_chainstate(&configuration, conflicting.clone())
+        .await
+        .expect("Received an error after submitting a conflicting chainstate.");
+    let post_conflict_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(first, resubmitted);
+    assert_eq!(second, resubmitted);
+    assert_eq!(gotten_chainstate, resubmitted);
+    assert_eq!(gotten_chaintip, resubmitted);
+    // The conflicting hash is detected and reorgs the chain onto it.
+    assert_eq!(post_conflict_chaintip, conflicting);
+    assert!(!is_canonical_chainstate(&configuration, &resubmitted).await);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn create_chainstate_batch_sets_every_chainstate() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let expected_chainstates: Vec<Chainstate> = (1123..1133)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+    let expected_chaintip = new_test_chainstate(1132, 0);
+
+    // Act.
+    // --------
+    let created_chainstates =
+        apis::chainstate_api::set_chainstate_batch(&configuration, expected_chainstates.clone())
+            .await
+            .expect("Received an error after making a valid chainstate batch api call.");
+
+    let mut gotten_chainstates: Vec<Chainstate> = Vec::new();
+    for chainstate in expected_chainstates.iter() {
+        let gotten_chainstate = apis::chainstate_api::get_chainstate_at_height(
+            &configuration,
+            chainstate.stacks_block_height,
+        )
+        .await
+        .expect("Received an error after making a valid get chainstate at height api call.");
+        gotten_chainstates.push(gotten_chainstate);
+    }
+
+    let gotten_chaintip = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(expected_chainstates, created_chainstates);
+    assert_eq!(expected_chainstates, gotten_chainstates);
+    assert_eq!(expected_chaintip, gotten_chaintip);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn conflicting_chainstate_batch_sets_nothing() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    // The batch has two different chainstates at the same height.
+    let mut chainstates: Vec<Chainstate> = (1123..1128)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+    chainstates.insert(3, new_test_chainstate(1125, 1));
+
+    // Act.
+    // --------
+    let error: StandardError =
+        apis::chainstate_api::set_chainstate_batch(&configuration, chainstates.clone())
+            .await
+            .expect_err("Received a success after making a conflicting chainstate batch api call.")
+            .into();
+
+    // Assert.
+    // --------
+    assert_eq!(error.status_code, 409);
+    for chainstate in chainstates.iter() {
+        assert!(!is_canonical_chainstate(&configuration, chainstate).await);
+    }
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn chainstate_batch_reorgs_onto_a_single_fork() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let original_chainstates: Vec<Chainstate> = (1123..1133)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+    // The fork shares the chainstates up to 1127 with the original chain
+    // and replaces everything above it.
+    let fork: Vec<Chainstate> = (1125..1128)
+        .map(|height| new_test_chainstate(height, 0))
+        .chain((1128..1136).map(|height| new_test_chainstate(height, 1)))
+        .collect();
+    // This batch leaves the original chain at 1128 and comes back to it at
+    // 1129, so it is not a single chain.
+    let mut interleaved = fork.clone();
+    interleaved[4] = new_test_chainstate(1129, 0);
+
+    // Act.
+    // --------
+    batch_set_chainstates(&configuration, original_chainstates.clone()).await;
+    let error: StandardError =
+        apis::chainstate_api::set_chainstate_batch(&configuration, interleaved.clone())
+            .await
+            .expect_err("Received a success after making an interleaved chainstate batch api call.")
+            .into();
+    let chaintip_after_error = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    apis::chainstate_api::set_chainstate_batch(&configuration, fork.clone())
+        .await
+        .expect("Received an error after making a valid chainstate batch api call.");
+    let chaintip_after_fork = apis::chainstate_api::get_chain_tip(&configuration)
+        .await
+        .expect("Received an error after making a valid get chaintip api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(error.status_code, 409);
+    assert_eq!(chaintip_after_error, new_test_chainstate(1132, 0));
+    assert_eq!(chaintip_after_fork, new_test_chainstate(1135, 1));
+    for chainstate in fork.iter() {
+        assert!(is_canonical_chainstate(&configuration, chainstate).await);
+    }
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn oversized_chainstate_batch_sets_nothing() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let chainstates: Vec<Chainstate> = (1123..1224)
+        .map(|height| new_test_chainstate(height, 0))
+        .collect();
+
+    // Act.
+    // --------
+    let error: StandardError =
+        apis::chainstate_api::set_chainstate_batch(&configuration, chainstates.clone())
+            .await
+            .expect_err("Received a success after making an oversized chainstate batch api call.")
+            .into();
+
+    // Assert.
+    // --------
+    assert_eq!(error.status_code, 400);
+    assert!(!is_canonical_chainstate(&configuration, &chainstates[0]).await);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn set_and_get_min_confirmations() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let unset = apis::chainstate_api::get_min_confirmations(&configuration)
+        .await
+        .expect("Received an error after making a valid get min confirmations api call.");
+    let request = MinConfirmations {
+        min_confirmations: Some(Some(3)),
+    };
+
+    // Act.
+    // --------
+    let set = apis::chainstate_api::set_min_confirmations(&configuration, request.clone())
+        .await
+        .expect("Received an error after making a valid set min confirmations api call.");
+    let gotten = apis::chainstate_api::get_min_confirmations(&configuration)
+        .await
+        .expect("Received an error after making a valid get min confirmations api call.");
+
+    // Assert.
+    // --------
+    assert_eq!(unset.min_confirmations.flatten(), None);
+    assert_eq!(set, request);
+    assert_eq!(gotten, request);
+}
@@ -5,8 +5,15 @@ use emily_client::{
         self,
         configuration::{ApiKey, Configuration},
     },
-    models::ErrorResponse,
+    models::{
+        Chainstate, CreateDepositRequestBody, CreateWithdrawalRequestBody, Deposit, DepositInfo,
+        ErrorResponse, Status, Withdrawal,
+    },
 };
+use emily_handler::api::models::common as handler_models;
+use emily_handler::api::models::deposit::requests::GetDepositsQuery;
+use emily_handler::api::models::deposit::responses::{CreateDepositsResponse, GetDepositsResponse};
+use emily_handler::api::models::webhook::{RegisterWebhookRequestBody, Webhook};
 use serde::{Deserialize, Serialize};
 
 use crate::config::SETTINGS;
@@ -28,6 +35,367 @@ pub async fn clean_setup() -> Configuration {
     configuration
 }
 
+/// Register a deposit status change webhook.
+///
+/// The generated client doesn't cover the webhook endpoint, so the request
+/// is made directly.
+pub async fn register_webhook(
+    configuration: &Configuration,
+    request: &RegisterWebhookRequestBody,
+) -> Webhook {
+    let mut builder = configuration
+        .client
+        .post(format!("{}/testing/webhooks", configuration.base_path));
+    if let Some(ApiKey { key, .. }) = &configuration.api_key {
+        builder = builder.header("x-api-key", key);
+    }
+    builder
+        .json(request)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .expect("Failed to register webhook during test.")
+        .json()
+        .await
+        .expect("Failed to deserialize the registered webhook during test.")
+}
+
+/// Check whether the given chainstate is on the canonical chain, which is
+/// the case when it matches the chainstate stored by the API at the same
+/// height. Nothing stored at that height means it is not canonical.
+pub async fn is_canonical_chainstate(
+    configuration: &Configuration,
+    chainstate: &Chainstate,
+) -> bool {
+    apis::chainstate_api::get_chainstate_at_height(configuration, chainstate.stacks_block_height)
+        .await
+        .is_ok_and(|stored| &stored == chainstate)
+}
+
+/// Create deposit requests for the given outputs of the transaction with
+/// the given txid, all locked with the same deposit and reclaim scripts.
+pub fn deposit_requests_for_outputs(
+    bitcoin_txid: &str,
+    bitcoin_tx_output_indices: impl IntoIterator<Item = u32>,
+    deposit_script: &str,
+    reclaim_script: &str,
+) -> Vec<CreateDepositRequestBody> {
+    bitcoin_tx_output_indices
+        .into_iter()
+        .map(|bitcoin_tx_output_index| CreateDepositRequestBody {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.into(),
+            deposit_script: deposit_script.into(),
+            reclaim_script: reclaim_script.into(),
+        })
+        .collect()
+}
+
+/// A query for the first page of deposits with the given status, with the
+/// given page size.
+pub fn base_query_from_status_with_page_size(
+    status: handler_models::Status,
+    page_size: i32,
+) -> GetDepositsQuery {
+    GetDepositsQuery {
+        status,
+        page_size: Some(page_size),
+        ..Default::default()
+    }
+}
+
+/// Get a single page of deposits with the given status, returning the raw
+/// response so that the caller can drive the pagination.
+///
+/// The generated client doesn't support the direction parameter, so the
+/// request is made directly.
+pub async fn get_deposits_page(
+    configuration: &Configuration,
+    status: handler_models::Status,
+    next_token: Option<&str>,
+    direction: handler_models::SortDirection,
+    page_size: i32,
+) -> GetDepositsResponse {
+    let query = GetDepositsQuery {
+        next_token: next_token.map(String::from),
+        direction,
+        ..base_query_from_status_with_page_size(status, page_size)
+    };
+    configuration
+        .client
+        .get(format!("{}/deposit", configuration.base_path))
+        .query(&query)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .expect("Failed to get a page of deposits during test.")
+        .json()
+        .await
+        .expect("Failed to deserialize the page of deposits during test.")
+}
+
+/// Walk all pages of deposits with the given status, returning the total
+/// reported with the first page along with every deposit that was walked.
+pub async fn count_then_list(
+    configuration: &Configuration,
+    status: Status,
+    page_size: i32,
+) -> (u64, Vec<DepositInfo>) {
+    let mut total = None;
+    let mut deposits = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let response = apis::deposit_api::get_deposits(
+            configuration,
+            status,
+            next_token.as_deref(),
+            Some(page_size),
+            None,
+            Some(true),
+        )
+        .await
+        .expect("Received an error after making a valid get deposits api call.");
+        // Only the first page reports the total.
+        total = total.or(response.total.flatten());
+        deposits.extend(response.deposits);
+        next_token = response.next_token.flatten();
+        if next_token.is_none() {
+            break;
+        }
+    }
+    let total = total.expect("The first page of deposits did not report a total.");
+    (total, deposits)
+}
+
+/// Walk the pages of deposits with the given status, stopping once
+/// `max_items` deposits have been collected. Returns the deposits along
+/// with whether the results were truncated, that is, whether the walk
+/// stopped before all matching deposits could be returned.
+///
+/// A page can come with a next token even when there are no deposits
+/// after it, so once `max_items` deposits are collected the walk goes on
+/// until it finds another deposit, or runs out of pages, to tell whether
+/// the results were truncated.
+pub async fn get_all_deposits_with_status(
+    configuration: &Configuration,
+    status: Status,
+    page_size: i32,
+    max_items: Option<usize>,
+) -> (Vec<DepositInfo>, bool) {
+    let max_items = max_items.unwrap_or(usize::MAX);
+    let mut deposits = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let response = apis::deposit_api::get_deposits(
+            configuration,
+            status,
+            next_token.as_deref(),
+            Some(page_size),
+            None,
+            None,
+        )
+        .await
+        .expect("Received an error after making a valid get deposits api call.");
+        next_token = response.next_token.flatten();
+
+        let remaining = max_items - deposits.len();
+        let page_len = response.deposits.len();
+        deposits.extend(response.deposits.into_iter().take(remaining));
+        if page_len > remaining {
+            return (deposits, true);
+        }
+        if next_token.is_none() {
+            return (deposits, false);
+        }
+    }
+}
+
+/// Get every deposit with an output in the transaction with the given
+/// txid, walking all pages of the response, in ascending order of output
+/// index.
+pub async fn get_deposits_for_txid(
+    configuration: &Configuration,
+    bitcoin_txid: &str,
+    page_size: Option<i32>,
+) -> Vec<DepositInfo> {
+    let mut deposits = Vec::new();
+    let mut next_token: Option<String> = None;
+    loop {
+        let response = apis::deposit_api::get_deposits_for_transaction(
+            configuration,
+            bitcoin_txid,
+            next_token.as_deref(),
+            page_size,
+        )
+        .await
+        .expect("Received an error after making a valid get deposits for transaction api call.");
+        deposits.extend(response.deposits.into_iter().map(|deposit| DepositInfo {
+            amount: deposit.amount,
+            bitcoin_tx_output_index: deposit.bitcoin_tx_output_index,
+            bitcoin_txid: deposit.bitcoin_txid,
+            deposit_script: deposit.deposit_script,
+            last_update_block_hash: deposit.last_update_block_hash,
+            last_update_height: deposit.last_update_height,
+            recipient: deposit.recipient,
+            reclaim_script: deposit.reclaim_script,
+            status: deposit.status,
+        }));
+        next_token = response.next_token.flatten();
+        if next_token.is_none() {
+            return deposits;
+        }
+    }
+}
+
+/// Create the given deposits in a single request.
+///
+/// The generated client doesn't cover the create deposits endpoint, so the
+/// request is made directly.
+pub async fn create_deposits(
+    configuration: &Configuration,
+    requests: &[CreateDepositRequestBody],
+) -> CreateDepositsResponse {
+    configuration
+        .client
+        .post(format!("{}/deposit/batch", configuration.base_path))
+        .json(&serde_json::json!({ "deposits": requests }))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .expect("Failed to create deposits during test.")
+        .json()
+        .await
+        .expect("Failed to deserialize the created deposits during test.")
+}
+
+/// Delete a deposit, returning the deposit that was deleted.
+///
+/// The generated client doesn't cover the delete deposit endpoint, so the
+/// request is made directly.
+pub async fn delete_deposit(
+    configuration: &Configuration,
+    bitcoin_txid: &str,
+    bitcoin_tx_output_index: u32,
+) -> Result<Deposit, StandardError> {
+    let mut builder = configuration.client.delete(format!(
+        "{}/deposit/{bitcoin_txid}/{bitcoin_tx_output_index}",
+        configuration.base_path
+    ));
+    if let Some(ApiKey { key, .. }) = &configuration.api_key {
+        builder = builder.header("x-api-key", key);
+    }
+    let response = builder
+        .send()
+        .await
+        .expect("Failed to send the delete deposit request during test.");
+    let status = response.status();
+    let content = response
+        .text()
+        .await
+        .expect("Failed to read the delete deposit response during test.");
+    if !status.is_success() {
+        return Err(TestError {
+            status_code: status.as_u16(),
+            body: serde_json::from_str(&content)
+                .expect("Failed to deserialize error body during test."),
+        });
+    }
+    Ok(serde_json::from_str(&content)
+        .expect("Failed to deserialize the deleted deposit during test."))
+}
+
+/// A clean test environment that has been seeded with data.
+pub struct TestContext {
+    /// Client configuration for making calls to the API under test.
+    pub configuration: Configuration,
+    /// The chainstates that were seeded, in the order they were set.
+    pub chainstates: Vec<Chainstate>,
+    /// The deposits that were seeded, as returned by the API.
+    pub deposits: Vec<Deposit>,
+    /// The withdrawals that were seeded, as returned by the API.
+    pub withdrawals: Vec<Withdrawal>,
+}
+
+/// Builder for a [`TestContext`]. Building the context wipes the databases
+/// and then seeds them with the given chainstates, deposits and
+/// withdrawals, in that order.
+#[derive(Debug, Default)]
+pub struct TestContextBuilder {
+    chainstates: Vec<Chainstate>,
+    deposits: Vec<CreateDepositRequestBody>,
+    withdrawals: Vec<CreateWithdrawalRequestBody>,
+}
+
+impl TestContextBuilder {
+    /// Create a new builder that seeds no data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed the given chainstates.
+    pub fn with_chainstates(mut self, chainstates: impl IntoIterator<Item = Chainstate>) -> Self {
+        self.chainstates.extend(chainstates);
+        self
+    }
+
+    /// Seed the given deposits.
+    pub fn with_deposits(
+        mut self,
+        deposits: impl IntoIterator<Item = CreateDepositRequestBody>,
+    ) -> Self {
+        self.deposits.extend(deposits);
+        self
+    }
+
+    /// Seed the given withdrawals.
+    pub fn with_withdrawals(
+        mut self,
+        withdrawals: impl IntoIterator<Item = CreateWithdrawalRequestBody>,
+    ) -> Self {
+        self.withdrawals.extend(withdrawals);
+        self
+    }
+
+    /// Wipe the databases, seed the data and return the context.
+    pub async fn build(self) -> TestContext {
+        let configuration = clean_setup().await;
+
+        let mut chainstates = Vec::with_capacity(self.chainstates.len());
+        for chainstate in self.chainstates {
+            chainstates.push(
+                apis::chainstate_api::set_chainstate(&configuration, chainstate)
+                    .await
+                    .expect("Failed to seed chainstate during test setup."),
+            );
+        }
+
+        let mut deposits = Vec::with_capacity(self.deposits.len());
+        for request in self.deposits {
+            deposits.push(
+                apis::deposit_api::create_deposit(&configuration, request)
+                    .await
+                    .expect("Failed to seed deposit during test setup."),
+            );
+        }
+
+        let mut withdrawals = Vec::with_capacity(self.withdrawals.len());
+        for request in self.withdrawals {
+            withdrawals.push(
+                apis::withdrawal_api::create_withdrawal(&configuration, request)
+                    .await
+                    .expect("Failed to seed withdrawal during test setup."),
+            );
+        }
+
+        TestContext {
+            configuration,
+            chainstates,
+            deposits,
+            withdrawals,
+        }
+    }
+}
+
 /// Error type that represents an error
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestError<T> {
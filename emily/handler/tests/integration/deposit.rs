@@ -1,15 +1,28 @@
 use std::cmp::Ordering;
+use std::collections::HashSet;
 
-use emily_client::models::{Fulfillment, Status, UpdateDepositsRequestBody};
+use emily_client::models::{
+    Fulfillment, GetDepositHistogramResponse, Status, UpdateDepositsRequestBody,
+};
 use emily_client::{
     apis::{self, configuration::Configuration},
     models::{CreateDepositRequestBody, Deposit, DepositInfo, DepositParameters, DepositUpdate},
 };
+use emily_handler::api::models::common as handler_models;
+use emily_handler::api::models::common::SortDirection;
+use emily_handler::api::models::deposit::responses::GetDepositsResponse;
+use emily_handler::api::models::webhook::RegisterWebhookRequestBody;
+use emily_handler::context::DEFAULT_MAX_RECLAIM_LOCK_TIME;
 use sbtc::testing;
 use sbtc::testing::deposits::TxSetup;
 use stacks_common::codec::StacksMessageCodec as _;
+use test_case::test_case;
 
-use crate::common::{clean_setup, StandardError};
+use crate::common::{
+    clean_setup, count_then_list, create_deposits, delete_deposit, deposit_requests_for_outputs,
+    get_all_deposits_with_status, get_deposits_for_txid, get_deposits_page, register_webhook,
+    StandardError, TestContextBuilder,
+};
 
 const BLOCK_HASH: &'static str = "";
 const BLOCK_HEIGHT: u64 = 0;
@@ -109,6 +122,7 @@ async fn create_and_get_deposit_happy_path() {
         amount: DEPOSIT_AMOUNT_SATS,
         bitcoin_tx_output_index,
         bitcoin_txid: bitcoin_txid.into(),
+        failure_reason: None,
         fulfillment: None,
         last_update_block_hash: BLOCK_HASH.into(),
         last_update_height: BLOCK_HEIGHT,
@@ -226,6 +240,7 @@ async fn get_deposits_for_transaction() {
             amount: DEPOSIT_AMOUNT_SATS,
             bitcoin_tx_output_index,
             bitcoin_txid: bitcoin_txid.into(),
+            failure_reason: None,
             fulfillment: None,
             last_update_block_hash: BLOCK_HASH.into(),
             last_update_height: BLOCK_HEIGHT,
@@ -255,16 +270,221 @@ async fn get_deposits_for_transaction() {
 
     // Assert.
     // -------
-    // Expect the deposits to be sorted by output index.
-    // TODO(506): Reverse this order of deposits for this specific api call.
-    expected_deposits.sort_by(|a, b| {
-        b.bitcoin_tx_output_index
-            .partial_cmp(&a.bitcoin_tx_output_index)
-            .expect("Failed to order the expected deposits")
-    });
+    // Expect the deposits to be sorted by ascending output index.
+    expected_deposits.sort_by_key(|deposit| deposit.bitcoin_tx_output_index);
     assert_eq!(expected_deposits, gotten_deposits.deposits);
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn create_deposit_rejects_oversized_reclaim_lock_time() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let create_request = |bitcoin_tx_output_index: u32, lock_time: u32| {
+        let DepositTxnData {
+            reclaim_script, deposit_script, ..
+        } = DepositTxnData::new(lock_time, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+        CreateDepositRequestBody {
+            bitcoin_tx_output_index,
+            bitcoin_txid: "bitcoin_txid".into(),
+            reclaim_script,
+            deposit_script,
+        }
+    };
+    let oversized_lock_time = DEFAULT_MAX_RECLAIM_LOCK_TIME + 1;
+
+    // Act.
+    // ----
+    let rejected: StandardError =
+        apis::deposit_api::create_deposit(&configuration, create_request(1, oversized_lock_time))
+            .await
+            .expect_err(
+                "Received a successful response creating a deposit with an oversized lock time.",
+            )
+            .into();
+
+    apis::deposit_api::create_deposit(&configuration, create_request(2, DEPOSIT_LOCK_TIME))
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(rejected.status_code, 422);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn create_deposit_rejects_overlong_reclaim_script() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData { deposit_script, .. } =
+        DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let request = CreateDepositRequestBody {
+        bitcoin_tx_output_index: 1,
+        bitcoin_txid: "bitcoin_txid".into(),
+        reclaim_script: "51".repeat(4096),
+        deposit_script,
+    };
+
+    // Act.
+    // ----
+    let rejected: StandardError = apis::deposit_api::create_deposit(&configuration, request)
+        .await
+        .expect_err("Received a successful response creating a deposit with an over-long script.")
+        .into();
+
+    // Assert.
+    // -------
+    assert_eq!(rejected.status_code, 422);
+    assert!(rejected.body.message.contains("reclaimScript"));
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_all_deposits_with_status_stops_at_the_cap() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs("bitcoin_txid", 0..50, &deposit_script, &reclaim_script);
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    let (capped, truncated) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 7, Some(10)).await;
+    let (all, all_truncated) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 7, None).await;
+    // A cap of exactly the number of deposits, reached at the end of a
+    // page that still comes with a next token, does not truncate anything.
+    let (exact, exact_truncated) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 10, Some(50)).await;
+
+    // Assert.
+    // -------
+    assert_eq!(capped.len(), 10);
+    assert!(truncated);
+    assert_eq!(all.len(), 50);
+    assert!(!all_truncated);
+    assert_eq!(exact.len(), 50);
+    assert!(!exact_truncated);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_deposits_pages_back_with_the_previous_token() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs("bitcoin_txid", 0..6, &deposit_script, &reclaim_script);
+    batch_create_deposits(&configuration, create_requests).await;
+
+    let status = handler_models::Status::Pending;
+    let page_indices = |response: &GetDepositsResponse| -> HashSet<u32> {
+        response
+            .deposits
+            .iter()
+            .map(|deposit| deposit.bitcoin_tx_output_index)
+            .collect()
+    };
+
+    // Act.
+    // ----
+    let first = get_deposits_page(&configuration, status, None, SortDirection::Desc, 2).await;
+    let second = get_deposits_page(
+        &configuration,
+        status,
+        first.next_token.as_deref(),
+        SortDirection::Desc,
+        2,
+    )
+    .await;
+    let back = get_deposits_page(
+        &configuration,
+        status,
+        second.prev_token.as_deref(),
+        SortDirection::Asc,
+        2,
+    )
+    .await;
+
+    // Assert.
+    // -------
+    assert!(first.prev_token.is_none());
+    assert_eq!(page_indices(&first).len(), 2);
+    assert_eq!(page_indices(&second).len(), 2);
+    assert!(page_indices(&first).is_disjoint(&page_indices(&second)));
+    assert_eq!(page_indices(&back), page_indices(&first));
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test_case(0; "zero")]
+#[test_case(1001; "above-maximum")]
+#[tokio::test]
+async fn get_deposits_rejects_out_of_range_page_sizes(page_size: i32) {
+    let configuration = clean_setup().await;
+
+    // Act.
+    // ----
+    let attempted_get: StandardError = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::Pending,
+        None,
+        Some(page_size),
+        None,
+        None,
+    )
+    .await
+    .expect_err("Received a successful response with an invalid page size.")
+    .into();
+
+    // Assert.
+    // -------
+    assert_eq!(attempted_get.status_code, 400);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_deposits_terminates_when_page_size_divides_the_count() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs("bitcoin_txid", 0..6, &deposit_script, &reclaim_script);
+    batch_create_deposits(&configuration, create_requests).await;
+
+    // Act.
+    // ----
+    let (deposits, truncated) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 3, None).await;
+
+    // Assert.
+    // -------
+    assert_eq!(deposits.len(), 6);
+    assert!(!truncated);
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn get_deposits() {
@@ -329,6 +549,8 @@ async fn get_deposits() {
             status,
             next_token.as_ref().and_then(|o| o.as_deref()),
             Some(chunksize),
+            None,
+            None,
         )
         .await
         .expect("Received an error after making a valid get deposits api call.");
@@ -360,6 +582,291 @@ async fn get_deposits() {
     assert_eq!(expected_deposit_infos, gotten_deposit_infos);
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_deposits_reports_total_count() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs("bitcoin_txid", 0..5, &deposit_script, &reclaim_script);
+    let num_deposits = create_requests.len();
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    let (total, deposits) = count_then_list(&configuration, Status::Pending, 2).await;
+
+    // Assert.
+    // -------
+    assert_eq!(total, num_deposits as u64);
+    assert_eq!(total, deposits.len() as u64);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_deposits_only_counts_when_asked() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs("bitcoin_txid", 0..3, &deposit_script, &reclaim_script);
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    let uncounted =
+        apis::deposit_api::get_deposits(&configuration, Status::Pending, None, None, None, None)
+            .await
+            .expect("Received an error after making a valid get deposits api call.");
+    let counted = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::Pending,
+        None,
+        None,
+        None,
+        Some(true),
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(uncounted.total.flatten(), None);
+    assert_eq!(counted.total.flatten(), Some(3));
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn get_deposits_orders_equal_height_deposits_deterministically() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    // All three deposits are created at the same height.
+    let create_requests: Vec<CreateDepositRequestBody> =
+        [("txid_b", 0), ("txid_a", 1), ("txid_a", 0)]
+            .into_iter()
+            .map(
+                |(bitcoin_txid, bitcoin_tx_output_index)| CreateDepositRequestBody {
+                    bitcoin_tx_output_index,
+                    bitcoin_txid: bitcoin_txid.into(),
+                    deposit_script: deposit_script.clone(),
+                    reclaim_script: reclaim_script.clone(),
+                },
+            )
+            .collect();
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    let page =
+        apis::deposit_api::get_deposits(&configuration, Status::Pending, None, Some(3), None, None)
+            .await
+            .expect("Received an error after making a valid get deposits api call.");
+    let (first_walk, _) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 1, None).await;
+    let (second_walk, _) =
+        get_all_deposits_with_status(&configuration, Status::Pending, 1, None).await;
+
+    // Assert.
+    // -------
+    let outpoints = |deposits: &[DepositInfo]| -> Vec<(String, u32)> {
+        deposits
+            .iter()
+            .map(|deposit| {
+                (
+                    deposit.bitcoin_txid.clone(),
+                    deposit.bitcoin_tx_output_index,
+                )
+            })
+            .collect()
+    };
+    let expected = vec![
+        ("txid_a".to_string(), 0),
+        ("txid_a".to_string(), 1),
+        ("txid_b".to_string(), 0),
+    ];
+    assert_eq!(outpoints(&page.deposits), expected);
+    // The tie break only applies within a page, so walking one deposit at
+    // a time need not follow it, but it has to be stable.
+    assert_eq!(first_walk.len(), 3);
+    assert_eq!(outpoints(&first_walk), outpoints(&second_walk));
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn deposit_histogram_counts_confirmed_deposits() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "histogram_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs(bitcoin_txid, 0..4, &deposit_script, &reclaim_script);
+    // Only swept deposits are counted, so confirm all but one of them.
+    let confirm_request = UpdateDepositsRequestBody {
+        deposits: (0..3)
+            .map(|bitcoin_tx_output_index| DepositUpdate {
+                bitcoin_tx_output_index,
+                bitcoin_txid: bitcoin_txid.into(),
+                failure_reason: None,
+                fulfillment: Some(Some(Box::new(Fulfillment::default()))),
+                last_update_block_hash: "update_block_hash".into(),
+                last_update_height: 1,
+                status: Status::Confirmed,
+                status_message: "test_status_message".into(),
+            })
+            .collect(),
+    };
+
+    // Act.
+    // ----
+    let empty = apis::deposit_api::get_deposit_histogram(&configuration)
+        .await
+        .expect("Received an error after making a valid get deposit histogram api call.");
+    batch_create_deposits(&configuration, create_requests).await;
+    apis::deposit_api::update_deposits(&configuration, confirm_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+    let histogram = apis::deposit_api::get_deposit_histogram(&configuration)
+        .await
+        .expect("Received an error after making a valid get deposit histogram api call.");
+
+    // Assert.
+    // -------
+    let counts = |histogram: &GetDepositHistogramResponse| -> Vec<u64> {
+        histogram
+            .buckets
+            .iter()
+            .map(|bucket| bucket.count)
+            .collect()
+    };
+    assert_eq!(counts(&empty), vec![0, 0, 0, 0]);
+    // Emily records every deposit created through the API with an amount
+    // of zero, so they all land in the smallest bucket.
+    assert_eq!(counts(&histogram), vec![3, 0, 0, 0]);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn seeded_deposits_are_queryable() {
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs(bitcoin_txid, 0..3, &deposit_script, &reclaim_script);
+
+    // Act.
+    // ----
+    let context = TestContextBuilder::new()
+        .with_deposits(create_requests)
+        .build()
+        .await;
+
+    // Assert.
+    // -------
+    assert_eq!(context.deposits.len(), 3);
+    for seeded_deposit in context.deposits.iter() {
+        let gotten_deposit = apis::deposit_api::get_deposit(
+            &context.configuration,
+            &seeded_deposit.bitcoin_txid,
+            &seeded_deposit.bitcoin_tx_output_index.to_string(),
+        )
+        .await
+        .expect("Received an error after making a valid get deposit request api call.");
+        assert_eq!(seeded_deposit, &gotten_deposit);
+    }
+
+    let mut gotten_deposits = apis::deposit_api::get_deposits_for_transaction(
+        &context.configuration,
+        bitcoin_txid,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits for transaction api call.")
+    .deposits;
+    let mut expected_deposits = context.deposits.clone();
+    expected_deposits.sort_by(arbitrary_deposit_partial_cmp);
+    gotten_deposits.sort_by(arbitrary_deposit_partial_cmp);
+    assert_eq!(expected_deposits, gotten_deposits);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test_case(None; "single-page")]
+#[test_case(Some(2); "multiple-pages")]
+#[tokio::test]
+async fn get_deposits_for_txid_returns_every_output_in_order(page_size: Option<i32>) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let other_bitcoin_txid: &str = "other_bitcoin_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    // The outputs are created out of order, and another transaction
+    // shares one of the output indexes.
+    let outputs = [
+        (bitcoin_txid, 5),
+        (bitcoin_txid, 0),
+        (other_bitcoin_txid, 1),
+        (bitcoin_txid, 3),
+        (bitcoin_txid, 1),
+        (bitcoin_txid, 4),
+    ];
+    let create_requests = outputs
+        .into_iter()
+        .map(|(txid, bitcoin_tx_output_index)| CreateDepositRequestBody {
+            bitcoin_tx_output_index,
+            bitcoin_txid: txid.into(),
+            deposit_script: deposit_script.clone(),
+            reclaim_script: reclaim_script.clone(),
+        })
+        .collect();
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    let deposits = get_deposits_for_txid(&configuration, bitcoin_txid, page_size).await;
+
+    // Assert.
+    // -------
+    let output_indices: Vec<u32> = deposits
+        .iter()
+        .map(|deposit| deposit.bitcoin_tx_output_index)
+        .collect();
+    assert_eq!(output_indices, vec![0, 1, 3, 4, 5]);
+    assert!(deposits
+        .iter()
+        .all(|deposit| deposit.bitcoin_txid == bitcoin_txid));
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn update_deposits() {
@@ -408,6 +915,7 @@ async fn update_deposits() {
             let deposit_update = DepositUpdate {
                 bitcoin_tx_output_index: bitcoin_tx_output_index,
                 bitcoin_txid: bitcoin_txid.into(),
+                failure_reason: None,
                 fulfillment: Some(Some(Box::new(update_fulfillment.clone()))),
                 last_update_block_hash: update_block_hash.into(),
                 last_update_height: update_block_height,
@@ -420,6 +928,7 @@ async fn update_deposits() {
                 amount: DEPOSIT_AMOUNT_SATS,
                 bitcoin_tx_output_index,
                 bitcoin_txid: bitcoin_txid.into(),
+                failure_reason: None,
                 fulfillment: Some(Some(Box::new(update_fulfillment.clone()))),
                 last_update_block_hash: update_block_hash.into(),
                 last_update_height: update_block_height,
@@ -456,6 +965,67 @@ async fn update_deposits() {
     assert_eq!(expected_deposits, updated_deposits);
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn update_deposits_notifies_webhooks() {
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let bitcoin_tx_output_index = 1;
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let context = TestContextBuilder::new()
+        .with_deposits([CreateDepositRequestBody {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.into(),
+            deposit_script,
+            reclaim_script,
+        }])
+        .build()
+        .await;
+
+    let receiver = mockito::mock("POST", "/deposit-updates")
+        .match_header(
+            emily_handler::common::webhooks::SIGNATURE_HEADER,
+            mockito::Matcher::Any,
+        )
+        .with_status(200)
+        .expect(1)
+        .create();
+
+    let webhook_request = RegisterWebhookRequestBody {
+        url: format!("{}/deposit-updates", mockito::server_url()),
+        statuses: vec![emily_handler::api::models::common::Status::Accepted],
+        secret: "test-secret".to_string(),
+    };
+    register_webhook(&context.configuration, &webhook_request).await;
+
+    let update_request = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.into(),
+            failure_reason: None,
+            fulfillment: None,
+            last_update_block_hash: "update_block_hash".into(),
+            last_update_height: 34,
+            status: Status::Accepted,
+            status_message: "accepted".into(),
+        }],
+    };
+
+    // Act.
+    // ----
+    apis::deposit_api::update_deposits(&context.configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // Assert.
+    // -------
+    receiver.assert();
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn update_deposits_updates_chainstate() {
@@ -493,6 +1063,7 @@ async fn update_deposits_updates_chainstate() {
         let deposit_update = DepositUpdate {
             bitcoin_tx_output_index: bitcoin_tx_output_index,
             bitcoin_txid: bitcoin_txid.into(),
+            failure_reason: None,
             fulfillment: None,
             last_update_block_hash: format!("hash_{}", update_block_height),
             last_update_height: update_block_height as u64,
@@ -548,3 +1119,345 @@ async fn update_deposits_updates_chainstate() {
         );
     }
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn delete_deposit_removes_only_that_deposit() {
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let context = TestContextBuilder::new()
+        .with_deposits(deposit_requests_for_outputs(
+            bitcoin_txid,
+            1..=2,
+            &deposit_script,
+            &reclaim_script,
+        ))
+        .build()
+        .await;
+    let configuration = &context.configuration;
+
+    // Act.
+    // ----
+    let deleted = delete_deposit(configuration, bitcoin_txid, 1)
+        .await
+        .expect("Received an error after making a valid delete deposit api call.");
+    let second_delete: StandardError = delete_deposit(configuration, bitcoin_txid, 1)
+        .await
+        .expect_err("Deleting a deposit twice should fail.");
+
+    // Assert.
+    // -------
+    assert_eq!(deleted, context.deposits[0]);
+    assert_eq!(second_delete.status_code, 404);
+
+    let attempted_get: StandardError =
+        apis::deposit_api::get_deposit(configuration, bitcoin_txid, "1")
+            .await
+            .expect_err("Received a successful response attempting to access a deleted deposit.")
+            .into();
+    assert_eq!(attempted_get.status_code, 404);
+
+    let remaining = apis::deposit_api::get_deposit(configuration, bitcoin_txid, "2")
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+    assert_eq!(remaining, context.deposits[1]);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn create_deposits_reports_failures_individually() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let request = |bitcoin_tx_output_index: u32, reclaim_script: &str| CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.into(),
+        deposit_script: deposit_script.clone(),
+        reclaim_script: reclaim_script.into(),
+    };
+    let requests = [
+        request(1, &reclaim_script),
+        request(2, "not a reclaim script"),
+        request(3, &reclaim_script),
+    ];
+
+    // Act.
+    // ----
+    let response = create_deposits(&configuration, &requests).await;
+
+    // Assert.
+    // -------
+    let created: Vec<u32> = response
+        .deposits
+        .iter()
+        .map(|deposit| deposit.bitcoin_tx_output_index)
+        .collect();
+    assert_eq!(created, vec![1, 3]);
+
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].bitcoin_txid, bitcoin_txid);
+    assert_eq!(response.errors[0].bitcoin_tx_output_index, 2);
+
+    for bitcoin_tx_output_index in ["1", "3"] {
+        apis::deposit_api::get_deposit(&configuration, bitcoin_txid, bitcoin_tx_output_index)
+            .await
+            .expect("Received an error after making a valid get deposit api call.");
+    }
+    let attempted_get: StandardError =
+        apis::deposit_api::get_deposit(&configuration, bitcoin_txid, "2")
+            .await
+            .expect_err("Received a successful response attempting to access a failed deposit.")
+            .into();
+    assert_eq!(attempted_get.status_code, 404);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn create_deposits_rejects_oversized_batches() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let batch_size = handler_models::requests::MAX_BATCH_SIZE as u32 + 1;
+    let requests = deposit_requests_for_outputs(
+        "bitcoin_txid",
+        0..batch_size,
+        &deposit_script,
+        &reclaim_script,
+    );
+
+    // Act.
+    // ----
+    let response = configuration
+        .client
+        .post(format!("{}/deposit/batch", configuration.base_path))
+        .json(&serde_json::json!({ "deposits": requests }))
+        .send()
+        .await
+        .expect("Failed to make the create deposits request during test.");
+
+    // Assert.
+    // -------
+    assert_eq!(response.status().as_u16(), 400);
+    let attempted_get: StandardError =
+        apis::deposit_api::get_deposit(&configuration, "bitcoin_txid", "0")
+            .await
+            .expect_err("Received a successful response for a deposit in a rejected batch.")
+            .into();
+    assert_eq!(attempted_get.status_code, 404);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn deposits_rejected_for_a_wrong_key_are_retrievable_by_status() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let bitcoin_tx_output_index = 1;
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_request = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.into(),
+        deposit_script,
+        reclaim_script,
+    };
+    let update_request = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.into(),
+            failure_reason: None,
+            fulfillment: None,
+            last_update_block_hash: "update_block_hash".into(),
+            last_update_height: 1,
+            status: Status::RejectedWrongKey,
+            status_message: "deposit is locked to an unknown signers' key".into(),
+        }],
+    };
+
+    // Act.
+    // ----
+    apis::deposit_api::create_deposit(&configuration, create_request)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    apis::deposit_api::update_deposits(&configuration, update_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    let rejected = apis::deposit_api::get_deposits(
+        &configuration,
+        Status::RejectedWrongKey,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("Received an error after making a valid get deposits api call.");
+    let pending =
+        apis::deposit_api::get_deposits(&configuration, Status::Pending, None, None, None, None)
+            .await
+            .expect("Received an error after making a valid get deposits api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(rejected.deposits.len(), 1);
+    assert_eq!(rejected.deposits[0].bitcoin_txid, bitcoin_txid);
+    assert_eq!(rejected.deposits[0].status, Status::RejectedWrongKey);
+    assert!(pending.deposits.is_empty());
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test_case(Status::Failed, true; "failed")]
+#[test_case(Status::Accepted, false; "accepted")]
+#[test_case(Status::Reprocessing, false; "reprocessing")]
+#[tokio::test]
+async fn update_deposits_only_accepts_failure_reasons_for_failed_deposits(
+    status: Status,
+    accepted: bool,
+) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let bitcoin_tx_output_index = 1;
+    let failure_reason = "deposit amount is below the dust limit";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_request = CreateDepositRequestBody {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.into(),
+        deposit_script,
+        reclaim_script,
+    };
+    let update_request = UpdateDepositsRequestBody {
+        deposits: vec![DepositUpdate {
+            bitcoin_tx_output_index,
+            bitcoin_txid: bitcoin_txid.into(),
+            failure_reason: Some(Some(failure_reason.into())),
+            fulfillment: None,
+            last_update_block_hash: "update_block_hash".into(),
+            last_update_height: 1,
+            status: status.clone(),
+            status_message: "test_status_message".into(),
+        }],
+    };
+
+    // Act.
+    // ----
+    apis::deposit_api::create_deposit(&configuration, create_request)
+        .await
+        .expect("Received an error after making a valid create deposit request api call.");
+    let update_result = apis::deposit_api::update_deposits(&configuration, update_request).await;
+
+    // Assert.
+    // -------
+    let deposit = apis::deposit_api::get_deposit(
+        &configuration,
+        bitcoin_txid,
+        &bitcoin_tx_output_index.to_string(),
+    )
+    .await
+    .expect("Received an error after making a valid get deposit api call.");
+
+    if accepted {
+        update_result.expect("Received an error after making a valid update deposits api call.");
+        assert_eq!(deposit.status, status);
+        assert_eq!(deposit.failure_reason, Some(Some(failure_reason.into())));
+    } else {
+        let error: StandardError = update_result
+            .expect_err("A failure reason was accepted for a deposit that had not failed.")
+            .into();
+        assert_eq!(error.status_code, 400);
+        assert_eq!(deposit.status, Status::Pending);
+        assert_eq!(deposit.failure_reason, None);
+    }
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn update_deposits_reports_invalid_status_transitions_individually() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let bitcoin_txid: &str = "bitcoin_txid";
+    let DepositTxnData {
+        reclaim_script, deposit_script, ..
+    } = DepositTxnData::new(DEPOSIT_LOCK_TIME, DEPOSIT_MAX_FEE, DEPOSIT_AMOUNT_SATS);
+
+    let create_requests =
+        deposit_requests_for_outputs(bitcoin_txid, [1, 2], &deposit_script, &reclaim_script);
+    let update = |bitcoin_tx_output_index: u32, status: Status, height: u64| DepositUpdate {
+        bitcoin_tx_output_index,
+        bitcoin_txid: bitcoin_txid.into(),
+        failure_reason: None,
+        fulfillment: (status == Status::Confirmed).then(|| Some(Box::new(Fulfillment::default()))),
+        last_update_block_hash: format!("update_block_hash_{height}"),
+        last_update_height: height,
+        status,
+        status_message: "test_status_message".into(),
+    };
+    let confirm_request = UpdateDepositsRequestBody {
+        deposits: vec![update(1, Status::Confirmed, 1)],
+    };
+
+    // Act.
+    // ----
+    batch_create_deposits(&configuration, create_requests).await;
+    apis::deposit_api::update_deposits(&configuration, confirm_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    // The first deposit cannot go back to pending once it is confirmed,
+    // but that shouldn't stop the second deposit from being accepted.
+    let reject_request = UpdateDepositsRequestBody {
+        deposits: vec![
+            update(1, Status::Pending, 2),
+            update(2, Status::Accepted, 2),
+        ],
+    };
+    let response = apis::deposit_api::update_deposits(&configuration, reject_request)
+        .await
+        .expect("Received an error after making a valid update deposits api call.");
+
+    let confirmed = apis::deposit_api::get_deposit(&configuration, bitcoin_txid, "1")
+        .await
+        .expect("Received an error after making a valid get deposit api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(response.deposits.len(), 1);
+    assert_eq!(response.deposits[0].bitcoin_tx_output_index, 2);
+    assert_eq!(response.deposits[0].status, Status::Accepted);
+
+    let errors = response.errors.unwrap_or_default();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].bitcoin_txid, bitcoin_txid);
+    assert_eq!(errors[0].bitcoin_tx_output_index, 1);
+    assert_eq!(errors[0].status_code, 409);
+
+    assert_eq!(confirmed.status, Status::Confirmed);
+}
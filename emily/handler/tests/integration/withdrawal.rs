@@ -1,12 +1,13 @@
 use std::cmp::Ordering;
 
-use crate::common::clean_setup;
+use crate::common::{clean_setup, StandardError};
 use emily_client::apis;
 use emily_client::apis::configuration::Configuration;
 use emily_client::models::{
     CreateWithdrawalRequestBody, Fulfillment, Status, UpdateWithdrawalsRequestBody, Withdrawal,
     WithdrawalInfo, WithdrawalParameters, WithdrawalUpdate,
 };
+use test_case::test_case;
 
 const RECIPIENT: &'static str = "";
 const BLOCK_HASH: &'static str = "TEST_BLOCK_HASH";
@@ -85,6 +86,8 @@ async fn create_and_get_withdrawal_happy_path() {
         stacks_block_height: BLOCK_HEIGHT,
         status: Status::Pending,
         status_message: INITIAL_WITHDRAWAL_STATUS_MESSAGE.into(),
+        signatures_collected: 0,
+        threshold: 0,
     };
 
     // Act.
@@ -160,6 +163,7 @@ async fn get_withdrawals() {
             status,
             next_token.as_ref().and_then(|o| o.as_deref()),
             Some(chunksize),
+            None,
         )
         .await
         .expect("Received an error after making a valid get withdrawal api call.");
@@ -241,6 +245,8 @@ async fn update_withdrawals() {
             last_update_height: update_block_height.clone(),
             status: update_status.clone(),
             status_message: update_status_message.into(),
+            signatures_collected: None,
+            threshold: None,
         };
         withdrawal_updates.push(withdrawal_update);
 
@@ -256,6 +262,8 @@ async fn update_withdrawals() {
             stacks_block_height: BLOCK_HEIGHT,
             status: update_status.clone(),
             status_message: update_status_message.into(),
+            signatures_collected: 0,
+            threshold: 0,
         };
         expected_withdrawals.push(expected);
     }
@@ -280,6 +288,152 @@ async fn update_withdrawals() {
     assert_eq!(expected_withdrawals, updated_withdrawals);
 }
 
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn update_withdrawals_tracks_collected_signatures() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request_id = 1;
+    let create_request = CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: RECIPIENT.into(),
+        request_id,
+        stacks_block_hash: BLOCK_HASH.into(),
+        stacks_block_height: BLOCK_HEIGHT,
+    };
+
+    let update = |height: u64, signatures_collected: u32| UpdateWithdrawalsRequestBody {
+        withdrawals: vec![WithdrawalUpdate {
+            request_id,
+            fulfillment: None,
+            last_update_block_hash: format!("hash_{height}"),
+            last_update_height: height,
+            status: Status::Accepted,
+            status_message: "collecting signatures".into(),
+            signatures_collected: Some(Some(signatures_collected)),
+            threshold: Some(Some(3)),
+        }],
+    };
+
+    // Act.
+    // ----
+    batch_create_withdrawals(&configuration, vec![create_request]).await;
+    let created = apis::withdrawal_api::get_withdrawal(&configuration, request_id)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    apis::withdrawal_api::update_withdrawals(&configuration, update(1, 1))
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+    let after_first = apis::withdrawal_api::get_withdrawal(&configuration, request_id)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    apis::withdrawal_api::update_withdrawals(&configuration, update(2, 2))
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+    let after_second = apis::withdrawal_api::get_withdrawal(&configuration, request_id)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    // There cannot be more signatures than the threshold asks for.
+    let past_threshold =
+        apis::withdrawal_api::update_withdrawals(&configuration, update(3, 4)).await;
+    let after_rejection = apis::withdrawal_api::get_withdrawal(&configuration, request_id)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    // Assert.
+    // -------
+    assert_eq!((created.signatures_collected, created.threshold), (0, 0));
+    assert_eq!(
+        (after_first.signatures_collected, after_first.threshold),
+        (1, 3)
+    );
+    assert_eq!(
+        (after_second.signatures_collected, after_second.threshold),
+        (2, 3)
+    );
+    assert!(past_threshold.is_err());
+    assert_eq!(
+        (
+            after_rejection.signatures_collected,
+            after_rejection.threshold
+        ),
+        (2, 3)
+    );
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn withdrawal_fulfillment_round_trips_only_when_confirmed() {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let create_requests = [1, 2].map(|request_id| CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: RECIPIENT.into(),
+        request_id,
+        stacks_block_hash: BLOCK_HASH.into(),
+        stacks_block_height: BLOCK_HEIGHT,
+    });
+
+    let fulfillment = Fulfillment {
+        bitcoin_block_hash: "bitcoin_block_hash".to_string(),
+        bitcoin_block_height: 23,
+        bitcoin_tx_index: 1,
+        bitcoin_txid: "test_fulfillment_bitcoin_txid".to_string(),
+        btc_fee: 2314,
+        stacks_txid: "test_fulfillment_stacks_txid".to_string(),
+    };
+
+    let update = |request_id: u64, status: Status| UpdateWithdrawalsRequestBody {
+        withdrawals: vec![WithdrawalUpdate {
+            request_id,
+            fulfillment: Some(Some(Box::new(fulfillment.clone()))),
+            last_update_block_hash: "update_block_hash".into(),
+            last_update_height: 1,
+            status,
+            status_message: "fulfilled".into(),
+            signatures_collected: None,
+            threshold: None,
+        }],
+    };
+
+    // Act.
+    // ----
+    batch_create_withdrawals(&configuration, create_requests.to_vec()).await;
+
+    apis::withdrawal_api::update_withdrawals(&configuration, update(1, Status::Confirmed))
+        .await
+        .expect("Received an error after making a valid update withdrawals api call.");
+    let confirmed = apis::withdrawal_api::get_withdrawal(&configuration, 1)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    let attempted_update: StandardError =
+        apis::withdrawal_api::update_withdrawals(&configuration, update(2, Status::Accepted))
+            .await
+            .expect_err("Fulfillment data was accepted for a withdrawal that isn't confirmed.")
+            .into();
+    let accepted = apis::withdrawal_api::get_withdrawal(&configuration, 2)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+
+    // Assert.
+    // -------
+    assert_eq!(confirmed.status, Status::Confirmed);
+    assert_eq!(confirmed.fulfillment, Some(Some(Box::new(fulfillment))));
+    assert_eq!(attempted_update.status_code, 400);
+    assert_eq!(accepted.status, Status::Pending);
+    assert_eq!(accepted.fulfillment, None);
+}
+
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
 async fn update_withdrawals_updates_chainstate() {
@@ -317,6 +471,8 @@ async fn update_withdrawals_updates_chainstate() {
             last_update_height: update_block_height as u64,
             status: update_status.clone(),
             status_message: update_status_message.into(),
+            signatures_collected: None,
+            threshold: None,
         };
         withdrawal_updates.push(withdrawal_update);
     }
@@ -368,3 +524,88 @@ async fn update_withdrawals_updates_chainstate() {
         );
     }
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test_case(0; "zero")]
+#[test_case(1001; "above-maximum")]
+#[tokio::test]
+async fn get_withdrawals_rejects_out_of_range_page_sizes(page_size: i32) {
+    let configuration = clean_setup().await;
+
+    // Act.
+    // ----
+    let attempted_get: StandardError = apis::withdrawal_api::get_withdrawals(
+        &configuration,
+        Status::Pending,
+        None,
+        Some(page_size),
+        None,
+    )
+    .await
+    .expect_err("Received a successful response with an invalid page size.")
+    .into();
+
+    // Assert.
+    // -------
+    assert_eq!(attempted_get.status_code, 400);
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[test_case(Status::Pending, true; "pending")]
+#[test_case(Status::Accepted, false; "accepted")]
+#[tokio::test]
+async fn only_pending_withdrawals_expire(status: Status, can_expire: bool) {
+    let configuration = clean_setup().await;
+
+    // Arrange.
+    // --------
+    let request_id = 1;
+    let create_request = CreateWithdrawalRequestBody {
+        amount: 0,
+        parameters: Box::new(WithdrawalParameters { max_fee: 123 }),
+        recipient: RECIPIENT.into(),
+        request_id,
+        stacks_block_hash: BLOCK_HASH.into(),
+        stacks_block_height: BLOCK_HEIGHT,
+    };
+    let update = |height: u64, status: Status| UpdateWithdrawalsRequestBody {
+        withdrawals: vec![WithdrawalUpdate {
+            request_id,
+            fulfillment: None,
+            last_update_block_hash: format!("hash_{height}"),
+            last_update_height: height,
+            status,
+            status_message: "status_message".into(),
+            signatures_collected: None,
+            threshold: None,
+        }],
+    };
+
+    batch_create_withdrawals(&configuration, vec![create_request]).await;
+    if status != Status::Pending {
+        apis::withdrawal_api::update_withdrawals(&configuration, update(1, status))
+            .await
+            .expect("Received an error after making a valid update withdrawals api call.");
+    }
+
+    // Act.
+    // ----
+    let expire_result =
+        apis::withdrawal_api::update_withdrawals(&configuration, update(2, Status::Expired)).await;
+
+    // Assert.
+    // -------
+    let withdrawal = apis::withdrawal_api::get_withdrawal(&configuration, request_id)
+        .await
+        .expect("Received an error after making a valid get withdrawal request api call.");
+    if can_expire {
+        expire_result.expect("Received an error after expiring a pending withdrawal.");
+        assert_eq!(withdrawal.status, Status::Expired);
+    } else {
+        let error: StandardError = expire_result
+            .expect_err("Received a successful response expiring an accepted withdrawal.")
+            .into();
+        assert_eq!(error.status_code, 400);
+        assert_eq!(withdrawal.status, status);
+    }
+}
@@ -7,12 +7,17 @@ use bitcoin::script::PushBytesBuf;
 use bitcoin::taproot::LeafVersion;
 use bitcoin::taproot::NodeInfo;
 use bitcoin::taproot::TaprootSpendInfo;
+use bitcoin::transaction::Version;
 use bitcoin::Address;
+use bitcoin::Amount;
 use bitcoin::Network;
 use bitcoin::OutPoint;
 use bitcoin::Script;
 use bitcoin::ScriptBuf;
 use bitcoin::Transaction;
+use bitcoin::TxIn;
+use bitcoin::TxOut;
+use bitcoin::Witness;
 use bitcoin::XOnlyPublicKey;
 use clarity::codec::StacksMessageCodec;
 use clarity::vm::types::PrincipalData;
@@ -39,6 +44,15 @@ const DEPOSIT_SCRIPT_FIXED_LENGTH: usize = 35;
 const STANDARD_SCRIPT_LENGTH: usize =
     1 + 1 + 8 + STACKS_ADDRESS_ENCODED_SIZE as usize + DEPOSIT_SCRIPT_FIXED_LENGTH;
 
+/// This is the default maximum number of bytes of a deposit script. The
+/// longest valid deposit scripts have a contract principal recipient with
+/// a 128 byte contract name, which is 151 bytes when serialized. So the
+/// maximum length is 1 byte for the OP_PUSHDATA1 opcode, 1 byte for the
+/// length of the data, 8 bytes for the max fee, 151 bytes for the
+/// recipient, followed by 35 bytes for the fixed length portion of the
+/// deposit script. So we have 1 + 1 + 8 + 151 + 35 = 196.
+pub const MAX_DEPOSIT_SCRIPT_LENGTH: usize = 196;
+
 /// This flag, from bitcoin-core, determines the following:
 /// * If the input to OP_CSV has this bit set, then OP_CSV is treated as a
 ///   NOP, effectively disabling the opcode when executing the script [^1].
@@ -88,6 +102,19 @@ pub struct CreateDepositRequest {
     pub deposit_script: ScriptBuf,
 }
 
+/// The deposit and reclaim scripts of a [`CreateDepositRequest`], parsed
+/// and checked against the expected formats for deposit transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedDepositScripts {
+    /// The parsed deposit script.
+    pub deposit: DepositScriptInputs,
+    /// The parsed reclaim script.
+    pub reclaim: ReclaimScriptInputs,
+    /// The `scriptPubKey` of a UTXO that is locked by the deposit and
+    /// reclaim scripts.
+    pub script_pubkey: ScriptBuf,
+}
+
 /// All the deposit script with the relevant parts of the deposit and
 /// reclaim scripts parsed.
 #[derive(Debug, Clone)]
@@ -124,6 +151,22 @@ impl CreateDepositRequest {
     /// * That deposit script and the reclaim script are part of the UTXO
     ///   ScriptPubKey.
     pub fn validate_tx(&self, tx: &Transaction) -> Result<DepositInfo, Error> {
+        let scripts = self.parse_scripts()?;
+        self.validate_tx_with(tx, &scripts)
+    }
+
+    /// Validate this deposit request using deposit and reclaim scripts
+    /// that have already been parsed with
+    /// [`CreateDepositRequest::parse_scripts`].
+    ///
+    /// This function performs the same checks as
+    /// [`CreateDepositRequest::validate_tx`], except for parsing the
+    /// scripts in the request.
+    pub fn validate_tx_with(
+        &self,
+        tx: &Transaction,
+        scripts: &ParsedDepositScripts,
+    ) -> Result<DepositInfo, Error> {
         if tx.compute_txid() != self.outpoint.txid {
             // The expectation is that the transaction was fetched from the
             // blockchain using the txid, so in practice this should never
@@ -137,38 +180,77 @@ impl CreateDepositRequest {
         let tx_out = tx
             .tx_out(self.outpoint.vout as usize)
             .map_err(|err| Error::OutpointIndex(err, self.outpoint))?;
-        // Validate that the deposit and reclaim scripts in the request
-        // match the expected formats for deposit transactions.
-        let deposit = DepositScriptInputs::parse(&self.deposit_script)?;
-        let reclaim = ReclaimScriptInputs::parse(&self.reclaim_script)?;
-        // Okay, the deposit and reclaim scripts are valid. Now make sure
-        // that the ScriptPubKey in the transaction matches the one implied
-        // by the given scripts. So now create the expected ScriptPubKey.
-        let deposit_script = deposit.deposit_script();
-        let reclaim_script = reclaim.reclaim_script();
-
-        debug_assert_eq!(deposit_script, self.deposit_script);
-        debug_assert_eq!(reclaim_script, self.reclaim_script);
-
-        let expected_script_pubkey =
-            to_script_pubkey(deposit_script.clone(), reclaim_script.clone());
         // Check that the expected scriptPubkey matches the actual public
         // key of our parsed UTXO.
-        if expected_script_pubkey != tx_out.script_pubkey {
+        if scripts.script_pubkey != tx_out.script_pubkey {
             return Err(Error::UtxoScriptPubKeyMismatch(self.outpoint));
         }
 
         Ok(DepositInfo {
-            max_fee: deposit.max_fee,
-            deposit_script,
-            reclaim_script,
-            signers_public_key: deposit.signers_public_key,
-            recipient: deposit.recipient,
-            lock_time: reclaim.lock_time,
+            max_fee: scripts.deposit.max_fee,
+            deposit_script: scripts.deposit.deposit_script(),
+            reclaim_script: scripts.reclaim.reclaim_script(),
+            signers_public_key: scripts.deposit.signers_public_key,
+            recipient: scripts.deposit.recipient.clone(),
+            lock_time: scripts.reclaim.lock_time,
             amount: tx_out.value.to_sat(),
             outpoint: self.outpoint,
         })
     }
+
+    /// Parse the deposit and reclaim scripts in this request.
+    ///
+    /// This checks that the scripts match the expected formats for
+    /// deposit transactions, and computes the `scriptPubKey` of the UTXO
+    /// that they lock. None of this depends on the deposit transaction,
+    /// so the result can be reused while the request is pending.
+    pub fn parse_scripts(&self) -> Result<ParsedDepositScripts, Error> {
+        self.parse_scripts_with_max_length(MAX_DEPOSIT_SCRIPT_LENGTH)
+    }
+
+    /// Parse the deposit and reclaim scripts in this request, rejecting
+    /// deposit scripts that are longer than `max_deposit_script_length`
+    /// bytes.
+    ///
+    /// This is the same as [`CreateDepositRequest::parse_scripts`], except
+    /// that the maximum length of the deposit script is given by the
+    /// caller, see [`DepositScriptInputs::parse_with_max_length`].
+    pub fn parse_scripts_with_max_length(
+        &self,
+        max_deposit_script_length: usize,
+    ) -> Result<ParsedDepositScripts, Error> {
+        let deposit = DepositScriptInputs::parse_with_max_length(
+            &self.deposit_script,
+            max_deposit_script_length,
+        )?;
+        let reclaim = ReclaimScriptInputs::parse(&self.reclaim_script)?;
+        // Okay, the deposit and reclaim scripts are valid. Now create the
+        // ScriptPubKey implied by the given scripts.
+        let deposit_script = deposit.deposit_script();
+        let reclaim_script = reclaim.reclaim_script();
+
+        debug_assert_eq!(deposit_script, self.deposit_script);
+        debug_assert_eq!(reclaim_script, self.reclaim_script);
+
+        Ok(ParsedDepositScripts {
+            script_pubkey: to_script_pubkey(deposit_script, reclaim_script),
+            deposit,
+            reclaim,
+        })
+    }
+
+    /// Return the reclaim script that is committed to in the taproot tree
+    /// of the deposit UTXO.
+    ///
+    /// This is the script that shows up in the witness of a transaction
+    /// that reclaims the deposit, so it can be used along with
+    /// [`to_taproot`] to watch for reclaim spends. Both the deposit and
+    /// reclaim scripts in the request must be valid.
+    pub fn reclaim_output_script(&self) -> Result<ScriptBuf, Error> {
+        DepositScriptInputs::parse(&self.deposit_script)?;
+        let reclaim = ReclaimScriptInputs::parse(&self.reclaim_script)?;
+        Ok(reclaim.reclaim_script())
+    }
 }
 
 /// Construct the expected taproot info for a deposit UTXO on the given
@@ -283,8 +365,29 @@ impl DepositScriptInputs {
     /// SIP-005:
     /// https://github.com/stacksgov/sips/blob/0b19b15a9f2dd43caf6607de4fe53cad8313ff40/sips/sip-005/sip-005-blocks-and-transactions.md#transaction-post-conditions
     pub fn parse(deposit_script: &ScriptBuf) -> Result<Self, Error> {
+        Self::parse_with_max_length(deposit_script, MAX_DEPOSIT_SCRIPT_LENGTH)
+    }
+
+    /// Parse the deposit script, rejecting scripts that are longer than
+    /// `max_length` bytes.
+    ///
+    /// This is the same as [`DepositScriptInputs::parse`], except that
+    /// the maximum length of the script is given by the caller instead of
+    /// being [`MAX_DEPOSIT_SCRIPT_LENGTH`].
+    pub fn parse_with_max_length(
+        deposit_script: &ScriptBuf,
+        max_length: usize,
+    ) -> Result<Self, Error> {
         let script = deposit_script.as_bytes();
 
+        // We check the length of the script before doing anything else
+        // with it.
+        if script.len() > max_length {
+            return Err(Error::ScriptTooLarge {
+                len: script.len(),
+                max: max_length,
+            });
+        }
         // Valid deposit scripts cannot be less than this length.
         if script.len() < STANDARD_SCRIPT_LENGTH {
             return Err(Error::InvalidDepositScriptLength);
@@ -484,6 +587,81 @@ impl ReclaimScriptInputs {
     }
 }
 
+/// The inputs needed to construct a transaction that spends a deposit
+/// UTXO through the reclaim script path of its taproot tree.
+///
+/// Bitcoin consensus rules only allow such a transaction into a block
+/// after the lock time in the reclaim script has elapsed, counted from the
+/// block that confirmed the deposit.
+#[derive(Debug, Clone)]
+pub struct ReclaimTxInputs {
+    /// The deposit script of the deposit being reclaimed.
+    pub deposit_script: ScriptBuf,
+    /// The full reclaim script of the deposit being reclaimed.
+    pub reclaim_script: ScriptBuf,
+    /// The outpoint of the deposit UTXO in the funding transaction.
+    pub outpoint: OutPoint,
+    /// The amount of sats to send to the destination. The difference
+    /// between the amount locked in the deposit UTXO and this value is
+    /// paid to the bitcoin miners as a fee.
+    pub value: Amount,
+    /// The ScriptPubKey that the reclaimed funds are sent to.
+    pub destination: ScriptBuf,
+    /// The witness elements that satisfy the user supplied part of the
+    /// reclaim script, in the order that they should appear in the
+    /// witness. These are typically signatures from the depositor.
+    pub witness: Vec<Vec<u8>>,
+}
+
+impl ReclaimTxInputs {
+    /// Get the lock time in the reclaim script, in bitcoin blocks.
+    pub fn lock_time(&self) -> Result<u32, Error> {
+        ReclaimScriptInputs::parse(&self.reclaim_script).map(|reclaim| reclaim.lock_time())
+    }
+
+    /// Construct the transaction that reclaims the deposit.
+    ///
+    /// The transaction has a single input spending the deposit UTXO using
+    /// the reclaim script, with its sequence number set to the lock time
+    /// in that script so that the `OP_CSV` check passes, and a single
+    /// output paying [`ReclaimTxInputs::value`] to the destination.
+    pub fn construct_tx(&self) -> Result<Transaction, Error> {
+        let reclaim = ReclaimScriptInputs::parse(&self.reclaim_script)?;
+
+        let leaf = (self.reclaim_script.clone(), LeafVersion::TapScript);
+        let taproot = to_taproot(self.deposit_script.clone(), self.reclaim_script.clone());
+        // The reclaim script is always a leaf in the taproot tree that we
+        // just constructed, so this error path cannot happen.
+        let control_block = taproot
+            .control_block(&leaf)
+            .ok_or(Error::InvalidReclaimScript)?;
+
+        let mut witness = Witness::new();
+        for item in self.witness.iter() {
+            witness.push(item);
+        }
+        witness.push(self.reclaim_script.as_bytes());
+        witness.push(control_block.serialize());
+
+        Ok(Transaction {
+            // Relative lock times are only enforced for transactions with
+            // a version of 2 or greater.
+            version: Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: self.outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: reclaim.lock_time.to_sequence(),
+                witness,
+            }],
+            output: vec![TxOut {
+                value: self.value,
+                script_pubkey: self.destination.clone(),
+            }],
+        })
+    }
+}
+
 /// Decodes an integer in script(minimal CScriptNum) format.
 ///
 /// # Notes
@@ -675,6 +853,59 @@ mod tests {
         assert_eq!(deposit, parsed_deposit);
     }
 
+    #[test]
+    fn deposit_script_max_length_boundary() {
+        let contract_name = std::iter::repeat('a').take(128).collect::<String>();
+        let principal_str = format!("{}.{contract_name}", StacksAddress::burn_address(false));
+        let secret_key = SecretKey::new(&mut OsRng);
+
+        let deposit = DepositScriptInputs {
+            signers_public_key: secret_key.x_only_public_key(SECP256K1).0,
+            max_fee: 25000,
+            recipient: PrincipalData::parse(&principal_str).unwrap(),
+        };
+
+        // The longest valid deposit script is accepted by default.
+        let deposit_script = deposit.deposit_script();
+        assert_eq!(deposit_script.len(), MAX_DEPOSIT_SCRIPT_LENGTH);
+        let parsed_deposit = DepositScriptInputs::parse(&deposit_script).unwrap();
+        assert_eq!(deposit, parsed_deposit);
+
+        // A script that is one byte over the maximum is rejected.
+        let mut bytes = deposit_script.to_bytes();
+        bytes.insert(0, opcodes::OP_NOP.to_u8());
+        let too_long = ScriptBuf::from_bytes(bytes);
+        match DepositScriptInputs::parse(&too_long) {
+            Err(Error::ScriptTooLarge { len, max }) => {
+                assert_eq!(len, MAX_DEPOSIT_SCRIPT_LENGTH + 1);
+                assert_eq!(max, MAX_DEPOSIT_SCRIPT_LENGTH);
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test_case(0, true; "at the maximum")]
+    #[test_case(1, false; "one over the maximum")]
+    fn deposit_script_configured_max_length(bytes_over: usize, accepted: bool) {
+        let secret_key = SecretKey::new(&mut OsRng);
+        let deposit = DepositScriptInputs {
+            signers_public_key: secret_key.x_only_public_key(SECP256K1).0,
+            max_fee: 15000,
+            recipient: PrincipalData::from(StacksAddress::burn_address(false)),
+        };
+
+        let deposit_script = deposit.deposit_script();
+        let max_length = deposit_script.len() - bytes_over;
+        let result = DepositScriptInputs::parse_with_max_length(&deposit_script, max_length);
+
+        if accepted {
+            assert_eq!(result.unwrap(), deposit);
+        } else {
+            assert!(matches!(result, Err(Error::ScriptTooLarge { len, max })
+                if len == deposit_script.len() && max == max_length));
+        }
+    }
+
     /// Basic check that we can create an address without any issues
     #[test_case(PrincipalData::from(StacksAddress::burn_address(false)) ; "standard address")]
     #[test_case(PrincipalData::parse(CONTRACT_ADDRESS).unwrap(); "contract address")]
@@ -833,6 +1064,34 @@ mod tests {
         assert_eq!(parsed.recipient, setup.deposit.recipient);
     }
 
+    #[test]
+    fn reclaim_output_script_matches_the_committed_script() {
+        let setup: TxSetup = testing::deposits::tx_setup(150, 15000, 500_000);
+
+        let mut request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            reclaim_script: setup.reclaim.reclaim_script(),
+            deposit_script: setup.deposit.deposit_script(),
+        };
+
+        let reclaim_script = request.reclaim_output_script().unwrap();
+        assert_eq!(reclaim_script, setup.reclaim.reclaim_script());
+
+        // The script is a leaf of the taproot tree that the deposit UTXO
+        // commits to.
+        let taproot = to_taproot(request.deposit_script.clone(), reclaim_script.clone());
+        let leaf = (reclaim_script.clone(), LeafVersion::TapScript);
+        assert!(taproot.control_block(&leaf).is_some());
+        assert_eq!(
+            to_script_pubkey(request.deposit_script.clone(), reclaim_script),
+            setup.tx.output[0].script_pubkey
+        );
+
+        request.reclaim_script = ScriptBuf::new();
+        let error = request.reclaim_output_script().unwrap_err();
+        assert!(matches!(error, Error::InvalidReclaimScript));
+    }
+
     #[test]
     fn valid_deposit_script_not_matching_tx_rejected() {
         let max_fee: u64 = 15000;
@@ -939,6 +1198,37 @@ mod tests {
         assert!(matches!(error, Error::InvalidReclaimScript));
     }
 
+    #[test]
+    fn reclaim_tx_spends_deposit_with_lock_time_sequence() {
+        let lock_time = 150;
+        let setup: TxSetup = testing::deposits::tx_setup(lock_time, 15000, 500_000);
+        let outpoint = OutPoint::new(setup.tx.compute_txid(), 0);
+
+        let inputs = ReclaimTxInputs {
+            deposit_script: setup.deposit.deposit_script(),
+            reclaim_script: setup.reclaim.reclaim_script(),
+            outpoint,
+            value: Amount::from_sat(490_000),
+            destination: ScriptBuf::new_op_return([0; 10]),
+            witness: Vec::new(),
+        };
+
+        assert_eq!(inputs.lock_time().unwrap(), lock_time);
+
+        let tx = inputs.construct_tx().unwrap();
+        assert_eq!(tx.input.len(), 1);
+        assert_eq!(tx.input[0].previous_output, outpoint);
+        assert_eq!(tx.input[0].sequence.to_consensus_u32(), lock_time);
+        // The witness is the reclaim script followed by the control block.
+        assert_eq!(tx.input[0].witness.len(), 2);
+        assert_eq!(
+            tx.input[0].witness.nth(0).unwrap(),
+            setup.reclaim.reclaim_script().as_bytes()
+        );
+        assert_eq!(tx.output[0].value, inputs.value);
+        assert_eq!(tx.output[0].script_pubkey, inputs.destination);
+    }
+
     #[test]
     fn unspendable_taproot_key_no_panic() {
         // The following function calls unwrap() when called the first
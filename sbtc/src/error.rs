@@ -21,6 +21,14 @@ pub enum Error {
     /// Length of the deposit script is necessarily too short.
     #[error("script is invalid, it is too short")]
     InvalidDepositScriptLength,
+    /// The deposit script is longer than the maximum allowed length.
+    #[error("deposit script is {len} bytes, but at most {max} bytes are allowed")]
+    ScriptTooLarge {
+        /// The length of the deposit script.
+        len: usize,
+        /// The maximum allowed length of a deposit script.
+        max: usize,
+    },
     /// The lock time included in the reclaim script was invalid. This
     /// could be because the number is out of range for an acceptable lock
     /// time, or because the 32nd bit has been set.
@@ -0,0 +1,8 @@
+-- The last stacks block that the block observer has processed, so that
+-- the block observer can reconcile it with the stacks node on startup.
+-- The table holds at most one row.
+CREATE TABLE sbtc_signer.stacks_checkpoint (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    block_hash BYTEA NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL
+);
@@ -0,0 +1,11 @@
+-- The withdrawals that were reported to Emily as accepted rather than
+-- confirmed, because their sweep transaction did not have enough
+-- confirmations yet. Each row points at the withdrawal accept event that
+-- was held back, and it is removed once the withdrawal is reported as
+-- confirmed or its sweep is no longer on the canonical bitcoin chain.
+CREATE TABLE sbtc_signer.held_withdrawals (
+    request_id BIGINT PRIMARY KEY,
+    txid BYTEA NOT NULL,
+    block_hash BYTEA NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW() NOT NULL
+);
@@ -18,14 +18,15 @@ use emily_client::models::WithdrawalParameters;
 use emily_client::models::WithdrawalUpdate;
 use futures::FutureExt;
 use std::sync::OnceLock;
+use std::time::Instant;
 
+use crate::bitcoin::BitcoinInteract as _;
 use crate::context::Context;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::stacks::events::CompletedDepositEvent;
 use crate::stacks::events::KeyRotationEvent;
 use crate::stacks::events::RegistryEvent;
-use crate::stacks::events::TxInfo;
 use crate::stacks::events::WithdrawalAcceptEvent;
 use crate::stacks::events::WithdrawalCreateEvent;
 use crate::stacks::events::WithdrawalRejectEvent;
@@ -101,16 +102,6 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
         }
     };
 
-    // Although transactions can fail, only successful transactions emit
-    // sBTC print events, since those events are emitted at the very end of
-    // the contract call.
-    let events = new_block_event
-        .events
-        .into_iter()
-        .filter(|x| x.committed)
-        .filter_map(|x| x.contract_event.map(|ev| (ev, x.txid)))
-        .filter(|(ev, _)| &ev.contract_identifier == registry_address && ev.topic == "print");
-
     let stacks_chaintip = StacksBlock {
         block_hash: StacksBlockHash::from(new_block_event.index_block_hash),
         block_height: new_block_event.block_height,
@@ -124,9 +115,8 @@ pub async fn new_block_handler(state: State<ApiState<impl Context>>, body: Strin
     let mut updated_withdrawals = Vec::new();
     let mut created_withdrawals = Vec::new();
 
-    for (ev, txid) in events {
-        let tx_info = TxInfo { txid, block_id };
-        let res = match RegistryEvent::try_new(ev.value, tx_info) {
+    for (tx_info, event) in new_block_event.registry_events(registry_address) {
+        let res = match event {
             Ok(RegistryEvent::CompletedDeposit(event)) => {
                 handle_completed_deposit(&api.ctx, event, &stacks_chaintip)
                     .await
@@ -259,11 +249,15 @@ async fn handle_completed_deposit(
     // and the amount minted in the completed deposit event.
     // This should never be negative, but we use saturating_sub just in case.
     let btc_fee = deposit_request.amount.saturating_sub(event.amount);
+    ctx.state()
+        .deposit_latencies()
+        .record_resolved(&event.outpoint, Instant::now());
 
     Ok(DepositUpdate {
         bitcoin_tx_output_index: event.outpoint.vout,
         bitcoin_txid: event.outpoint.txid.to_string(),
         status: Status::Confirmed,
+        failure_reason: None,
         fulfillment: Some(Some(Box::new(Fulfillment {
             bitcoin_block_hash: event.sweep_block_hash.to_string(),
             bitcoin_block_height: event.sweep_block_height,
@@ -289,8 +283,12 @@ async fn handle_completed_deposit(
 ///
 /// # Returns
 /// - `Result<WithdrawalUpdate, Error>`: On success, returns a `WithdrawalUpdate` struct
-///   for Emily containing relevant withdrawal information.
-///   In case of a database error, returns an `Error`
+///   for Emily containing relevant withdrawal information. If the sweep
+///   transaction has fewer confirmations than the configured
+///   `withdrawal_min_confirmations`, the withdrawal is reported as accepted
+///   rather than confirmed, and it is held back in the database so that
+///   the block observer can report it as confirmed later.
+///   In case of a database or bitcoin client error, returns an `Error`
 async fn handle_withdrawal_accept(
     ctx: &impl Context,
     event: WithdrawalAcceptEvent,
@@ -300,7 +298,42 @@ async fn handle_withdrawal_accept(
         .write_withdrawal_accept_event(&event)
         .await?;
 
-    Ok(WithdrawalUpdate {
+    if let Some(min_confirmations) = ctx.config().signer.withdrawal_min_confirmations {
+        let confirmations = ctx
+            .get_bitcoin_client()
+            .get_tx_confirmations(&event.sweep_txid)
+            .await?
+            .unwrap_or_default();
+
+        if confirmations < min_confirmations {
+            let update = WithdrawalUpdate {
+                request_id: event.request_id,
+                status: Status::Accepted,
+                fulfillment: None,
+                status_message: format!(
+                    "Awaiting {min_confirmations} confirmations of sweep transaction {}, it has {confirmations}",
+                    event.sweep_txid
+                ),
+                last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
+                last_update_height: stacks_chaintip.block_height,
+                signatures_collected: None,
+                threshold: None,
+            };
+            ctx.get_storage_mut().write_held_withdrawal(&event).await?;
+            return Ok(update);
+        }
+    }
+
+    Ok(withdrawal_confirmed_update(&event, stacks_chaintip))
+}
+
+/// Build the update for Emily that marks the withdrawal in the given
+/// accept event as confirmed.
+pub(crate) fn withdrawal_confirmed_update(
+    event: &WithdrawalAcceptEvent,
+    stacks_chaintip: &StacksBlock,
+) -> WithdrawalUpdate {
+    WithdrawalUpdate {
         request_id: event.request_id,
         status: Status::Confirmed,
         fulfillment: Some(Some(Box::new(Fulfillment {
@@ -314,7 +347,9 @@ async fn handle_withdrawal_accept(
         status_message: format!("Included in block {}", event.block_id.to_hex()),
         last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
         last_update_height: stacks_chaintip.block_height,
-    })
+        signatures_collected: None,
+        threshold: None,
+    }
 }
 
 /// Processes a withdrawal creation event, adding new withdrawal records to the
@@ -364,20 +399,56 @@ async fn handle_withdrawal_reject(
     event: WithdrawalRejectEvent,
     stacks_chaintip: &StacksBlock,
 ) -> Result<WithdrawalUpdate, Error> {
+    // The signers reject withdrawals that have sat pending for longer than
+    // the expiry window, so rejections of such requests are reported to
+    // Emily as expirations rather than failures. This must be checked
+    // before the reject event is written, since rejected requests are no
+    // longer considered expired.
+    let expired = is_expired_withdrawal(ctx, event.request_id).await?;
+
     ctx.get_storage_mut()
         .write_withdrawal_reject_event(&event)
         .await?;
 
+    let (status, status_message) = if expired {
+        (Status::Expired, "Rejected after expiring")
+    } else {
+        (Status::Failed, "Rejected")
+    };
+
     Ok(WithdrawalUpdate {
         fulfillment: None,
         last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
         last_update_height: stacks_chaintip.block_height,
         request_id: event.request_id,
-        status: Status::Failed,
-        status_message: "Rejected".to_string(),
+        status,
+        status_message: status_message.to_string(),
+        signatures_collected: None,
+        threshold: None,
     })
 }
 
+/// Whether the withdrawal request with the given ID has expired with
+/// respect to the canonical bitcoin chain tip and the configured expiry
+/// window.
+async fn is_expired_withdrawal(ctx: &impl Context, request_id: u64) -> Result<bool, Error> {
+    let config = &ctx.config().signer;
+    let Some(expiry_window) = config.withdrawal_expiry_window else {
+        return Ok(false);
+    };
+
+    let db = ctx.get_storage();
+    let Some(chain_tip) = db.get_bitcoin_canonical_chain_tip().await? else {
+        return Ok(false);
+    };
+
+    let expired = db
+        .get_expired_withdrawal_requests(&chain_tip, config.context_window, expiry_window)
+        .await?;
+
+    Ok(expired.iter().any(|req| req.request_id == request_id))
+}
+
 async fn handle_key_rotation(
     ctx: &impl Context,
     event: KeyRotationEvent,
@@ -477,7 +548,9 @@ mod tests {
                 .expect_update_deposits()
                 .times(1)
                 .returning(move |_| {
-                    Box::pin(async { Ok(UpdateDepositsResponse { deposits: vec![] }) })
+                    Box::pin(async {
+                        Ok(UpdateDepositsResponse { deposits: vec![], errors: None })
+                    })
                 });
             client
                 .expect_update_withdrawals()
@@ -571,7 +644,9 @@ mod tests {
                 .expect_update_deposits()
                 .times(1)
                 .returning(move |_| {
-                    Box::pin(async { Ok(UpdateDepositsResponse { deposits: vec![] }) })
+                    Box::pin(async {
+                        Ok(UpdateDepositsResponse { deposits: vec![], errors: None })
+                    })
                 });
             client
                 .expect_update_withdrawals()
@@ -644,6 +719,7 @@ mod tests {
             bitcoin_tx_output_index: event.outpoint.vout,
             bitcoin_txid: txid.to_string(),
             status: Status::Confirmed,
+            failure_reason: None,
             fulfillment: Some(Some(Box::new(Fulfillment {
                 bitcoin_block_hash: bitcoin_block.block_hash.to_string(),
                 bitcoin_block_height: bitcoin_block.block_height,
@@ -774,6 +850,8 @@ mod tests {
             status_message: format!("Included in block {}", event.block_id.to_hex()),
             last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
             last_update_height: stacks_chaintip.block_height,
+            signatures_collected: None,
+            threshold: None,
         };
         let res = handle_withdrawal_accept(&ctx, event, stacks_chaintip).await;
 
@@ -787,6 +865,80 @@ mod tests {
             .is_some());
     }
 
+    /// Tests that a withdrawal is only reported as confirmed once its
+    /// sweep transaction has the configured number of confirmations.
+    #[tokio::test]
+    async fn withdrawal_accept_waits_for_sweep_confirmations() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.withdrawal_min_confirmations = Some(6);
+
+        let mut ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let test_params = crate::testing::storage::model::Params {
+            num_bitcoin_blocks: 2,
+            num_stacks_blocks_per_bitcoin_block: 1,
+            num_deposit_requests_per_block: 2,
+            num_withdraw_requests_per_block: 2,
+            num_signers_per_request: 0,
+        };
+        let test_data = TestData::generate(&mut rng, &[], &test_params);
+
+        let txid = test_data.bitcoin_transactions[0].txid;
+        let stacks_tx = &test_data.stacks_transactions[0];
+        let bitcoin_block = &test_data.bitcoin_blocks[0];
+        let stacks_chaintip = test_data
+            .stacks_blocks
+            .last()
+            .expect("STX block generation failed");
+
+        let event = WithdrawalAcceptEvent {
+            request_id: 1,
+            outpoint: OutPoint { txid: *txid, vout: 0 },
+            txid: *stacks_tx.txid,
+            block_id: *stacks_tx.block_hash,
+            fee: 1,
+            signer_bitmap: BitArray::<_>::ZERO,
+            sweep_block_hash: *bitcoin_block.block_hash,
+            sweep_block_height: bitcoin_block.block_height,
+            sweep_txid: *txid,
+        };
+
+        // The sweep starts out with two confirmations and has six by the
+        // time the withdrawal is handled again.
+        let confirmations = std::sync::Arc::new(std::sync::Mutex::new(vec![6, 2]));
+        ctx.with_bitcoin_client(|client| {
+            client
+                .expect_get_tx_confirmations()
+                .times(2)
+                .returning(move |_| {
+                    let next = confirmations.lock().unwrap().pop();
+                    Box::pin(async move { Ok(next) })
+                });
+        })
+        .await;
+
+        let update = handle_withdrawal_accept(&ctx, event.clone(), stacks_chaintip)
+            .await
+            .unwrap();
+        assert_eq!(update.status, Status::Accepted);
+        assert_eq!(update.fulfillment, None);
+        let held = ctx.get_storage().get_held_withdrawals().await.unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].request_id, event.request_id);
+
+        let update = handle_withdrawal_accept(&ctx, event, stacks_chaintip)
+            .await
+            .unwrap();
+        assert_eq!(update.status, Status::Confirmed);
+        assert!(update.fulfillment.is_some());
+    }
+
     /// Tests handling of a withdrawal request.
     /// This test confirms that when a withdrawal is created, the system updates
     /// the database correctly and returns the expected response.
@@ -890,6 +1042,8 @@ mod tests {
             last_update_block_hash: stacks_chaintip.block_hash.to_hex(),
             last_update_height: stacks_chaintip.block_height,
             status_message: "Rejected".to_string(),
+            signatures_collected: None,
+            threshold: None,
         };
 
         let res = handle_withdrawal_reject(&ctx, event, stacks_chaintip).await;
@@ -19,6 +19,8 @@ use bitcoin::Txid;
 use bitcoincore_rpc_json::GetTxOutResult;
 use url::Url;
 
+use crate::config::BitcoinConfig;
+use crate::util::RetryPolicy;
 use crate::{error::Error, util::ApiFallbackClient};
 
 use super::rpc::BitcoinCoreClient;
@@ -41,17 +43,53 @@ impl TryFrom<&[Url]> for ApiFallbackClient<BitcoinCoreClient> {
     }
 }
 
+/// Create the [`ApiFallbackClient`] for the configured bitcoin-core nodes,
+/// retrying reads according to the configured [`RetryPolicy`].
+impl TryFrom<&BitcoinConfig> for ApiFallbackClient<BitcoinCoreClient> {
+    type Error = Error;
+    fn try_from(config: &BitcoinConfig) -> Result<Self, Self::Error> {
+        let client = Self::try_from(config.rpc_endpoints.as_slice())?;
+        Ok(client.with_retry_policy(RetryPolicy::from_config(config)))
+    }
+}
+
+impl ApiFallbackClient<BitcoinCoreClient> {
+    /// Check that the connected bitcoin-core node is running on the
+    /// expected network. See [`BitcoinCoreClient::check_network`].
+    pub async fn check_network(&self, expected: bitcoin::Network) -> Result<(), Error> {
+        self.exec(|client, retry| async move {
+            let result = client.check_network(expected);
+            // A node on the wrong network is not a transient failure, so
+            // there is no point in retrying the check.
+            retry.abort_if(|| matches!(result, Err(Error::BitcoinNetworkMismatch { .. })));
+            result
+        })
+        .await
+    }
+}
+
+/// Reads are retried on the same bitcoin-core node according to the
+/// [`RetryPolicy`](crate::util::RetryPolicy) of the client before failing
+/// over, so that a network blip does not cause a failover.
 impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
     async fn get_block(
         &self,
         block_hash: &bitcoin::BlockHash,
     ) -> Result<Option<bitcoin::Block>, Error> {
-        self.exec(|client, _| async { client.get_block(block_hash) })
+        self.exec_idempotent(|client, _| async { client.get_block(block_hash) })
+            .await
+    }
+
+    async fn get_block_header(
+        &self,
+        block_hash: &bitcoin::BlockHash,
+    ) -> Result<Option<bitcoin::block::Header>, Error> {
+        self.exec_idempotent(|client, _| async { client.get_block_header(block_hash) })
             .await
     }
 
     async fn get_tx(&self, txid: &Txid) -> Result<Option<GetTxResponse>, Error> {
-        self.exec(|client, _| BitcoinInteract::get_tx(client, txid))
+        self.exec_idempotent(|client, _| BitcoinInteract::get_tx(client, txid))
             .await
     }
 
@@ -60,13 +98,13 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         txid: &Txid,
         block_hash: &BlockHash,
     ) -> Result<Option<BitcoinTxInfo>, Error> {
-        self.exec(|client, _| BitcoinInteract::get_tx_info(client, txid, block_hash))
+        self.exec_idempotent(|client, _| BitcoinInteract::get_tx_info(client, txid, block_hash))
             .await
     }
 
     async fn estimate_fee_rate(&self) -> Result<f64, Error> {
         // TODO(542)
-        self.exec(|client, _| BitcoinInteract::estimate_fee_rate(client))
+        self.exec_idempotent(|client, _| BitcoinInteract::estimate_fee_rate(client))
             .await
     }
 
@@ -79,12 +117,21 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         &self,
         outpoint: &bitcoin::OutPoint,
     ) -> Result<Vec<Txid>, Error> {
-        self.exec(|client, _| client.find_mempool_transactions_spending_output(outpoint))
+        self.exec_idempotent(|client, _| client.find_mempool_transactions_spending_output(outpoint))
             .await
     }
 
     async fn find_mempool_descendants(&self, txid: &Txid) -> Result<Vec<Txid>, Error> {
-        self.exec(|client, _| client.find_mempool_descendants(txid))
+        self.exec_idempotent(|client, _| client.find_mempool_descendants(txid))
+            .await
+    }
+
+    async fn find_replacement_tx(
+        &self,
+        txid: &Txid,
+        funding_inputs: &[bitcoin::OutPoint],
+    ) -> Result<Option<Txid>, Error> {
+        self.exec_idempotent(|client, _| async { client.find_replacement_tx(txid, funding_inputs) })
             .await
     }
 
@@ -93,7 +140,7 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         outpoint: &bitcoin::OutPoint,
         include_mempool: bool,
     ) -> Result<Option<GetTxOutResult>, Error> {
-        self.exec(|client, _| client.get_transaction_output(outpoint, include_mempool))
+        self.exec_idempotent(|client, _| client.get_transaction_output(outpoint, include_mempool))
             .await
     }
 
@@ -102,7 +149,12 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         txid: &bitcoin::Txid,
         lookup_hint: Option<TransactionLookupHint>,
     ) -> Result<super::GetTransactionFeeResult, Error> {
-        self.exec(|client, _| client.get_transaction_fee(txid, lookup_hint))
+        self.exec_idempotent(|client, _| client.get_transaction_fee(txid, lookup_hint))
+            .await
+    }
+
+    async fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        self.exec_idempotent(|client, _| async { client.get_raw_mempool() })
             .await
     }
 
@@ -110,7 +162,7 @@ impl BitcoinInteract for ApiFallbackClient<BitcoinCoreClient> {
         &self,
         txid: &Txid,
     ) -> Result<Option<bitcoincore_rpc_json::GetMempoolEntryResult>, Error> {
-        self.exec(|client, _| async { client.get_mempool_entry(txid) })
+        self.exec_idempotent(|client, _| async { client.get_mempool_entry(txid) })
             .await
     }
 }
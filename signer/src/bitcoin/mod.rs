@@ -9,6 +9,7 @@ use bitcoincore_rpc_json::GetMempoolEntryResult;
 use bitcoincore_rpc_json::GetTxOutResult;
 use rpc::BitcoinTxInfo;
 use rpc::GetTxResponse;
+use sbtc::deposits::ReclaimTxInputs;
 
 use crate::error::Error;
 
@@ -31,6 +32,17 @@ pub struct GetTransactionFeeResult {
     pub vsize: u64,
 }
 
+/// An unspent transaction output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utxo {
+    /// The outpoint of the UTXO.
+    pub outpoint: bitcoin::OutPoint,
+    /// The amount locked in the UTXO.
+    pub amount: bitcoin::Amount,
+    /// The scriptPubKey locking the UTXO.
+    pub script_pubkey: bitcoin::ScriptBuf,
+}
+
 /// An enum representing the possible locations of a transaction, used to
 /// optimize certain lookups. It is assumed that an
 /// `Option<TransactionLookupHint>` is used to indicate that the caller is
@@ -52,6 +64,13 @@ pub trait BitcoinInteract: Sync + Send {
         block_hash: &BlockHash,
     ) -> impl Future<Output = Result<Option<bitcoin::Block>, Error>> + Send;
 
+    /// Get the header of the block with the given hash, without fetching
+    /// the transactions in the block.
+    fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> impl Future<Output = Result<Option<bitcoin::block::Header>, Error>> + Send;
+
     /// get tx
     fn get_tx(
         &self,
@@ -116,6 +135,41 @@ pub trait BitcoinInteract: Sync + Send {
         include_mempool: bool,
     ) -> impl Future<Output = Result<Option<GetTxOutResult>, Error>> + Send;
 
+    /// Get the unspent output with the given outpoint, if it exists and
+    /// has not been spent by a confirmed transaction.
+    ///
+    /// The default implementation uses
+    /// [`BitcoinInteract::get_transaction_output`], without including
+    /// transactions from the mempool.
+    fn get_utxo(
+        &self,
+        outpoint: &bitcoin::OutPoint,
+    ) -> impl Future<Output = Result<Option<Utxo>, Error>> + Send {
+        async move {
+            let tx_out = self.get_transaction_output(outpoint, false).await?;
+            Ok(tx_out.map(|tx_out| Utxo {
+                outpoint: *outpoint,
+                amount: tx_out.value,
+                script_pubkey: bitcoin::ScriptBuf::from_bytes(tx_out.script_pub_key.hex),
+            }))
+        }
+    }
+
+    /// Get the number of confirmations of the transaction with the given
+    /// txid, or `None` if the transaction is unknown. Transactions in the
+    /// mempool have zero confirmations.
+    ///
+    /// The default implementation uses [`BitcoinInteract::get_tx`].
+    fn get_tx_confirmations(
+        &self,
+        txid: &Txid,
+    ) -> impl Future<Output = Result<Option<u32>, Error>> + Send {
+        async move {
+            let response = self.get_tx(txid).await?;
+            Ok(response.map(|response| response.confirmations.unwrap_or_default()))
+        }
+    }
+
     /// Gets the associated fees for the given transaction. It is expected that
     /// the provided transaction is known to the Bitcoin core node, either
     /// confirmed or in the mempool, otherwise an error will be returned.
@@ -125,9 +179,213 @@ pub trait BitcoinInteract: Sync + Send {
         lookup_hint: Option<TransactionLookupHint>,
     ) -> impl Future<Output = Result<GetTransactionFeeResult, Error>> + Send;
 
+    /// Get the transaction IDs of all transactions in the mempool.
+    fn get_raw_mempool(&self) -> impl Future<Output = Result<Vec<Txid>, Error>> + Send;
+
     /// Attempts to get the mempool entry for the given transaction ID.
     fn get_mempool_entry(
         &self,
         txid: &Txid,
     ) -> impl Future<Output = Result<Option<GetMempoolEntryResult>, Error>> + Send;
+
+    /// Get the transactions, with additional information about them, for
+    /// each of the given transaction IDs and block hashes.
+    ///
+    /// The returned vector has the same length and order as the given
+    /// queries, with `None` in the position of each transaction that could
+    /// not be found. The default implementation makes one
+    /// [`BitcoinInteract::get_tx_info`] call for each query.
+    fn get_txs_info(
+        &self,
+        queries: &[(Txid, BlockHash)],
+    ) -> impl Future<Output = Result<Vec<Option<BitcoinTxInfo>>, Error>> + Send {
+        async move {
+            let mut infos = Vec::with_capacity(queries.len());
+            for (txid, block_hash) in queries {
+                infos.push(self.get_tx_info(txid, block_hash).await?);
+            }
+            Ok(infos)
+        }
+    }
+
+    /// Find the transaction that replaced the transaction with the given
+    /// txid using replace-by-fee, if there is one. That is, a transaction
+    /// other than the given one that spends one of its `funding_inputs`.
+    ///
+    /// The inputs of a transaction that has been replaced cannot be looked
+    /// up once it has been evicted from the mempool, so they are passed in
+    /// by the caller, and may be empty when they are not known. An
+    /// [`Error::FundingInputsSpent`] error is returned when the funding
+    /// inputs were spent, but no replacement can be found, since then the
+    /// transaction will never be confirmed. The default implementation
+    /// never finds any replacements.
+    fn find_replacement_tx(
+        &self,
+        _txid: &Txid,
+        _funding_inputs: &[bitcoin::OutPoint],
+    ) -> impl Future<Output = Result<Option<Txid>, Error>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Construct a transaction reclaiming the deposit described by the
+    /// given inputs and broadcast it, returning the broadcast transaction.
+    ///
+    /// The transaction is only broadcast if the lock time in the reclaim
+    /// script has elapsed. That is, the funding transaction must be
+    /// confirmed and the next block must be at least `lock_time` blocks
+    /// after the block that confirmed it, otherwise a
+    /// [`Error::ReclaimLockTimeNotElapsed`] error is returned.
+    fn reclaim_deposit(
+        &self,
+        inputs: &ReclaimTxInputs,
+    ) -> impl Future<Output = Result<bitcoin::Transaction, Error>> + Send {
+        async move {
+            let outpoint = inputs.outpoint;
+            let lock_time = inputs.lock_time()?;
+            let funding_tx = self
+                .get_tx(&outpoint.txid)
+                .await?
+                .ok_or(Error::BitcoinTxMissing(outpoint.txid, None))?;
+
+            // The number of confirmations is one more than the difference
+            // between the current height and the height of the block that
+            // confirmed the funding transaction, so it is the height
+            // difference for a transaction included in the next block.
+            let confirmations = funding_tx.confirmations.unwrap_or_default();
+            if confirmations < lock_time {
+                return Err(Error::ReclaimLockTimeNotElapsed {
+                    outpoint,
+                    confirmations,
+                    lock_time,
+                });
+            }
+
+            let tx = inputs.construct_tx()?;
+            self.broadcast_transaction(&tx).await?;
+            Ok(tx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::Amount;
+    use bitcoin::OutPoint;
+    use bitcoin::ScriptBuf;
+    use rand::SeedableRng as _;
+
+    use crate::testing::block_observer::TestHarness;
+
+    use super::*;
+
+    const LOCK_TIME: u32 = 10;
+
+    /// Create a test harness holding a deposit transaction with the given
+    /// number of confirmations, along with the inputs needed to reclaim
+    /// that deposit.
+    fn reclaim_setup(confirmations: u32) -> (TestHarness, ReclaimTxInputs) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = TestHarness::generate(&mut rng, 1, 0..1);
+
+        let setup = sbtc::testing::deposits::tx_setup(LOCK_TIME, 1000, 100_000);
+        let txid = setup.tx.compute_txid();
+        let response = GetTxResponse {
+            tx: setup.tx,
+            block_hash: Some(BlockHash::all_zeros()),
+            confirmations: Some(confirmations),
+            block_time: None,
+        };
+        harness.add_deposit(txid, response);
+
+        let inputs = ReclaimTxInputs {
+            deposit_script: setup.deposit.deposit_script(),
+            reclaim_script: setup.reclaim.reclaim_script(),
+            outpoint: OutPoint::new(txid, 0),
+            value: Amount::from_sat(99_000),
+            destination: ScriptBuf::new_op_return([1; 20]),
+            witness: Vec::new(),
+        };
+
+        (harness, inputs)
+    }
+
+    #[tokio::test]
+    async fn get_txs_info_preserves_query_order() {
+        let (mut harness, inputs) = reclaim_setup(LOCK_TIME);
+        let known_txid1 = inputs.outpoint.txid;
+
+        let setup = sbtc::testing::deposits::tx_setup(LOCK_TIME, 2000, 200_000);
+        let known_txid2 = setup.tx.compute_txid();
+        let response = GetTxResponse {
+            tx: setup.tx,
+            block_hash: Some(BlockHash::all_zeros()),
+            confirmations: Some(1),
+            block_time: None,
+        };
+        harness.add_deposit(known_txid2, response);
+
+        let unknown_txid = Txid::from_byte_array([1; 32]);
+        let block_hash = BlockHash::all_zeros();
+        let queries = [
+            (unknown_txid, block_hash),
+            (known_txid1, block_hash),
+            (unknown_txid, block_hash),
+            (known_txid2, block_hash),
+        ];
+
+        let infos = harness.get_txs_info(&queries).await.unwrap();
+        assert_eq!(infos.len(), queries.len());
+
+        let txids: Vec<Option<Txid>> = infos.iter().map(|x| x.as_ref().map(|x| x.txid)).collect();
+        assert_eq!(txids, [None, Some(known_txid1), None, Some(known_txid2)]);
+    }
+
+    #[tokio::test]
+    async fn reclaim_before_lock_time_elapses_is_refused() {
+        let (harness, inputs) = reclaim_setup(LOCK_TIME - 1);
+
+        let result = harness.reclaim_deposit(&inputs).await;
+        match result {
+            Err(Error::ReclaimLockTimeNotElapsed { confirmations, lock_time, .. }) => {
+                assert_eq!(confirmations, LOCK_TIME - 1);
+                assert_eq!(lock_time, LOCK_TIME);
+            }
+            result => panic!("expected the reclaim to be refused, got {result:?}"),
+        }
+
+        assert!(harness.broadcast_transactions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reclaim_after_lock_time_elapses_is_broadcast() {
+        let (harness, inputs) = reclaim_setup(LOCK_TIME);
+
+        let tx = harness.reclaim_deposit(&inputs).await.unwrap();
+
+        let broadcast = harness.broadcast_transactions();
+        assert_eq!(broadcast, vec![tx.clone()]);
+        assert_eq!(tx.input[0].previous_output, inputs.outpoint);
+        assert_eq!(tx.output[0].value, inputs.value);
+    }
+
+    #[tokio::test]
+    async fn get_utxo_returns_known_outputs_only() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = TestHarness::generate(&mut rng, 1, 0..1);
+
+        let utxo = Utxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), 2),
+            amount: Amount::from_sat(50_000),
+            script_pubkey: ScriptBuf::new_op_return([2; 20]),
+        };
+        harness.add_utxo(utxo.clone());
+
+        let present = harness.get_utxo(&utxo.outpoint).await.unwrap();
+        assert_eq!(present, Some(utxo.clone()));
+
+        let absent_outpoint = OutPoint::new(utxo.outpoint.txid, 3);
+        let absent = harness.get_utxo(&absent_outpoint).await.unwrap();
+        assert!(absent.is_none());
+    }
 }
@@ -1,14 +1,18 @@
 //! Contains client wrappers for bitcoin core and electrum.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use bitcoin::Address;
 use bitcoin::Amount;
 use bitcoin::Block;
 use bitcoin::BlockHash;
+use bitcoin::CompressedPublicKey;
 use bitcoin::Denomination;
 use bitcoin::OutPoint;
 use bitcoin::ScriptBuf;
 use bitcoin::Transaction;
+use bitcoin::TxIn;
 use bitcoin::Txid;
 use bitcoin::Wtxid;
 use bitcoincore_rpc::json::EstimateMode;
@@ -57,6 +61,55 @@ pub struct GetTxResponse {
     pub block_time: Option<u64>,
 }
 
+impl GetTxResponse {
+    /// Return the addresses that funded this transaction, as far as they
+    /// can be derived from the transaction inputs alone.
+    ///
+    /// The addresses of P2PKH, P2WPKH and P2SH-P2WPKH inputs are derived
+    /// from the public key revealed when spending them. Inputs of any
+    /// other type are skipped, so the returned set is empty if none of
+    /// the inputs are standard key-hash spends.
+    pub fn funding_addresses(&self, network: bitcoin::Network) -> HashSet<Address> {
+        self.tx
+            .input
+            .iter()
+            .filter_map(|tx_in| input_address(tx_in, network))
+            .collect()
+    }
+}
+
+/// Derive the address of the output spent by the given input, if the
+/// output was a standard key-hash output.
+fn input_address(tx_in: &TxIn, network: bitcoin::Network) -> Option<Address> {
+    let witness: Vec<&[u8]> = tx_in.witness.iter().collect();
+    let script_sig: Vec<&[u8]> = tx_in
+        .script_sig
+        .instructions()
+        .map(|instruction| instruction.ok()?.push_bytes().map(|bytes| bytes.as_bytes()))
+        .collect::<Option<_>>()?;
+
+    match (script_sig.as_slice(), witness.as_slice()) {
+        // P2WPKH inputs have an empty scriptSig and a witness with a
+        // signature and a compressed public key.
+        ([], [_, public_key]) => {
+            let public_key = CompressedPublicKey::from_slice(public_key).ok()?;
+            Some(Address::p2wpkh(&public_key, network))
+        }
+        // P2SH-P2WPKH inputs push the P2WPKH script as the redeem script.
+        ([redeem_script], [_, public_key]) => {
+            let public_key = CompressedPublicKey::from_slice(public_key).ok()?;
+            let expected = ScriptBuf::new_p2wpkh(&public_key.wpubkey_hash());
+            (*redeem_script == expected.as_bytes()).then(|| Address::p2shwpkh(&public_key, network))
+        }
+        // P2PKH inputs push a signature and a public key in the scriptSig.
+        ([_, public_key], []) => {
+            let public_key = bitcoin::PublicKey::from_slice(public_key).ok()?;
+            Some(Address::p2pkh(public_key, network))
+        }
+        _ => None,
+    }
+}
+
 /// A struct containing the response from bitcoin-core for a
 /// `getrawtransaction` RPC where verbose is set to 2 where the block hash
 /// is supplied as an RPC argument.
@@ -231,6 +284,23 @@ pub struct PrevoutScriptPubKey {
     pub script: ScriptBuf,
 }
 
+/// A slimmed down type representing a response from bitcoin-core's
+/// getblockchaininfo RPC.
+///
+/// The docs for the getblockchaininfo RPC call can be found here:
+/// <https://bitcoincore.org/en/doc/25.0.0/rpc/blockchain/getblockchaininfo/>.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetBlockchainInfoResponse {
+    /// The current network name, in the format used by bitcoin-core. This
+    /// is one of "main", "test", "testnet4", "signet" or "regtest".
+    pub chain: String,
+    /// The height of the most-work fully-validated chain.
+    pub blocks: u64,
+    /// The hash of the currently best block.
+    #[serde(rename = "bestblockhash")]
+    pub best_block_hash: BlockHash,
+}
+
 /// A struct representing the recommended fee, in sats per vbyte, from a
 /// particular source.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -239,6 +309,11 @@ pub struct FeeEstimate {
     pub sats_per_vbyte: f64,
 }
 
+/// The number of blocks, starting at the chain tip, that
+/// [`BitcoinCoreClient::find_replacement_tx`] searches for a confirmed
+/// transaction spending one of the given funding inputs.
+pub const REPLACEMENT_SEARCH_DEPTH: u64 = 6;
+
 /// A client for interacting with bitcoin-core
 #[derive(Debug, Clone)]
 pub struct BitcoinCoreClient {
@@ -294,6 +369,19 @@ impl BitcoinCoreClient {
         }
     }
 
+    /// Fetch the header of the block identified by the given block hash
+    /// using the getblockheader RPC.
+    pub fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<bitcoin::block::Header>, Error> {
+        match self.inner.get_block_header(block_hash) {
+            Ok(header) => Ok(Some(header)),
+            Err(BtcRpcError::JsonRpc(JsonRpcError::Rpc(RpcError { code: -5, .. }))) => Ok(None),
+            Err(error) => Err(Error::BitcoinCoreGetBlockHeader(error, *block_hash)),
+        }
+    }
+
     /// Fetch and decode raw transaction from bitcoin-core using the
     /// getrawtransaction RPC with a verbosity of 1. None is returned if
     /// the node cannot find the transaction in a bitcoin block or the
@@ -518,6 +606,31 @@ impl BitcoinCoreClient {
         Ok(FeeEstimate { sats_per_vbyte })
     }
 
+    /// Fetch general information about the state of the blockchain from
+    /// bitcoin-core using the `getblockchaininfo` RPC.
+    pub fn get_blockchain_info(&self) -> Result<GetBlockchainInfoResponse, Error> {
+        self.inner
+            .call::<GetBlockchainInfoResponse>("getblockchaininfo", &[])
+            .map_err(Error::BitcoinCoreGetBlockchainInfo)
+    }
+
+    /// Check that the connected bitcoin-core node is running on the
+    /// expected network, returning an [`Error::BitcoinNetworkMismatch`]
+    /// error if it is not.
+    pub fn check_network(&self, expected: bitcoin::Network) -> Result<(), Error> {
+        let info = self.get_blockchain_info()?;
+        let expected = expected.to_core_arg();
+
+        if info.chain != expected {
+            return Err(Error::BitcoinNetworkMismatch {
+                expected: expected.to_string(),
+                actual: info.chain,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Gets mempool data for the given transaction id. If the transaction was
     /// not found in the mempool, `None` is returned.
     ///
@@ -530,6 +643,74 @@ impl BitcoinCoreClient {
             Err(err) => Err(Error::BitcoinCoreRpc(err)),
         }
     }
+
+    /// Find a transaction, other than the one with the given txid, that
+    /// spends one of the given funding inputs. The mempool is searched
+    /// first, using the `gettxspendingprevout` RPC, followed by the last
+    /// [`REPLACEMENT_SEARCH_DEPTH`] blocks of the best chain.
+    ///
+    /// # Notes
+    ///
+    /// Bitcoin-core does not index confirmed transactions by the outputs
+    /// that they spend, so blocks are only scanned for funding inputs that
+    /// are no longer in the UTXO set, and a replacement confirmed deeper
+    /// than [`REPLACEMENT_SEARCH_DEPTH`] blocks is not found.
+    ///
+    /// This is meant for transactions that are not confirmed. When their
+    /// funding inputs were spent deeper than the search goes, they were
+    /// spent by some other transaction, so the transaction can never be
+    /// confirmed, and an [`Error::FundingInputsSpent`] error is returned.
+    /// This way callers can stop looking for a replacement, rather than
+    /// scanning the same blocks for as long as the transaction is around.
+    pub fn find_replacement_tx(
+        &self,
+        txid: &Txid,
+        funding_inputs: &[OutPoint],
+    ) -> Result<Option<Txid>, Error> {
+        for outpoint in funding_inputs {
+            let spenders = self.get_tx_spending_prevout(outpoint)?;
+            if let Some(spender) = spenders.into_iter().find(|spender| spender != txid) {
+                return Ok(Some(spender));
+            }
+        }
+
+        let mut spent = HashSet::new();
+        for outpoint in funding_inputs {
+            if self.get_tx_out(outpoint, false)?.is_none() {
+                spent.insert(*outpoint);
+            }
+        }
+        if spent.is_empty() {
+            return Ok(None);
+        }
+
+        let mut block_hash = self.get_blockchain_info()?.best_block_hash;
+        for _ in 0..REPLACEMENT_SEARCH_DEPTH {
+            let Some(block) = self.get_block(&block_hash)? else {
+                return Ok(None);
+            };
+            let spender = block.txdata.iter().find(|tx| {
+                tx.input
+                    .iter()
+                    .any(|tx_in| spent.contains(&tx_in.previous_output))
+            });
+            if let Some(spender) = spender {
+                let spender_txid = spender.compute_txid();
+                return Ok((spender_txid != *txid).then_some(spender_txid));
+            }
+            block_hash = block.header.prev_blockhash;
+        }
+
+        Err(Error::FundingInputsSpent(*txid))
+    }
+
+    /// Gets the transaction IDs of all transactions in the mempool.
+    ///
+    /// Documentation for the `getrawmempool` RPC call can be found here:
+    /// https://bitcoincore.org/en/doc/25.0.0/rpc/blockchain/getrawmempool/
+    pub fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        self.inner.get_raw_mempool().map_err(Error::BitcoinCoreRpc)
+    }
 }
 
 impl BitcoinInteract for BitcoinCoreClient {
@@ -544,6 +725,13 @@ impl BitcoinInteract for BitcoinCoreClient {
         self.get_block(block_hash)
     }
 
+    async fn get_block_header(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Option<bitcoin::block::Header>, Error> {
+        self.get_block_header(block_hash)
+    }
+
     async fn get_tx(&self, txid: &Txid) -> Result<Option<GetTxResponse>, Error> {
         self.get_tx(txid)
     }
@@ -575,6 +763,14 @@ impl BitcoinInteract for BitcoinCoreClient {
         self.get_mempool_descendants(txid)
     }
 
+    async fn find_replacement_tx(
+        &self,
+        txid: &Txid,
+        funding_inputs: &[OutPoint],
+    ) -> Result<Option<Txid>, Error> {
+        self.find_replacement_tx(txid, funding_inputs)
+    }
+
     async fn get_transaction_output(
         &self,
         outpoint: &OutPoint,
@@ -659,7 +855,112 @@ impl BitcoinInteract for BitcoinCoreClient {
         Ok(GetTransactionFeeResult { fee, fee_rate, vsize })
     }
 
+    async fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        self.get_raw_mempool()
+    }
+
     async fn get_mempool_entry(&self, txid: &Txid) -> Result<Option<GetMempoolEntryResult>, Error> {
         self.get_mempool_entry(txid)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+
+    use super::*;
+
+    /// Return a mock JSON-RPC response for the getblockchaininfo RPC with
+    /// the given chain.
+    fn blockchain_info_body(chain: &str) -> String {
+        serde_json::json!({
+            "result": {
+                "chain": chain,
+                "blocks": 100,
+                "bestblockhash": BlockHash::all_zeros(),
+            },
+            "error": null,
+            "id": 1,
+        })
+        .to_string()
+    }
+
+    fn mock_node(chain: &str) -> (mockito::ServerGuard, mockito::Mock) {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"method": "getblockchaininfo"}"#.to_string(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(blockchain_info_body(chain))
+            .expect(1)
+            .create();
+
+        (server, mock)
+    }
+
+    #[test_case::test_case("main", bitcoin::Network::Bitcoin; "mainnet")]
+    #[test_case::test_case("test", bitcoin::Network::Testnet; "testnet")]
+    #[test_case::test_case("regtest", bitcoin::Network::Regtest; "regtest")]
+    fn check_network_accepts_matching_chain(chain: &str, network: bitcoin::Network) {
+        let (server, mock) = mock_node(chain);
+        let client = BitcoinCoreClient::new(&server.url(), "user".into(), "pass".into()).unwrap();
+
+        client.check_network(network).unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn check_network_rejects_wrong_chain() {
+        let (server, mock) = mock_node("main");
+        let client = BitcoinCoreClient::new(&server.url(), "user".into(), "pass".into()).unwrap();
+
+        match client.check_network(bitcoin::Network::Regtest) {
+            Err(Error::BitcoinNetworkMismatch { expected, actual }) => {
+                assert_eq!(expected, "regtest");
+                assert_eq!(actual, "main");
+            }
+            result => panic!("expected a network mismatch error, got {result:?}"),
+        }
+        mock.assert();
+    }
+
+    #[test]
+    fn funding_addresses_extracts_p2wpkh_inputs() {
+        let secret_key = secp256k1::SecretKey::from_slice(&[3; 32]).unwrap();
+        let public_key = CompressedPublicKey(secret_key.public_key(secp256k1::SECP256K1));
+
+        let p2wpkh_input = TxIn {
+            witness: bitcoin::Witness::from_slice(&[vec![1; 72], public_key.to_bytes().to_vec()]),
+            ..Default::default()
+        };
+        // A taproot key-spend input does not reveal enough to derive the
+        // address of the output that it spends.
+        let p2tr_input = TxIn {
+            witness: bitcoin::Witness::from_slice(&[vec![2; 64]]),
+            ..Default::default()
+        };
+        let mut response = GetTxResponse {
+            tx: Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: bitcoin::absolute::LockTime::ZERO,
+                input: vec![p2wpkh_input, p2tr_input.clone()],
+                output: Vec::new(),
+            },
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+
+        let addresses = response.funding_addresses(bitcoin::Network::Regtest);
+        let expected = Address::p2wpkh(&public_key, bitcoin::Network::Regtest);
+        assert_eq!(addresses, HashSet::from([expected]));
+
+        response.tx.input = vec![p2tr_input];
+        assert!(response
+            .funding_addresses(bitcoin::Network::Regtest)
+            .is_empty());
+    }
+}
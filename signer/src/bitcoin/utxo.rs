@@ -36,6 +36,7 @@ use serde::Serialize;
 use crate::bitcoin::packaging::compute_optimal_packages;
 use crate::bitcoin::packaging::Weighted;
 use crate::bitcoin::rpc::BitcoinTxInfo;
+use crate::config::OutputOrdering;
 use crate::context::SbtcLimits;
 use crate::error::Error;
 use crate::keys::SignerScriptPubKey as _;
@@ -118,6 +119,148 @@ pub struct SignerBtcState {
     /// Two byte prefix for BTC transactions that are related to the Stacks
     /// blockchain.
     pub magic_bytes: [u8; 2],
+    /// The order of the withdrawal outputs of the transactions.
+    pub output_ordering: OutputOrdering,
+}
+
+/// The maximum number of subsets that the branch-and-bound selection
+/// strategy will explore before settling on the best selection it has
+/// found so far.
+const BRANCH_AND_BOUND_MAX_TRIES: usize = 100_000;
+
+/// The strategy used for choosing which deposit UTXOs get swept when the
+/// total amount of the candidates exceeds the amount that may be minted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// Take the inputs in the order that they were given, which is
+    /// expected to be oldest first, skipping any input that would push
+    /// the total over the target. This helps consolidate small deposits
+    /// that would otherwise keep getting passed over.
+    #[default]
+    OldestFirst,
+    /// Take the inputs with the largest amounts first, skipping any input
+    /// that would push the total over the target. This minimizes the
+    /// number of inputs in the sweep transaction.
+    LargestFirst,
+    /// Search for the subset of inputs whose total is as close to the
+    /// target as possible without exceeding it. This minimizes the amount
+    /// that is left over.
+    BranchAndBound,
+}
+
+impl SelectionStrategy {
+    /// Select the inputs to use given their amounts and the maximum total
+    /// amount that may be selected.
+    ///
+    /// The returned indices are into the given `amounts` slice and are in
+    /// ascending order.
+    pub fn select(&self, amounts: &[u64], target: u64) -> Vec<usize> {
+        let mut selected = match self {
+            SelectionStrategy::OldestFirst => first_fit(amounts, 0..amounts.len(), target),
+            SelectionStrategy::LargestFirst => first_fit(amounts, largest_first(amounts), target),
+            SelectionStrategy::BranchAndBound => branch_and_bound(amounts, target),
+        };
+        selected.sort_unstable();
+        selected
+    }
+}
+
+/// Return the indices of the given amounts, ordered from the largest
+/// amount to the smallest.
+fn largest_first(amounts: &[u64]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..amounts.len()).collect();
+    order.sort_by_key(|&index| std::cmp::Reverse(amounts[index]));
+    order
+}
+
+/// Walk the amounts in the given order, taking each one that still fits
+/// under the target.
+fn first_fit<I>(amounts: &[u64], order: I, target: u64) -> Vec<usize>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut total: u64 = 0;
+    let mut selected = Vec::new();
+    for index in order {
+        match total.checked_add(amounts[index]) {
+            Some(new_total) if new_total <= target => {
+                total = new_total;
+                selected.push(index);
+            }
+            _ => {}
+        }
+    }
+    selected
+}
+
+/// Search for the subset of amounts with the largest total that does not
+/// exceed the target.
+///
+/// The search is a depth-first walk over the amounts sorted from largest
+/// to smallest, pruning any branch that cannot beat the best total found
+/// so far. If the search gives up after [`BRANCH_AND_BOUND_MAX_TRIES`]
+/// then the best selection found so far is returned, and that is never
+/// worse than the largest-first selection.
+fn branch_and_bound(amounts: &[u64], target: u64) -> Vec<usize> {
+    let order = largest_first(amounts);
+    let fallback = first_fit(amounts, order.iter().copied(), target);
+
+    // The candidates that can be selected at all, along with the sum of
+    // the amounts from each position onward, which bounds how much any
+    // branch can still add.
+    let candidates: Vec<usize> = order
+        .into_iter()
+        .filter(|&index| amounts[index] <= target)
+        .collect();
+    let mut remaining = vec![0u64; candidates.len() + 1];
+    for (position, &index) in candidates.iter().enumerate().rev() {
+        remaining[position] = remaining[position + 1].saturating_add(amounts[index]);
+    }
+
+    let mut best_total: u64 = fallback.iter().map(|&index| amounts[index]).sum();
+    let mut best = fallback;
+    let mut current = Vec::new();
+    let mut tries = 0;
+
+    // Each stack entry is the position of the next candidate to consider,
+    // the total of the current selection, and whether the candidate at
+    // that position should be included.
+    let mut stack = vec![(0, 0u64, true), (0, 0u64, false)];
+    while let Some((position, total, include)) = stack.pop() {
+        if best_total == target || tries >= BRANCH_AND_BOUND_MAX_TRIES {
+            break;
+        }
+        tries += 1;
+        current.truncate(position);
+
+        let total = if include {
+            let amount = amounts[candidates[position]];
+            match total.checked_add(amount) {
+                Some(new_total) if new_total <= target => new_total,
+                _ => continue,
+            }
+        } else {
+            total
+        };
+        current.push(include);
+
+        if total > best_total {
+            best_total = total;
+            best = current
+                .iter()
+                .zip(&candidates)
+                .filter_map(|(&included, &index)| included.then_some(index))
+                .collect();
+        }
+
+        let next = position + 1;
+        if next < candidates.len() && total.saturating_add(remaining[next]) > best_total {
+            stack.push((next, total, false));
+            stack.push((next, total, true));
+        }
+    }
+
+    best
 }
 
 /// The set of sBTC requests with additional relevant
@@ -137,6 +280,10 @@ pub struct SbtcRequests {
     pub num_signers: u16,
     /// The maximum amount of sBTC that can be minted in sats.
     pub sbtc_limits: SbtcLimits,
+    /// The minimum total amount, in sats, of the deposits swept by a
+    /// transaction package. Deposits worth less than this in total are
+    /// deferred so that their value can accumulate across blocks.
+    pub min_sweep_value: u64,
 }
 
 impl SbtcRequests {
@@ -146,6 +293,19 @@ impl SbtcRequests {
     /// This function can fail if the output amounts are greater than the
     /// input amounts.
     pub fn construct_transactions(&self) -> Result<Vec<UnsignedTransaction>, Error> {
+        self.construct_transactions_with(SelectionStrategy::default())
+    }
+
+    /// Construct the next transaction package, using the given strategy
+    /// to choose which deposits to sweep when the eligible deposits exceed
+    /// the maximum mintable amount.
+    ///
+    /// This function can fail if the output amounts are greater than the
+    /// input amounts.
+    pub fn construct_transactions_with(
+        &self,
+        strategy: SelectionStrategy,
+    ) -> Result<Vec<UnsignedTransaction>, Error> {
         if self.deposits.is_empty() && self.withdrawals.is_empty() {
             tracing::info!("No deposits or withdrawals so no BTC transaction");
             return Ok(Vec::new());
@@ -165,32 +325,10 @@ impl SbtcRequests {
             })
             .map(RequestRef::Withdrawal);
 
-        // Filter deposit requests based on two constraints:
-        // 1. The user's max fee must be >= our minimum required fee for deposits
-        //     (based on fixed deposit tx size)
-        // 2. The deposit amount must be less than the per-deposit limit
-        // 3. The total amount being minted must stay under the maximum allowed mintable amount
-        let minimum_deposit_fee = self.compute_minimum_fee(SOLO_DEPOSIT_TX_VSIZE);
-        let max_mintable_cap = self.sbtc_limits.max_mintable_cap().to_sat();
-        let per_deposit_cap = self.sbtc_limits.per_deposit_cap().to_sat();
-
-        let mut amount_to_mint: u64 = 0;
-        let deposits = self.deposits.iter().filter_map(|req| {
-            let is_fee_valid = req.max_fee.min(req.amount) >= minimum_deposit_fee;
-            let is_within_per_deposit_cap = req.amount <= per_deposit_cap;
-            let is_within_max_mintable_cap =
-                if let Some(new_amount) = amount_to_mint.checked_add(req.amount) {
-                    new_amount <= max_mintable_cap
-                } else {
-                    false
-                };
-            if is_fee_valid && is_within_per_deposit_cap && is_within_max_mintable_cap {
-                amount_to_mint += req.amount;
-                Some(RequestRef::Deposit(req))
-            } else {
-                None
-            }
-        });
+        let deposits = self
+            .sweepable_deposits(strategy)
+            .into_iter()
+            .map(RequestRef::Deposit);
         // Create a list of requests where each request can be approved on its own.
         let items = deposits.chain(withdrawals);
 
@@ -214,6 +352,79 @@ impl SbtcRequests {
             .collect()
     }
 
+    /// Return the deposits that are eligible for the next transaction
+    /// package, but are not swept because their total amount is below
+    /// the minimum sweep value.
+    pub fn deferred_deposits(&self) -> Vec<&DepositRequest> {
+        let selected = self.select_deposits(SelectionStrategy::default());
+        if self.meets_min_sweep_value(&selected) {
+            return Vec::new();
+        }
+        selected
+    }
+
+    /// Return the deposits to sweep in the next transaction package.
+    fn sweepable_deposits(&self, strategy: SelectionStrategy) -> Vec<&DepositRequest> {
+        let selected = self.select_deposits(strategy);
+        if self.meets_min_sweep_value(&selected) {
+            return selected;
+        }
+        tracing::info!(
+            num_deposits = %selected.len(),
+            min_sweep_value = %self.min_sweep_value,
+            "deferring deposits whose total amount is below the minimum sweep value"
+        );
+        Vec::new()
+    }
+
+    /// Whether the total amount of the given deposits is at least the
+    /// minimum sweep value.
+    fn meets_min_sweep_value(&self, deposits: &[&DepositRequest]) -> bool {
+        let total: u64 = deposits.iter().map(|req| req.amount).sum();
+        total >= self.min_sweep_value
+    }
+
+    /// Select the deposits that could be swept in the next transaction
+    /// package, ignoring the minimum sweep value.
+    fn select_deposits(&self, strategy: SelectionStrategy) -> Vec<&DepositRequest> {
+        // Filter deposit requests based on two constraints:
+        // 1. The user's max fee must be >= our minimum required fee for deposits
+        //     (based on fixed deposit tx size)
+        // 2. The deposit amount must be less than the per-deposit limit
+        // 3. The total amount being minted must stay under the maximum allowed mintable amount
+        let minimum_deposit_fee = self.compute_minimum_fee(SOLO_DEPOSIT_TX_VSIZE);
+        let max_mintable_cap = self.sbtc_limits.max_mintable_cap().to_sat();
+        let per_deposit_cap = self.sbtc_limits.per_deposit_cap().to_sat();
+
+        let eligible: Vec<&DepositRequest> = self
+            .deposits
+            .iter()
+            .filter(|req| {
+                let is_fee_valid = req.max_fee.min(req.amount) >= minimum_deposit_fee;
+                let is_within_per_deposit_cap = req.amount <= per_deposit_cap;
+                is_fee_valid && is_within_per_deposit_cap
+            })
+            .collect();
+        let amounts: Vec<u64> = eligible.iter().map(|req| req.amount).collect();
+        strategy
+            .select(&amounts, max_mintable_cap)
+            .into_iter()
+            .map(|index| eligible[index])
+            .collect()
+    }
+
+    /// Preview the next transaction package without signing or
+    /// broadcasting any of its transactions.
+    ///
+    /// The previews are built from the same transactions that
+    /// [`SbtcRequests::construct_transactions`] returns, so they have the
+    /// same inputs, outputs and fees as the transactions that would be
+    /// signed.
+    pub fn preview_sweep(&self) -> Result<Vec<SweepPreview>, Error> {
+        let transactions = self.construct_transactions()?;
+        Ok(transactions.iter().map(SweepPreview::from).collect())
+    }
+
     fn reject_capacity(&self) -> u32 {
         self.num_signers.saturating_sub(self.accept_threshold) as u32
     }
@@ -535,7 +746,8 @@ impl<'a> Weighted for RequestRef<'a> {
 /// and withdrawal requests.
 #[derive(Debug)]
 pub struct Requests<'a> {
-    /// A sorted list of requests.
+    /// A sorted list of requests, with the withdrawal requests ordered
+    /// according to the output ordering of the transaction.
     request_refs: Vec<RequestRef<'a>>,
     /// This is a dummy signature here only to simplify the creation of
     /// TxIns that have the correct weight with a proper signature.
@@ -560,6 +772,39 @@ impl<'a> Requests<'a> {
         Self { request_refs, signature }
     }
 
+    /// Reorder the withdrawal requests, and with them the withdrawal
+    /// outputs of the transaction, according to the given ordering. The
+    /// deposit requests come first and keep their order.
+    ///
+    /// The seeded ordering sorts the withdrawals by the SHA256 hash of the
+    /// given outpoint together with the data identifying each withdrawal,
+    /// so every signer computes the same order from the same inputs.
+    pub fn order_withdrawals(&mut self, ordering: OutputOrdering, seed: &OutPoint) {
+        let start = self
+            .request_refs
+            .iter()
+            .position(|req| req.as_withdrawal().is_some())
+            .unwrap_or(self.request_refs.len());
+        let withdrawals = &mut self.request_refs[start..];
+
+        match ordering {
+            OutputOrdering::RequestOrder => {}
+            OutputOrdering::Bip69 => withdrawals.sort_by_cached_key(|req| {
+                let tx_out = req.as_withdrawal().map(WithdrawalRequest::as_tx_output);
+                tx_out.map(|tx_out| (tx_out.value, tx_out.script_pubkey.into_bytes()))
+            }),
+            OutputOrdering::Seeded => withdrawals.sort_by_cached_key(|req| {
+                req.as_withdrawal().map(|req| {
+                    let mut data = Vec::with_capacity(36 + 72);
+                    data.extend_from_slice(&seed.txid.to_byte_array());
+                    data.extend_from_slice(&seed.vout.to_be_bytes());
+                    data.extend_from_slice(&req.sbtc_data());
+                    bitcoin::hashes::sha256::Hash::hash(&data).to_byte_array()
+                })
+            }),
+        }
+    }
+
     /// Return an iterator for the transaction inputs for the deposit
     /// requests. These transaction inputs include a dummy signature so
     /// that the transaction inputs have the correct weight.
@@ -637,7 +882,8 @@ impl SignerUtxo {
 /// 2. All other inputs are deposit inputs.
 /// 3. The signer output UTXO is the first output.
 /// 4. The second output is the OP_RETURN data output.
-/// 5. All other outputs are withdrawal outputs.
+/// 5. All other outputs are withdrawal outputs, in the order given by the
+///    [`OutputOrdering`] of the signers.
 #[derive(Debug)]
 pub struct UnsignedTransaction<'a> {
     /// The requests used to construct the transaction.
@@ -654,6 +900,71 @@ pub struct UnsignedTransaction<'a> {
     pub tx_vsize: u32,
 }
 
+/// A preview of a sweep transaction that has not been signed or
+/// broadcast.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SweepPreview {
+    /// The outpoints spent by the transaction. The first one is always
+    /// the signers' UTXO.
+    pub inputs: Vec<OutPoint>,
+    /// The outputs of the transaction. The first one is always the
+    /// signers' new UTXO.
+    pub outputs: Vec<TxOut>,
+    /// The total amount of fees associated with the transaction.
+    pub tx_fee: u64,
+    /// The total virtual size of the transaction, once it is signed.
+    pub tx_vsize: u32,
+}
+
+impl From<&UnsignedTransaction<'_>> for SweepPreview {
+    fn from(unsigned: &UnsignedTransaction<'_>) -> Self {
+        SweepPreview {
+            inputs: unsigned
+                .tx
+                .input
+                .iter()
+                .map(|tx_in| tx_in.previous_output)
+                .collect(),
+            outputs: unsigned.tx.output.clone(),
+            tx_fee: unsigned.tx_fee,
+            tx_vsize: unsigned.tx_vsize,
+        }
+    }
+}
+
+/// A breakdown of how the fee of an [`UnsignedTransaction`] is attributed
+/// to the deposits and withdrawals that it services.
+///
+/// Each request is assessed a share of the fee that is proportional to
+/// the weight of its input or output, following the same logic as
+/// [`FeeAssessment`]. Shares are rounded down and the leftover sats are
+/// handed out one at a time in transaction order, so the shares always sum
+/// to the total fee of the transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeAllocation {
+    /// The total fee of the transaction, in sats.
+    pub tx_fee: u64,
+    /// The fee share, in sats, of each deposit input, keyed by the
+    /// outpoint of the deposit. These are in the same order as the inputs
+    /// of the transaction.
+    pub deposits: Vec<(OutPoint, u64)>,
+    /// The fee share, in sats, of each withdrawal output, keyed by the
+    /// identifier of the withdrawal request. These are in the same order
+    /// as the outputs of the transaction.
+    pub withdrawals: Vec<(QualifiedRequestId, u64)>,
+}
+
+impl FeeAllocation {
+    /// The sum of all fee shares, in sats.
+    pub fn total(&self) -> u64 {
+        self.deposits
+            .iter()
+            .map(|(_, fee)| fee)
+            .chain(self.withdrawals.iter().map(|(_, fee)| fee))
+            .sum()
+    }
+}
+
 /// A struct containing Taproot-tagged hashes used for computing taproot
 /// signature hashes.
 #[derive(Debug)]
@@ -722,6 +1033,8 @@ impl<'a> UnsignedTransaction<'a> {
     ///      is the OP_RETURN data output.
     ///   4. Each input needs a signature in the witness data.
     ///   5. There is no witness data for deposit UTXOs.
+    ///   6. The withdrawal outputs follow the output ordering of the
+    ///      signers' state.
     pub fn new(requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
         // Construct a transaction. This transaction's inputs have witness
         // data with dummy signatures so that our virtual size estimates
@@ -747,7 +1060,10 @@ impl<'a> UnsignedTransaction<'a> {
     ///   4. Each input has a fake signature in the witness data.
     ///   5. All witness data is correctly set, except for the fake
     ///      signatures from (4).
-    pub fn new_stub(requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
+    ///   6. The withdrawal outputs follow the output ordering of the
+    ///      signers' state.
+    pub fn new_stub(mut requests: Requests<'a>, state: &SignerBtcState) -> Result<Self, Error> {
+        requests.order_withdrawals(state.output_ordering, &state.utxo.outpoint);
         // Construct a transaction base. This transaction's inputs have
         // witness data with dummy signatures so that our virtual size
         // estimates are accurate. Later we will update the fees.
@@ -843,6 +1159,70 @@ impl<'a> UnsignedTransaction<'a> {
         self.tx.output.iter().map(|out| out.value.to_sat()).sum()
     }
 
+    /// Compute how the fee of this transaction is attributed to each of
+    /// the deposits and withdrawals that it services.
+    ///
+    /// The weights of the deposit inputs include witness data with a
+    /// dummy signature, since fees are paid for the signed transaction.
+    pub fn fee_allocation(&self) -> FeeAllocation {
+        let deposit_weights: Vec<(OutPoint, u64)> = self
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_deposit)
+            .map(|req| req.outpoint)
+            .zip(
+                self.requests
+                    .tx_ins()
+                    .map(|tx_in| tx_in.segwit_weight().to_wu()),
+            )
+            .collect();
+        let withdrawal_weights: Vec<(QualifiedRequestId, u64)> = self
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(WithdrawalRequest::qualified_id)
+            .zip(
+                self.requests
+                    .tx_outs()
+                    .map(|tx_out| tx_out.weight().to_wu()),
+            )
+            .collect();
+
+        let request_weight: u64 = deposit_weights
+            .iter()
+            .map(|(_, weight)| weight)
+            .chain(withdrawal_weights.iter().map(|(_, weight)| weight))
+            .sum();
+        // This follows the logic in FeeAssessment, except that we round
+        // down here and hand out the leftover sats below.
+        let fee_share = |weight: u64| (weight * self.tx_fee) / request_weight.max(1);
+
+        let mut allocation = FeeAllocation {
+            tx_fee: self.tx_fee,
+            deposits: deposit_weights
+                .into_iter()
+                .map(|(outpoint, weight)| (outpoint, fee_share(weight)))
+                .collect(),
+            withdrawals: withdrawal_weights
+                .into_iter()
+                .map(|(id, weight)| (id, fee_share(weight)))
+                .collect(),
+        };
+
+        // Rounding down leaves fewer sats unassessed than there are
+        // requests, so each request gets at most one extra sat.
+        let leftover = self.tx_fee.saturating_sub(allocation.total()) as usize;
+        allocation
+            .deposits
+            .iter_mut()
+            .map(|(_, fee)| fee)
+            .chain(allocation.withdrawals.iter_mut().map(|(_, fee)| fee))
+            .take(leftover)
+            .for_each(|fee| *fee += 1);
+
+        allocation
+    }
+
     /// Construct a "stub" BTC transaction from the given requests.
     ///
     /// The returned BTC transaction is signed with dummy signatures, so it
@@ -995,22 +1375,32 @@ impl<'a> UnsignedTransaction<'a> {
     /// Compute the final amount for the signers' UTXO given the current
     /// UTXO amount and the incoming requests.
     ///
-    /// This amount does not take into account fees.
+    /// This amount does not take into account fees. An error is returned
+    /// if the sum of the inputs or the sum of the withdrawal amounts
+    /// overflows a u64.
     fn compute_signer_amount(reqs: &Requests, state: &SignerBtcState) -> Result<u64, Error> {
-        let amount = reqs
+        let input_amount = reqs
             .iter()
-            .fold(state.utxo.amount as i64, |amount, req| match req {
-                RequestRef::Deposit(req) => amount + req.amount as i64,
-                RequestRef::Withdrawal(req) => amount - req.amount as i64,
-            });
+            .filter_map(RequestRef::as_deposit)
+            .try_fold(state.utxo.amount, |acc, req| acc.checked_add(req.amount))
+            .ok_or(Error::AmountOverflow)?;
+
+        let withdrawal_amount = reqs
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .try_fold(0u64, |acc, req| acc.checked_add(req.amount))
+            .ok_or(Error::AmountOverflow)?;
 
         // This should never happen
-        if amount < 0 {
+        let Some(amount) = input_amount.checked_sub(withdrawal_amount) else {
             tracing::error!("Transaction deposits greater than the inputs!");
-            return Err(Error::InvalidAmount(amount));
-        }
+            let shortfall = withdrawal_amount - input_amount;
+            return Err(Error::InvalidAmount(
+                i64::try_from(shortfall).map_or(i64::MIN, |x| -x),
+            ));
+        };
 
-        Ok(amount as u64)
+        Ok(amount)
     }
 
     /// Adjust the amounts for each output given the transaction fee.
@@ -1389,6 +1779,7 @@ mod tests {
     use stacks_common::types::chainstate::StacksAddress;
     use test_case::test_case;
 
+    use crate::config::ProcessingOrder;
     use crate::testing;
     use crate::testing::btc::base_signer_transaction;
 
@@ -1567,10 +1958,12 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 2,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
         let keypair = Keypair::new_global(&mut OsRng);
 
@@ -1666,10 +2059,12 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // This should all be in one transaction since there are no votes
@@ -1729,6 +2124,7 @@ mod tests {
             public_key,
             last_fees: None,
             magic_bytes: [0; 2],
+            output_ordering: OutputOrdering::default(),
         };
 
         // No requests so the first output should be the signers UTXO and
@@ -1741,6 +2137,35 @@ mod tests {
         assert!(unsigned.tx.output[0].script_pubkey.is_p2tr());
     }
 
+    /// Deposit amounts that sum past u64::MAX are rejected instead of
+    /// wrapping around.
+    #[test]
+    fn deposit_amounts_that_overflow_are_rejected() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let signer_state = SignerBtcState {
+            utxo: SignerUtxo {
+                outpoint: OutPoint::null(),
+                amount: 55,
+                public_key,
+            },
+            fee_rate: 0.0,
+            public_key,
+            last_fees: None,
+            magic_bytes: [0; 2],
+            output_ordering: OutputOrdering::default(),
+        };
+
+        let deposit1 = create_deposit(u64::MAX - 100, 0, 0);
+        let deposit2 = create_deposit(100, 0, 0);
+        let requests = Requests::new(vec![
+            RequestRef::Deposit(&deposit1),
+            RequestRef::Deposit(&deposit2),
+        ]);
+
+        let result = UnsignedTransaction::new(requests, &signer_state);
+        assert!(matches!(result, Err(Error::AmountOverflow)));
+    }
+
     /// We aggregate the bitmaps to form a single one at the end. Check
     /// that it is aggregated correctly.
     #[test]
@@ -1761,10 +2186,12 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // We'll have the deposit get two vote against, and the withdrawals
@@ -1869,10 +2296,12 @@ mod tests {
                 public_key: generate_x_only_public_key(),
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -1954,10 +2383,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // This should all be in one transaction since there are no votes
@@ -1998,10 +2429,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // This should all be in one transaction since there are no votes
@@ -2048,10 +2481,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2095,10 +2530,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2154,10 +2591,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let transactions = requests.construct_transactions().unwrap();
@@ -2253,10 +2692,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2292,6 +2733,102 @@ mod tests {
         });
     }
 
+    #[test]
+    fn sweep_preview_matches_the_built_transactions() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 0),
+                create_deposit(56780, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(10000, 100_000, 0),
+                create_withdrawal(20000, 100_000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 25.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
+        };
+
+        let previews = requests.preview_sweep().unwrap();
+        let transactions = requests.construct_transactions().unwrap();
+
+        assert_eq!(previews.len(), transactions.len());
+        assert!(!previews.is_empty());
+        for (preview, utx) in previews.iter().zip(transactions.iter()) {
+            assert_eq!(preview.tx_fee, utx.tx_fee);
+            assert_eq!(preview.tx_vsize, utx.tx_vsize);
+            assert_eq!(preview.outputs, utx.tx.output);
+
+            let inputs: Vec<OutPoint> = utx.tx.input.iter().map(|i| i.previous_output).collect();
+            assert_eq!(preview.inputs, inputs);
+        }
+        assert_eq!(previews[0].inputs[0], requests.signer_state.utxo.outpoint);
+    }
+
+    #[test_case(25.0; "fee rate 25")]
+    #[test_case(3.3; "fee rate 3.3")]
+    fn fee_allocation_sums_to_total_fee(fee_rate: f64) {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let requests = SbtcRequests {
+            deposits: vec![
+                create_deposit(12340, 100_000, 0),
+                create_deposit(56780, 100_000, 0),
+                create_deposit(90120, 100_000, 0),
+            ],
+            withdrawals: vec![
+                create_withdrawal(10000, 100_000, 0),
+                create_withdrawal(20000, 100_000, 0),
+            ],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
+        };
+
+        let transactions = requests.construct_transactions().unwrap();
+        assert_eq!(transactions.len(), 1);
+        let utx = &transactions[0];
+
+        let allocation = utx.fee_allocation();
+        assert_eq!(allocation.deposits.len(), 3);
+        assert_eq!(allocation.withdrawals.len(), 2);
+        assert!(allocation.deposits.iter().all(|(_, fee)| *fee > 0));
+        assert!(allocation.withdrawals.iter().all(|(_, fee)| *fee > 0));
+
+        assert_eq!(allocation.tx_fee, utx.tx_fee);
+        assert_eq!(allocation.total(), utx.tx_fee);
+        assert_eq!(
+            utx.input_amounts() - utx.output_amounts(),
+            allocation.total()
+        );
+    }
+
     #[test]
     fn rbf_txs_have_greater_total_fee() {
         // Each deposit and withdrawal has a max fee greater than the current market fee rate
@@ -2318,10 +2855,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let (old_fee_total, old_fee_rate) = {
@@ -2397,10 +2936,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
         let mut transactions = requests.construct_transactions().unwrap();
         assert_eq!(transactions.len(), 1);
@@ -2433,10 +2974,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 0,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let transactions = requests.construct_transactions();
@@ -2490,10 +3033,12 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         let mut transactions = requests.construct_transactions().unwrap();
@@ -2802,6 +3347,7 @@ mod tests {
                 public_key,
                 last_fees: None,
                 magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
             },
             num_signers: 10,
             accept_threshold: 8,
@@ -2811,6 +3357,7 @@ mod tests {
                 None,
                 Some(Amount::from_sat(max_mintable)),
             ),
+            min_sweep_value: 0,
         };
         let txs = requests.construct_transactions().unwrap();
         let nr_requests = txs.iter().map(|tx| tx.requests.len()).sum::<usize>();
@@ -2826,4 +3373,247 @@ mod tests {
         assert_eq!(nr_requests, num_accepted_requests);
         assert_eq!(total_amount, accepted_amount);
     }
+
+    #[test_case(SelectionStrategy::OldestFirst, &[0, 1]; "oldest first")]
+    #[test_case(SelectionStrategy::LargestFirst, &[1, 2]; "largest first")]
+    #[test_case(SelectionStrategy::BranchAndBound, &[0, 2, 3]; "branch and bound")]
+    fn selection_strategies_pick_different_inputs(strategy: SelectionStrategy, expected: &[usize]) {
+        let amounts = [30_000, 60_000, 50_000, 40_000];
+        assert_eq!(strategy.select(&amounts, 120_000), expected);
+
+        // The builder should sweep exactly the deposits that the strategy
+        // selected.
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let deposits: Vec<DepositRequest> = amounts
+            .iter()
+            .map(|&amount| create_deposit(amount, 10_000, 0))
+            .collect();
+        let requests = SbtcRequests {
+            deposits: deposits.clone(),
+            withdrawals: vec![],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 5.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::new(None, None, None, Some(Amount::from_sat(120_000))),
+            min_sweep_value: 0,
+        };
+        let txs = requests.construct_transactions_with(strategy).unwrap();
+        let swept: BTreeSet<OutPoint> = txs
+            .iter()
+            .flat_map(|tx| tx.requests.iter())
+            .map(|req| req.as_deposit().unwrap().outpoint)
+            .collect();
+        let expected: BTreeSet<OutPoint> = expected
+            .iter()
+            .map(|&index| deposits[index].outpoint)
+            .collect();
+        assert_eq!(swept, expected);
+    }
+
+    #[test]
+    fn branch_and_bound_never_does_worse_than_largest_first() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        for _ in 0..100 {
+            let amounts: Vec<u64> = (0..20).map(|_| rng.gen_range(1..1_000)).collect();
+            let target = rng.gen_range(1..10_000);
+            let total = |selected: Vec<usize>| -> u64 {
+                selected.into_iter().map(|index| amounts[index]).sum()
+            };
+
+            let bnb = total(SelectionStrategy::BranchAndBound.select(&amounts, target));
+            let largest = total(SelectionStrategy::LargestFirst.select(&amounts, target));
+            assert!(bnb <= target);
+            assert!(bnb >= largest);
+        }
+    }
+
+    #[test_case(ProcessingOrder::Fifo, SelectionStrategy::OldestFirst; "fifo")]
+    #[test_case(ProcessingOrder::ValueDescending, SelectionStrategy::LargestFirst; "value descending")]
+    fn processing_order_maps_to_a_selection_strategy(
+        order: ProcessingOrder,
+        expected: SelectionStrategy,
+    ) {
+        assert_eq!(order.selection_strategy(), expected);
+    }
+
+    #[test]
+    fn deposits_below_the_min_sweep_value_are_deferred() {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        let mut requests = SbtcRequests {
+            deposits: vec![create_deposit(20_000, 10_000, 0)],
+            withdrawals: vec![],
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint: generate_outpoint(300_000, 0),
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 5.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                output_ordering: OutputOrdering::default(),
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 50_000,
+        };
+
+        // The single deposit is worth less than the minimum sweep value,
+        // so no transaction is built and the deposit is deferred.
+        let txs = requests.construct_transactions().unwrap();
+        assert!(txs.is_empty());
+        let deferred: Vec<OutPoint> = requests
+            .deferred_deposits()
+            .iter()
+            .map(|req| req.outpoint)
+            .collect();
+        assert_eq!(deferred, vec![requests.deposits[0].outpoint]);
+
+        // Once enough value has accumulated, all of the deposits are swept.
+        requests.deposits.push(create_deposit(40_000, 10_000, 0));
+
+        let txs = requests.construct_transactions().unwrap();
+        assert_eq!(txs.len(), 1);
+        let swept: BTreeSet<OutPoint> = txs[0]
+            .requests
+            .iter()
+            .map(|req| req.as_deposit().unwrap().outpoint)
+            .collect();
+        let expected: BTreeSet<OutPoint> =
+            requests.deposits.iter().map(|req| req.outpoint).collect();
+        assert_eq!(swept, expected);
+        assert!(requests.deferred_deposits().is_empty());
+    }
+
+    fn sweep_with_ordering(
+        withdrawals: Vec<WithdrawalRequest>,
+        outpoint: OutPoint,
+        output_ordering: OutputOrdering,
+    ) -> SbtcRequests {
+        let public_key = XOnlyPublicKey::from_str(X_ONLY_PUBLIC_KEY1).unwrap();
+        SbtcRequests {
+            deposits: vec![],
+            withdrawals,
+            signer_state: SignerBtcState {
+                utxo: SignerUtxo {
+                    outpoint,
+                    amount: 300_000_000,
+                    public_key,
+                },
+                fee_rate: 5.0,
+                public_key,
+                last_fees: None,
+                magic_bytes: [0; 2],
+                output_ordering,
+            },
+            num_signers: 10,
+            accept_threshold: 8,
+            sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
+        }
+    }
+
+    /// Create withdrawal requests whose amounts decrease as their request
+    /// IDs increase, so that ordering the outputs by amount reverses the
+    /// order of the requests.
+    fn withdrawals_with_decreasing_amounts(num_withdrawals: u64) -> Vec<WithdrawalRequest> {
+        (0..num_withdrawals)
+            .map(|request_id| WithdrawalRequest {
+                request_id,
+                amount: 10_000 * (num_withdrawals - request_id),
+                ..create_withdrawal(0, 40_000, 0)
+            })
+            .collect()
+    }
+
+    #[test_case(OutputOrdering::Bip69; "bip69")]
+    #[test_case(OutputOrdering::Seeded; "seeded")]
+    fn output_ordering_is_identical_across_builds(output_ordering: OutputOrdering) {
+        let withdrawals = withdrawals_with_decreasing_amounts(12);
+        let outpoint = generate_outpoint(300_000, 0);
+
+        // Each signer builds the sweep on their own, and may have received
+        // the withdrawal requests in a different order.
+        let mut reversed = withdrawals.clone();
+        reversed.reverse();
+        let requests1 = sweep_with_ordering(withdrawals.clone(), outpoint, output_ordering);
+        let requests2 = sweep_with_ordering(reversed, outpoint, output_ordering);
+        let request_ordered =
+            sweep_with_ordering(withdrawals, outpoint, OutputOrdering::RequestOrder);
+
+        let txs1 = requests1.construct_transactions().unwrap();
+        let txs2 = requests2.construct_transactions().unwrap();
+        let request_ordered_txs = request_ordered.construct_transactions().unwrap();
+        assert_eq!(txs1.len(), 1);
+        assert_eq!(txs2.len(), 1);
+        assert_eq!(request_ordered_txs.len(), 1);
+        assert_eq!(txs1[0].tx, txs2[0].tx);
+
+        // The signers' UTXO and the OP_RETURN output always come first,
+        // and the withdrawal requests follow the order of their outputs.
+        let unsigned = &txs1[0];
+        assert_eq!(unsigned.tx.output.len(), 14);
+        assert_eq!(
+            unsigned.tx.output[0],
+            unsigned.new_signer_utxo().as_tx_output()
+        );
+        assert!(unsigned.tx.output[1].script_pubkey.is_op_return());
+        let request_outputs: Vec<TxOut> = unsigned.requests.tx_outs().collect();
+        assert_eq!(unsigned.tx.output[2..], request_outputs[..]);
+
+        // The ordering actually moves the withdrawal outputs around, since
+        // `Requests::new` sorts the requests by their request ID
+        // regardless of the order that they were received in.
+        let outputs = &unsigned.tx.output[2..];
+        let request_ordered_outputs = &request_ordered_txs[0].tx.output[2..];
+        assert_ne!(outputs, request_ordered_outputs);
+
+        let script_pubkeys = |outputs: &[TxOut]| {
+            let mut script_pubkeys: Vec<ScriptBuf> = outputs
+                .iter()
+                .map(|out| out.script_pubkey.clone())
+                .collect();
+            script_pubkeys.sort();
+            script_pubkeys
+        };
+        assert_eq!(
+            script_pubkeys(outputs),
+            script_pubkeys(request_ordered_outputs)
+        );
+    }
+
+    #[test]
+    fn bip69_output_ordering_sorts_by_amount_then_script_pubkey() {
+        let mut withdrawals = withdrawals_with_decreasing_amounts(4);
+        withdrawals[1].amount = withdrawals[3].amount;
+        let outpoint = generate_outpoint(300_000, 0);
+        let requests = sweep_with_ordering(withdrawals, outpoint, OutputOrdering::Bip69);
+
+        let txs = requests.construct_transactions().unwrap();
+        assert_eq!(txs.len(), 1);
+
+        let outputs = &txs[0].tx.output[2..];
+        let mut expected = outputs.to_vec();
+        expected.sort_by(|a, b| {
+            a.value
+                .cmp(&b.value)
+                .then_with(|| a.script_pubkey.as_bytes().cmp(b.script_pubkey.as_bytes()))
+        });
+        assert_eq!(outputs, expected.as_slice());
+        let amounts: Vec<u64> = outputs.iter().map(|out| out.value.to_sat()).collect();
+        assert_eq!(amounts, vec![10_000, 10_000, 20_000, 40_000]);
+    }
 }
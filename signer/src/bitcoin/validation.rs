@@ -224,6 +224,7 @@ impl BitcoinPreSignRequest {
             public_key: bitcoin::XOnlyPublicKey::from(btc_ctx.aggregate_key),
             last_fees: self.last_fees,
             magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            output_ordering: self.output_ordering,
         };
         let mut outputs = Vec::new();
 
@@ -287,6 +288,15 @@ impl BitcoinPreSignRequest {
         let mut signer_state = signer_state;
         let tx = reports.create_transaction()?;
         let sighashes = tx.construct_digests()?;
+        // The withdrawal outputs may have been reordered when creating
+        // the transaction, and the reports need to follow the order of
+        // the outputs.
+        let withdrawal_order: Vec<QualifiedRequestId> = tx
+            .requests
+            .iter()
+            .filter_map(RequestRef::as_withdrawal)
+            .map(WithdrawalRequest::qualified_id)
+            .collect();
 
         signer_state.utxo = tx.new_signer_utxo();
         // The first transaction is the only one whose input UTXOs that
@@ -297,7 +307,7 @@ impl BitcoinPreSignRequest {
         // network.
         signer_state.last_fees = None;
         let sbtc_limits = ctx.state().get_current_limits();
-        let out = BitcoinTxValidationData {
+        let mut out = BitcoinTxValidationData {
             signer_sighash: sighashes.signer_sighash(),
             deposit_sighashes: sighashes.deposit_sighashes(),
             chain_tip: btc_ctx.chain_tip,
@@ -309,6 +319,9 @@ impl BitcoinPreSignRequest {
             max_deposit_amount: sbtc_limits.per_deposit_cap(),
             max_withdrawal_amount: sbtc_limits.per_withdrawal_cap(),
         };
+        out.reports
+            .withdrawals
+            .sort_by_key(|(_, report)| withdrawal_order.iter().position(|id| id == &report.id));
 
         Ok((out, signer_state))
     }
@@ -854,6 +867,7 @@ mod tests {
     use bitcoin::Witness;
     use test_case::test_case;
 
+    use crate::config::OutputOrdering;
     use crate::context::SbtcLimits;
     use crate::storage::model::StacksBlockHash;
     use crate::storage::model::StacksTxId;
@@ -1276,6 +1290,7 @@ mod tests {
             request_package: vec![],
             fee_rate: 2.0,
             last_fees: None,
+            output_ordering: OutputOrdering::default(),
         };
         let result = request.validate_max_mintable(&context, &cache).await;
 
@@ -0,0 +1,4242 @@
+//! # Block observer
+//!
+//! This module contains the block observer implementation for the sBTC signer.
+//! The block observer is responsible for populating the signer database with
+//! information from the Bitcoin and Stacks blockchains, and notifying
+//! the signer event loop whenever the state has been updated.
+//!
+//! The following information is extracted by the block observer:
+//! - Bitcoin blocks
+//! - Stacks blocks
+//! - Deposit requests
+//! - sBTC transactions
+//! - Withdraw requests
+//! - Deposit accept transactions
+//! - Withdraw accept transactions
+//! - Withdraw reject transactions
+//! - Update signer set transactions
+//! - Set aggregate key transactions
+
+mod tenure;
+mod validation;
+
+pub use tenure::*;
+pub use validation::*;
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::api::new_block;
+use crate::bitcoin::rpc::BitcoinTxInfo;
+use crate::bitcoin::utxo::TxDeconstructor as _;
+use crate::bitcoin::BitcoinInteract;
+use crate::config::SignerConfig;
+use crate::context::BackoffPolicy;
+use crate::context::BlockObserverStats;
+use crate::context::Context;
+use crate::context::DepositLatencyBucket;
+use crate::context::ReorgEvent;
+use crate::context::SbtcLimits;
+use crate::context::SignerEvent;
+use crate::context::SignerSignal;
+use crate::emily_client::EmilyInteract;
+use crate::error::Error;
+use crate::keys::PublicKey;
+use crate::keys::SignerScriptPubKey as _;
+use crate::stacks::api::StacksInteract;
+use crate::stacks::api::TenureBlocks;
+use crate::stacks::events::WithdrawalAcceptEvent;
+use crate::storage;
+use crate::storage::model;
+use crate::storage::DbRead;
+use crate::storage::DbWrite;
+use bitcoin::hashes::Hash as _;
+use bitcoin::Amount;
+use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Transaction;
+use blockstack_lib::types::chainstate::StacksBlockId;
+use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::DepositUpdate;
+use emily_client::models::Status;
+use futures::stream::Stream;
+use futures::stream::StreamExt;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositInfo;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Block observer
+#[derive(Debug)]
+pub struct BlockObserver<Context, BlockHashStream> {
+    /// Signer context
+    pub context: Context,
+    /// Stream of blocks from the block notifier
+    pub bitcoin_blocks: BlockHashStream,
+    /// How far back in time the observer should look
+    pub horizon: u32,
+}
+
+/// The default amount of time the block observer waits for a new block
+/// hash before checking for a shutdown signal again.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The default maximum number of deposit requests that the block observer
+/// validates at the same time.
+pub const DEFAULT_DEPOSIT_VALIDATION_CONCURRENCY: usize = 8;
+
+/// How long the block observer waits on the block hash stream during each
+/// iteration of its event loop.
+///
+/// A random amount of jitter, up to `jitter`, is added to the base
+/// interval on each iteration so that signers do not poll their nodes in
+/// lockstep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollInterval {
+    /// The minimum amount of time to wait.
+    pub base: Duration,
+    /// The maximum amount of time added at random to the base interval.
+    pub jitter: Duration,
+}
+
+impl PollInterval {
+    /// Create a poll interval from the signer configuration.
+    pub fn from_config(config: &SignerConfig) -> Self {
+        Self {
+            base: config
+                .block_observer_poll_interval
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_POLL_INTERVAL),
+            jitter: config
+                .block_observer_poll_jitter
+                .map(Duration::from_millis)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Return the amount of time to wait on the next loop iteration,
+    /// drawing the jitter from the given source of randomness.
+    pub fn next_interval<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        if self.jitter.is_zero() {
+            return self.base;
+        }
+        self.base + rng.gen_range(Duration::ZERO..=self.jitter)
+    }
+}
+
+/// The default number of stacks blocks within which a stacks transaction
+/// submitted by the signers has to be confirmed before it is flagged for
+/// resubmission.
+pub const DEFAULT_SUBMISSION_DEADLINE_BLOCKS: u64 = 10;
+
+/// A one-shot summary of the health of the block observer, as returned
+/// by [`BlockObserver::health_summary`].
+#[derive(Debug, Clone)]
+pub struct ObserverHealth {
+    /// The height of the bitcoin chain tip that the observer has
+    /// processed, or `None` if no bitcoin blocks have been processed yet.
+    pub processed_height: Option<u64>,
+    /// The number of bitcoin blocks known to the stacks node that the
+    /// observer has not processed yet.
+    pub lag: u64,
+    /// The last error encountered while processing bitcoin blocks, along
+    /// with when it happened.
+    pub last_error: Option<(String, Instant)>,
+    /// Whether the stacks node reports that it is fully synced.
+    pub node_synced: bool,
+    /// Whether the last call to Emily succeeded. When this is false the
+    /// observer is backing off from Emily.
+    pub emily_healthy: bool,
+}
+
+/// A full "deposit", containing the bitcoin transaction and a fully
+/// extracted and verified `scriptPubKey` from one of the transaction's
+/// UTXOs.
+#[derive(Debug, Clone)]
+pub struct Deposit {
+    /// The transaction spent to the signers as a deposit for sBTC.
+    pub tx_info: BitcoinTxInfo,
+    /// The deposit information included in one of the output
+    /// `scriptPubKey`s of the above transaction.
+    pub info: DepositInfo,
+    /// The virtual size of the above transaction, computed from the
+    /// transaction itself.
+    pub tx_vsize: u64,
+}
+
+/// A deposit request found by [`BlockObserver::discover_deposits`],
+/// along with whether the block observer would accept it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredDeposit {
+    /// The outpoint of the deposit in the request.
+    pub outpoint: OutPoint,
+    /// Whether the deposit request would be accepted.
+    pub status: DiscoveryStatus,
+}
+
+/// The outcome of validating a discovered deposit request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryStatus {
+    /// The deposit request passed validation and would be stored.
+    Accepted,
+    /// The deposit request would not be stored, for the given reason.
+    Rejected(String),
+}
+
+/// What the block observer does with a pending deposit request, see
+/// [`BlockObserver::load_requests`].
+enum RequestOutcome {
+    /// The deposit passed validation and is stored.
+    Accepted(Deposit),
+    /// The deposit passed validation, but it is locked to a key that is
+    /// not one of the signers' aggregate keys in the registry, so it is
+    /// rejected in Emily.
+    WrongKey(Deposit),
+    /// The request fails validation, and will fail it every time, so it
+    /// is evicted from Emily.
+    Invalid(Error),
+    /// The request is left pending in Emily, for the given reason, and is
+    /// looked at again later.
+    Skipped(String),
+}
+
+/// A handle to a block observer that has been spawned onto the tokio
+/// runtime with [`BlockObserver::spawn`].
+#[derive(Debug)]
+pub struct BlockObserverHandle {
+    /// Set once a shutdown of the block observer has been requested.
+    stop: Arc<AtomicBool>,
+    /// The task running the block observer loop.
+    task: tokio::task::JoinHandle<Result<(), Error>>,
+}
+
+impl BlockObserverHandle {
+    /// Ask the block observer to stop. The observer finishes processing
+    /// the block that it is working on, if any, before it exits.
+    pub fn shutdown(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+
+    /// Wait for the block observer loop to exit.
+    pub async fn join(self) -> Result<(), Error> {
+        self.task.await.map_err(Error::BlockObserverTask)?
+    }
+}
+
+impl<C, S> BlockObserver<C, S>
+where
+    C: Context,
+    S: Stream<Item = Result<bitcoin::BlockHash, Error>> + Unpin,
+{
+    /// Run the block observer
+    pub async fn run(self) -> Result<(), Error> {
+        self.run_until_stopped(Arc::new(AtomicBool::new(false)))
+            .await
+    }
+
+    /// Spawn the block observer onto the tokio runtime, returning a
+    /// handle that can be used to stop it gracefully.
+    pub fn spawn(self) -> BlockObserverHandle
+    where
+        C: 'static,
+        S: Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let task = tokio::spawn(self.run_until_stopped(stop.clone()));
+        BlockObserverHandle { stop, task }
+    }
+
+    /// Run the block observer until the signer shuts down or the given
+    /// flag is set. The flag is only checked between blocks, so a block
+    /// that is being processed is always written to the database before
+    /// the observer stops.
+    #[tracing::instrument(skip_all, name = "block-observer")]
+    async fn run_until_stopped(mut self, stop: Arc<AtomicBool>) -> Result<(), Error> {
+        let term = self.context.get_termination_handle();
+        // The hash of the last block that we processed. We compare the
+        // parent of the next unprocessed block against it to detect reorgs.
+        let mut last_tip: Option<BlockHash> = None;
+        let poll_interval = PollInterval::from_config(&self.context.config().signer);
+        let mut rng = rand::rngs::OsRng;
+
+        loop {
+            if term.shutdown_signalled() || stop.load(Ordering::SeqCst) {
+                break;
+            }
+
+            // Bitcoin blocks will generally arrive in ~10 minute intervals, so
+            // we don't need to be so aggressive in our timeout here.
+            let timeout = poll_interval.next_interval(&mut rng);
+            let poll = tokio::time::timeout(timeout, self.bitcoin_blocks.next());
+
+            match poll.await {
+                Ok(Some(Ok(block_hash))) => {
+                    tracing::info!("observed new bitcoin block from stream");
+
+                    let next_blocks = match self.next_blocks_to_process(block_hash).await {
+                        Ok(blocks) => blocks,
+                        Err(error) => {
+                            tracing::warn!(%error, %block_hash, "could not get next blocks to process");
+                            self.record_error(&error);
+                            continue;
+                        }
+                    };
+
+                    match self.detect_reorg(last_tip, &next_blocks).await {
+                        Ok(Some(event)) => {
+                            tracing::info!(?event, "observed a bitcoin reorg");
+                            self.context.signal(event.into())?;
+                        }
+                        Ok(None) => {}
+                        Err(error) => tracing::warn!(%error, "could not check for a bitcoin reorg"),
+                    }
+
+                    if let Some(block) = next_blocks.last() {
+                        last_tip = Some(block.block_hash());
+                    }
+
+                    for block in next_blocks {
+                        match self.process_bitcoin_block(block).await {
+                            Ok(()) => self.context.state().set_block_observer_last_error(None),
+                            Err(error) => {
+                                tracing::warn!(%error, "could not process bitcoin block");
+                                self.record_error(&error);
+                            }
+                        }
+                    }
+
+                    if let Err(error) = self.evict_settled_sweeps().await {
+                        tracing::warn!(%error, "could not evict settled sweep transactions");
+                    }
+
+                    if let Err(error) = self.release_held_withdrawals().await {
+                        tracing::warn!(%error, "could not report held withdrawals as confirmed");
+                    }
+
+                    if let Err(error) = self.update_sbtc_limits().await {
+                        tracing::warn!(%error, "could not update sBTC limits");
+                        continue;
+                    }
+
+                    tracing::info!("loading latest deposit requests from Emily");
+                    if let Err(error) = self.load_latest_deposit_requests().await {
+                        tracing::warn!(%error, "could not load latest deposit requests from Emily");
+                    }
+
+                    self.context
+                        .signal(SignerEvent::BitcoinBlockObserved.into())?;
+                }
+                Ok(Some(Err(error))) => {
+                    tracing::warn!(%error, "error decoding new bitcoin block hash from stream");
+                    continue;
+                }
+                _ => continue,
+            };
+        }
+
+        tracing::info!("block observer has stopped");
+
+        Ok(())
+    }
+
+    /// Return the last error encountered while processing bitcoin blocks,
+    /// along with when it happened. The error is cleared once the next
+    /// bitcoin block is processed successfully.
+    pub fn last_error(&self) -> Option<(String, Instant)> {
+        self.context.state().block_observer_last_error()
+    }
+
+    /// Return the counts of the new and reprocessed bitcoin blocks that
+    /// the block observer has processed.
+    pub fn stats(&self) -> &BlockObserverStats {
+        self.context.state().block_observer_stats()
+    }
+
+    /// Record the given error as the last error encountered while
+    /// processing bitcoin blocks.
+    fn record_error(&self, error: &Error) {
+        let last_error = Some((error.to_string(), Instant::now()));
+        self.context
+            .state()
+            .set_block_observer_last_error(last_error);
+    }
+
+    /// Return a summary of the health of the block observer, combining
+    /// the processed bitcoin chain tip height, how far the observer lags
+    /// behind the stacks node, the last processing error, and the sync
+    /// state of the stacks node.
+    pub async fn health_summary(&self) -> Result<ObserverHealth, Error> {
+        let db = self.context.get_storage();
+        let processed_height = match db.get_bitcoin_canonical_chain_tip().await? {
+            Some(chain_tip) => db
+                .get_bitcoin_block(&chain_tip)
+                .await?
+                .map(|block| block.block_height),
+            None => None,
+        };
+
+        let node_info = self.context.get_stacks_client().get_node_info().await?;
+        let lag = match processed_height {
+            Some(height) => node_info.burn_block_height.saturating_sub(height),
+            None => node_info.burn_block_height,
+        };
+
+        Ok(ObserverHealth {
+            processed_height,
+            lag,
+            last_error: self.last_error(),
+            node_synced: node_info.is_fully_synced,
+            emily_healthy: self.context.state().emily_backoff().is_healthy(),
+        })
+    }
+
+    /// Return a stream of the bitcoin reorgs that are detected by the
+    /// block observer.
+    ///
+    /// Only reorgs that are detected after this function has been called
+    /// are returned in the stream.
+    pub fn subscribe_reorgs(&self) -> impl Stream<Item = ReorgEvent> + Send + Unpin {
+        self.context
+            .as_signal_stream(|signal| {
+                matches!(signal, SignerSignal::Event(SignerEvent::BitcoinReorg(_)))
+            })
+            .filter_map(|signal| match signal {
+                SignerSignal::Event(SignerEvent::BitcoinReorg(event)) => {
+                    std::future::ready(Some(event))
+                }
+                _ => std::future::ready(None),
+            })
+    }
+}
+
+impl<C: Context, B> BlockObserver<C, B> {
+    /// Process all bitcoin blocks after the block with hash `from` up to,
+    /// and including, the block with hash `to`, in chronological order.
+    /// Returns the number of bitcoin blocks that were processed.
+    ///
+    /// This is meant to be called before [`BlockObserver::run`] when the
+    /// signer has fallen many blocks behind, say on startup. Unlike the
+    /// live stream, the range is not limited by the horizon. The range is
+    /// found by walking the block headers back from `to`, then all bitcoin
+    /// blocks in the range are read up front, and the stacks blocks,
+    /// sBTC limits and deposit requests are fetched once for the whole
+    /// range rather than once for each bitcoin block. We also stop early
+    /// if we reach a block that we have already processed.
+    ///
+    /// If we run out of bitcoin blocks before reaching `from`, or if
+    /// `from` is more than `context_window` blocks behind `to`, then an
+    /// error is returned without processing anything.
+    #[tracing::instrument(skip(self))]
+    pub async fn catch_up(&self, from: BlockHash, to: BlockHash) -> Result<usize, Error> {
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let max_blocks = usize::from(self.context.config().signer.context_window);
+        let mut block_hashes = Vec::new();
+        let mut block_hash = to;
+
+        // We only walk the headers here, so that we do not download any
+        // blocks when the range turns out to be disconnected.
+        while block_hash != from && !self.have_already_processed_block(&block_hash).await? {
+            // Requests older than the context window are ignored anyway,
+            // and a `from` that is this far back is most likely on a
+            // different chain.
+            if block_hashes.len() >= max_blocks {
+                return Err(Error::CatchUpRangeDisconnected { from, to });
+            }
+            // The parent of the genesis block is all zeros, so we land
+            // here if we walk past the genesis block.
+            let header = bitcoin_client
+                .get_block_header(&block_hash)
+                .await?
+                .ok_or(Error::CatchUpRangeDisconnected { from, to })?;
+
+            block_hashes.push(block_hash);
+            block_hash = header.prev_blockhash;
+        }
+
+        if block_hashes.is_empty() {
+            return Ok(0);
+        }
+        // Make order chronological
+        block_hashes.reverse();
+        tracing::info!(
+            num_blocks = block_hashes.len(),
+            "catching up on bitcoin blocks"
+        );
+
+        let mut blocks = Vec::with_capacity(block_hashes.len());
+        for block_hash in block_hashes {
+            let block = bitcoin_client
+                .get_block(&block_hash)
+                .await?
+                .ok_or(Error::MissingBitcoinBlock(block_hash.into()))?;
+            blocks.push(block);
+        }
+
+        let stacks_client = self.context.get_stacks_client();
+        let tenure_info = get_consistent_tenure_info(&stacks_client).await?;
+        let stacks_blocks = crate::stacks::api::fetch_unknown_ancestors(
+            &stacks_client,
+            &self.context.get_storage(),
+            tenure_info.tip_block_id,
+        )
+        .await?;
+        self.write_stacks_blocks(&stacks_blocks).await?;
+        self.set_stacks_checkpoint(Some(tenure_info.tip_block_id))
+            .await?;
+
+        for block in blocks.iter() {
+            self.write_bitcoin_block(block).await?;
+            self.report_reclaimed_deposits(block).await?;
+        }
+
+        self.update_sbtc_limits().await?;
+        self.load_latest_deposit_requests().await?;
+        self.context
+            .signal(SignerEvent::BitcoinBlockObserved.into())?;
+
+        tracing::info!("finished catching up on bitcoin blocks");
+        Ok(blocks.len())
+    }
+
+    /// Return the txids of the known sweep transactions that are still in
+    /// the mempool at least `min_age_blocks` bitcoin blocks after they
+    /// were broadcast, in no particular order. These are candidates for
+    /// replace-by-fee.
+    ///
+    /// Sweeps broadcast at a block that is not in the database are
+    /// skipped, since we cannot tell how old they are.
+    pub async fn stuck_sweeps(&self, min_age_blocks: u64) -> Result<Vec<bitcoin::Txid>, Error> {
+        let db = self.context.get_storage();
+        let chain_tip = db
+            .get_bitcoin_canonical_chain_tip()
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let chain_tip_height = db
+            .get_bitcoin_block(&chain_tip)
+            .await?
+            .ok_or(Error::MissingBitcoinBlock(chain_tip))?
+            .block_height;
+
+        let mempool: HashSet<bitcoin::Txid> = self
+            .context
+            .get_bitcoin_client()
+            .get_raw_mempool()
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut stuck_sweeps = Vec::new();
+        for (txid, created_at_block_hash) in self.context.state().known_sweeps().get_all() {
+            if !mempool.contains(&txid) {
+                continue;
+            }
+            let Some(created_at) = db.get_bitcoin_block(&created_at_block_hash).await? else {
+                continue;
+            };
+            if chain_tip_height.saturating_sub(created_at.block_height) >= min_age_blocks {
+                stuck_sweeps.push(txid);
+            }
+        }
+
+        Ok(stuck_sweeps)
+    }
+
+    /// Stop tracking the known sweep transactions that are no longer in
+    /// the mempool, either because they have been confirmed or because
+    /// they have been dropped or replaced. Returns the evicted txids.
+    pub async fn evict_settled_sweeps(&self) -> Result<Vec<bitcoin::Txid>, Error> {
+        let known_sweeps = self.context.state().known_sweeps();
+        if known_sweeps.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mempool: HashSet<bitcoin::Txid> = self
+            .context
+            .get_bitcoin_client()
+            .get_raw_mempool()
+            .await?
+            .into_iter()
+            .collect();
+
+        let settled: Vec<bitcoin::Txid> = known_sweeps
+            .get_all()
+            .into_iter()
+            .map(|(txid, _)| txid)
+            .filter(|txid| !mempool.contains(txid))
+            .collect();
+
+        known_sweeps.evict(settled.iter().copied());
+        Ok(settled)
+    }
+
+    /// Report the withdrawals that were held back as accepted, because
+    /// their sweep transaction was too shallow, as confirmed in Emily once
+    /// the sweep has at least `withdrawal_min_confirmations`
+    /// confirmations. Returns the request IDs of the withdrawals that were
+    /// reported.
+    ///
+    /// A withdrawal whose sweep is no longer on the canonical bitcoin
+    /// chain is released without being reported, since we will observe a
+    /// new accept event for it once it is swept again. If we fail to check
+    /// a withdrawal, it stays held and is checked again on the next
+    /// bitcoin block.
+    pub async fn release_held_withdrawals(&self) -> Result<Vec<u64>, Error> {
+        let db = self.context.get_storage_mut();
+        let held = db.get_held_withdrawals().await?;
+        if held.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let chain_tip = db
+            .get_bitcoin_canonical_chain_tip()
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let chain_tip_ref = db
+            .get_bitcoin_block(&chain_tip)
+            .await?
+            .map(model::BitcoinBlockRef::from)
+            .ok_or(Error::MissingBitcoinBlock(chain_tip))?;
+        let stacks_chain_tip = db
+            .get_stacks_chain_tip(&chain_tip)
+            .await?
+            .ok_or(Error::NoStacksChainTip)?;
+
+        let mut updates = Vec::new();
+        for event in held {
+            match self.check_held_withdrawal(&event, &chain_tip_ref).await {
+                Ok(true) => updates.push(new_block::withdrawal_confirmed_update(
+                    &event,
+                    &stacks_chain_tip,
+                )),
+                Ok(false) => {}
+                Err(error) => tracing::warn!(
+                    %error,
+                    request_id = event.request_id,
+                    "could not check the sweep of a held withdrawal"
+                ),
+            }
+        }
+
+        if updates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request_ids: Vec<u64> = updates.iter().map(|update| update.request_id).collect();
+        self.context
+            .get_emily_client()
+            .update_withdrawals(updates)
+            .await?;
+
+        for &request_id in &request_ids {
+            // If this fails then the withdrawal is reported as confirmed
+            // again on the next bitcoin block, which is harmless.
+            if let Err(error) = db.delete_held_withdrawal(request_id).await {
+                tracing::warn!(%error, request_id, "could not release a held withdrawal");
+            }
+        }
+
+        Ok(request_ids)
+    }
+
+    /// Check whether the sweep of the given held withdrawal is deep
+    /// enough to report the withdrawal as confirmed. Withdrawals whose
+    /// sweep block is no longer on the canonical bitcoin chain are
+    /// released here.
+    async fn check_held_withdrawal(
+        &self,
+        event: &WithdrawalAcceptEvent,
+        chain_tip: &model::BitcoinBlockRef,
+    ) -> Result<bool, Error> {
+        let db = self.context.get_storage_mut();
+        let sweep_block = model::BitcoinBlockRef {
+            block_hash: event.sweep_block_hash.into(),
+            block_height: event.sweep_block_height,
+        };
+        if !db
+            .in_canonical_bitcoin_blockchain(chain_tip, &sweep_block)
+            .await?
+        {
+            tracing::info!(
+                request_id = event.request_id,
+                sweep_txid = %event.sweep_txid,
+                "the sweep of a held withdrawal was reorged out, releasing it"
+            );
+            db.delete_held_withdrawal(event.request_id).await?;
+            return Ok(false);
+        }
+
+        let min_confirmations = self
+            .context
+            .config()
+            .signer
+            .withdrawal_min_confirmations
+            .unwrap_or_default();
+        let confirmations = self
+            .context
+            .get_bitcoin_client()
+            .get_tx_confirmations(&event.sweep_txid)
+            .await?
+            .unwrap_or_default();
+
+        Ok(confirmations >= min_confirmations)
+    }
+
+    /// Return the histogram of the time it took deposits to go from being
+    /// discovered to reaching a terminal status.
+    pub fn deposit_processing_latencies(&self) -> Vec<DepositLatencyBucket> {
+        self.context.state().deposit_latencies().histogram()
+    }
+
+    /// Return the txids of the stacks transactions submitted by the
+    /// signers that have not been confirmed within the configured
+    /// submission deadline, in no particular order. These should be
+    /// resubmitted with a fresh nonce and fee.
+    pub async fn pending_submissions(&self) -> Result<Vec<model::StacksTxId>, Error> {
+        let db = self.context.get_storage();
+        let chain_tip = db
+            .get_bitcoin_canonical_chain_tip()
+            .await?
+            .ok_or(Error::NoChainTip)?;
+        let stacks_tip_height = db
+            .get_stacks_chain_tip(&chain_tip)
+            .await?
+            .map_or(0, |block| block.block_height);
+
+        let deadline_blocks = self
+            .context
+            .config()
+            .signer
+            .submission_deadline_blocks
+            .unwrap_or(DEFAULT_SUBMISSION_DEADLINE_BLOCKS);
+
+        Ok(self
+            .context
+            .state()
+            .pending_submissions()
+            .past_deadline(stacks_tip_height, deadline_blocks))
+    }
+
+    /// Reconcile the stacks checkpoint of the block observer with the
+    /// canonical stacks chain of the node, returning the reconciled
+    /// checkpoint.
+    ///
+    /// This is meant to be called on startup, before
+    /// [`BlockObserver::run`]. The checkpoint is read from the database,
+    /// where it is persisted whenever it is set. If no checkpoint has been
+    /// set we start from the stacks chain tip in the database. A checkpoint that is an
+    /// ancestor of the node's tip is kept as is. Otherwise, we walk back
+    /// through the parents of the checkpoint until we reach a block that
+    /// is an ancestor of the node's tip, and roll the checkpoint back to
+    /// it. The checkpoint is cleared if we run out of known ancestors.
+    #[tracing::instrument(skip_all)]
+    pub async fn reconcile_on_start(&self) -> Result<Option<StacksBlockId>, Error> {
+        let db = self.context.get_storage();
+        let checkpoint = match self.context.state().stacks_checkpoint() {
+            Some(checkpoint) => Some(checkpoint),
+            None => match db.get_stacks_checkpoint().await? {
+                Some(checkpoint) => Some(checkpoint.into()),
+                None => match db.get_bitcoin_canonical_chain_tip().await? {
+                    Some(chain_tip) => db
+                        .get_stacks_chain_tip(&chain_tip)
+                        .await?
+                        .map(|block| block.block_hash.into()),
+                    None => None,
+                },
+            },
+        };
+        let Some(mut block_id) = checkpoint else {
+            return Ok(None);
+        };
+
+        let stacks_client = self.context.get_stacks_client();
+        let tenure_info = get_consistent_tenure_info(&stacks_client).await?;
+
+        let mut tip_id = tenure_info.tip_block_id;
+        let (mut tip_height, mut tip_parent) = self
+            .stacks_block_header(tip_id)
+            .await?
+            .ok_or(Error::MissingBlock)?;
+
+        let reconciled = match self.stacks_block_header(block_id).await? {
+            None => None,
+            Some((mut height, mut parent)) => loop {
+                // Walk the node's chain back to the height of the
+                // checkpoint, so that the checkpoint is an ancestor of the
+                // tip only if we land on it.
+                while tip_height > height {
+                    tip_id = tip_parent;
+                    (tip_height, tip_parent) = self
+                        .stacks_block_header(tip_id)
+                        .await?
+                        .ok_or(Error::MissingBlock)?;
+                }
+                if tip_id == block_id {
+                    break Some(block_id);
+                }
+
+                tracing::info!(%block_id, "stacks checkpoint is no longer canonical, rolling back");
+                match self.stacks_block_header(parent).await? {
+                    Some(header) => (block_id, (height, parent)) = (parent, header),
+                    None => break None,
+                }
+            },
+        };
+
+        self.set_stacks_checkpoint(reconciled).await?;
+        Ok(reconciled)
+    }
+
+    /// Set the stacks checkpoint of the block observer, persisting it in
+    /// the database so that it survives restarts.
+    async fn set_stacks_checkpoint(&self, checkpoint: Option<StacksBlockId>) -> Result<(), Error> {
+        let block_hash = checkpoint.map(model::StacksBlockHash::from);
+        self.context
+            .get_storage_mut()
+            .write_stacks_checkpoint(block_hash.as_ref())
+            .await?;
+        self.context.state().set_stacks_checkpoint(checkpoint);
+        Ok(())
+    }
+
+    /// Return the height and the parent of the stacks block with the given
+    /// ID, looking in the database first and then asking the stacks node.
+    ///
+    /// `None` is returned if neither knows of the block. Any other error
+    /// from the stacks node is returned as is.
+    async fn stacks_block_header(
+        &self,
+        block_id: StacksBlockId,
+    ) -> Result<Option<(u64, StacksBlockId)>, Error> {
+        let db = self.context.get_storage();
+        if let Some(block) = db.get_stacks_block(&block_id.into()).await? {
+            return Ok(Some((block.block_height, block.parent_hash.into())));
+        }
+
+        match self.context.get_stacks_client().get_block(block_id).await {
+            Ok(block) => Ok(Some((
+                block.header.chain_length,
+                block.header.parent_block_id,
+            ))),
+            Err(Error::MissingBlock) => Ok(None),
+            Err(Error::StacksNodeResponse(error))
+                if error.status() == Some(reqwest::StatusCode::NOT_FOUND) =>
+            {
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Report what the pending deposit requests in Emily for
+    /// transactions in the given block would produce, without storing or
+    /// updating anything.
+    ///
+    /// Each request is classified the way [`BlockObserver::load_requests`]
+    /// classifies it, see [`BlockObserver::classify_requests`], and is
+    /// reported as either accepted or rejected along with the reason.
+    pub async fn discover_deposits(
+        &self,
+        block: &bitcoin::Block,
+    ) -> Result<Vec<DiscoveredDeposit>, Error> {
+        let txids: HashSet<bitcoin::Txid> =
+            block.txdata.iter().map(|tx| tx.compute_txid()).collect();
+        let requests: Vec<CreateDepositRequest> = self
+            .context
+            .get_emily_client()
+            .get_deposits()
+            .await?
+            .into_iter()
+            .filter(|req| txids.contains(&req.outpoint.txid))
+            .collect();
+
+        let discovered = self
+            .classify_requests(&requests)
+            .await?
+            .into_iter()
+            .map(|(request, outcome)| {
+                let status = match outcome {
+                    RequestOutcome::Accepted(_) => DiscoveryStatus::Accepted,
+                    RequestOutcome::WrongKey(_) => DiscoveryStatus::Rejected(
+                        "deposit is locked to an unregistered signers' key".to_string(),
+                    ),
+                    RequestOutcome::Invalid(error) => DiscoveryStatus::Rejected(error.to_string()),
+                    RequestOutcome::Skipped(reason) => DiscoveryStatus::Rejected(reason),
+                };
+                DiscoveredDeposit {
+                    outpoint: request.outpoint,
+                    status,
+                }
+            })
+            .collect();
+
+        Ok(discovered)
+    }
+
+    /// Fetch deposit requests from Emily and store the ones that pass
+    /// validation into the database.
+    ///
+    /// After a failed call to Emily we back off, according to the
+    /// configured [`BackoffPolicy`], skipping the calls that would come
+    /// before the backoff delay has elapsed.
+    #[tracing::instrument(skip_all)]
+    async fn load_latest_deposit_requests(&self) -> Result<(), Error> {
+        let backoff = self.context.state().emily_backoff();
+        if !backoff.should_attempt(Instant::now()) {
+            tracing::debug!("backing off from Emily; not fetching deposit requests");
+            return Ok(());
+        }
+
+        let requests = match self.context.get_emily_client().get_deposits().await {
+            Ok(requests) => {
+                backoff.record_success();
+                requests
+            }
+            Err(error) => {
+                let policy = BackoffPolicy::emily_from_config(&self.context.config().signer);
+                let delay = backoff.record_failure(&policy, Instant::now());
+                tracing::warn!(
+                    ?delay,
+                    "could not fetch deposit requests; backing off from Emily"
+                );
+                return Err(error);
+            }
+        };
+
+        // An unavailable override is not a reason to stop processing
+        // deposits, so we keep using the last value that we fetched.
+        match self
+            .context
+            .get_emily_client()
+            .get_min_confirmations()
+            .await
+        {
+            Ok(min_confirmations) => self
+                .context
+                .state()
+                .set_deposit_min_confirmations(min_confirmations),
+            Err(error) => tracing::warn!(
+                %error,
+                "could not fetch the deposit minimum confirmations; using the last known value"
+            ),
+        }
+        self.load_requests(&requests).await
+    }
+
+    /// Validate the given deposit requests and store the ones that pass
+    /// validation into the database.
+    ///
+    /// There are three types of errors that can happen during validation
+    /// 1. The transaction fails primary validation. This means the deposit
+    ///    script itself does not align with what we expect. If probably
+    ///    does not follow our protocol.
+    /// 2. The transaction passes step (1), but we don't recognize the
+    ///    x-only public key in the deposit script.
+    /// 3. We cannot find the associated transaction confirmed on a bitcoin
+    ///    block, or when we encountered some unexpected error when
+    ///    reaching out to bitcoin-core or our database.
+    ///
+    /// Errors of type (1) are permanent, the request will fail validation
+    /// every time we see it, so these requests are evicted from the set
+    /// of pending deposits in Emily. See [`Self::evict_deposit_requests`].
+    ///
+    /// Requests for a funding output that another request claims, and
+    /// whose scripts do not match the output, are superseded and skipped
+    /// without being validated. See [`supersede_duplicate_requests`].
+    ///
+    /// Deposits whose reclaim script does not follow one of the templates
+    /// in the configured allowlist are skipped, but left pending in Emily,
+    /// since the allowlist is local to this signer.
+    ///
+    /// Deposits with fewer bitcoin confirmations than the minimum set in
+    /// Emily are skipped, but left pending, so that they are loaded once
+    /// they are buried deep enough.
+    ///
+    /// Deposits that confirmed with a replacement of the transaction in
+    /// the request are moved to the outpoint in the replacement in Emily.
+    /// See [`Self::register_replaced_deposit_requests`].
+    #[tracing::instrument(skip_all)]
+    pub async fn load_requests(&self, requests: &[CreateDepositRequest]) -> Result<(), Error> {
+        // Requests that are no longer pending do not need their scripts
+        // anymore.
+        self.context.state().deposit_scripts().retain(requests);
+
+        let now = Instant::now();
+        let latencies = self.context.state().deposit_latencies();
+        for request in requests {
+            latencies.record_discovered(request.outpoint, now);
+        }
+        latencies.retain(requests, now);
+
+        let mut deposit_requests = Vec::new();
+        let mut invalid_requests = Vec::new();
+        let mut replaced_requests = Vec::new();
+        let mut wrong_key_requests = Vec::new();
+        for (request, outcome) in self.classify_requests(requests).await? {
+            match outcome {
+                RequestOutcome::Accepted(deposit) => {
+                    if deposit.info.outpoint != request.outpoint {
+                        replaced_requests.push((request, deposit.info.outpoint));
+                    }
+                    deposit_requests.push(deposit);
+                }
+                RequestOutcome::WrongKey(deposit) => {
+                    if deposit.info.outpoint != request.outpoint {
+                        replaced_requests.push((request, deposit.info.outpoint));
+                    }
+                    wrong_key_requests.push(deposit);
+                }
+                RequestOutcome::Invalid(error) => invalid_requests.push((request, error)),
+                RequestOutcome::Skipped(_) => {}
+            }
+        }
+
+        // Emily has to know about the replacements before any of the
+        // updates below, or later ones, are made for their outpoints.
+        self.register_replaced_deposit_requests(replaced_requests)
+            .await?;
+        self.store_deposit_requests(deposit_requests).await?;
+        self.evict_deposit_requests(invalid_requests).await?;
+        self.reject_wrong_key_deposit_requests(wrong_key_requests)
+            .await?;
+
+        tracing::debug!("finished processing deposit requests");
+        Ok(())
+    }
+
+    /// Validate the given deposit requests and decide what the block
+    /// observer does with each of them. The outcomes are returned in the
+    /// order that the requests were given, except for the requests that
+    /// are superseded by another request, which come last. Nothing is
+    /// stored or sent to Emily here, see [`Self::load_requests`] for what
+    /// happens to each type of outcome.
+    async fn classify_requests<'a>(
+        &self,
+        requests: &'a [CreateDepositRequest],
+    ) -> Result<Vec<(&'a CreateDepositRequest, RequestOutcome)>, Error> {
+        let concurrency = self
+            .context
+            .config()
+            .signer
+            .deposit_validation_concurrency
+            .map_or(DEFAULT_DEPOSIT_VALIDATION_CONCURRENCY, usize::from)
+            .max(1);
+        let allowlist = self
+            .context
+            .config()
+            .signer
+            .reclaim_script_allowlist
+            .as_deref();
+        let bitcoin_client = self.context.get_bitcoin_client();
+        let signer_state = self.context.state();
+        let cache = signer_state.deposit_scripts();
+        let min_confirmations = signer_state.deposit_min_confirmations();
+
+        // Only the funding outputs that several requests claim need to be
+        // looked up, so that the request matching the output is the one
+        // that gets validated.
+        let mut funding_script_pubkeys = HashMap::new();
+        for outpoint in contested_funding_outputs(requests) {
+            match bitcoin_client.get_tx(&outpoint.txid).await {
+                Ok(Some(response)) => {
+                    let tx_out = usize::try_from(outpoint.vout)
+                        .ok()
+                        .and_then(|vout| response.tx.output.get(vout));
+                    if let Some(tx_out) = tx_out {
+                        funding_script_pubkeys.insert(outpoint, tx_out.script_pubkey.clone());
+                    }
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    tracing::warn!(%error, %outpoint, "could not fetch contested funding output");
+                }
+            }
+        }
+        let (active, superseded) = supersede_duplicate_requests(requests, &funding_script_pubkeys);
+
+        // Validating a request means reaching out to bitcoin-core a few
+        // times, so we validate several requests at once, up to the
+        // configured limit.
+        let validated = futures::stream::iter(active)
+            .map(|request| {
+                let bitcoin_client = &bitcoin_client;
+                async move {
+                    (
+                        request,
+                        request.validate_cached(bitcoin_client, cache).await,
+                    )
+                }
+            })
+            .buffered(concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut outcomes = Vec::with_capacity(requests.len());
+        for (request, deposit) in validated {
+            let deposit = deposit
+                .inspect_err(|error| tracing::warn!(%error, "could not validate deposit request"));
+
+            // We log the error above, so we just need to extract the
+            // deposit now.
+            let outcome = match deposit {
+                // The deposit may reach the minimum number of
+                // confirmations later, so it is not evicted.
+                Ok(Some(deposit))
+                    if min_confirmations
+                        .is_some_and(|min| u64::from(deposit.tx_info.confirmations) < min) =>
+                {
+                    tracing::debug!(
+                        outpoint = %request.outpoint,
+                        confirmations = deposit.tx_info.confirmations,
+                        "skipping deposit request without enough confirmations"
+                    );
+                    RequestOutcome::Skipped(
+                        "deposit transaction does not have enough confirmations".to_string(),
+                    )
+                }
+                // The reclaim script allowlist is configured by each
+                // signer, so a deposit that is not allowed here may be
+                // allowed by the other signers. These are skipped without
+                // telling Emily.
+                Ok(Some(deposit)) => match check_reclaim_script_allowlist(allowlist, &deposit.info)
+                {
+                    Ok(()) => RequestOutcome::Accepted(deposit),
+                    Err(error) => {
+                        tracing::info!(
+                            %error,
+                            outpoint = %request.outpoint,
+                            "skipping deposit request with a reclaim script that is not allowlisted"
+                        );
+                        RequestOutcome::Skipped(error.to_string())
+                    }
+                },
+                Ok(None) => {
+                    RequestOutcome::Skipped("deposit transaction is not confirmed".to_string())
+                }
+                // These errors come from checking the deposit request
+                // against its confirmed transaction, or from finding that
+                // the transaction can never be confirmed, so the outcome
+                // will not change the next time that we try.
+                Err(error @ (Error::SbtcLib(_) | Error::FundingInputsSpent(_))) => {
+                    RequestOutcome::Invalid(error)
+                }
+                Err(error) => RequestOutcome::Skipped(error.to_string()),
+            };
+            outcomes.push((request, outcome));
+        }
+
+        for request in superseded {
+            tracing::info!(
+                outpoint = %request.outpoint,
+                "skipping deposit request superseded by another request for its funding output"
+            );
+            let reason = "deposit request is superseded by another request for its funding output";
+            outcomes.push((request, RequestOutcome::Skipped(reason.to_string())));
+        }
+
+        // Only the accepted deposits need their key checked, and the
+        // check goes through the database, so it is done in one go.
+        let accepted: Vec<Deposit> = outcomes
+            .iter()
+            .filter_map(|(_, outcome)| match outcome {
+                RequestOutcome::Accepted(deposit) => Some(deposit.clone()),
+                _ => None,
+            })
+            .collect();
+        let (_, unknown_key) = self.partition_by_signers_key(accepted).await?;
+
+        // Our database may be missing keys that the other signers know
+        // about, say because we missed a key rotation or were restored
+        // from a backup, so a key that we do not know about is checked
+        // against the keys in the registry before the deposit is
+        // rejected for every signer. Otherwise we skip the deposit
+        // without telling Emily.
+        let mut wrong_key = HashSet::new();
+        let mut unknown_locally = HashSet::new();
+        for deposit in unknown_key {
+            let signers_public_key = deposit.info.signers_public_key;
+            let outpoint = deposit.info.outpoint;
+            match self.is_registered_signers_key(signers_public_key).await {
+                Ok(false) => {
+                    wrong_key.insert(outpoint);
+                }
+                Ok(true) => {
+                    tracing::warn!(
+                        %outpoint,
+                        %signers_public_key,
+                        "skipping deposit request locked to a registered key that we do not know about"
+                    );
+                    unknown_locally.insert(outpoint);
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        %outpoint,
+                        %signers_public_key,
+                        "could not check the key of a deposit request against the registry"
+                    );
+                    unknown_locally.insert(outpoint);
+                }
+            }
+        }
+
+        for (_, outcome) in outcomes.iter_mut() {
+            if let RequestOutcome::Accepted(deposit) = outcome {
+                let outpoint = deposit.info.outpoint;
+                if wrong_key.contains(&outpoint) {
+                    *outcome = RequestOutcome::WrongKey(deposit.clone());
+                } else if unknown_locally.contains(&outpoint) {
+                    let reason = "deposit request is locked to a key that we do not know about";
+                    *outcome = RequestOutcome::Skipped(reason.to_string());
+                }
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Register the deposits of the given requests, whose deposit
+    /// transaction was replaced by one that moved the deposit to the given
+    /// outpoint, with Emily.
+    ///
+    /// Emily keys deposits on their outpoint, so the updates that the
+    /// signers make once the deposit has been swept are made for the
+    /// outpoint in the replacement. We create a deposit for that outpoint,
+    /// with the scripts of the original request, and mark the original
+    /// deposit as failed with a status message that points to the
+    /// replacement, so that it is no longer returned as pending.
+    async fn register_replaced_deposit_requests(
+        &self,
+        requests: Vec<(&CreateDepositRequest, OutPoint)>,
+    ) -> Result<(), Error> {
+        if requests.is_empty() {
+            return Ok(());
+        }
+
+        let emily_client = self.context.get_emily_client();
+        let create_deposits = requests
+            .iter()
+            .map(|(request, replacement)| CreateDepositRequestBody {
+                bitcoin_tx_output_index: replacement.vout,
+                bitcoin_txid: replacement.txid.to_string(),
+                deposit_script: request.deposit_script.to_hex_string(),
+                reclaim_script: request.reclaim_script.to_hex_string(),
+            })
+            .collect();
+        let response = emily_client.create_deposits(create_deposits).await?;
+        // Other signers register the same replacements, so some of these
+        // are expected to already exist.
+        for error in response.errors {
+            tracing::debug!(?error, "could not register replacement deposit in Emily");
+        }
+
+        let tenure_info = self.context.get_stacks_client().get_tenure_info().await?;
+        let stacks_tip = model::StacksBlockHash::from(tenure_info.tip_block_id);
+
+        let updates = requests
+            .into_iter()
+            .map(|(request, replacement)| {
+                tracing::info!(
+                    original = %request.outpoint,
+                    %replacement,
+                    "moving replaced deposit request to its replacement"
+                );
+                let message = format!("deposit transaction was replaced, see {replacement}");
+                DepositUpdate {
+                    bitcoin_tx_output_index: request.outpoint.vout,
+                    bitcoin_txid: request.outpoint.txid.to_string(),
+                    status: Status::Failed,
+                    failure_reason: Some(Some(message.clone())),
+                    fulfillment: None,
+                    status_message: message,
+                    last_update_block_hash: stacks_tip.to_string(),
+                    last_update_height: tenure_info.tip_height,
+                }
+            })
+            .collect();
+
+        emily_client.update_deposits(updates).await?;
+        Ok(())
+    }
+
+    /// Mark the given deposit requests as failed in Emily, so that they
+    /// are no longer returned as pending deposits and reprocessed with
+    /// each new bitcoin block. The reason for the failure is recorded in
+    /// the status message of the deposit.
+    async fn evict_deposit_requests(
+        &self,
+        requests: Vec<(&CreateDepositRequest, Error)>,
+    ) -> Result<(), Error> {
+        if requests.is_empty() {
+            return Ok(());
+        }
+
+        let tenure_info = self.context.get_stacks_client().get_tenure_info().await?;
+        let stacks_tip = model::StacksBlockHash::from(tenure_info.tip_block_id);
+
+        let now = Instant::now();
+        let latencies = self.context.state().deposit_latencies();
+
+        let updates = requests
+            .into_iter()
+            .map(|(request, error)| {
+                latencies.record_resolved(&request.outpoint, now);
+                tracing::info!(%error, outpoint = %request.outpoint, "evicting invalid deposit request");
+                DepositUpdate {
+                    bitcoin_tx_output_index: request.outpoint.vout,
+                    bitcoin_txid: request.outpoint.txid.to_string(),
+                    status: Status::Failed,
+                    failure_reason: Some(Some(error.to_string())),
+                    fulfillment: None,
+                    status_message: error.to_string(),
+                    last_update_block_hash: stacks_tip.to_string(),
+                    last_update_height: tenure_info.tip_height,
+                }
+            })
+            .collect();
+
+        self.context
+            .get_emily_client()
+            .update_deposits(updates)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Split the given deposits into the ones locked to one of the
+    /// signers' keys and the ones locked to a key that the signers do not
+    /// know about, say a stale aggregate key.
+    ///
+    /// Before the signers have run DKG for the first time they cannot tell
+    /// which keys are stale, so all deposits are taken to be locked to a
+    /// known key.
+    async fn partition_by_signers_key(
+        &self,
+        deposits: Vec<Deposit>,
+    ) -> Result<(Vec<Deposit>, Vec<Deposit>), Error> {
+        let db = self.context.get_storage();
+        if db.get_latest_encrypted_dkg_shares().await?.is_none() {
+            return Ok((deposits, Vec::new()));
+        }
+
+        let mut known_key = Vec::new();
+        let mut wrong_key = Vec::new();
+        for deposit in deposits {
+            let script_pubkey = deposit.info.signers_public_key.signers_script_pubkey();
+            if db.is_signer_script_pub_key(&script_pubkey.into()).await? {
+                known_key.push(deposit);
+            } else {
+                wrong_key.push(deposit);
+            }
+        }
+
+        Ok((known_key, wrong_key))
+    }
+
+    /// Check whether the given key, taken from a deposit script, is one of
+    /// the signers' current or past aggregate keys in the registry.
+    ///
+    /// The registry holds the full aggregate keys while deposit scripts
+    /// only hold their x-coordinate, so both parities are checked.
+    async fn is_registered_signers_key(
+        &self,
+        signers_public_key: secp256k1::XOnlyPublicKey,
+    ) -> Result<bool, Error> {
+        let stacks_client = self.context.get_stacks_client();
+        let deployer = &self.context.config().signer.deployer;
+        for parity in [secp256k1::Parity::Even, secp256k1::Parity::Odd] {
+            let aggregate_key = PublicKey::from(secp256k1::PublicKey::from_x_only_public_key(
+                signers_public_key,
+                parity,
+            ));
+            if stacks_client
+                .is_registered_aggregate_key(deployer, &aggregate_key)
+                .await?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Mark the given deposits, which are locked to a key that is not one
+    /// of the signers' aggregate keys in the registry, as rejected in
+    /// Emily, so that operators can see which deposits the signers are
+    /// skipping and why.
+    async fn reject_wrong_key_deposit_requests(&self, deposits: Vec<Deposit>) -> Result<(), Error> {
+        if deposits.is_empty() {
+            return Ok(());
+        }
+
+        let tenure_info = self.context.get_stacks_client().get_tenure_info().await?;
+        let stacks_tip = model::StacksBlockHash::from(tenure_info.tip_block_id);
+
+        let now = Instant::now();
+        let latencies = self.context.state().deposit_latencies();
+
+        let updates = deposits
+            .into_iter()
+            .map(|deposit| {
+                let outpoint = deposit.info.outpoint;
+                latencies.record_resolved(&outpoint, now);
+                let signers_public_key = deposit.info.signers_public_key;
+                tracing::info!(
+                    %outpoint,
+                    %signers_public_key,
+                    "rejecting deposit request locked to an unregistered signers' key"
+                );
+                DepositUpdate {
+                    bitcoin_tx_output_index: outpoint.vout,
+                    bitcoin_txid: outpoint.txid.to_string(),
+                    status: Status::RejectedWrongKey,
+                    failure_reason: None,
+                    fulfillment: None,
+                    status_message: format!(
+                        "deposit is locked to the unregistered signers' key {signers_public_key}"
+                    ),
+                    last_update_block_hash: stacks_tip.to_string(),
+                    last_update_height: tenure_info.tip_height,
+                }
+            })
+            .collect();
+
+        self.context
+            .get_emily_client()
+            .update_deposits(updates)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find the deposits that are reclaimed by transactions in the given
+    /// block and mark them as reclaimed in Emily, so that they are no
+    /// longer tracked as pending.
+    ///
+    /// A deposit is reclaimed when its UTXO is spent using the reclaim
+    /// script, which is revealed in the witness of the spending input.
+    async fn report_reclaimed_deposits(&self, block: &bitcoin::Block) -> Result<(), Error> {
+        let db = self.context.get_storage();
+        let mut reclaimed = Vec::new();
+
+        let tx_ins = block
+            .txdata
+            .iter()
+            .filter(|tx| !tx.is_coinbase())
+            .flat_map(|tx| tx.input.iter().map(move |tx_in| (tx.compute_txid(), tx_in)));
+
+        for (txid, tx_in) in tx_ins {
+            // Only script path spends reveal a script, and deposits can
+            // only be spent using a script path spend.
+            let Some(script) = tx_in.witness.tapscript() else {
+                continue;
+            };
+            let outpoint = tx_in.previous_output;
+            let Some(deposit) = db
+                .get_deposit_request(&outpoint.txid.into(), outpoint.vout)
+                .await?
+            else {
+                continue;
+            };
+            if script.as_bytes() == deposit.reclaim_script.as_slice() {
+                reclaimed.push((outpoint, txid));
+            }
+        }
+
+        if reclaimed.is_empty() {
+            return Ok(());
+        }
+
+        let tenure_info = self.context.get_stacks_client().get_tenure_info().await?;
+        let stacks_tip = model::StacksBlockHash::from(tenure_info.tip_block_id);
+
+        let now = Instant::now();
+        let latencies = self.context.state().deposit_latencies();
+
+        let updates = reclaimed
+            .into_iter()
+            .map(|(outpoint, txid)| {
+                latencies.record_resolved(&outpoint, now);
+                tracing::info!(%outpoint, %txid, "observed reclaimed deposit");
+                DepositUpdate {
+                    bitcoin_tx_output_index: outpoint.vout,
+                    bitcoin_txid: outpoint.txid.to_string(),
+                    status: Status::Reclaimed,
+                    failure_reason: None,
+                    fulfillment: None,
+                    status_message: format!("deposit reclaimed in bitcoin transaction {txid}"),
+                    last_update_block_hash: stacks_tip.to_string(),
+                    last_update_height: tenure_info.tip_height,
+                }
+            })
+            .collect();
+
+        self.context
+            .get_emily_client()
+            .update_deposits(updates)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Check whether the given unprocessed blocks, in chronological
+    /// order, build on the last processed chain tip. If they do not then
+    /// we are looking at a reorg, and the returned event has the height of
+    /// the block that both chains have in common.
+    ///
+    /// No reorg is reported if we have not processed any blocks yet, or if
+    /// we do not have the parent of the first unprocessed block in our
+    /// database, since then we do not know where the chains fork.
+    async fn detect_reorg(
+        &self,
+        last_tip: Option<BlockHash>,
+        blocks: &[bitcoin::Block],
+    ) -> Result<Option<ReorgEvent>, Error> {
+        let (Some(old_tip), Some(first), Some(last)) = (last_tip, blocks.first(), blocks.last())
+        else {
+            return Ok(None);
+        };
+
+        let fork_point = first.header.prev_blockhash;
+        if fork_point == old_tip {
+            return Ok(None);
+        }
+
+        let Some(fork_block) = self
+            .context
+            .get_storage()
+            .get_bitcoin_block(&fork_point.into())
+            .await?
+        else {
+            tracing::debug!(%fork_point, "parent of the new blocks is unknown, skipping reorg check");
+            return Ok(None);
+        };
+
+        Ok(Some(ReorgEvent {
+            old_tip: old_tip.into(),
+            new_tip: last.block_hash().into(),
+            fork_height: fork_block.block_height,
+        }))
+    }
+
+    /// Find the parent blocks from the given block that are also missing from our database
+    #[tracing::instrument(skip_all, fields(%block_hash))]
+    async fn next_blocks_to_process(
+        &self,
+        mut block_hash: bitcoin::BlockHash,
+    ) -> Result<Vec<bitcoin::Block>, Error> {
+        let mut blocks = Vec::new();
+
+        for _ in 0..self.horizon {
+            if self.have_already_processed_block(&block_hash).await? {
+                tracing::debug!(%block_hash, "already processed block");
+                break;
+            }
+
+            let block = self
+                .context
+                .get_bitcoin_client()
+                .get_block(&block_hash)
+                .await?
+                .ok_or(Error::MissingBitcoinBlock(block_hash.into()))?;
+
+            // TODO(685): Remove this and replace with a better limiting
+            // condition.
+            if block.bip34_block_height().unwrap_or(0) == 0 {
+                break;
+            }
+
+            block_hash = block.header.prev_blockhash;
+            blocks.push(block);
+        }
+
+        // Make order chronological
+        blocks.reverse();
+        Ok(blocks)
+    }
+
+    /// Check whether we have already processed this block in our
+    /// database.
+    #[tracing::instrument(skip(self))]
+    async fn have_already_processed_block(
+        &self,
+        block_hash: &bitcoin::BlockHash,
+    ) -> Result<bool, Error> {
+        Ok(self
+            .context
+            .get_storage()
+            .get_bitcoin_block(&block_hash.to_byte_array().into())
+            .await?
+            .is_some())
+    }
+
+    /// Process the bitcoin block. Also process all recent stacks blocks.
+    #[tracing::instrument(skip_all, fields(block_hash = %block.block_hash()))]
+    async fn process_bitcoin_block(&self, block: bitcoin::Block) -> Result<(), Error> {
+        tracing::info!("processing bitcoin block");
+        let stacks_client = self.context.get_stacks_client();
+        let tenure_info = get_consistent_tenure_info(&stacks_client).await?;
+
+        tracing::debug!("fetching unknown ancestral blocks from stacks-core");
+        let stacks_blocks = crate::stacks::api::fetch_unknown_ancestors(
+            &stacks_client,
+            &self.context.get_storage(),
+            tenure_info.tip_block_id,
+        )
+        .await?;
+
+        self.write_stacks_blocks(&stacks_blocks).await?;
+        self.set_stacks_checkpoint(Some(tenure_info.tip_block_id))
+            .await?;
+        self.write_bitcoin_block(&block).await?;
+        self.report_reclaimed_deposits(&block).await?;
+
+        tracing::debug!("finished processing bitcoin block");
+        Ok(())
+    }
+
+    /// For each of the deposit requests, persist the corresponding
+    /// transaction and the parsed deposit info into the database.
+    ///
+    /// This function does three things:
+    /// 1. For all deposit requests, check to see if there are bitcoin
+    ///    blocks that we do not have in our database.
+    /// 2. If we do not have a record of the bitcoin block then write it
+    ///    to the database.
+    /// 3. Write the deposit transaction and the extracted deposit info
+    ///    into the database.
+    async fn store_deposit_requests(&self, requests: Vec<Deposit>) -> Result<(), Error> {
+        // We need to check to see if we have a record of the bitcoin block
+        // that contains the deposit request in our database. If we don't
+        // then write them to our database.
+        self.store_deposit_request_blocks(&requests).await?;
+
+        // Okay now we write the deposit requests and the transactions to
+        // the database.
+        let (deposit_requests, deposit_request_txs) = requests
+            .into_iter()
+            .map(|deposit| {
+                let tx = model::Transaction {
+                    txid: deposit.tx_info.txid.to_byte_array(),
+                    tx: bitcoin::consensus::serialize(&deposit.tx_info.tx),
+                    tx_type: model::TransactionType::DepositRequest,
+                    block_hash: deposit.tx_info.block_hash.to_byte_array(),
+                };
+
+                (model::DepositRequest::from(deposit), tx)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .unzip();
+
+        let db = self.context.get_storage_mut();
+        db.write_bitcoin_transactions(deposit_request_txs).await?;
+        db.write_deposit_requests(deposit_requests).await?;
+
+        Ok(())
+    }
+
+    /// This function does two things:
+    /// 1. For all deposit requests, check to see if there are bitcoin
+    ///    blocks that we do not have in our database.
+    /// 2. If we do not have a record of the bitcoin block then write it
+    ///    to the database.
+    async fn store_deposit_request_blocks(&self, requests: &[Deposit]) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+        let mut deposit_blocks = Vec::new();
+
+        // We need to check to see if we have a record of the bitcoin block
+        // that contains the deposit request in our database.
+        for deposit in requests.iter() {
+            let blocks = self
+                .next_blocks_to_process(deposit.tx_info.block_hash)
+                .await?
+                .into_iter()
+                .map(model::BitcoinBlock::from);
+            deposit_blocks.extend(blocks);
+        }
+
+        // We now get the distinct blocks and write them to the database.
+        deposit_blocks.sort_by_key(|block| (block.block_height, block.block_hash));
+        deposit_blocks.dedup();
+
+        for block in deposit_blocks {
+            db.write_bitcoin_block(&block).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Extract all BTC transactions from the block where one of the UTXOs
+    /// can be spent by the signers.
+    ///
+    /// # Note
+    ///
+    /// When using the postgres storage, we need to make sure that this
+    /// function is called after the `Self::write_bitcoin_block` function
+    /// because of the foreign key constraints.
+    pub async fn extract_sbtc_transactions(
+        &self,
+        block_hash: BlockHash,
+        txs: &[Transaction],
+    ) -> Result<(), Error> {
+        let db = self.context.get_storage_mut();
+        // We store all the scriptPubKeys associated with the signers'
+        // aggregate public key. Let's get the last years worth of them.
+        let signer_script_pubkeys: HashSet<ScriptBuf> = db
+            .get_signers_script_pubkeys()
+            .await?
+            .into_iter()
+            .map(ScriptBuf::from_bytes)
+            .collect();
+
+        let btc_rpc = self.context.get_bitcoin_client();
+        // Look through all the UTXOs in the given transaction slice and
+        // keep the transactions where a UTXO is locked with a
+        // `scriptPubKey` controlled by the signers.
+        let mut sbtc_txs = Vec::new();
+        for tx in txs {
+            tracing::debug!(txid = %tx.compute_txid(), "attempting to extract sbtc transaction");
+            // If any of the outputs are spent to one of the signers'
+            // addresses, then we care about it
+            let outputs_spent_to_signers = tx
+                .output
+                .iter()
+                .any(|tx_out| signer_script_pubkeys.contains(&tx_out.script_pubkey));
+
+            if !outputs_spent_to_signers {
+                continue;
+            }
+
+            // This function is called after we have received a
+            // notification of a bitcoin block, and we are iterating
+            // through all of the transactions within that block. This
+            // means the `get_tx_info` call below should not fail.
+            let txid = tx.compute_txid();
+            let tx_info = btc_rpc
+                .get_tx_info(&txid, &block_hash)
+                .await?
+                .ok_or(Error::BitcoinTxMissing(txid, None))?;
+
+            // sBTC transactions have as first txin a signers spendable output
+            let tx_type = if tx_info.is_signer_created(&signer_script_pubkeys) {
+                model::TransactionType::SbtcTransaction
+            } else {
+                model::TransactionType::Donation
+            };
+
+            let txid = tx.compute_txid();
+            sbtc_txs.push(model::Transaction {
+                txid: txid.to_byte_array(),
+                tx: bitcoin::consensus::serialize(&tx),
+                tx_type,
+                block_hash: block_hash.to_byte_array(),
+            });
+
+            for prevout in tx_info.to_inputs(&signer_script_pubkeys) {
+                db.write_tx_prevout(&prevout).await?;
+            }
+
+            for output in tx_info.to_outputs(&signer_script_pubkeys) {
+                db.write_tx_output(&output).await?;
+            }
+        }
+
+        // Write these transactions into storage.
+        db.write_bitcoin_transactions(sbtc_txs).await?;
+        Ok(())
+    }
+
+    /// Write the given stacks blocks to the database.
+    ///
+    /// This function also extracts sBTC Stacks transactions from the given
+    /// blocks and stores them into the database.
+    async fn write_stacks_blocks(&self, tenures: &[TenureBlocks]) -> Result<(), Error> {
+        let deployer = &self.context.config().signer.deployer;
+        let txs = tenures
+            .iter()
+            .flat_map(|tenure| {
+                storage::postgres::extract_relevant_transactions(tenure.blocks(), deployer)
+            })
+            .collect::<Vec<_>>();
+
+        let headers = tenures
+            .iter()
+            .flat_map(TenureBlocks::as_stacks_blocks)
+            .collect::<Vec<_>>();
+
+        let storage = self.context.get_storage_mut();
+        storage.write_stacks_block_headers(headers).await?;
+        storage.write_stacks_transactions(txs).await?;
+
+        // Submitted transactions in these blocks have been confirmed.
+        let confirmed = tenures
+            .iter()
+            .flat_map(TenureBlocks::blocks)
+            .flat_map(|block| block.txs.iter())
+            .map(|tx| model::StacksTxId::from(tx.txid()));
+        self.context
+            .state()
+            .pending_submissions()
+            .confirm(confirmed);
+        Ok(())
+    }
+
+    /// Write the bitcoin block to the database. We also write any
+    /// transactions that are spend to any of the signers `scriptPubKey`s
+    async fn write_bitcoin_block(&self, block: &bitcoin::Block) -> Result<(), Error> {
+        let db_block = model::BitcoinBlock::from(block);
+
+        self.context
+            .get_storage_mut()
+            .write_bitcoin_block(&db_block)
+            .await?;
+        self.extract_sbtc_transactions(block.block_hash(), &block.txdata)
+            .await?;
+
+        self.context
+            .state()
+            .block_observer_stats()
+            .record_processed_block(db_block.block_height);
+
+        Ok(())
+    }
+
+    /// Update the sBTC peg limits from Emily
+    async fn update_sbtc_limits(&self) -> Result<(), Error> {
+        let limits = self.context.get_emily_client().get_limits().await?;
+        let max_mintable = match limits.total_cap() {
+            Amount::MAX_MONEY => Amount::MAX_MONEY,
+            total_cap => {
+                let sbtc_supply = self
+                    .context
+                    .get_stacks_client()
+                    .get_sbtc_total_supply(&self.context.config().signer.deployer)
+                    .await?;
+                // The maximum amount of sBTC that can be minted is the total cap
+                // minus the current supply.
+                total_cap.checked_sub(sbtc_supply).unwrap_or(Amount::ZERO)
+            }
+        };
+
+        let limits = SbtcLimits::new(
+            Some(limits.total_cap()),
+            Some(limits.per_deposit_cap()),
+            Some(limits.per_withdrawal_cap()),
+            Some(max_mintable),
+        );
+
+        let signer_state = self.context.state();
+        if limits == signer_state.get_current_limits() {
+            tracing::trace!(%limits, "sBTC limits have not changed");
+        } else {
+            tracing::debug!(%limits, "updated sBTC limits from Emily");
+            signer_state.update_current_limits(limits);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::Amount;
+    use bitcoin::BlockHash;
+    use bitcoin::TxOut;
+    use fake::Dummy;
+    use fake::Fake;
+    use model::BitcoinTxId;
+    use model::ScriptPubKey;
+    use rand::SeedableRng;
+    use sbtc::deposits::ParsedDepositScripts;
+    use test_log::test;
+
+    use crate::bitcoin::rpc::GetTxResponse;
+    use crate::context::DepositScriptCache;
+    use crate::context::SignerSignal;
+    use crate::keys::PublicKey;
+    use crate::keys::SignerScriptPubKey as _;
+    use crate::storage;
+    use crate::testing::block_observer::TestHarness;
+    use crate::testing::context::*;
+
+    use super::*;
+
+    #[test(tokio::test)]
+    async fn should_be_able_to_extract_bitcoin_blocks_given_a_block_header_stream() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // There must be at least one signal receiver alive when the block observer
+        // later tries to send a signal, hence this line.
+        let _signal_rx = ctx.get_signal_receiver();
+        let block_hash_stream = test_harness.spawn_block_hash_stream();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: block_hash_stream,
+            horizon: 1,
+        };
+
+        let handle = tokio::spawn(block_observer.run());
+        ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+            )
+        })
+        .await
+        .expect("block observer failed to complete within timeout");
+
+        for block in test_harness.bitcoin_blocks() {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash().into())
+                .await
+                .expect("storage error")
+                .expect("block wasn't persisted");
+
+            assert_eq!(persisted.block_hash, block.block_hash().into())
+        }
+
+        handle.abort();
+    }
+
+    /// Test that a block observer that is asked to shut down exits its
+    /// loop after the block that it is processing has been written to
+    /// the database.
+    #[test(tokio::test)]
+    async fn block_observer_shuts_down_after_processing_a_block() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+
+        // We keep the sender alive for the duration of the test so that
+        // the observer can only exit because it was asked to.
+        let block_hash = test_harness.bitcoin_blocks()[0].block_hash();
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tx.send(Ok(block_hash)).await.unwrap();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(rx),
+            horizon: 1,
+        };
+
+        let handle = block_observer.spawn();
+        ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+            )
+        })
+        .await
+        .expect("block observer failed to complete within timeout");
+
+        handle.shutdown();
+        tokio::time::timeout(Duration::from_secs(3), handle.join())
+            .await
+            .expect("block observer failed to stop within timeout")
+            .expect("block observer returned an error");
+
+        let persisted = storage
+            .get_bitcoin_block(&block_hash.into())
+            .await
+            .expect("storage error")
+            .expect("block wasn't persisted");
+        assert_eq!(persisted.block_hash, block_hash.into());
+
+        drop(tx);
+    }
+
+    /// Test that the block observer emits a single reorg event, with the
+    /// height of the block where the chains fork, when it observes a
+    /// block that does not build on the last processed chain tip.
+    #[tokio::test]
+    async fn block_observer_emits_reorg_events() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let mut test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+
+        let chain = test_harness.bitcoin_blocks().to_vec();
+        let old_tip = chain.last().unwrap().block_hash();
+        let fork_point = &chain[5];
+        let fork = test_harness.add_bitcoin_fork(&mut rng, fork_point.block_hash(), 2);
+        let new_tip = fork.last().unwrap().block_hash();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // We first observe the original chain, one block at a time, and
+        // then the blocks on the fork.
+        let (block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(32);
+        for block in chain.iter().chain(fork.iter()) {
+            block_hash_tx.send(Ok(block.block_hash())).await.unwrap();
+        }
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+            horizon: 1,
+        };
+
+        let mut reorgs = block_observer.subscribe_reorgs();
+        let handle = tokio::spawn(block_observer.run());
+
+        let event = tokio::time::timeout(Duration::from_secs(3), reorgs.next())
+            .await
+            .expect("block observer did not emit a reorg event within timeout")
+            .unwrap();
+
+        let expected_fork_height = model::BitcoinBlock::from(fork_point).block_height;
+        assert_eq!(event.old_tip, old_tip.into());
+        assert_eq!(event.new_tip, fork[0].block_hash().into());
+        assert_eq!(event.fork_height, expected_fork_height);
+
+        // The second block on the fork builds on the new chain tip, so
+        // there are no more reorgs.
+        let next = tokio::time::timeout(Duration::from_millis(500), reorgs.next()).await;
+        assert!(next.is_err());
+
+        let db_tip = storage
+            .get_bitcoin_block(&new_tip.into())
+            .await
+            .unwrap()
+            .expect("fork tip wasn't persisted");
+        assert_eq!(db_tip.parent_hash, fork[0].block_hash().into());
+
+        handle.abort();
+    }
+
+    /// Test that `BlockObserver::catch_up` returns an error, and processes
+    /// nothing, when the block that it should start from is not an
+    /// ancestor of the block that it should end at.
+    #[tokio::test]
+    async fn catch_up_rejects_a_start_block_that_is_not_an_ancestor() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let mut test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+
+        let blocks = test_harness.bitcoin_blocks().to_vec();
+        let to = blocks.last().unwrap().block_hash();
+        // The fork branches off the first block, so it is not on the
+        // chain that leads to `to`.
+        let fork = test_harness.add_bitcoin_fork(&mut rng, blocks[0].block_hash(), 1);
+        let from = fork[0].block_hash();
+        // Only the headers should be fetched before we notice that the
+        // range is disconnected, so this failure is never hit.
+        test_harness.fail_next_get_block();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let result = block_observer.catch_up(from, to).await;
+        assert!(matches!(
+            result,
+            Err(Error::CatchUpRangeDisconnected { from: f, to: t }) if f == from && t == to
+        ));
+
+        for block in &blocks {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash().into())
+                .await
+                .unwrap();
+            assert!(persisted.is_none());
+        }
+    }
+
+    /// Test that `BlockObserver::catch_up` gives up on walking back from
+    /// the end block once it has gone through the context window without
+    /// reaching the start block.
+    #[tokio::test]
+    async fn catch_up_rejects_a_range_longer_than_the_context_window() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+
+        let blocks = test_harness.bitcoin_blocks().to_vec();
+        let from = blocks[0].block_hash();
+        let to = blocks.last().unwrap().block_hash();
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.context_window = 5;
+
+        let ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let result = block_observer.catch_up(from, to).await;
+        assert!(matches!(
+            result,
+            Err(Error::CatchUpRangeDisconnected { from: f, to: t }) if f == from && t == to
+        ));
+
+        for block in &blocks {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash().into())
+                .await
+                .unwrap();
+            assert!(persisted.is_none());
+        }
+    }
+
+    /// Test that `BlockObserver::catch_up` processes the entire backlog
+    /// of bitcoin blocks, and that the live stream only needs to process
+    /// the blocks that come after it.
+    #[tokio::test]
+    async fn catch_up_processes_backlog_before_live_stream() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let storage = storage::in_memory::Store::new_shared();
+        let mut test_harness = TestHarness::generate(&mut rng, 21, 0..5);
+
+        // The first block is the last one that we processed, so there is
+        // a backlog of 20 blocks.
+        let backlog = test_harness.bitcoin_blocks().to_vec();
+        let from = backlog[0].block_hash();
+        let to = backlog.last().unwrap().block_hash();
+        let live = test_harness.add_bitcoin_fork(&mut rng, to, 1);
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        let (block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(32);
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+            horizon: 1,
+        };
+
+        let num_processed = block_observer.catch_up(from, to).await.unwrap();
+        assert_eq!(num_processed, 20);
+
+        for block in &backlog[1..] {
+            let persisted = storage
+                .get_bitcoin_block(&block.block_hash().into())
+                .await
+                .unwrap();
+            assert!(persisted.is_some());
+        }
+        let first = storage.get_bitcoin_block(&from.into()).await.unwrap();
+        assert!(first.is_none());
+
+        // Now the live stream takes over. With a horizon of one, the
+        // observer can only process the new block because the backlog has
+        // already been processed.
+        block_hash_tx.send(Ok(live[0].block_hash())).await.unwrap();
+        let handle = tokio::spawn(block_observer.run());
+
+        ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+            )
+        })
+        .await
+        .expect("block observer failed to complete within timeout");
+
+        let db_tip = storage
+            .get_bitcoin_block(&live[0].block_hash().into())
+            .await
+            .unwrap()
+            .expect("live block wasn't persisted");
+        assert_eq!(db_tip.parent_hash, to.into());
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn validated_deposits_record_the_funding_tx_vsize() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid, response);
+
+        let deposit = deposit_request
+            .validate(&test_harness)
+            .await
+            .unwrap()
+            .expect("confirmed deposit was not validated");
+        assert_eq!(deposit.tx_vsize, tx_setup.tx.vsize() as u64);
+    }
+
+    #[tokio::test]
+    async fn load_requests_validates_deposits_with_bounded_concurrency() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        test_harness.set_tx_info_delay(Duration::from_millis(20));
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        // All 20 deposits are confirmed in the same block.
+        let requests: Vec<CreateDepositRequest> = (0..20)
+            .map(|_| {
+                let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+                let txid = tx_setup.tx.compute_txid();
+                let response = GetTxResponse {
+                    tx: tx_setup.tx.clone(),
+                    block_hash: Some(block_hash),
+                    confirmations: None,
+                    block_time: None,
+                };
+                test_harness.add_deposit(txid, response);
+
+                CreateDepositRequest {
+                    outpoint: bitcoin::OutPoint::new(txid, 0),
+                    deposit_script: tx_setup.deposit.deposit_script(),
+                    reclaim_script: tx_setup.reclaim.reclaim_script(),
+                }
+            })
+            .collect();
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.deposit_validation_concurrency = Some(4);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_requests(&requests).await.unwrap();
+
+        let storage = storage.lock().await;
+        assert_eq!(storage.deposit_requests.len(), requests.len());
+        for request in requests.iter() {
+            let outpoint: (BitcoinTxId, u32) =
+                (request.outpoint.txid.into(), request.outpoint.vout);
+            assert!(storage.deposit_requests.contains_key(&outpoint));
+        }
+        assert_eq!(test_harness.max_tx_info_calls_in_flight(), 4);
+    }
+
+    /// Test that deposits with a reclaim script that is not in the
+    /// allowlist of this signer are skipped, but left pending in Emily.
+    #[tokio::test]
+    async fn load_requests_skips_deposits_outside_the_reclaim_allowlist() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        // The deposit has an empty user reclaim script.
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid, response);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        test_harness.add_pending_deposit(request.clone()).unwrap();
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.reclaim_script_allowlist =
+            Some(vec![crate::config::ReclaimScriptTemplate::SingleSig]);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer
+            .load_requests(&[request.clone()])
+            .await
+            .unwrap();
+
+        assert!(storage.lock().await.deposit_requests.is_empty());
+        assert!(test_harness.evicted_deposits().is_empty());
+        let pending: Vec<OutPoint> = test_harness
+            .pending_deposits()
+            .iter()
+            .map(|request| request.outpoint)
+            .collect();
+        assert_eq!(pending, vec![request.outpoint]);
+    }
+
+    /// Test that the block observer fetches the tenure info again when
+    /// the stacks node returns a tenure that does not contain the tip
+    /// block, and carries on with the good response.
+    #[tokio::test]
+    async fn observer_recovers_from_inconsistent_tenure_info() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        let expected = test_harness.get_tenure_info().await.unwrap();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        test_harness.make_next_tenure_info_inconsistent();
+        let tenure_info = get_consistent_tenure_info(&test_harness).await.unwrap();
+        assert_eq!(tenure_info.consensus_hash, expected.consensus_hash);
+
+        // The observer gets one bad response and still processes the
+        // block, including the stacks blocks up to the tip.
+        test_harness.make_next_tenure_info_inconsistent();
+        let block = test_harness.bitcoin_blocks().last().cloned().unwrap();
+        block_observer.process_bitcoin_block(block).await.unwrap();
+
+        let tip = model::StacksBlockHash::from(expected.tip_block_id);
+        assert!(storage.lock().await.stacks_blocks.contains_key(&tip));
+    }
+
+    /// Test that the block observer follows the heavier of two competing
+    /// stacks chains in the same tenure.
+    #[tokio::test]
+    async fn observer_follows_the_heavier_stacks_fork() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 5, 2..3);
+        let original_tip = test_harness.get_tenure_info().await.unwrap().tip_block_id;
+        let original_tip_block = test_harness.get_block(original_tip).await.unwrap();
+
+        // The fork competes with the original tip, and is one block
+        // longer.
+        let fork =
+            test_harness.add_stacks_fork(&mut rng, original_tip_block.header.parent_block_id, 2);
+        let fork_tip = *fork.last().unwrap();
+        assert_eq!(
+            test_harness.get_tenure_info().await.unwrap().tip_block_id,
+            fork_tip
+        );
+
+        let tenure = test_harness.get_tenure(fork_tip).await.unwrap();
+        let tenure_ids: Vec<_> = tenure
+            .blocks()
+            .iter()
+            .map(|block| block.block_id())
+            .collect();
+        assert!(tenure_ids.ends_with(&fork));
+        assert!(!tenure_ids.contains(&original_tip));
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let block = test_harness.bitcoin_blocks().last().cloned().unwrap();
+        block_observer.process_bitcoin_block(block).await.unwrap();
+
+        let db = storage.lock().await;
+        assert!(db
+            .stacks_blocks
+            .contains_key(&model::StacksBlockHash::from(fork_tip)));
+        assert!(!db
+            .stacks_blocks
+            .contains_key(&model::StacksBlockHash::from(original_tip)));
+        assert_eq!(ctx.state().stacks_checkpoint(), Some(fork_tip));
+    }
+
+    /// Test that processing a block at a height that was already
+    /// processed, like after a bitcoin reorg, counts as reprocessing the
+    /// height and not as a new block.
+    #[tokio::test]
+    async fn reprocessed_heights_are_counted_separately_from_new_blocks() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let block = test_harness.bitcoin_blocks()[0].clone();
+        block_observer.write_bitcoin_block(&block).await.unwrap();
+        assert_eq!(block_observer.stats().blocks_processed(), 1);
+        assert_eq!(block_observer.stats().reprocessed_blocks(), 0);
+
+        // A competing block at the same height, as if the first block had
+        // been reorged out.
+        let mut fork = block.clone();
+        fork.header.nonce = fork.header.nonce.wrapping_add(1);
+        assert_ne!(fork.block_hash(), block.block_hash());
+
+        block_observer.write_bitcoin_block(&fork).await.unwrap();
+        assert_eq!(block_observer.stats().blocks_processed(), 1);
+        assert_eq!(block_observer.stats().reprocessed_blocks(), 1);
+
+        // A block at a height that has not been processed yet is a new
+        // block.
+        let next_block = test_harness.bitcoin_blocks()[1].clone();
+        assert_ne!(
+            next_block.bip34_block_height().unwrap(),
+            block.bip34_block_height().unwrap()
+        );
+        block_observer
+            .write_bitcoin_block(&next_block)
+            .await
+            .unwrap();
+        assert_eq!(block_observer.stats().blocks_processed(), 2);
+        assert_eq!(block_observer.stats().reprocessed_blocks(), 1);
+    }
+
+    #[tokio::test]
+    async fn health_summary_reports_a_lagging_observer() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 0..5);
+        let blocks = test_harness.bitcoin_blocks().to_vec();
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // The node knows about five bitcoin blocks, but the observer has
+        // only processed up to the block at height two.
+        let processed = model::BitcoinBlock {
+            block_hash: blocks[0].block_hash().into(),
+            block_height: 2,
+            parent_hash: blocks[0].header.prev_blockhash.into(),
+        };
+        ctx.get_storage_mut()
+            .write_bitcoin_block(&processed)
+            .await
+            .unwrap();
+
+        let (_block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(1);
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+            horizon: 10,
+        };
+
+        let health = block_observer.health_summary().await.unwrap();
+        let node_info = test_harness.get_node_info().await.unwrap();
+
+        assert_eq!(node_info.burn_block_height, 5);
+        assert_eq!(health.processed_height, Some(2));
+        assert_eq!(health.lag, 3);
+        assert!(health.last_error.is_none());
+        assert!(health.node_synced);
+    }
+
+    #[tokio::test]
+    async fn last_error_is_cleared_after_a_successful_block() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 3, 0..5);
+        let blocks = test_harness.bitcoin_blocks().to_vec();
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let _signal_rx = ctx.get_signal_receiver();
+        let (block_hash_tx, block_hash_rx) = tokio::sync::mpsc::channel(32);
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: tokio_stream::wrappers::ReceiverStream::new(block_hash_rx),
+            horizon: 10,
+        };
+        assert!(block_observer.last_error().is_none());
+
+        let before = Instant::now();
+        test_harness.fail_next_get_block();
+        block_hash_tx
+            .send(Ok(blocks[0].block_hash()))
+            .await
+            .unwrap();
+        let handle = tokio::spawn(block_observer.run());
+
+        let (message, timestamp) = tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                if let Some(last_error) = ctx.state().block_observer_last_error() {
+                    break last_error;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("block observer did not record an error within timeout");
+        assert_eq!(message, Error::MissingBlock.to_string());
+        assert!(timestamp >= before);
+
+        // The harness only fails once, so the next block is processed
+        // successfully and the error is cleared.
+        block_hash_tx
+            .send(Ok(blocks[1].block_hash()))
+            .await
+            .unwrap();
+        ctx.wait_for_signal(Duration::from_secs(3), |signal| {
+            matches!(
+                signal,
+                SignerSignal::Event(SignerEvent::BitcoinBlockObserved)
+            )
+        })
+        .await
+        .expect("block observer failed to complete within timeout");
+
+        assert!(ctx.state().block_observer_last_error().is_none());
+
+        handle.abort();
+    }
+
+    /// Test that `BlockObserver::load_latest_deposit_requests` takes
+    /// deposits from emily, validates them and only keeps the ones that
+    /// pass validation and have been confirmed.
+    #[tokio::test]
+    async fn validated_confirmed_deposits_get_added_to_state() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        // We want the test harness to fetch a block from our
+        // "bitcoin-core", which in this case is the test harness. So we
+        // use a block hash that the test harness knows about.
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        let lock_time = 150;
+        let max_fee = 32000;
+        let amount = 500_000;
+
+        // We're going to create two deposit requests, the first one valid
+        // and the second one invalid. Emily will return both the valid and
+        // invalid requests (even though it should've validated them) and
+        // BitcoinClient will return the right transaction for both of
+        // them.
+        let tx_setup0 = sbtc::testing::deposits::tx_setup(lock_time, max_fee, amount);
+        let deposit_request0 = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup0.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup0.deposit.deposit_script(),
+            reclaim_script: tx_setup0.reclaim.reclaim_script(),
+        };
+        let req0 = deposit_request0.clone();
+        // When we validate the deposit request, we fetch the transaction
+        // from bitcoin-core's blockchain. The stubs out that response.
+        let get_tx_resp0 = GetTxResponse {
+            tx: tx_setup0.tx.clone(),
+            block_hash,
+            confirmations: None,
+            block_time: None,
+        };
+
+        let tx_setup1 = sbtc::testing::deposits::tx_setup(300, 2000, amount);
+        // This one is an invalid deposit request because the deposit
+        // script is wrong
+        let deposit_request1 = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup1.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: bitcoin::ScriptBuf::new(),
+            reclaim_script: tx_setup1.reclaim.reclaim_script(),
+        };
+        // The transaction is also in the mempool, even though it is an
+        // invalid deposit.
+        let get_tx_resp1 = GetTxResponse {
+            tx: tx_setup1.tx.clone(),
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+
+        // This deposit transaction is a fine deposit, it just hasn't been
+        // confirmed yet.
+        let tx_setup2 = sbtc::testing::deposits::tx_setup(400, 3000, amount);
+        let get_tx_resp2 = GetTxResponse {
+            tx: tx_setup2.tx.clone(),
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+
+        let deposit_request2 = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup2.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup2.deposit.deposit_script(),
+            reclaim_script: tx_setup2.reclaim.reclaim_script(),
+        };
+
+        // Let's add the "responses" to the field that feeds the
+        // response to the `BitcoinClient::get_tx` call.
+        test_harness.add_deposits(&[
+            (get_tx_resp0.tx.compute_txid(), get_tx_resp0),
+            (get_tx_resp1.tx.compute_txid(), get_tx_resp1),
+            (get_tx_resp2.tx.compute_txid(), get_tx_resp2),
+        ]);
+
+        // Add the deposit requests to the pending deposits which
+        // would be returned by Emily.
+        test_harness
+            .add_pending_deposits(&[deposit_request0, deposit_request1, deposit_request2])
+            .unwrap();
+
+        // Now we finish setting up the block observer.
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        {
+            let db = storage.lock().await;
+            assert_eq!(db.deposit_requests.len(), 0);
+        }
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        // Only the transaction from tx_setup0 was valid. Note that, since
+        // we are not using a real block hash stored in the database. Our
+        // DbRead function won't actually find it. And in prod we won't
+        // actually store the deposit request transaction.
+        let deposit = {
+            let db = storage.lock().await;
+            assert_eq!(db.deposit_requests.len(), 1);
+            db.deposit_requests.values().next().cloned().unwrap()
+        };
+
+        assert_eq!(deposit.outpoint(), req0.outpoint);
+    }
+
+    /// Test that `BlockObserver::extract_deposit_requests` after
+    /// `BlockObserver::load_latest_deposit_requests` stores validated
+    /// deposit requests into "storage".
+    #[tokio::test]
+    async fn extract_deposit_requests_stores_validated_deposits() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(365);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+
+        // We want the test harness to fetch a block from our
+        // "bitcoin-core", which in this case is the test harness. So we
+        // use a block hash that the test harness knows about.
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+        let lock_time = 150;
+        let max_fee = 32000;
+        let amount = 500_000;
+
+        // We're going to create two deposit requests, the first one valid
+        // and the second one invalid. Emily will return both the valid and
+        // invalid requests (even though it should've validated them) and
+        // BitcoinClient will return the right transaction for both of
+        // them.
+        let tx_setup0 = sbtc::testing::deposits::tx_setup(lock_time, max_fee, amount);
+        let deposit_request0 = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup0.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup0.deposit.deposit_script(),
+            reclaim_script: tx_setup0.reclaim.reclaim_script(),
+        };
+        // When we validate the deposit request, we fetch the transaction
+        // from bitcoin-core's blockchain. The stubs out that
+        // response.
+        let get_tx_resp0 = GetTxResponse {
+            tx: tx_setup0.tx.clone(),
+            block_hash,
+            confirmations: None,
+            block_time: None,
+        };
+
+        // Let's add the "responses" to the field that feeds the
+        // response to the `BitcoinClient::get_tx` call.
+        test_harness.add_deposit(get_tx_resp0.tx.compute_txid(), get_tx_resp0);
+        // Add the deposit request to the pending deposits which
+        // would be returned by Emily.
+        test_harness.add_pending_deposit(deposit_request0).unwrap();
+
+        // Now we finish setting up the block observer.
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        let storage = storage.lock().await;
+        assert_eq!(storage.deposit_requests.len(), 1);
+        let db_outpoint: (BitcoinTxId, u32) = (tx_setup0.tx.compute_txid().into(), 0);
+        assert!(storage.deposit_requests.get(&db_outpoint).is_some());
+
+        assert!(storage
+            .bitcoin_transactions_to_blocks
+            .get(&db_outpoint.0)
+            .is_some());
+        assert_eq!(
+            storage
+                .raw_transactions
+                .get(db_outpoint.0.as_byte_array())
+                .unwrap()
+                .tx_type,
+            model::TransactionType::DepositRequest
+        );
+    }
+
+    /// Test that the scripts of a deposit request are parsed once, and
+    /// that later validations only re-check the deposit transaction.
+    #[tokio::test]
+    async fn deposit_scripts_are_parsed_once_across_validations() {
+        static PARSE_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        fn counting_parser(
+            request: &CreateDepositRequest,
+            max_deposit_script_length: usize,
+        ) -> Result<ParsedDepositScripts, sbtc::error::Error> {
+            PARSE_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            request.parse_scripts_with_max_length(max_deposit_script_length)
+        }
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid, response.clone());
+
+        let cache = DepositScriptCache::new(counting_parser);
+
+        // The transaction is unconfirmed, so there is nothing to parse
+        // yet.
+        let deposit = deposit_request
+            .validate_cached(&test_harness, &cache)
+            .await
+            .unwrap();
+        assert!(deposit.is_none());
+        assert_eq!(PARSE_CALLS.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // Once the transaction is confirmed, every validation pass checks
+        // it against the transaction, but the scripts are only parsed on
+        // the first pass.
+        let confirmed = GetTxResponse {
+            block_hash: Some(block_hash),
+            confirmations: Some(1),
+            ..response
+        };
+        test_harness.add_deposit(txid, confirmed);
+        for _ in 0..3 {
+            let deposit = deposit_request
+                .validate_cached(&test_harness, &cache)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(deposit.info.outpoint, deposit_request.outpoint);
+        }
+        assert_eq!(PARSE_CALLS.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(cache.len(), 1);
+
+        // Requests that are no longer pending are dropped from the cache.
+        cache.retain(&[]);
+        assert!(cache.is_empty());
+    }
+
+    /// Test that `BlockObserver::load_latest_deposit_requests` evicts
+    /// confirmed deposit requests that fail validation from the pending
+    /// deposits in Emily, and that the reason for the eviction is
+    /// recorded.
+    #[tokio::test]
+    async fn permanently_invalid_deposits_get_evicted() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        // This deposit request has been confirmed but the deposit script
+        // in the request does not match the one in the transaction, so it
+        // will never pass validation.
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: bitcoin::ScriptBuf::new(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash,
+            confirmations: None,
+            block_time: None,
+        };
+
+        test_harness.add_deposit(tx_setup.tx.compute_txid(), get_tx_resp);
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        assert_eq!(test_harness.pending_deposits().len(), 1);
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        // The request is gone from the pending deposits, the reason was
+        // recorded, and nothing was written to the database.
+        assert!(test_harness.pending_deposits().is_empty());
+        let evicted = test_harness.evicted_deposits();
+        let reason = evicted.get(&deposit_request.outpoint).unwrap();
+        assert_eq!(
+            reason,
+            &Error::SbtcLib(sbtc::error::Error::InvalidDepositScriptLength).to_string()
+        );
+        assert!(storage.lock().await.deposit_requests.is_empty());
+    }
+
+    /// Test that `BlockObserver::load_latest_deposit_requests` evicts
+    /// deposit requests whose funding inputs were spent without a
+    /// replacement being found, rather than searching for a replacement
+    /// again on every block.
+    #[tokio::test]
+    async fn deposits_with_spent_funding_inputs_get_evicted() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+        test_harness.abandon_transaction(txid);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        assert!(test_harness.pending_deposits().is_empty());
+        let evicted = test_harness.evicted_deposits();
+        let reason = evicted.get(&deposit_request.outpoint).unwrap();
+        assert_eq!(reason, &Error::FundingInputsSpent(txid).to_string());
+        assert!(storage.lock().await.deposit_requests.is_empty());
+    }
+
+    /// Test that discovering the deposits of a block reports both valid
+    /// and invalid deposit requests, and leaves everything untouched.
+    #[tokio::test]
+    async fn discover_deposits_reports_valid_and_invalid_deposits() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let mut block = test_harness.bitcoin_blocks()[0].clone();
+        let block_hash = Some(block.block_hash());
+
+        // The first request matches its transaction, the second has a
+        // deposit script that does not match the one in its transaction.
+        let valid_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let invalid_setup = sbtc::testing::deposits::tx_setup(150, 32000, 600_000);
+        let valid_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: valid_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: valid_setup.deposit.deposit_script(),
+            reclaim_script: valid_setup.reclaim.reclaim_script(),
+        };
+        let invalid_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: invalid_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: bitcoin::ScriptBuf::new(),
+            reclaim_script: invalid_setup.reclaim.reclaim_script(),
+        };
+
+        for tx in [&valid_setup.tx, &invalid_setup.tx] {
+            let get_tx_resp = GetTxResponse {
+                tx: tx.clone(),
+                block_hash,
+                confirmations: None,
+                block_time: None,
+            };
+            test_harness.add_deposit(tx.compute_txid(), get_tx_resp);
+            block.txdata.push(tx.clone());
+        }
+        test_harness
+            .add_pending_deposit(valid_request.clone())
+            .unwrap();
+        test_harness
+            .add_pending_deposit(invalid_request.clone())
+            .unwrap();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let discovered = block_observer.discover_deposits(&block).await.unwrap();
+
+        let reason = Error::SbtcLib(sbtc::error::Error::InvalidDepositScriptLength).to_string();
+        let expected = vec![
+            DiscoveredDeposit {
+                outpoint: valid_request.outpoint,
+                status: DiscoveryStatus::Accepted,
+            },
+            DiscoveredDeposit {
+                outpoint: invalid_request.outpoint,
+                status: DiscoveryStatus::Rejected(reason),
+            },
+        ];
+        assert_eq!(discovered, expected);
+
+        // Nothing was stored or updated.
+        assert_eq!(test_harness.pending_deposits().len(), 2);
+        assert!(test_harness.evicted_deposits().is_empty());
+        assert!(storage.lock().await.deposit_requests.is_empty());
+    }
+
+    /// Test that discovering the deposits of a block skips the deposits
+    /// that do not have the minimum number of confirmations set in Emily,
+    /// the same way that loading them does.
+    #[tokio::test]
+    async fn discover_deposits_applies_the_minimum_confirmations() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let mut block = test_harness.bitcoin_blocks()[0].clone();
+
+        let setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: setup.deposit.deposit_script(),
+            reclaim_script: setup.reclaim.reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: setup.tx.clone(),
+            block_hash: Some(block.block_hash()),
+            confirmations: Some(1),
+            block_time: None,
+        };
+        test_harness.add_deposit(setup.tx.compute_txid(), get_tx_resp);
+        test_harness.add_pending_deposit(request.clone()).unwrap();
+        block.txdata.push(setup.tx.clone());
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let discovered = block_observer.discover_deposits(&block).await.unwrap();
+        assert_eq!(discovered[0].status, DiscoveryStatus::Accepted);
+
+        ctx.state().set_deposit_min_confirmations(Some(2));
+        let discovered = block_observer.discover_deposits(&block).await.unwrap();
+        let reason = "deposit transaction does not have enough confirmations".to_string();
+        assert_eq!(discovered[0].status, DiscoveryStatus::Rejected(reason));
+
+        // Nothing was stored or updated.
+        assert_eq!(test_harness.pending_deposits().len(), 1);
+    }
+
+    /// Test that a submitted stacks transaction that is not confirmed
+    /// once the stacks chain tip moves past the deadline is flagged for
+    /// resubmission, while a confirmed one is not.
+    #[tokio::test]
+    async fn unconfirmed_submissions_past_the_deadline_are_flagged() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.submission_deadline_blocks = Some(3);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let bitcoin_block: model::BitcoinBlock = fake::Faker.fake_with_rng(&mut rng);
+        storage.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        let stacks_tip = |block_height: u64| model::StacksBlock {
+            block_hash: fake::Faker.fake_with_rng(&mut rand::rngs::OsRng),
+            block_height,
+            parent_hash: fake::Faker.fake_with_rng(&mut rand::rngs::OsRng),
+            bitcoin_anchor: bitcoin_block.block_hash,
+        };
+        storage.write_stacks_block(&stacks_tip(100)).await.unwrap();
+
+        // One submission never confirms, the other one is in a stacks
+        // block that the block observer processes.
+        let (block_id, _, _) = test_harness
+            .stacks_blocks()
+            .iter()
+            .find(|(_, block, _)| !block.txs.is_empty())
+            .unwrap();
+        let tenure = test_harness.get_tenure(*block_id).await.unwrap();
+        let confirmed = model::StacksTxId::from(tenure.blocks().last().unwrap().txs[0].txid());
+        let unconfirmed: model::StacksTxId = fake::Faker.fake_with_rng(&mut rng);
+
+        let submissions = ctx.state().pending_submissions();
+        submissions.insert(confirmed, 100);
+        submissions.insert(unconfirmed, 100);
+        block_observer.write_stacks_blocks(&[tenure]).await.unwrap();
+
+        // The deadline has not passed yet.
+        storage.write_stacks_block(&stacks_tip(102)).await.unwrap();
+        assert!(block_observer
+            .pending_submissions()
+            .await
+            .unwrap()
+            .is_empty());
+
+        storage.write_stacks_block(&stacks_tip(103)).await.unwrap();
+        let pending = block_observer.pending_submissions().await.unwrap();
+        assert_eq!(pending, vec![unconfirmed]);
+    }
+
+    /// Test that a stacks checkpoint that points to an orphaned block is
+    /// rolled back to its last ancestor that is still canonical.
+    #[tokio::test]
+    async fn reconcile_on_start_rolls_back_an_orphaned_checkpoint() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 20, 1..5);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let (canonical_id, _, bitcoin_anchor) = &test_harness.stacks_blocks()[10];
+        let canonical_id = *canonical_id;
+        let bitcoin_anchor: model::BitcoinBlockHash = (*bitcoin_anchor).into();
+
+        // The orphan builds on a canonical stacks block and its child
+        // builds on the orphan, but the node does not know of either.
+        let orphan = model::StacksBlock {
+            block_hash: fake::Faker.fake_with_rng(&mut rng),
+            block_height: 1_000,
+            parent_hash: canonical_id.into(),
+            bitcoin_anchor,
+        };
+        let orphan_child = model::StacksBlock {
+            block_hash: fake::Faker.fake_with_rng(&mut rng),
+            block_height: 1_001,
+            parent_hash: orphan.block_hash,
+            bitcoin_anchor,
+        };
+        storage
+            .write_stacks_block_headers(vec![orphan.clone(), orphan_child.clone()])
+            .await
+            .unwrap();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // The checkpoint was persisted before the signer restarted, so it
+        // is only in the database.
+        storage
+            .write_stacks_checkpoint(Some(&orphan_child.block_hash))
+            .await
+            .unwrap();
+        assert_eq!(ctx.state().stacks_checkpoint(), None);
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let reconciled = block_observer.reconcile_on_start().await.unwrap();
+        assert_eq!(reconciled, Some(canonical_id));
+        assert_eq!(ctx.state().stacks_checkpoint(), Some(canonical_id));
+        let persisted = storage.get_stacks_checkpoint().await.unwrap();
+        assert_eq!(persisted, Some(canonical_id.into()));
+
+        // A checkpoint that is still canonical is left alone.
+        let reconciled = block_observer.reconcile_on_start().await.unwrap();
+        assert_eq!(reconciled, Some(canonical_id));
+    }
+
+    /// Test that a stacks checkpoint on a fork that the node still knows
+    /// about, but that the node's tip does not build on, is rolled back
+    /// to where the fork branched off.
+    #[tokio::test]
+    async fn reconcile_on_start_rolls_back_a_checkpoint_on_a_known_fork() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 1..5);
+
+        let (branch_id, _, _) = test_harness.stacks_blocks()[10];
+        let fork = test_harness.add_stacks_fork(&mut rng, branch_id, 1);
+
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        ctx.state().set_stacks_checkpoint(Some(fork[0]));
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let reconciled = block_observer.reconcile_on_start().await.unwrap();
+        assert_eq!(reconciled, Some(branch_id));
+        assert_eq!(ctx.state().stacks_checkpoint(), Some(branch_id));
+    }
+
+    /// Test that deposits locked to a key that is not one of the signers'
+    /// aggregate keys in the registry are marked as rejected in Emily
+    /// instead of being stored.
+    #[tokio::test]
+    async fn deposits_locked_to_a_wrong_key_get_rejected() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        // This deposit request is valid, but it is locked to a random key
+        // rather than to the signers' aggregate key.
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash,
+            confirmations: None,
+            block_time: None,
+        };
+
+        test_harness.add_deposit(tx_setup.tx.compute_txid(), get_tx_resp);
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let shares = model::EncryptedDkgShares {
+            aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: aggregate_key.signers_script_pubkey().into(),
+            encrypted_private_shares: Vec::new(),
+            public_shares: Vec::new(),
+            signer_set_public_keys: vec![aggregate_key],
+            signature_share_threshold: 1,
+        };
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+        test_harness.register_aggregate_key(aggregate_key);
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        // The request is gone from the pending deposits, it was rejected
+        // rather than evicted, and nothing was written to the database.
+        assert!(test_harness.pending_deposits().is_empty());
+        assert_eq!(
+            test_harness.rejected_wrong_key_deposits(),
+            vec![deposit_request.outpoint]
+        );
+        assert!(test_harness.evicted_deposits().is_empty());
+        assert!(storage.lock().await.deposit_requests.is_empty());
+    }
+
+    /// Test that deposits locked to a key that is in the registry, but
+    /// that our database does not know about, are skipped without telling
+    /// Emily, since the other signers may know about the key.
+    #[tokio::test]
+    async fn deposits_locked_to_a_registered_key_unknown_locally_get_skipped() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness
+            .bitcoin_blocks()
+            .first()
+            .map(|block| block.block_hash());
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint {
+                txid: tx_setup.tx.compute_txid(),
+                vout: 0,
+            },
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash,
+            confirmations: None,
+            block_time: None,
+        };
+
+        test_harness.add_deposit(tx_setup.tx.compute_txid(), get_tx_resp);
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+
+        // Our database only knows about a stale key, while the registry
+        // knows about the key that the deposit is locked to, as if we
+        // missed the key rotation.
+        let storage = storage::in_memory::Store::new_shared();
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let shares = model::EncryptedDkgShares {
+            aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: aggregate_key.signers_script_pubkey().into(),
+            encrypted_private_shares: Vec::new(),
+            public_shares: Vec::new(),
+            signer_set_public_keys: vec![aggregate_key],
+            signature_share_threshold: 1,
+        };
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+        let registered_key = PublicKey::from(secp256k1::PublicKey::from_x_only_public_key(
+            tx_setup.deposit.signers_public_key,
+            secp256k1::Parity::Odd,
+        ));
+        test_harness.register_aggregate_key(aggregate_key);
+        test_harness.register_aggregate_key(registered_key);
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+
+        // The request is still pending, and nothing was reported to Emily
+        // or written to the database.
+        let pending = test_harness.pending_deposits();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].outpoint, deposit_request.outpoint);
+        assert!(test_harness.rejected_wrong_key_deposits().is_empty());
+        assert!(test_harness.evicted_deposits().is_empty());
+        assert!(storage.lock().await.deposit_requests.is_empty());
+    }
+
+    /// Test that a deposit whose funding transaction was replaced using
+    /// RBF before it was confirmed is validated against the confirmed
+    /// replacement.
+    #[tokio::test]
+    async fn replaced_deposits_confirm_with_the_replacement() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let original_txid = tx_setup.tx.compute_txid();
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(original_txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let original_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(original_txid, original_resp);
+
+        // The original transaction is unconfirmed, so it is not ready yet.
+        let deposit = deposit_request.validate(&test_harness).await.unwrap();
+        assert!(deposit.is_none());
+
+        // The replacement spends the same inputs, but it has a new output
+        // in front of the deposit, so the deposit output moves.
+        let mut replacement_tx = tx_setup.tx.clone();
+        replacement_tx.output.insert(
+            0,
+            bitcoin::TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf::new_op_return([1; 4]),
+            },
+        );
+        let replacement_txid = replacement_tx.compute_txid();
+        let replacement_resp = GetTxResponse {
+            tx: replacement_tx,
+            block_hash: Some(block_hash),
+            confirmations: Some(1),
+            block_time: None,
+        };
+        test_harness.add_replacement(original_txid, replacement_resp);
+
+        let deposit = deposit_request
+            .validate(&test_harness)
+            .await
+            .unwrap()
+            .expect("deposit should confirm with the replacement");
+
+        let expected_outpoint = bitcoin::OutPoint::new(replacement_txid, 1);
+        assert_eq!(deposit.info.outpoint, expected_outpoint);
+        assert_eq!(deposit.tx_info.txid, replacement_txid);
+        assert_eq!(deposit.tx_info.block_hash, block_hash);
+        assert_eq!(deposit.info.amount, 500_000);
+    }
+
+    /// Test that a deposit that confirmed with a replacement of its
+    /// deposit transaction is moved to the outpoint in the replacement in
+    /// Emily, so that later updates for the deposit find it there.
+    #[tokio::test]
+    async fn replaced_deposits_are_moved_to_the_replacement_in_emily() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let original_txid = tx_setup.tx.compute_txid();
+        let deposit_request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(original_txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+
+        let mut replacement_tx = tx_setup.tx.clone();
+        replacement_tx.output.insert(
+            0,
+            bitcoin::TxOut {
+                value: Amount::from_sat(1000),
+                script_pubkey: ScriptBuf::new_op_return([1; 4]),
+            },
+        );
+        let replacement_txid = replacement_tx.compute_txid();
+        let replacement_resp = GetTxResponse {
+            tx: replacement_tx,
+            block_hash: Some(block_hash),
+            confirmations: Some(1),
+            block_time: None,
+        };
+        test_harness.add_replacement(original_txid, replacement_resp);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer
+            .load_requests(&[deposit_request.clone()])
+            .await
+            .unwrap();
+
+        // The deposit is stored, and pending in Emily, under the outpoint
+        // in the replacement, while the original request is no longer
+        // pending and points to the replacement.
+        let replacement_outpoint = bitcoin::OutPoint::new(replacement_txid, 1);
+        let outpoint: (BitcoinTxId, u32) = (replacement_txid.into(), 1);
+        assert!(storage
+            .lock()
+            .await
+            .deposit_requests
+            .contains_key(&outpoint));
+
+        let pending: Vec<OutPoint> = test_harness
+            .pending_deposits()
+            .iter()
+            .map(|request| request.outpoint)
+            .collect();
+        assert_eq!(pending, vec![replacement_outpoint]);
+
+        let evicted = test_harness.evicted_deposits();
+        let message = evicted
+            .get(&deposit_request.outpoint)
+            .expect("the original request should no longer be pending");
+        assert!(message.contains(&replacement_outpoint.to_string()));
+    }
+
+    /// Test that spending a deposit using its reclaim script marks the
+    /// deposit as reclaimed in Emily.
+    #[tokio::test]
+    async fn reclaimed_deposits_are_reported_to_emily() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[0].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let outpoint = bitcoin::OutPoint::new(tx_setup.tx.compute_txid(), 0);
+        let deposit_request = CreateDepositRequest {
+            outpoint,
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        let get_tx_resp = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(outpoint.txid, get_tx_resp);
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+
+        let storage = storage::in_memory::Store::new_shared();
+        let deposit = deposit_request
+            .validate(&test_harness)
+            .await
+            .unwrap()
+            .unwrap();
+        storage
+            .write_deposit_request(&model::DepositRequest::from(deposit))
+            .await
+            .unwrap();
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        // This transaction spends the deposit through the reclaim script,
+        // so the witness has the reclaim script followed by the control
+        // block.
+        let witness = bitcoin::Witness::from_slice(&[
+            vec![0; 64],
+            tx_setup.reclaim.reclaim_script().to_bytes(),
+            vec![0xc0; 33],
+        ]);
+        let reclaim_tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output: outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: bitcoin::Sequence(150),
+                witness,
+            }],
+            output: Vec::new(),
+        };
+        let mut block = test_harness.bitcoin_blocks()[1].clone();
+        block.txdata.push(reclaim_tx);
+
+        block_observer
+            .report_reclaimed_deposits(&block)
+            .await
+            .unwrap();
+
+        assert_eq!(test_harness.reclaimed_deposits(), vec![outpoint]);
+        assert!(test_harness.pending_deposits().is_empty());
+        assert!(test_harness.evicted_deposits().is_empty());
+    }
+
+    /// Test that `BlockObserver::extract_sbtc_transactions` takes the
+    /// stored signer `scriptPubKey`s and stores all transactions from a
+    /// bitcoin block that match one of those `scriptPubkey`s.
+    #[tokio::test]
+    async fn sbtc_transactions_get_stored() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+
+        let block_hash = BlockHash::from_byte_array([1u8; 32]);
+        // We're going to do the following:
+        // 1. pretend that the below bytes represent the signers
+        //    `scriptPubKey`. We store it in our datastore along with some
+        //    "DKG shares".
+        // 2. We then create two transactions, one spending to our
+        //    scriptPubKey and another not spending to it.
+        // 3. We try "extracting" a block with one transaction that does
+        //    not spend to the signers. This one transaction should not be
+        //    extracted (we should not see it in storage).
+        // 4. We try "extracting" a block with two transactions where one
+        //    of them spends to the signers. The one transaction should be
+        //    stored in our storage.
+        let signers_script_pubkey: ScriptPubKey = fake::Faker.fake_with_rng(&mut rng);
+
+        // We start by storing our `scriptPubKey`.
+        let storage = storage::in_memory::Store::new_shared();
+        let aggregate_key = PublicKey::dummy_with_rng(&fake::Faker, &mut rng);
+        let shares = model::EncryptedDkgShares {
+            aggregate_key,
+            tweaked_aggregate_key: aggregate_key.signers_tweaked_pubkey().unwrap(),
+            script_pubkey: signers_script_pubkey.clone(),
+            encrypted_private_shares: Vec::new(),
+            public_shares: Vec::new(),
+            signer_set_public_keys: vec![aggregate_key],
+            signature_share_threshold: 1,
+        };
+        storage.write_encrypted_dkg_shares(&shares).await.unwrap();
+
+        // Now let's create two transactions, one spending to the signers
+        // and another not spending to the signers. We use
+        // sbtc::testing::deposits::tx_setup just to quickly create a
+        // transaction; any one will do since we will be adding the UTXO
+        // that spends to the signer afterward.
+        let mut tx_setup0 = sbtc::testing::deposits::tx_setup(0, 0, 100);
+        tx_setup0.tx.output.push(TxOut {
+            value: Amount::ONE_BTC,
+            script_pubkey: signers_script_pubkey.into(),
+        });
+
+        // This one does not spend to the signers :(
+        let tx_setup1 = sbtc::testing::deposits::tx_setup(1, 10, 2000);
+        let txid0 = tx_setup0.tx.compute_txid();
+        let txid1 = tx_setup1.tx.compute_txid();
+
+        let response0 = GetTxResponse {
+            tx: tx_setup0.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        let response1 = GetTxResponse {
+            tx: tx_setup1.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid0, response0);
+        test_harness.add_deposit(txid1, response1);
+
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        // First we try extracting the transactions from a block that does
+        // not contain any transactions spent to the signers
+        let txs = [tx_setup1.tx.clone()];
+        block_observer
+            .extract_sbtc_transactions(block_hash, &txs)
+            .await
+            .unwrap();
+
+        // We need to change the scope so that the mutex guard is dropped.
+        {
+            let store = storage.lock().await;
+            // Under the hood, bitcoin transactions get stored in the
+            // `bitcoin_block_to_transactions` field, so lets check there
+            let stored_transactions = store.bitcoin_block_to_transactions.get(&block_hash.into());
+
+            // Nothing should be stored so the map get call should return
+            // None.
+            assert!(stored_transactions.is_none());
+        }
+
+        // Now we try again, but we include the transaction that spends to
+        // the signer. This one should turn out differently.
+        let txs = [tx_setup0.tx.clone(), tx_setup1.tx.clone()];
+        block_observer
+            .extract_sbtc_transactions(block_hash, &txs)
+            .await
+            .unwrap();
+
+        let store = storage.lock().await;
+        let stored_transactions = store.bitcoin_block_to_transactions.get(&block_hash.into());
+
+        // Is our one transaction stored? This block hash should now have
+        // only one transaction with the expected txid.
+        let tx_ids = stored_transactions.unwrap();
+        let expected_tx_id = tx_setup0.tx.compute_txid().into();
+        assert_eq!(tx_ids.len(), 1);
+        assert_eq!(tx_ids[0], expected_tx_id);
+    }
+
+    #[test]
+    fn poll_intervals_vary_within_jitter_bounds() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let poll_interval = PollInterval {
+            base: Duration::from_millis(100),
+            jitter: Duration::from_millis(50),
+        };
+
+        let intervals: Vec<Duration> = (0..20)
+            .map(|_| poll_interval.next_interval(&mut rng))
+            .collect();
+
+        for interval in intervals.iter() {
+            assert!(*interval >= poll_interval.base);
+            assert!(*interval <= poll_interval.base + poll_interval.jitter);
+        }
+        let distinct: HashSet<Duration> = intervals.iter().copied().collect();
+        assert!(distinct.len() > 1);
+
+        // Without any jitter we always wait for the base interval.
+        let no_jitter = PollInterval {
+            base: Duration::from_millis(100),
+            jitter: Duration::ZERO,
+        };
+        assert_eq!(no_jitter.next_interval(&mut rng), no_jitter.base);
+    }
+
+    #[tokio::test]
+    async fn only_old_unconfirmed_sweeps_are_stuck() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // A chain of three blocks where the chain tip is at height 12.
+        let db = ctx.get_storage_mut();
+        let mut parent_hash: model::BitcoinBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let mut blocks = Vec::new();
+        for block_height in 10..13 {
+            let block = model::BitcoinBlock {
+                block_hash: fake::Faker.fake_with_rng(&mut rng),
+                block_height,
+                parent_hash,
+            };
+            db.write_bitcoin_block(&block).await.unwrap();
+            parent_hash = block.block_hash;
+            blocks.push(block);
+        }
+
+        // Both sweeps are broadcast but neither has been confirmed. The
+        // old one was broadcast two blocks before the chain tip, the
+        // recent one at the chain tip.
+        let bitcoin_client = ctx.get_bitcoin_client();
+        let old_sweep = crate::testing::dummy::tx(&fake::Faker, &mut rng);
+        let recent_sweep = crate::testing::dummy::tx(&fake::Faker, &mut rng);
+        bitcoin_client
+            .broadcast_transaction(&old_sweep)
+            .await
+            .unwrap();
+        bitcoin_client
+            .broadcast_transaction(&recent_sweep)
+            .await
+            .unwrap();
+
+        let known_sweeps = ctx.state().known_sweeps();
+        known_sweeps.insert(old_sweep.compute_txid(), blocks[0].block_hash);
+        known_sweeps.insert(recent_sweep.compute_txid(), blocks[2].block_hash);
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let stuck_sweeps = block_observer.stuck_sweeps(2).await.unwrap();
+        assert_eq!(stuck_sweeps, vec![old_sweep.compute_txid()]);
+    }
+
+    #[tokio::test]
+    async fn sweeps_that_leave_the_mempool_are_evicted() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        let ctx = TestContext::builder()
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        // One sweep is still in the mempool while the other one is not,
+        // as if it had been confirmed or dropped.
+        let pending_sweep = crate::testing::dummy::tx(&fake::Faker, &mut rng);
+        let settled_sweep = crate::testing::dummy::tx(&fake::Faker, &mut rng);
+        ctx.get_bitcoin_client()
+            .broadcast_transaction(&pending_sweep)
+            .await
+            .unwrap();
+
+        let block_hash: model::BitcoinBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let known_sweeps = ctx.state().known_sweeps();
+        known_sweeps.insert(pending_sweep.compute_txid(), block_hash);
+        known_sweeps.insert(settled_sweep.compute_txid(), block_hash);
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let evicted = block_observer.evict_settled_sweeps().await.unwrap();
+        assert_eq!(evicted, vec![settled_sweep.compute_txid()]);
+
+        let remaining = ctx.state().known_sweeps().get_all();
+        assert_eq!(remaining, vec![(pending_sweep.compute_txid(), block_hash)]);
+    }
+
+    /// Write a bitcoin block, and a stacks block anchored to it, to the
+    /// given storage. Returns the bitcoin block.
+    async fn write_anchored_blocks<R: rand::Rng>(
+        db: &impl DbWrite,
+        rng: &mut R,
+        block_height: u64,
+        parent_hash: model::BitcoinBlockHash,
+    ) -> model::BitcoinBlock {
+        let bitcoin_block = model::BitcoinBlock {
+            block_height,
+            parent_hash,
+            ..fake::Faker.fake_with_rng(rng)
+        };
+        let stacks_block = model::StacksBlock {
+            bitcoin_anchor: bitcoin_block.block_hash,
+            ..fake::Faker.fake_with_rng(rng)
+        };
+        db.write_bitcoin_block(&bitcoin_block).await.unwrap();
+        db.write_stacks_block(&stacks_block).await.unwrap();
+        bitcoin_block
+    }
+
+    /// Build a withdrawal accept event for a withdrawal swept in the
+    /// given bitcoin block.
+    fn held_withdrawal_accept_event<R: rand::Rng>(
+        rng: &mut R,
+        sweep_block: &model::BitcoinBlock,
+    ) -> WithdrawalAcceptEvent {
+        let sweep_txid: BitcoinTxId = fake::Faker.fake_with_rng(rng);
+        WithdrawalAcceptEvent {
+            request_id: 1,
+            outpoint: OutPoint { txid: *sweep_txid, vout: 1 },
+            sweep_block_hash: *sweep_block.block_hash,
+            sweep_block_height: sweep_block.block_height,
+            sweep_txid: *sweep_txid,
+            ..fake::Faker.fake_with_rng(rng)
+        }
+    }
+
+    #[tokio::test]
+    async fn held_withdrawals_are_confirmed_once_their_sweep_is_deep_enough() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.withdrawal_min_confirmations = Some(6);
+
+        let mut ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        let parent_hash: model::BitcoinBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let db = ctx.get_storage_mut();
+        let sweep_block = write_anchored_blocks(&db, &mut rng, 10, parent_hash).await;
+        let event = held_withdrawal_accept_event(&mut rng, &sweep_block);
+        db.write_withdrawal_accept_event(&event).await.unwrap();
+        db.write_held_withdrawal(&event).await.unwrap();
+
+        // The sweep has three confirmations on the first bitcoin block
+        // and six on the second one.
+        let confirmations = Arc::new(std::sync::Mutex::new(vec![6, 3]));
+        ctx.with_bitcoin_client(|client| {
+            client
+                .expect_get_tx_confirmations()
+                .times(2)
+                .returning(move |_| {
+                    let next = confirmations.lock().unwrap().pop();
+                    Box::pin(async move { Ok(next) })
+                });
+        })
+        .await;
+        ctx.with_emily_client(|client| {
+            client
+                .expect_update_withdrawals()
+                .times(1)
+                .withf(|updates| {
+                    updates.len() == 1
+                        && updates[0].request_id == 1
+                        && updates[0].status == Status::Confirmed
+                })
+                .returning(|_| {
+                    Box::pin(async {
+                        Ok(emily_client::models::UpdateWithdrawalsResponse { withdrawals: vec![] })
+                    })
+                });
+        })
+        .await;
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let released = block_observer.release_held_withdrawals().await.unwrap();
+        assert!(released.is_empty());
+        assert_eq!(db.get_held_withdrawals().await.unwrap().len(), 1);
+
+        let released = block_observer.release_held_withdrawals().await.unwrap();
+        assert_eq!(released, vec![1]);
+        assert!(db.get_held_withdrawals().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn held_withdrawals_are_released_when_their_sweep_is_reorged_out() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.withdrawal_min_confirmations = Some(6);
+
+        let mut ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+
+        // The sweep block and the new chain tip share a parent, so the
+        // sweep block is no longer canonical.
+        let parent_hash: model::BitcoinBlockHash = fake::Faker.fake_with_rng(&mut rng);
+        let db = ctx.get_storage_mut();
+        let sweep_block = write_anchored_blocks(&db, &mut rng, 10, parent_hash).await;
+        let fork_block = write_anchored_blocks(&db, &mut rng, 10, parent_hash).await;
+        write_anchored_blocks(&db, &mut rng, 11, fork_block.block_hash).await;
+
+        let event = held_withdrawal_accept_event(&mut rng, &sweep_block);
+        db.write_withdrawal_accept_event(&event).await.unwrap();
+        db.write_held_withdrawal(&event).await.unwrap();
+
+        ctx.with_bitcoin_client(|client| {
+            client.expect_get_tx_confirmations().times(0);
+        })
+        .await;
+        ctx.with_emily_client(|client| {
+            client.expect_update_withdrawals().times(0);
+        })
+        .await;
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        let released = block_observer.release_held_withdrawals().await.unwrap();
+        assert!(released.is_empty());
+        assert!(db.get_held_withdrawals().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn observer_backs_off_from_emily_until_it_recovers() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.emily_backoff_base = Some(20);
+        settings.signer.emily_backoff_max = Some(1_000);
+
+        let ctx = TestContext::builder()
+            .with_settings(settings)
+            .with_in_memory_storage()
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+        let backoff = ctx.state().emily_backoff();
+        let policy = BackoffPolicy::emily_from_config(&ctx.config().signer);
+        test_harness.fail_next_get_deposits(2);
+
+        // The first failure starts the backoff.
+        assert!(block_observer.load_latest_deposit_requests().await.is_err());
+        assert_eq!(backoff.consecutive_failures(), 1);
+        assert!(!backoff.is_healthy());
+
+        // Calls made while backing off do not reach Emily, so they do not
+        // use up the second failure.
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        assert_eq!(backoff.consecutive_failures(), 1);
+
+        tokio::time::sleep(policy.delay(1)).await;
+        assert!(block_observer.load_latest_deposit_requests().await.is_err());
+        assert_eq!(backoff.consecutive_failures(), 2);
+        assert!(policy.delay(2) > policy.delay(1));
+
+        // Emily is back once the longer delay has elapsed.
+        tokio::time::sleep(policy.delay(2)).await;
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        assert!(backoff.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn requests_that_do_not_match_a_contested_funding_output_are_superseded() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[5].block_hash();
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(block_hash),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid, response);
+
+        // Both requests claim the same funding output, but only the second
+        // one has the scripts that the output is locked to.
+        let outpoint = bitcoin::OutPoint::new(txid, 0);
+        let other_setup = sbtc::testing::deposits::tx_setup(200, 33000, 500_000);
+        let requests = vec![
+            CreateDepositRequest {
+                outpoint,
+                deposit_script: other_setup.deposit.deposit_script(),
+                reclaim_script: other_setup.reclaim.reclaim_script(),
+            },
+            CreateDepositRequest {
+                outpoint,
+                deposit_script: tx_setup.deposit.deposit_script(),
+                reclaim_script: tx_setup.reclaim.reclaim_script(),
+            },
+        ];
+
+        assert_eq!(
+            contested_funding_outputs(&requests),
+            HashSet::from([outpoint])
+        );
+
+        // Without the funding output, the first request is kept.
+        let (active, superseded) = supersede_duplicate_requests(&requests, &HashMap::new());
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].deposit_script, requests[0].deposit_script);
+        assert_eq!(superseded.len(), 1);
+        assert_eq!(superseded[0].deposit_script, requests[1].deposit_script);
+
+        // With it, the request that matches the output is kept, even
+        // though it came later.
+        let funding_script_pubkeys =
+            HashMap::from([(outpoint, tx_setup.tx.output[0].script_pubkey.clone())]);
+        let (active, superseded) = supersede_duplicate_requests(&requests, &funding_script_pubkeys);
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].deposit_script, requests[1].deposit_script);
+        assert_eq!(superseded.len(), 1);
+        assert_eq!(superseded[0].deposit_script, requests[0].deposit_script);
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx,
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_requests(&requests).await.unwrap();
+
+        // The active request is stored, while the superseded one neither
+        // replaces it nor gets the funding output evicted.
+        let storage = storage.lock().await;
+        assert_eq!(storage.deposit_requests.len(), 1);
+        let stored = storage
+            .deposit_requests
+            .get(&(txid.into(), 0))
+            .expect("active deposit request was not stored");
+        assert_eq!(
+            stored.spend_script,
+            tx_setup.deposit.deposit_script().to_bytes()
+        );
+        assert!(test_harness.evicted_deposits().is_empty());
+    }
+
+    #[tokio::test]
+    async fn deposits_below_the_emily_min_confirmations_are_deferred() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 20, 0..5);
+        let block_hash = test_harness.bitcoin_blocks()[0].block_hash();
+
+        let mut requests = Vec::new();
+        for confirmations in [1, 2, 3, 5] {
+            let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+            let txid = tx_setup.tx.compute_txid();
+            let response = GetTxResponse {
+                tx: tx_setup.tx.clone(),
+                block_hash: Some(block_hash),
+                confirmations: Some(confirmations),
+                block_time: None,
+            };
+            test_harness.add_deposit(txid, response);
+            requests.push(CreateDepositRequest {
+                outpoint: bitcoin::OutPoint::new(txid, 0),
+                deposit_script: tx_setup.deposit.deposit_script(),
+                reclaim_script: tx_setup.reclaim.reclaim_script(),
+            });
+        }
+        test_harness.add_pending_deposits(&requests).unwrap();
+        test_harness.set_min_confirmations(Some(3));
+
+        let storage = storage::in_memory::Store::new_shared();
+        let ctx = TestContext::builder()
+            .with_storage(storage.clone())
+            .with_stacks_client(test_harness.clone())
+            .with_emily_client(test_harness.clone())
+            .with_bitcoin_client(test_harness.clone())
+            .build();
+
+        let block_observer = BlockObserver {
+            context: ctx.clone(),
+            bitcoin_blocks: (),
+            horizon: 1,
+        };
+
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        assert_eq!(ctx.state().deposit_min_confirmations(), Some(3));
+
+        let stored: HashSet<OutPoint> = {
+            let db = storage.lock().await;
+            db.deposit_requests
+                .values()
+                .map(|deposit| deposit.outpoint())
+                .collect()
+        };
+        let expected = HashSet::from([requests[2].outpoint, requests[3].outpoint]);
+        assert_eq!(stored, expected);
+
+        // The deferred deposits are still pending in Emily.
+        assert!(test_harness.evicted_deposits().is_empty());
+        assert_eq!(test_harness.pending_deposits().len(), requests.len());
+
+        // Once the override is removed, the deferred deposits are loaded
+        // too.
+        test_harness.set_min_confirmations(None);
+        block_observer.load_latest_deposit_requests().await.unwrap();
+        assert_eq!(ctx.state().deposit_min_confirmations(), None);
+
+        let db = storage.lock().await;
+        assert_eq!(db.deposit_requests.len(), requests.len());
+    }
+}
@@ -0,0 +1,134 @@
+//! Helpers for following the current stacks tenure
+
+use std::time::Duration;
+
+use blockstack_lib::chainstate::burn::ConsensusHash;
+use blockstack_lib::net::api::gettenureinfo::RPCGetTenureInfo;
+use blockstack_lib::types::chainstate::StacksBlockId;
+
+use crate::error::Error;
+use crate::stacks::api::StacksInteract;
+
+/// The maximum number of times the block observer fetches the current
+/// tenure info from the stacks node while the responses are inconsistent.
+pub const MAX_TENURE_INFO_ATTEMPTS: u32 = 3;
+
+/// How long the block observer waits before fetching the tenure info
+/// again after an inconsistent response.
+const TENURE_INFO_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// A change of the current stacks tenure, as observed by a
+/// [`TenureWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenureChange {
+    /// The consensus hash of the tenure that was current before the change.
+    pub previous_consensus_hash: ConsensusHash,
+    /// The consensus hash of the new tenure.
+    pub consensus_hash: ConsensusHash,
+    /// The first stacks block of the new tenure.
+    pub tenure_start_block_id: StacksBlockId,
+}
+
+/// Detects when a new stacks tenure starts by comparing the consensus
+/// hashes of consecutive [`StacksInteract::get_tenure_info`] responses.
+#[derive(Debug, Default)]
+pub struct TenureWatcher {
+    /// The consensus hash of the last tenure that was observed.
+    last_consensus_hash: Option<ConsensusHash>,
+}
+
+impl TenureWatcher {
+    /// Fetch the current tenure info and return the tenure change if the
+    /// current tenure differs from the one observed on the last call.
+    ///
+    /// The first call only records the current tenure, so it never
+    /// returns a change.
+    pub async fn poll<S>(&mut self, stacks_client: &S) -> Result<Option<TenureChange>, Error>
+    where
+        S: StacksInteract,
+    {
+        let tenure_info = stacks_client.get_tenure_info().await?;
+        let consensus_hash = tenure_info.consensus_hash;
+
+        let previous = self.last_consensus_hash.replace(consensus_hash);
+        Ok(previous
+            .filter(|previous| *previous != consensus_hash)
+            .map(|previous_consensus_hash| TenureChange {
+                previous_consensus_hash,
+                consensus_hash,
+                tenure_start_block_id: tenure_info.tenure_start_block_id,
+            }))
+    }
+}
+
+/// Fetch the current tenure info, retrying if the response is
+/// inconsistent.
+///
+/// The stacks node can return data from the middle of a tenure change,
+/// where the reported tip block does not belong to the reported tenure.
+/// We check this by looking at the consensus hash in the header of the
+/// tip block, and fetch the tenure info again, up to
+/// [`MAX_TENURE_INFO_ATTEMPTS`] times, if it does not match.
+pub async fn get_consistent_tenure_info<S>(stacks_client: &S) -> Result<RPCGetTenureInfo, Error>
+where
+    S: StacksInteract,
+{
+    let mut attempt = 1;
+    loop {
+        let tenure_info = stacks_client.get_tenure_info().await?;
+        let tip_block_id = tenure_info.tip_block_id;
+        let tip_block = stacks_client.get_block(tip_block_id).await?;
+        if tip_block.header.consensus_hash == tenure_info.consensus_hash {
+            return Ok(tenure_info);
+        }
+
+        if attempt >= MAX_TENURE_INFO_ATTEMPTS {
+            return Err(Error::InconsistentTenureInfo(tip_block_id));
+        }
+
+        tracing::warn!(
+            attempt,
+            %tip_block_id,
+            "the tip block is not in the tenure reported by the stacks node; retrying"
+        );
+        attempt += 1;
+        tokio::time::sleep(TENURE_INFO_RETRY_DELAY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use crate::testing::block_observer::TestHarness;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn tenure_watcher_detects_new_tenures() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        let mut watcher = TenureWatcher::default();
+
+        // The first poll only records the current tenure, and nothing
+        // changes after that until a new tenure starts.
+        assert!(watcher.poll(&test_harness).await.unwrap().is_none());
+        assert!(watcher.poll(&test_harness).await.unwrap().is_none());
+
+        let before = test_harness.get_tenure_info().await.unwrap();
+        test_harness.add_stacks_tenure(&mut rng, 3);
+        let after = test_harness.get_tenure_info().await.unwrap();
+
+        let change = watcher
+            .poll(&test_harness)
+            .await
+            .unwrap()
+            .expect("no tenure change detected");
+        assert_eq!(change.previous_consensus_hash, before.consensus_hash);
+        assert_eq!(change.consensus_hash, after.consensus_hash);
+        assert_eq!(change.tenure_start_block_id, after.tenure_start_block_id);
+
+        // The tenure change is only reported once.
+        assert!(watcher.poll(&test_harness).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,368 @@
+//! Validation of deposit requests against the bitcoin blockchain
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::future::Future;
+
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::DepositInfo;
+use sbtc::deposits::ReclaimScriptInputs;
+
+use crate::bitcoin::rpc::GetTxResponse;
+use crate::bitcoin::BitcoinInteract;
+use crate::config::ReclaimScriptTemplate;
+use crate::context::DepositScriptCache;
+use crate::error::Error;
+
+use super::Deposit;
+
+/// A trait to add validation functionality to the [`CreateDepositRequest`]
+/// type.
+pub trait DepositRequestValidator {
+    /// Validate this deposit request from the transaction.
+    ///
+    /// This function fetches the transaction using the given client and
+    /// checks that the transaction has been submitted. The transaction
+    /// need not be confirmed.
+    fn validate<C>(&self, client: &C) -> impl Future<Output = Result<Option<Deposit>, Error>>
+    where
+        C: BitcoinInteract;
+
+    /// Validate this deposit request from the transaction, taking the
+    /// parsed deposit and reclaim scripts from the given cache.
+    ///
+    /// This performs the same checks as
+    /// [`DepositRequestValidator::validate`], but the scripts of the
+    /// request are only parsed if they are not already in the cache.
+    fn validate_cached<C>(
+        &self,
+        client: &C,
+        cache: &DepositScriptCache,
+    ) -> impl Future<Output = Result<Option<Deposit>, Error>>
+    where
+        C: BitcoinInteract;
+}
+
+impl DepositRequestValidator for CreateDepositRequest {
+    async fn validate<C>(&self, client: &C) -> Result<Option<Deposit>, Error>
+    where
+        C: BitcoinInteract,
+    {
+        self.validate_cached(client, &DepositScriptCache::default())
+            .await
+    }
+
+    async fn validate_cached<C>(
+        &self,
+        client: &C,
+        cache: &DepositScriptCache,
+    ) -> Result<Option<Deposit>, Error>
+    where
+        C: BitcoinInteract,
+    {
+        // Fetch the transaction from either a block or from the mempool
+        let response = client.get_tx(&self.outpoint.txid).await?;
+
+        // If the transaction is unknown or has not been confirmed yet,
+        // it may have been replaced using RBF by a transaction that has
+        // been confirmed. In that case the deposit confirms with the
+        // replacement, so this is the transaction that we validate.
+        if response
+            .as_ref()
+            .map_or(true, |resp| resp.block_hash.is_none())
+        {
+            // The inputs of the transaction can only be looked up while it
+            // is known to bitcoin-core, so we remember them in case it
+            // gets replaced and evicted from the mempool.
+            if let Some(resp) = response.as_ref() {
+                let inputs = resp.tx.input.iter().map(|tx_in| tx_in.previous_output);
+                cache.record_funding_inputs(self.outpoint.txid, inputs.collect());
+            }
+            let funding_inputs = cache
+                .funding_inputs(&self.outpoint.txid)
+                .unwrap_or_default();
+
+            if let Some((request, response)) =
+                find_confirmed_replacement(self, &funding_inputs, client).await?
+            {
+                tracing::debug!(
+                    original = %self.outpoint,
+                    replacement = %request.outpoint,
+                    "deposit transaction was replaced"
+                );
+                return validate_confirmed_deposit(&request, response, client, cache).await;
+            }
+        }
+
+        let Some(response) = response else {
+            return Ok(None);
+        };
+        validate_confirmed_deposit(self, response, client, cache).await
+    }
+}
+
+/// Validate the deposit request against the transaction in the response,
+/// if the transaction has been confirmed.
+async fn validate_confirmed_deposit<C>(
+    request: &CreateDepositRequest,
+    response: GetTxResponse,
+    client: &C,
+    cache: &DepositScriptCache,
+) -> Result<Option<Deposit>, Error>
+where
+    C: BitcoinInteract,
+{
+    // If the transaction has not been confirmed yet, then the block
+    // hash will be None. The trasnaction has not failed validation,
+    // let's try again when it gets confirmed.
+    let Some(block_hash) = response.block_hash else {
+        return Ok(None);
+    };
+
+    // The `get_tx_info` call here should not return None, we know that
+    // it has been included in a block.
+    let Some(tx_info) = client
+        .get_tx_info(&request.outpoint.txid, &block_hash)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    // TODO(515): After the transaction passes validation, we need to
+    // check whether we know about the public key in the deposit
+    // script.
+
+    let scripts = cache.get_or_parse(request)?;
+    Ok(Some(Deposit {
+        info: request.validate_tx_with(&tx_info.tx, &scripts)?,
+        tx_vsize: tx_info.tx.vsize() as u64,
+        tx_info,
+    }))
+}
+
+/// Follow the RBF replacements of the deposit transaction in the request
+/// and, if the last replacement has been confirmed, return the request
+/// for the deposit in the replacement along with the replacement.
+///
+/// The deposit in the replacement is the output with the same
+/// `scriptPubKey` as the one in the request, which need not be at the
+/// same output index. The `funding_inputs` are the outpoints spent by the
+/// deposit transaction in the request, if they are known.
+async fn find_confirmed_replacement<C>(
+    request: &CreateDepositRequest,
+    funding_inputs: &[bitcoin::OutPoint],
+    client: &C,
+) -> Result<Option<(CreateDepositRequest, GetTxResponse)>, Error>
+where
+    C: BitcoinInteract,
+{
+    // Replacements can themselves be replaced, so we follow them to the
+    // last one, taking care not to loop forever. A replacement has to
+    // spend one of the inputs of the transaction that it replaces, so
+    // the original funding inputs are the ones that we look for.
+    let mut txid = request.outpoint.txid;
+    let mut seen = HashSet::from([txid]);
+    while let Some(replacement_txid) = client.find_replacement_tx(&txid, funding_inputs).await? {
+        if !seen.insert(replacement_txid) {
+            break;
+        }
+        txid = replacement_txid;
+    }
+
+    if txid == request.outpoint.txid {
+        return Ok(None);
+    }
+
+    let Some(response) = client.get_tx(&txid).await? else {
+        return Ok(None);
+    };
+    if response.block_hash.is_none() {
+        return Ok(None);
+    }
+
+    let script_pubkey = sbtc::deposits::to_script_pubkey(
+        request.deposit_script.clone(),
+        request.reclaim_script.clone(),
+    );
+    let Some(vout) = response
+        .tx
+        .output
+        .iter()
+        .position(|tx_out| tx_out.script_pubkey == script_pubkey)
+    else {
+        return Ok(None);
+    };
+
+    let replacement_request = CreateDepositRequest {
+        outpoint: bitcoin::OutPoint::new(txid, vout as u32),
+        deposit_script: request.deposit_script.clone(),
+        reclaim_script: request.reclaim_script.clone(),
+    };
+    Ok(Some((replacement_request, response)))
+}
+
+/// Return the funding outputs that are claimed by more than one of the
+/// given deposit requests.
+pub fn contested_funding_outputs(requests: &[CreateDepositRequest]) -> HashSet<OutPoint> {
+    let mut seen = HashSet::new();
+    requests
+        .iter()
+        .map(|request| request.outpoint)
+        .filter(|outpoint| !seen.insert(*outpoint))
+        .collect()
+}
+
+/// Split the given deposit requests into the ones that are active and the
+/// ones that are superseded by another request for the same funding
+/// output.
+///
+/// Only one request can be valid for a given `(funding_txid, vout)`, the
+/// one whose scripts match the `scriptPubKey` of the funding output. So
+/// when several requests claim an output, the first one that matches the
+/// `scriptPubKey` given for the output is kept and the others are
+/// superseded, regardless of the order that they came in. If none of them
+/// match, or the `scriptPubKey` of the output is not given, the first one
+/// is kept and left for validation to judge. This way a bogus request for
+/// an output cannot compete with, or get the output evicted from under,
+/// the valid request.
+///
+/// Emily keys deposits by their funding output, so there is no separate
+/// entry for a superseded request to move to a superseded status, and
+/// they are skipped instead.
+pub fn supersede_duplicate_requests<'a>(
+    requests: &'a [CreateDepositRequest],
+    funding_script_pubkeys: &HashMap<OutPoint, ScriptBuf>,
+) -> (Vec<&'a CreateDepositRequest>, Vec<&'a CreateDepositRequest>) {
+    let matches_funding_output = |request: &CreateDepositRequest| {
+        funding_script_pubkeys
+            .get(&request.outpoint)
+            .is_some_and(|script_pubkey| {
+                *script_pubkey
+                    == sbtc::deposits::to_script_pubkey(
+                        request.deposit_script.clone(),
+                        request.reclaim_script.clone(),
+                    )
+            })
+    };
+
+    let mut kept: HashMap<OutPoint, usize> = HashMap::new();
+    for (index, request) in requests.iter().enumerate() {
+        match kept.entry(request.outpoint) {
+            Entry::Vacant(entry) => {
+                entry.insert(index);
+            }
+            Entry::Occupied(mut entry) => {
+                if !matches_funding_output(&requests[*entry.get()])
+                    && matches_funding_output(request)
+                {
+                    entry.insert(index);
+                }
+            }
+        }
+    }
+
+    let (active, superseded): (Vec<_>, Vec<_>) = requests
+        .iter()
+        .enumerate()
+        .partition(|(index, request)| kept.get(&request.outpoint) == Some(index));
+
+    let strip = |requests: Vec<(usize, &'a CreateDepositRequest)>| {
+        requests.into_iter().map(|(_, request)| request).collect()
+    };
+    (strip(active), strip(superseded))
+}
+
+/// Check that the reclaim script of the deposit follows one of the
+/// templates in the given allowlist. Any reclaim script is allowed when
+/// there is no allowlist.
+pub fn check_reclaim_script_allowlist(
+    allowlist: Option<&[ReclaimScriptTemplate]>,
+    deposit: &DepositInfo,
+) -> Result<(), Error> {
+    let Some(allowlist) = allowlist else {
+        return Ok(());
+    };
+    let reclaim = ReclaimScriptInputs::parse(&deposit.reclaim_script)?;
+    if allowlist
+        .iter()
+        .any(|template| template.matches(reclaim.user_script()))
+    {
+        Ok(())
+    } else {
+        Err(Error::ReclaimNotAllowlisted(deposit.outpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::OutPoint;
+    use bitcoin::ScriptBuf;
+
+    use super::*;
+
+    fn single_sig_reclaim_script() -> ScriptBuf {
+        let secret_key = secp256k1::SecretKey::new(&mut rand::rngs::OsRng);
+        let (public_key, _) = secret_key.x_only_public_key(secp256k1::SECP256K1);
+        ScriptBuf::builder()
+            .push_opcode(bitcoin::opcodes::all::OP_DROP)
+            .push_slice(public_key.serialize())
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG)
+            .into_script()
+    }
+
+    fn multi_sig_reclaim_script(threshold: i64) -> ScriptBuf {
+        let mut builder = ScriptBuf::builder().push_opcode(bitcoin::opcodes::all::OP_DROP);
+        for index in 0..3 {
+            let secret_key = secp256k1::SecretKey::new(&mut rand::rngs::OsRng);
+            let (public_key, _) = secret_key.x_only_public_key(secp256k1::SECP256K1);
+            builder = builder
+                .push_slice(public_key.serialize())
+                .push_opcode(if index == 0 {
+                    bitcoin::opcodes::all::OP_CHECKSIG
+                } else {
+                    bitcoin::opcodes::all::OP_CHECKSIGADD
+                });
+        }
+        builder
+            .push_int(threshold)
+            .push_opcode(bitcoin::opcodes::all::OP_NUMEQUAL)
+            .into_script()
+    }
+
+    #[test_case::test_case(None, ScriptBuf::new(), true; "no allowlist")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::Empty]), ScriptBuf::new(), true; "allowlisted empty script")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::SingleSig]), ScriptBuf::new(), false; "non-allowlisted empty script")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::SingleSig]), single_sig_reclaim_script(), true; "allowlisted single sig script")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::MultiSig]), single_sig_reclaim_script(), false; "non-allowlisted single sig script")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::MultiSig]), multi_sig_reclaim_script(2), true; "allowlisted multi sig script")]
+    #[test_case::test_case(Some(vec![ReclaimScriptTemplate::MultiSig]), multi_sig_reclaim_script(4), false; "multi sig script with an unreachable threshold")]
+    fn reclaim_script_allowlist_rejects_scripts_without_an_approved_template(
+        allowlist: Option<Vec<ReclaimScriptTemplate>>,
+        user_script: ScriptBuf,
+        allowed: bool,
+    ) {
+        let setup = sbtc::testing::deposits::tx_setup(10, 10_000, 50_000);
+        let reclaim = ReclaimScriptInputs::try_new(10, user_script).unwrap();
+        let request = CreateDepositRequest {
+            outpoint: OutPoint::new(setup.tx.compute_txid(), 0),
+            deposit_script: setup.deposit.deposit_script(),
+            reclaim_script: setup.reclaim.reclaim_script(),
+        };
+        let mut info = request.validate_tx(&setup.tx).unwrap();
+        info.reclaim_script = reclaim.reclaim_script();
+
+        let result = check_reclaim_script_allowlist(allowlist.as_deref(), &info);
+        if allowed {
+            result.unwrap();
+        } else {
+            match result.unwrap_err() {
+                Error::ReclaimNotAllowlisted(outpoint) => assert_eq!(outpoint, request.outpoint),
+                error => panic!("unexpected error: {error}"),
+            }
+        }
+    }
+}
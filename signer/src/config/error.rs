@@ -65,4 +65,14 @@ pub enum SignerConfigError {
     /// An error returned for duration parameters that must be positive.
     #[error("Duration for {0} must be nonzero")]
     ZeroDurationForbidden(&'static str),
+
+    /// An error for a max_sweep_fee_rate value that is not a positive,
+    /// finite, number.
+    #[error("The max sweep fee rate must be a positive number, got {0}")]
+    InvalidMaxSweepFeeRate(f64),
+
+    /// An error for a withdrawal_expiry_window value that is zero or not
+    /// smaller than the context window.
+    #[error("The withdrawal expiry window must be between 1 and {1} blocks, got {0}")]
+    InvalidWithdrawalExpiryWindow(u16, u16),
 }
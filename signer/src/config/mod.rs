@@ -10,6 +10,7 @@ use std::collections::BTreeSet;
 use std::path::Path;
 use url::Url;
 
+use crate::bitcoin::utxo::SelectionStrategy;
 use crate::config::error::SignerConfigError;
 use crate::config::serialization::duration_seconds_deserializer;
 use crate::config::serialization::p2p_multiaddr_deserializer_vec;
@@ -83,6 +84,129 @@ impl NetworkKind {
     }
 }
 
+/// The order in which the signers act on pending deposit requests when
+/// not all of them can be swept under the maximum mintable amount.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingOrder {
+    /// The deposit requests are acted on in the order that they were
+    /// confirmed.
+    #[default]
+    Fifo,
+    /// The deposit requests with the largest amounts are acted on first.
+    ValueDescending,
+}
+
+impl ProcessingOrder {
+    /// The strategy for choosing the deposits to sweep that acts on the
+    /// deposit requests in this order.
+    pub fn selection_strategy(&self) -> SelectionStrategy {
+        match self {
+            ProcessingOrder::Fifo => SelectionStrategy::OldestFirst,
+            ProcessingOrder::ValueDescending => SelectionStrategy::LargestFirst,
+        }
+    }
+}
+
+/// The order of the withdrawal outputs of the sweep transactions. The
+/// signers' UTXO and the OP_RETURN output always come first.
+///
+/// The coordinator sends its ordering to the other signers along with the
+/// requests of the sweep transactions, since every signer must construct
+/// the exact transactions that they sign.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "testing", derive(fake::Dummy))]
+#[serde(rename_all = "snake_case")]
+pub enum OutputOrdering {
+    /// The withdrawal outputs follow the order of the identifiers of
+    /// their withdrawal requests.
+    #[default]
+    RequestOrder,
+    /// The withdrawal outputs are sorted by amount and then by
+    /// `scriptPubKey`, following BIP-69.
+    Bip69,
+    /// The withdrawal outputs are shuffled using the signers' UTXO that
+    /// the transaction spends as the seed, so that all signers produce
+    /// the same order while the order differs between transactions.
+    Seeded,
+}
+
+/// A template for the user supplied part of the reclaim script of a
+/// deposit, the part after `<locked-time> OP_CHECKSEQUENCEVERIFY`.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReclaimScriptTemplate {
+    /// There is no user supplied script, so anyone can spend the deposit
+    /// once the lock time has elapsed.
+    Empty,
+    /// A single x-only public key signs the reclaim transaction:
+    /// ```text
+    ///   OP_DROP <x-only-public-key> OP_CHECKSIG
+    /// ```
+    SingleSig,
+    /// At least `m` of `n` x-only public keys sign the reclaim
+    /// transaction:
+    /// ```text
+    ///   OP_DROP <key-1> OP_CHECKSIG <key-2> OP_CHECKSIGADD ... <key-n> OP_CHECKSIGADD <m> OP_NUMEQUAL
+    /// ```
+    MultiSig,
+}
+
+impl ReclaimScriptTemplate {
+    /// Whether the given user supplied part of a reclaim script follows
+    /// this template.
+    pub fn matches(&self, user_script: &bitcoin::Script) -> bool {
+        use bitcoin::opcodes::all::OP_CHECKSIG;
+        use bitcoin::opcodes::all::OP_CHECKSIGADD;
+        use bitcoin::opcodes::all::OP_DROP;
+        use bitcoin::opcodes::all::OP_NUMEQUAL;
+        use bitcoin::script::Instruction;
+
+        let Ok(instructions) = user_script
+            .instructions_minimal()
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            return false;
+        };
+        let is_key = |instruction: &Instruction| match instruction {
+            Instruction::PushBytes(bytes) => bytes.len() == 32,
+            Instruction::Op(_) => false,
+        };
+
+        match self {
+            ReclaimScriptTemplate::Empty => instructions.is_empty(),
+            ReclaimScriptTemplate::SingleSig => match instructions.as_slice() {
+                [Instruction::Op(OP_DROP), key, Instruction::Op(OP_CHECKSIG)] => is_key(key),
+                _ => false,
+            },
+            ReclaimScriptTemplate::MultiSig => {
+                let [Instruction::Op(OP_DROP), keys @ .., threshold, Instruction::Op(OP_NUMEQUAL)] =
+                    instructions.as_slice()
+                else {
+                    return false;
+                };
+                // Each key is followed by OP_CHECKSIG for the first key
+                // and OP_CHECKSIGADD for the others.
+                let num_keys = keys.len() / 2;
+                let keys_are_valid = num_keys >= 2
+                    && keys.len() % 2 == 0
+                    && keys.chunks_exact(2).enumerate().all(|(index, pair)| {
+                        let op = if index == 0 {
+                            OP_CHECKSIG
+                        } else {
+                            OP_CHECKSIGADD
+                        };
+                        is_key(&pair[0]) && pair[1] == Instruction::Op(op)
+                    });
+                let threshold_is_valid = threshold
+                    .script_num()
+                    .is_some_and(|m| (1..=num_keys as i64).contains(&m));
+                keys_are_valid && threshold_is_valid
+            }
+        }
+    }
+}
+
 /// Top-level configuration for the signer
 #[derive(Deserialize, Clone, Debug)]
 pub struct Settings {
@@ -108,6 +232,38 @@ pub struct BitcoinConfig {
     /// Bitcoin ZeroMQ block-hash stream endpoint.
     #[serde(deserialize_with = "url_deserializer_vec")]
     pub block_hash_stream_endpoints: Vec<Url>,
+
+    /// The network that the connected bitcoin-core node(s) are expected
+    /// to be running on. If not provided, the signer's configured network
+    /// is used.
+    #[serde(default)]
+    pub network: Option<NetworkKind>,
+
+    /// The maximum number of attempts made for a read with a bitcoin-core
+    /// node, when it fails with a transient error, before failing over to
+    /// the next node. If not set, a single attempt is made.
+    #[serde(default)]
+    pub rpc_max_attempts: Option<u8>,
+
+    /// The amount of time, in milliseconds, before the first retry of a
+    /// read with the same bitcoin-core node. The delay doubles with each
+    /// retry after that. If not set, reads are retried right away.
+    #[serde(default)]
+    pub rpc_retry_base_delay: Option<u64>,
+
+    /// The upper bound, in milliseconds, of the random amount of time that
+    /// is added to each retry delay. If not set, no jitter is added.
+    #[serde(default)]
+    pub rpc_retry_jitter: Option<u64>,
+}
+
+impl BitcoinConfig {
+    /// Return the network that the connected bitcoin-core node(s) are
+    /// expected to report, falling back to the given signer network if
+    /// one was not explicitly configured.
+    pub fn expected_network(&self, signer_network: NetworkKind) -> NetworkKind {
+        self.network.unwrap_or(signer_network)
+    }
 }
 
 /// Signer network configuration
@@ -215,6 +371,10 @@ pub struct SignerConfig {
     /// The number of signatures required for the signers' bootstrapped
     /// multi-sig wallet on Stacks.
     pub bootstrap_signatures_required: u16,
+    /// The expected number of signers in the signer set. If set, signer
+    /// sets of any other size are rejected.
+    #[serde(default)]
+    pub signer_set_size: Option<u16>,
     /// The number of seconds the coordinator will wait
     /// before processing a new Bitcoin block
     /// (allowing it to propagate to the others signers)
@@ -242,6 +402,79 @@ pub struct SignerConfig {
     /// receiving a DKG begin message before relaying to give the other
     /// signers time to catch up.
     pub dkg_begin_pause: Option<u64>,
+    /// The amount of time, in milliseconds, the block observer waits for a
+    /// new block hash on each iteration of its event loop.
+    #[serde(default)]
+    pub block_observer_poll_interval: Option<u64>,
+    /// The maximum amount of time, in milliseconds, added at random to the
+    /// block observer poll interval on each iteration of its event loop.
+    #[serde(default)]
+    pub block_observer_poll_jitter: Option<u64>,
+    /// The number of confirmations the sweep transaction fulfilling a
+    /// withdrawal must have before the withdrawal is reported as
+    /// confirmed. If not set, withdrawals are reported as confirmed as
+    /// soon as they are accepted on Stacks.
+    #[serde(default)]
+    pub withdrawal_min_confirmations: Option<u32>,
+    /// The maximum fee rate, in sats per virtual byte, that the signers
+    /// will pay for a sweep transaction. When the estimated market fee
+    /// rate is above this cap the coordinator does not sweep. If not set,
+    /// sweeps are built at any estimated fee rate.
+    #[serde(default)]
+    pub max_sweep_fee_rate: Option<f64>,
+    /// The maximum number of deposit requests that the block observer
+    /// validates at the same time.
+    #[serde(default)]
+    pub deposit_validation_concurrency: Option<u16>,
+    /// The amount of time, in seconds, that the signer uses the current
+    /// signer set read from the `sbtc-registry` contract before it reads
+    /// it again.
+    #[serde(default)]
+    pub signer_set_refresh_interval: Option<u64>,
+    /// The maximum length, in bytes, of the deposit script of a deposit
+    /// request. Requests with a longer deposit script fail validation. If
+    /// not set, [`sbtc::deposits::MAX_DEPOSIT_SCRIPT_LENGTH`] is used.
+    #[serde(default)]
+    pub max_deposit_script_length: Option<usize>,
+    /// The minimum total amount, in sats, of the deposits swept in a
+    /// bitcoin transaction package. Deposits worth less than this in total
+    /// are deferred until enough value accumulates. If not set, deposits
+    /// are swept regardless of their total amount.
+    pub min_sweep_value: Option<u64>,
+    /// The order in which pending deposit requests are considered when
+    /// selecting the deposits to sweep. If not set, they are considered in
+    /// the order that they were confirmed.
+    pub deposit_processing_order: Option<ProcessingOrder>,
+    /// The amount of time, in milliseconds, the block observer waits
+    /// before calling Emily again after a failed call. The wait doubles
+    /// with each consecutive failure.
+    #[serde(default)]
+    pub emily_backoff_base: Option<u64>,
+    /// The maximum amount of time, in milliseconds, the block observer
+    /// waits before calling Emily again after consecutive failed calls.
+    #[serde(default)]
+    pub emily_backoff_max: Option<u64>,
+    /// The number of stacks blocks within which a stacks transaction
+    /// submitted by the signers has to be confirmed before it is flagged
+    /// for resubmission.
+    #[serde(default)]
+    pub submission_deadline_blocks: Option<u64>,
+    /// The number of bitcoin blocks that a withdrawal request can stay
+    /// pending before the signers reject it, so that the requested amount
+    /// is returned to the requester. It must be smaller than the context
+    /// window. If not set, withdrawal requests do not expire.
+    #[serde(default)]
+    pub withdrawal_expiry_window: Option<u16>,
+    /// The templates that the reclaim scripts of deposits must follow.
+    /// Deposits whose reclaim script does not follow any of them are
+    /// rejected. If not set, any reclaim script is accepted.
+    #[serde(default)]
+    pub reclaim_script_allowlist: Option<Vec<ReclaimScriptTemplate>>,
+    /// The order of the withdrawal outputs of the sweep transactions
+    /// proposed by this signer when it is the coordinator. If not set,
+    /// they follow the order of their withdrawal requests.
+    #[serde(default)]
+    pub sweep_output_ordering: Option<OutputOrdering>,
 }
 
 impl Validatable for SignerConfig {
@@ -297,6 +530,22 @@ impl Validatable for SignerConfig {
                 SignerConfigError::ZeroDurationForbidden("signer_round_max_duration").to_string(),
             ));
         }
+        if let Some(fee_rate) = cfg.signer.max_sweep_fee_rate {
+            if !fee_rate.is_finite() || fee_rate <= 0.0 {
+                return Err(ConfigError::Message(
+                    SignerConfigError::InvalidMaxSweepFeeRate(fee_rate).to_string(),
+                ));
+            }
+        }
+        if let Some(window) = cfg.signer.withdrawal_expiry_window {
+            let max_window = cfg.signer.context_window.saturating_sub(1);
+            if window == 0 || window > max_window {
+                return Err(ConfigError::Message(
+                    SignerConfigError::InvalidWithdrawalExpiryWindow(window, max_window)
+                        .to_string(),
+                ));
+            }
+        }
         // db_endpoint note: we don't validate the host because we will never
         // get here; the URL deserializer will fail if the host is empty.
         Ok(())
@@ -361,6 +610,7 @@ impl Settings {
             .with_list_parse_key("signer.p2p.seeds")
             .with_list_parse_key("signer.p2p.listen_on")
             .with_list_parse_key("signer.p2p.public_endpoints")
+            .with_list_parse_key("signer.reclaim_script_allowlist")
             .with_list_parse_key("bitcoin.rpc_endpoints")
             .with_list_parse_key("bitcoin.block_hash_stream_endpoints")
             .with_list_parse_key("stacks.endpoints")
@@ -434,6 +684,7 @@ mod tests {
     use std::str::FromStr;
 
     use tempfile;
+    use test_case::test_case;
     use toml_edit::DocumentMut;
 
     use crate::config::serialization::try_parse_p2p_multiaddr;
@@ -498,6 +749,21 @@ mod tests {
         );
         assert!(!settings.signer.bootstrap_signing_set.is_empty());
         assert!(settings.signer.dkg_begin_pause.is_none());
+        assert!(settings.signer.block_observer_poll_interval.is_none());
+        assert!(settings.signer.block_observer_poll_jitter.is_none());
+        assert!(settings.signer.withdrawal_min_confirmations.is_none());
+        assert!(settings.signer.max_sweep_fee_rate.is_none());
+        assert!(settings.signer.deposit_validation_concurrency.is_none());
+        assert!(settings.signer.signer_set_refresh_interval.is_none());
+        assert!(settings.signer.max_deposit_script_length.is_none());
+        assert!(settings.signer.min_sweep_value.is_none());
+        assert!(settings.signer.deposit_processing_order.is_none());
+        assert!(settings.signer.emily_backoff_base.is_none());
+        assert!(settings.signer.emily_backoff_max.is_none());
+        assert!(settings.signer.submission_deadline_blocks.is_none());
+        assert!(settings.signer.withdrawal_expiry_window.is_none());
+        assert!(settings.signer.reclaim_script_allowlist.is_none());
+        assert!(settings.signer.sweep_output_ordering.is_none());
         assert_eq!(settings.signer.bootstrap_signatures_required, 2);
         assert_eq!(settings.signer.bitcoin_block_horizon, 1500);
         assert_eq!(settings.signer.context_window, 10000);
@@ -595,6 +861,27 @@ mod tests {
             .contains(&url("tcp://localhost:5678")));
     }
 
+    #[test]
+    fn bitcoin_network_defaults_to_signer_network() {
+        clear_env();
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert!(settings.bitcoin.network.is_none());
+        assert_eq!(
+            settings.bitcoin.expected_network(settings.signer.network),
+            NetworkKind::Regtest
+        );
+
+        std::env::set_var("SIGNER_BITCOIN__NETWORK", "testnet");
+
+        let settings = Settings::new_from_default_config().unwrap();
+        assert_eq!(settings.bitcoin.network, Some(NetworkKind::Testnet));
+        assert_eq!(
+            settings.bitcoin.expected_network(settings.signer.network),
+            NetworkKind::Testnet
+        );
+    }
+
     #[test]
     fn default_config_toml_loads_signer_private_key_config_with_environment() {
         clear_env();
@@ -844,6 +1131,171 @@ mod tests {
         assert_eq!(config.signer.dkg_begin_pause, Some(1234));
     }
 
+    #[test]
+    fn block_observer_poll_env_variables_work() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__BLOCK_OBSERVER_POLL_INTERVAL", "250");
+        std::env::set_var("SIGNER_SIGNER__BLOCK_OBSERVER_POLL_JITTER", "50");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.block_observer_poll_interval, Some(250));
+        assert_eq!(config.signer.block_observer_poll_jitter, Some(50));
+    }
+
+    #[test]
+    fn withdrawal_min_confirmations_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__WITHDRAWAL_MIN_CONFIRMATIONS", "6");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.withdrawal_min_confirmations, Some(6));
+    }
+
+    #[test]
+    fn max_sweep_fee_rate_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__MAX_SWEEP_FEE_RATE", "250.5");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.max_sweep_fee_rate, Some(250.5));
+    }
+
+    #[test_case("0"; "zero")]
+    #[test_case("-1.5"; "negative")]
+    #[test_case("NaN"; "not a number")]
+    #[test_case("inf"; "infinite")]
+    fn invalid_max_sweep_fee_rate_returns_correct_error(fee_rate: &str) {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__MAX_SWEEP_FEE_RATE", fee_rate);
+        let settings = Settings::new_from_default_config();
+        assert!(matches!(
+            settings.unwrap_err(),
+            ConfigError::Message(msg) if msg.starts_with("The max sweep fee rate must be a positive number")
+        ));
+    }
+
+    #[test]
+    fn withdrawal_expiry_window_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__WITHDRAWAL_EXPIRY_WINDOW", "144");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.withdrawal_expiry_window, Some(144));
+    }
+
+    #[test_case("0"; "zero")]
+    #[test_case("10000"; "the context window")]
+    fn invalid_withdrawal_expiry_window_returns_correct_error(window: &str) {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__WITHDRAWAL_EXPIRY_WINDOW", window);
+        let settings = Settings::new_from_default_config();
+        assert!(matches!(
+            settings.unwrap_err(),
+            ConfigError::Message(msg) if msg.starts_with("The withdrawal expiry window must be between")
+        ));
+    }
+
+    #[test]
+    fn signer_set_refresh_interval_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__SIGNER_SET_REFRESH_INTERVAL", "120");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.signer_set_refresh_interval, Some(120));
+    }
+
+    #[test]
+    fn max_deposit_script_length_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__MAX_DEPOSIT_SCRIPT_LENGTH", "256");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.max_deposit_script_length, Some(256));
+    }
+
+    #[test]
+    fn min_sweep_value_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__MIN_SWEEP_VALUE", "100000");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.min_sweep_value, Some(100_000));
+    }
+
+    #[test_case("fifo", ProcessingOrder::Fifo; "fifo")]
+    #[test_case("value_descending", ProcessingOrder::ValueDescending; "value descending")]
+    fn deposit_processing_order_env_variable_works(value: &str, expected: ProcessingOrder) {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__DEPOSIT_PROCESSING_ORDER", value);
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.deposit_processing_order, Some(expected));
+    }
+
+    #[test]
+    fn bitcoin_retry_env_variables_work() {
+        clear_env();
+
+        std::env::set_var("SIGNER_BITCOIN__RPC_MAX_ATTEMPTS", "3");
+        std::env::set_var("SIGNER_BITCOIN__RPC_RETRY_BASE_DELAY", "200");
+        std::env::set_var("SIGNER_BITCOIN__RPC_RETRY_JITTER", "50");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.bitcoin.rpc_max_attempts, Some(3));
+        assert_eq!(config.bitcoin.rpc_retry_base_delay, Some(200));
+        assert_eq!(config.bitcoin.rpc_retry_jitter, Some(50));
+    }
+
+    #[test]
+    fn emily_backoff_env_variables_work() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__EMILY_BACKOFF_BASE", "500");
+        std::env::set_var("SIGNER_SIGNER__EMILY_BACKOFF_MAX", "60000");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.emily_backoff_base, Some(500));
+        assert_eq!(config.signer.emily_backoff_max, Some(60_000));
+    }
+
+    #[test]
+    fn submission_deadline_blocks_env_variable_works() {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__SUBMISSION_DEADLINE_BLOCKS", "25");
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.submission_deadline_blocks, Some(25));
+    }
+
+    #[test]
+    fn reclaim_script_allowlist_env_variable_works() {
+        clear_env();
+
+        std::env::set_var(
+            "SIGNER_SIGNER__RECLAIM_SCRIPT_ALLOWLIST",
+            "single_sig,multi_sig",
+        );
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(
+            config.signer.reclaim_script_allowlist,
+            Some(vec![
+                ReclaimScriptTemplate::SingleSig,
+                ReclaimScriptTemplate::MultiSig
+            ])
+        );
+    }
+
+    #[test_case("request_order", OutputOrdering::RequestOrder; "request order")]
+    #[test_case("bip69", OutputOrdering::Bip69; "bip69")]
+    #[test_case("seeded", OutputOrdering::Seeded; "seeded")]
+    fn sweep_output_ordering_env_variable_works(value: &str, expected: OutputOrdering) {
+        clear_env();
+
+        std::env::set_var("SIGNER_SIGNER__SWEEP_OUTPUT_ORDERING", value);
+        let config = Settings::new_from_default_config().unwrap();
+        assert_eq!(config.signer.sweep_output_ordering, Some(expected));
+    }
+
     #[test]
     fn invalid_p2p_uri_scheme_returns_correct_error() {
         clear_env();
@@ -969,8 +1421,8 @@ mod tests {
         assert_eq!(*actual, expected);
     }
 
-    #[test_case::test_case(NetworkKind::Mainnet; "mainnet network, testnet deployer")]
-    #[test_case::test_case(NetworkKind::Testnet; "testnet network, mainnet deployer")]
+    #[test_case(NetworkKind::Mainnet; "mainnet network, testnet deployer")]
+    #[test_case(NetworkKind::Testnet; "testnet network, mainnet deployer")]
     fn network_mismatch_network_of_deployer(network: NetworkKind) {
         clear_env();
 
@@ -994,9 +1446,9 @@ mod tests {
         ));
     }
 
-    #[test_case::test_case(NetworkKind::Mainnet; "mainnet")]
-    #[test_case::test_case(NetworkKind::Testnet; "testnet")]
-    #[test_case::test_case(NetworkKind::Regtest; "regtest")]
+    #[test_case(NetworkKind::Mainnet; "mainnet")]
+    #[test_case(NetworkKind::Testnet; "testnet")]
+    #[test_case(NetworkKind::Regtest; "regtest")]
     fn network_matches_network_of_deployer(network: NetworkKind) {
         clear_env();
 
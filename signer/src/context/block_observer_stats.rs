@@ -0,0 +1,47 @@
+//! Module for the block observer's processing statistics
+
+use std::collections::HashSet;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+/// Counts of the bitcoin blocks processed by the block observer.
+///
+/// Reorgs can make the block observer process more than one block at the
+/// same height. Only the first block processed at each height counts as a
+/// new block, the others count as reprocessed blocks, so that reorgs do
+/// not inflate the number of new blocks.
+#[derive(Debug, Default)]
+pub struct BlockObserverStats {
+    /// The heights of the bitcoin blocks that have been processed.
+    heights: Mutex<HashSet<u64>>,
+    /// The number of bitcoin blocks processed at a new height.
+    blocks_processed: AtomicU64,
+    /// The number of bitcoin blocks processed at a height that had
+    /// already been processed.
+    reprocessed_blocks: AtomicU64,
+}
+
+impl BlockObserverStats {
+    /// Record that a bitcoin block at the given height was processed.
+    pub fn record_processed_block(&self, block_height: u64) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut heights = self.heights.lock().expect("BUG: Failed to acquire lock");
+        if heights.insert(block_height) {
+            self.blocks_processed.fetch_add(1, Ordering::SeqCst);
+        } else {
+            self.reprocessed_blocks.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// The number of bitcoin blocks processed at a new height.
+    pub fn blocks_processed(&self) -> u64 {
+        self.blocks_processed.load(Ordering::SeqCst)
+    }
+
+    /// The number of bitcoin blocks processed at a height that had
+    /// already been processed.
+    pub fn reprocessed_blocks(&self) -> u64 {
+        self.reprocessed_blocks.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,238 @@
+//! Module for tracking deposit processing latencies
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use bitcoin::OutPoint;
+use sbtc::deposits::CreateDepositRequest;
+
+/// The upper bounds of the buckets of the deposit processing latency
+/// histogram. Latencies above the last bound fall into a final bucket
+/// without an upper bound.
+pub const DEPOSIT_LATENCY_BUCKETS: [Duration; 5] = [
+    Duration::from_secs(60),
+    Duration::from_secs(10 * 60),
+    Duration::from_secs(60 * 60),
+    Duration::from_secs(6 * 60 * 60),
+    Duration::from_secs(24 * 60 * 60),
+];
+
+/// How long a deposit that is no longer pending is kept track of, waiting
+/// for it to reach a terminal status, before it is forgotten.
+pub const DEPOSIT_LATENCY_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// When a deposit was discovered, and when it was last seen pending.
+#[derive(Debug, Clone, Copy)]
+struct Discovery {
+    discovered_at: Instant,
+    last_pending_at: Instant,
+}
+
+/// A bucket of the deposit processing latency histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositLatencyBucket {
+    /// The latency that every deposit in the bucket was processed within.
+    /// There is no upper bound for the last bucket.
+    pub max_latency: Option<Duration>,
+    /// The number of deposits in the bucket.
+    pub count: u64,
+}
+
+/// The time it takes each deposit to go from being discovered by the
+/// block observer to reaching a terminal status.
+///
+/// The current time is passed in by the caller so that the latencies can
+/// be tested deterministically.
+#[derive(Debug, Default)]
+pub struct DepositLatencies {
+    /// When each deposit that has not reached a terminal status yet was
+    /// discovered.
+    discovered: Mutex<HashMap<OutPoint, Discovery>>,
+    /// The number of deposits in each bucket of the histogram, with one
+    /// more entry than there are bounds for the unbounded bucket.
+    counts: Mutex<[u64; DEPOSIT_LATENCY_BUCKETS.len() + 1]>,
+}
+
+impl DepositLatencies {
+    /// Record that the deposit with the given outpoint was discovered at
+    /// the given time. Only the first discovery of a deposit is recorded.
+    pub fn record_discovered(&self, outpoint: OutPoint, now: Instant) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.discovered
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .entry(outpoint)
+            .or_insert(Discovery {
+                discovered_at: now,
+                last_pending_at: now,
+            });
+    }
+
+    /// Record that the given requests are still pending at the given
+    /// time, and forget the deposits that have not been pending for more
+    /// than [`DEPOSIT_LATENCY_RETENTION`].
+    ///
+    /// Deposits stop being pending once the signers accept them, so they
+    /// are kept around for a while for their terminal status to be seen.
+    pub fn retain(&self, requests: &[CreateDepositRequest], now: Instant) {
+        let pending: HashSet<OutPoint> = requests.iter().map(|req| req.outpoint).collect();
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut discovered = self.discovered.lock().expect("BUG: Failed to acquire lock");
+        discovered.retain(|outpoint, discovery| {
+            if pending.contains(outpoint) {
+                discovery.last_pending_at = now;
+            }
+            now.saturating_duration_since(discovery.last_pending_at) <= DEPOSIT_LATENCY_RETENTION
+        });
+    }
+
+    /// The number of deposits that have been discovered but have not
+    /// reached a terminal status yet.
+    pub fn len(&self) -> usize {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.discovered
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .len()
+    }
+
+    /// Whether there are no deposits that have been discovered but have
+    /// not reached a terminal status yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record that the deposit with the given outpoint reached a terminal
+    /// status at the given time, returning how long it took to get there.
+    ///
+    /// Deposits that were never discovered are not counted, so `None` is
+    /// returned for them.
+    pub fn record_resolved(&self, outpoint: &OutPoint, now: Instant) -> Option<Duration> {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let discovered_at = self
+            .discovered
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .remove(outpoint)?
+            .discovered_at;
+        let latency = now.saturating_duration_since(discovered_at);
+
+        let bucket = DEPOSIT_LATENCY_BUCKETS
+            .iter()
+            .position(|max_latency| latency <= *max_latency)
+            .unwrap_or(DEPOSIT_LATENCY_BUCKETS.len());
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.counts.lock().expect("BUG: Failed to acquire lock")[bucket] += 1;
+        Some(latency)
+    }
+
+    /// The histogram of the latencies of the deposits that have reached a
+    /// terminal status, ordered by latency.
+    pub fn histogram(&self) -> Vec<DepositLatencyBucket> {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let counts = *self.counts.lock().expect("BUG: Failed to acquire lock");
+        DEPOSIT_LATENCY_BUCKETS
+            .iter()
+            .copied()
+            .map(Some)
+            .chain([None])
+            .zip(counts)
+            .map(|(max_latency, count)| DepositLatencyBucket { max_latency, count })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash as _;
+
+    use super::*;
+
+    #[test]
+    fn deposit_latencies_are_bucketed_by_time_to_terminal_status() {
+        let latencies = DepositLatencies::default();
+        let discovered_at = Instant::now();
+        let resolved = [
+            (Duration::from_secs(30), 1),
+            (Duration::from_secs(5 * 60), 2),
+            (Duration::from_secs(2 * 60 * 60), 3),
+            (Duration::from_secs(2 * 24 * 60 * 60), 4),
+        ];
+
+        for (_, vout) in resolved {
+            let outpoint = OutPoint::new(bitcoin::Txid::all_zeros(), vout);
+            latencies.record_discovered(outpoint, discovered_at);
+            // Discovering a deposit again does not reset its start time.
+            latencies.record_discovered(outpoint, discovered_at + Duration::from_secs(20));
+        }
+
+        for (latency, vout) in resolved {
+            let outpoint = OutPoint::new(bitcoin::Txid::all_zeros(), vout);
+            let recorded = latencies.record_resolved(&outpoint, discovered_at + latency);
+            assert_eq!(recorded, Some(latency));
+        }
+
+        // Deposits that were never discovered, or that were already
+        // resolved, are not counted.
+        let unknown = OutPoint::new(bitcoin::Txid::all_zeros(), 5);
+        assert_eq!(latencies.record_resolved(&unknown, discovered_at), None);
+        let resolved_twice = OutPoint::new(bitcoin::Txid::all_zeros(), 1);
+        assert_eq!(
+            latencies.record_resolved(&resolved_twice, discovered_at),
+            None
+        );
+
+        let counts: Vec<(Option<Duration>, u64)> = latencies
+            .histogram()
+            .into_iter()
+            .map(|bucket| (bucket.max_latency, bucket.count))
+            .collect();
+        let expected = vec![
+            (Some(Duration::from_secs(60)), 1),
+            (Some(Duration::from_secs(10 * 60)), 1),
+            (Some(Duration::from_secs(60 * 60)), 0),
+            (Some(Duration::from_secs(6 * 60 * 60)), 1),
+            (Some(Duration::from_secs(24 * 60 * 60)), 0),
+            (None, 1),
+        ];
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn deposits_that_stop_being_pending_are_forgotten_after_a_while() {
+        let latencies = DepositLatencies::default();
+        let start = Instant::now();
+        let requests: Vec<CreateDepositRequest> = (0..2)
+            .map(|vout| CreateDepositRequest {
+                outpoint: OutPoint::new(bitcoin::Txid::all_zeros(), vout),
+                reclaim_script: bitcoin::ScriptBuf::new(),
+                deposit_script: bitcoin::ScriptBuf::new(),
+            })
+            .collect();
+        for request in &requests {
+            latencies.record_discovered(request.outpoint, start);
+        }
+
+        // The first deposit stays pending while the second one does not,
+        // so only the second one is forgotten, and only once it has not
+        // been pending for long enough.
+        let later = start + DEPOSIT_LATENCY_RETENTION;
+        latencies.retain(&requests[..1], later);
+        assert_eq!(latencies.len(), 2);
+
+        let much_later = later + Duration::from_secs(1);
+        latencies.retain(&requests[..1], much_later);
+        assert_eq!(latencies.len(), 1);
+        let forgotten = latencies.record_resolved(&requests[1].outpoint, much_later);
+        assert_eq!(forgotten, None);
+
+        // The latency of the deposit that was kept is still measured from
+        // when it was discovered.
+        let resolved = latencies.record_resolved(&requests[0].outpoint, much_later);
+        assert_eq!(resolved, Some(much_later - start));
+        assert!(latencies.is_empty());
+    }
+}
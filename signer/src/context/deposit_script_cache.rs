@@ -0,0 +1,151 @@
+//! Module for caching parsed deposit scripts
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
+use bitcoin::Txid;
+use sbtc::deposits::CreateDepositRequest;
+use sbtc::deposits::ParsedDepositScripts;
+use sbtc::deposits::MAX_DEPOSIT_SCRIPT_LENGTH;
+
+use crate::config::SignerConfig;
+
+/// The function used by a [`DepositScriptCache`] to parse the scripts of
+/// a deposit request, given the maximum length of a deposit script.
+pub type DepositScriptParser =
+    fn(&CreateDepositRequest, usize) -> Result<ParsedDepositScripts, sbtc::error::Error>;
+
+/// A cache of the parsed deposit and reclaim scripts of pending deposit
+/// requests.
+///
+/// The scripts of a deposit request do not change while the request is
+/// pending, so they only need to be parsed the first time that the
+/// request is validated. Entries are keyed on the outpoint and scripts of
+/// the request, and only scripts that parsed successfully are cached.
+#[derive(Debug)]
+pub struct DepositScriptCache {
+    /// The function used to parse the scripts of requests that are not in
+    /// the cache.
+    parser: DepositScriptParser,
+    /// The maximum length, in bytes, of the deposit scripts that are
+    /// accepted by the parser.
+    max_deposit_script_length: usize,
+    /// The parsed scripts, keyed on the outpoint, deposit script and
+    /// reclaim script of the request.
+    entries: Mutex<HashMap<(OutPoint, ScriptBuf, ScriptBuf), ParsedDepositScripts>>,
+    /// The outpoints spent by unconfirmed deposit transactions, keyed on
+    /// the txid of the deposit transaction.
+    funding_inputs: Mutex<HashMap<Txid, Vec<OutPoint>>>,
+}
+
+impl Default for DepositScriptCache {
+    fn default() -> Self {
+        Self::new(CreateDepositRequest::parse_scripts_with_max_length)
+    }
+}
+
+impl DepositScriptCache {
+    /// Create a new, empty, cache that parses scripts with the given
+    /// function, accepting deposit scripts of up to
+    /// [`MAX_DEPOSIT_SCRIPT_LENGTH`] bytes.
+    pub fn new(parser: DepositScriptParser) -> Self {
+        Self {
+            parser,
+            max_deposit_script_length: MAX_DEPOSIT_SCRIPT_LENGTH,
+            entries: Mutex::new(HashMap::new()),
+            funding_inputs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a new, empty, cache that accepts deposit scripts of up to
+    /// the maximum length in the given config.
+    pub fn from_config(config: &SignerConfig) -> Self {
+        Self {
+            max_deposit_script_length: config
+                .max_deposit_script_length
+                .unwrap_or(MAX_DEPOSIT_SCRIPT_LENGTH),
+            ..Self::default()
+        }
+    }
+
+    /// Return the parsed scripts of the given request, parsing them only
+    /// if they are not already in the cache.
+    pub fn get_or_parse(
+        &self,
+        request: &CreateDepositRequest,
+    ) -> Result<ParsedDepositScripts, sbtc::error::Error> {
+        let key = (
+            request.outpoint,
+            request.deposit_script.clone(),
+            request.reclaim_script.clone(),
+        );
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut entries = self.entries.lock().expect("BUG: Failed to acquire lock");
+        if let Some(scripts) = entries.get(&key) {
+            return Ok(scripts.clone());
+        }
+
+        let scripts = (self.parser)(request, self.max_deposit_script_length)?;
+        entries.insert(key, scripts.clone());
+        Ok(scripts)
+    }
+
+    /// Remember the outpoints spent by the unconfirmed deposit transaction
+    /// with the given txid.
+    pub fn record_funding_inputs(&self, txid: Txid, inputs: Vec<OutPoint>) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.funding_inputs
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .insert(txid, inputs);
+    }
+
+    /// Return the outpoints spent by the deposit transaction with the given
+    /// txid, if they were recorded while it was unconfirmed.
+    pub fn funding_inputs(&self, txid: &Txid) -> Option<Vec<OutPoint>> {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.funding_inputs
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .get(txid)
+            .cloned()
+    }
+
+    /// Remove the scripts and funding inputs of all requests that are not
+    /// in the given requests from the cache.
+    pub fn retain(&self, requests: &[CreateDepositRequest]) {
+        let keep: HashSet<_> = requests
+            .iter()
+            .map(|req| (req.outpoint, &req.deposit_script, &req.reclaim_script))
+            .collect();
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut entries = self.entries.lock().expect("BUG: Failed to acquire lock");
+        entries.retain(|(outpoint, deposit, reclaim), _| {
+            keep.contains(&(*outpoint, deposit, reclaim))
+        });
+
+        let txids: HashSet<Txid> = requests.iter().map(|req| req.outpoint.txid).collect();
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.funding_inputs
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .retain(|txid, _| txids.contains(txid));
+    }
+
+    /// The number of requests whose scripts are in the cache.
+    pub fn len(&self) -> usize {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.entries
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,98 @@
+//! Module for backing off from Emily after failed calls
+
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::config::SignerConfig;
+
+/// The default amount of time the block observer waits before calling
+/// Emily again after a failed call.
+pub const DEFAULT_EMILY_BACKOFF_BASE: Duration = Duration::from_secs(1);
+
+/// The default maximum amount of time the block observer waits before
+/// calling Emily again after consecutive failed calls.
+pub const DEFAULT_EMILY_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// An exponential backoff policy for calls that keep failing.
+///
+/// The delay after the first failure is `base`, and it doubles with each
+/// consecutive failure until it reaches `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// The delay after the first failure.
+    pub base: Duration,
+    /// The maximum delay.
+    pub max: Duration,
+}
+
+impl BackoffPolicy {
+    /// Create the backoff policy for calls to Emily from the signer
+    /// configuration.
+    pub fn emily_from_config(config: &SignerConfig) -> Self {
+        Self {
+            base: config
+                .emily_backoff_base
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_EMILY_BACKOFF_BASE),
+            max: config
+                .emily_backoff_max
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_EMILY_BACKOFF_MAX),
+        }
+    }
+
+    /// Return the delay after the given number of consecutive failures.
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.base.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// Tracks the consecutive failures of the block observer's calls to
+/// Emily, so that the observer backs off from Emily while it is down
+/// instead of calling it on every bitcoin block.
+#[derive(Debug, Default)]
+pub struct EmilyBackoff {
+    /// The number of consecutive failed calls, and the earliest time that
+    /// Emily may be called again.
+    state: Mutex<(u32, Option<Instant>)>,
+}
+
+impl EmilyBackoff {
+    /// Whether Emily may be called at the given time.
+    pub fn should_attempt(&self, now: Instant) -> bool {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let state = self.state.lock().expect("BUG: Failed to acquire lock");
+        state.1.map_or(true, |retry_at| now >= retry_at)
+    }
+
+    /// Record a failed call made at the given time, returning how long we
+    /// should wait before calling Emily again.
+    pub fn record_failure(&self, policy: &BackoffPolicy, now: Instant) -> Duration {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut state = self.state.lock().expect("BUG: Failed to acquire lock");
+        state.0 = state.0.saturating_add(1);
+        let delay = policy.delay(state.0);
+        state.1 = Some(now + delay);
+        delay
+    }
+
+    /// Record a successful call, ending any backoff.
+    pub fn record_success(&self) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        *self.state.lock().expect("BUG: Failed to acquire lock") = (0, None);
+    }
+
+    /// The number of consecutive failed calls to Emily.
+    pub fn consecutive_failures(&self) -> u32 {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.state.lock().expect("BUG: Failed to acquire lock").0
+    }
+
+    /// Whether the last call to Emily succeeded, or no calls have been
+    /// made yet.
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures() == 0
+    }
+}
@@ -0,0 +1,61 @@
+//! Module for tracking broadcast sweep transactions
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::storage::model;
+
+/// The sweep transactions that the signers have broadcast, along with
+/// the bitcoin chain tip at the time that each of them was broadcast.
+#[derive(Debug, Default)]
+pub struct KnownSweeps {
+    /// Maps the txid of each known sweep transaction to the block hash of
+    /// the bitcoin chain tip when it was broadcast.
+    sweeps: Mutex<HashMap<bitcoin::Txid, model::BitcoinBlockHash>>,
+}
+
+impl KnownSweeps {
+    /// Record a sweep transaction broadcast when the bitcoin chain tip
+    /// was the block with the given hash.
+    pub fn insert(&self, txid: bitcoin::Txid, created_at_block_hash: model::BitcoinBlockHash) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.sweeps
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .insert(txid, created_at_block_hash);
+    }
+
+    /// Get the txids of the known sweep transactions along with the chain
+    /// tip when each of them was broadcast.
+    pub fn get_all(&self) -> Vec<(bitcoin::Txid, model::BitcoinBlockHash)> {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.sweeps
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .iter()
+            .map(|(txid, block_hash)| (*txid, *block_hash))
+            .collect()
+    }
+
+    /// Whether there are no known sweep transactions.
+    pub fn is_empty(&self) -> bool {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.sweeps
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .is_empty()
+    }
+
+    /// Stop tracking the given sweep transactions. Txids that are not
+    /// known are ignored.
+    pub fn evict<I>(&self, txids: I)
+    where
+        I: IntoIterator<Item = bitcoin::Txid>,
+    {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut sweeps = self.sweeps.lock().expect("BUG: Failed to acquire lock");
+        for txid in txids {
+            sweeps.remove(&txid);
+        }
+    }
+}
@@ -37,6 +37,8 @@ pub enum SignerEvent {
     P2P(P2PEvent),
     /// Signals that a block observer event has occurred.
     BitcoinBlockObserved,
+    /// Signals that the block observer has observed a bitcoin reorg.
+    BitcoinReorg(ReorgEvent),
     /// A Request decider event has occurred.
     RequestDecider(RequestDeciderEvent),
     /// Transaction signer events
@@ -59,6 +61,20 @@ pub enum P2PEvent {
     PeerConnected(libp2p::PeerId),
 }
 
+/// A bitcoin reorg that was detected by the block observer. This is
+/// emitted when the block observer processes a block that does not extend
+/// the chain tip that it last processed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReorgEvent {
+    /// The chain tip before the reorg.
+    pub old_tip: crate::storage::model::BitcoinBlockHash,
+    /// The chain tip after the reorg.
+    pub new_tip: crate::storage::model::BitcoinBlockHash,
+    /// The height of the last block that is common to both the old and
+    /// the new chain.
+    pub fork_height: u64,
+}
+
 /// Events that can be triggered from the request decider.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RequestDeciderEvent {
@@ -130,6 +146,12 @@ impl From<SignerEvent> for SignerSignal {
     }
 }
 
+impl From<ReorgEvent> for SignerSignal {
+    fn from(event: ReorgEvent) -> Self {
+        SignerSignal::Event(SignerEvent::BitcoinReorg(event))
+    }
+}
+
 impl From<P2PEvent> for SignerSignal {
     fn from(event: P2PEvent) -> Self {
         SignerSignal::Event(SignerEvent::P2P(event))
@@ -1,10 +1,19 @@
 //! Context module for the signer binary.
 
+mod block_observer_stats;
+mod deposit_latencies;
+mod deposit_script_cache;
+mod emily_backoff;
+mod known_sweeps;
 mod messaging;
+mod pending_submissions;
 mod signer_context;
 mod signer_state;
 mod termination;
 
+use std::future::Future;
+use std::time::Instant;
+
 use tokio::sync::broadcast::error::RecvError;
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -12,12 +21,19 @@ use crate::bitcoin::BitcoinInteract;
 use crate::config::Settings;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
+use crate::keys::PublicKey;
 use crate::stacks::api::StacksInteract;
 use crate::storage::DbRead;
 use crate::storage::DbWrite;
 use crate::SIGNER_CHANNEL_CAPACITY;
 
+pub use block_observer_stats::*;
+pub use deposit_latencies::*;
+pub use deposit_script_cache::*;
+pub use emily_backoff::*;
+pub use known_sweeps::*;
 pub use messaging::*;
+pub use pending_submissions::*;
 pub use signer_context::SignerContext;
 pub use signer_state::*;
 pub use termination::*;
@@ -48,6 +64,21 @@ pub trait Context: Clone + Sync + Send {
     /// Get a handle to an Emily client.
     fn get_emily_client(&self) -> impl EmilyInteract + Clone + 'static;
 
+    /// Get the current signer set in the `sbtc-registry` contract. The
+    /// set is only read from the stacks node once the configured refresh
+    /// interval has elapsed since it was last read, so this should be
+    /// used instead of asking the stacks client directly.
+    fn get_current_signer_set(&self) -> impl Future<Output = Result<Vec<PublicKey>, Error>> + Send {
+        async move {
+            let stacks_client = self.get_stacks_client();
+            let deployer = &self.config().signer.deployer;
+            self.state()
+                .signer_set_cache()
+                .get(&stacks_client, deployer, Instant::now())
+                .await
+        }
+    }
+
     /// Create a new signal stream containing signer messages from:
     /// 1. The signer network, as defined by the given network object
     ///    implementing [`MessageTransfer`].
@@ -0,0 +1,64 @@
+//! Module for tracking unconfirmed stacks transaction submissions
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::storage::model;
+
+/// The stacks transactions that the signers have submitted and that have
+/// not been confirmed yet, along with the height of the stacks chain tip
+/// at the time that each of them was submitted.
+#[derive(Debug, Default)]
+pub struct PendingSubmissions {
+    /// Maps the txid of each pending stacks transaction to the height of
+    /// the stacks chain tip when it was submitted.
+    submissions: Mutex<HashMap<model::StacksTxId, u64>>,
+}
+
+impl PendingSubmissions {
+    /// Record a stacks transaction submitted when the stacks chain tip
+    /// was at the given height.
+    pub fn insert(&self, txid: model::StacksTxId, submitted_at_height: u64) {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.submissions
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .insert(txid, submitted_at_height);
+    }
+
+    /// Stop tracking the given stacks transactions, since they have been
+    /// confirmed.
+    pub fn confirm<I>(&self, txids: I)
+    where
+        I: IntoIterator<Item = model::StacksTxId>,
+    {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        let mut submissions = self
+            .submissions
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+        for txid in txids {
+            submissions.remove(&txid);
+        }
+    }
+
+    /// Return the txids of the pending stacks transactions that were
+    /// submitted at least `deadline_blocks` stacks blocks below the given
+    /// stacks chain tip height, in no particular order.
+    pub fn past_deadline(
+        &self,
+        stacks_tip_height: u64,
+        deadline_blocks: u64,
+    ) -> Vec<model::StacksTxId> {
+        // We should never fail to acquire a lock from the Mutex so that it panics.
+        self.submissions
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .iter()
+            .filter(|(_, submitted_at)| {
+                stacks_tip_height.saturating_sub(**submitted_at) >= deadline_blocks
+            })
+            .map(|(txid, _)| *txid)
+            .collect()
+    }
+}
@@ -1,11 +1,10 @@
 use std::sync::Arc;
 
 use tokio::sync::broadcast::Sender;
-use url::Url;
 
 use crate::{
     bitcoin::BitcoinInteract,
-    config::{EmilyClientConfig, Settings},
+    config::{BitcoinConfig, EmilyClientConfig, Settings},
     emily_client::EmilyInteract,
     error::Error,
     stacks::api::StacksInteract,
@@ -48,17 +47,17 @@ pub struct SignerContext<S, BC, ST, EM> {
 impl<S, BC, ST, EM> SignerContext<S, BC, ST, EM>
 where
     S: DbRead + DbWrite + Clone + Sync + Send + 'static,
-    BC: for<'a> TryFrom<&'a [Url]> + BitcoinInteract + Clone + 'static,
+    BC: for<'a> TryFrom<&'a BitcoinConfig> + BitcoinInteract + Clone + 'static,
     ST: for<'a> TryFrom<&'a Settings> + StacksInteract + Clone + Sync + Send + 'static,
     EM: for<'a> TryFrom<&'a EmilyClientConfig> + EmilyInteract + Clone + Sync + Send + 'static,
-    Error: for<'a> From<<BC as TryFrom<&'a [Url]>>::Error>,
+    Error: for<'a> From<<BC as TryFrom<&'a BitcoinConfig>>::Error>,
     Error: for<'a> From<<ST as TryFrom<&'a Settings>>::Error>,
     Error: for<'a> From<<EM as TryFrom<&'a EmilyClientConfig>>::Error>,
 {
     /// Initializes a new [`SignerContext`], automatically creating clients
     /// based on the provided types.
     pub fn init(config: Settings, db: S) -> Result<Self, Error> {
-        let bc = BC::try_from(&config.bitcoin.rpc_endpoints)?;
+        let bc = BC::try_from(&config.bitcoin)?;
         let st = ST::try_from(&config)?;
         let em = EM::try_from(&config.emily)?;
 
@@ -88,8 +87,8 @@ where
         let (term_tx, _) = tokio::sync::watch::channel(false);
 
         Self {
+            state: Arc::new(SignerState::new(&config)),
             config,
-            state: Default::default(),
             signal_tx,
             term_tx,
             storage: db,
@@ -236,4 +235,30 @@ mod tests {
         // Ensure that the signal was received.
         assert_eq!(recv_count.load(std::sync::atomic::Ordering::Relaxed), 1);
     }
+
+    /// Test that reading the signer set through the context goes through
+    /// the cache in the signer state, so the stacks node is only asked
+    /// for it once within the refresh interval.
+    #[tokio::test]
+    async fn current_signer_set_is_read_through_the_cache() {
+        let context = TestContext::builder()
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .modify_settings(|settings| settings.signer.signer_set_refresh_interval = Some(3600))
+            .build();
+
+        context
+            .with_stacks_client(|client| {
+                client
+                    .expect_get_current_signer_set()
+                    .times(1)
+                    .returning(|_| Box::pin(std::future::ready(Ok(Vec::new()))));
+            })
+            .await;
+
+        for _ in 0..3 {
+            let signer_set = context.get_current_signer_set().await.unwrap();
+            assert!(signer_set.is_empty());
+        }
+    }
 }
@@ -1,12 +1,23 @@
 //! Module for signer state
 
 use std::sync::RwLock;
+use std::time::Instant;
 
 use bitcoin::Amount;
+use blockstack_lib::types::chainstate::StacksBlockId;
 use hashbrown::HashSet;
 use libp2p::PeerId;
 
+use crate::config::Settings;
 use crate::keys::PublicKey;
+use crate::stacks::api::SignerSetCache;
+
+use super::BlockObserverStats;
+use super::DepositLatencies;
+use super::DepositScriptCache;
+use super::EmilyBackoff;
+use super::KnownSweeps;
+use super::PendingSubmissions;
 
 /// A struct for holding internal signer state. This struct is served by
 /// the [`SignerContext`] and can be used to cache global state instead of
@@ -15,9 +26,29 @@ use crate::keys::PublicKey;
 pub struct SignerState {
     current_signer_set: SignerSet,
     current_limits: RwLock<SbtcLimits>,
+    block_observer_last_error: RwLock<Option<(String, Instant)>>,
+    block_observer_stats: BlockObserverStats,
+    deposit_scripts: DepositScriptCache,
+    deposit_latencies: DepositLatencies,
+    known_sweeps: KnownSweeps,
+    pending_submissions: PendingSubmissions,
+    emily_backoff: EmilyBackoff,
+    signer_set_cache: SignerSetCache,
+    stacks_checkpoint: RwLock<Option<StacksBlockId>>,
+    deposit_min_confirmations: RwLock<Option<u64>>,
 }
 
 impl SignerState {
+    /// Create a new signer state, using the given config for the parts of
+    /// the state that are configurable.
+    pub fn new(config: &Settings) -> Self {
+        Self {
+            deposit_scripts: DepositScriptCache::from_config(&config.signer),
+            signer_set_cache: SignerSetCache::from_config(&config.signer),
+            ..Default::default()
+        }
+    }
+
     /// Get the current signer set.
     pub fn current_signer_set(&self) -> &SignerSet {
         &self.current_signer_set
@@ -41,6 +72,110 @@ impl SignerState {
             .expect("BUG: Failed to acquire write lock");
         *limits = new_limits;
     }
+
+    /// Get the last error encountered by the block observer while
+    /// processing bitcoin blocks, along with when it happened. This is
+    /// `None` if the last bitcoin block was processed successfully.
+    pub fn block_observer_last_error(&self) -> Option<(String, Instant)> {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        self.block_observer_last_error
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+            .clone()
+    }
+
+    /// Set the last error encountered by the block observer, or clear it
+    /// with `None`.
+    pub fn set_block_observer_last_error(&self, last_error: Option<(String, Instant)>) {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        let mut current = self
+            .block_observer_last_error
+            .write()
+            .expect("BUG: Failed to acquire write lock");
+        *current = last_error;
+    }
+
+    /// Get the counts of the bitcoin blocks processed by the block
+    /// observer.
+    pub fn block_observer_stats(&self) -> &BlockObserverStats {
+        &self.block_observer_stats
+    }
+
+    /// Get the cache of parsed deposit and reclaim scripts of pending
+    /// deposit requests.
+    pub fn deposit_scripts(&self) -> &DepositScriptCache {
+        &self.deposit_scripts
+    }
+
+    /// Get the sweep transactions that the signers have broadcast.
+    pub fn known_sweeps(&self) -> &KnownSweeps {
+        &self.known_sweeps
+    }
+
+    /// Get the time it took deposits to go from being discovered to
+    /// reaching a terminal status.
+    pub fn deposit_latencies(&self) -> &DepositLatencies {
+        &self.deposit_latencies
+    }
+
+    /// Get the stacks transactions that the signers have submitted and
+    /// that have not been confirmed yet.
+    pub fn pending_submissions(&self) -> &PendingSubmissions {
+        &self.pending_submissions
+    }
+
+    /// Get the backoff state of the block observer's calls to Emily.
+    pub fn emily_backoff(&self) -> &EmilyBackoff {
+        &self.emily_backoff
+    }
+
+    /// Get the cached copy of the signer set in the `sbtc-registry`
+    /// contract.
+    pub fn signer_set_cache(&self) -> &SignerSetCache {
+        &self.signer_set_cache
+    }
+
+    /// Get the ID of the last stacks block that the block observer has
+    /// processed, if any.
+    pub fn stacks_checkpoint(&self) -> Option<StacksBlockId> {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        *self
+            .stacks_checkpoint
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+    }
+
+    /// Set the ID of the last stacks block that the block observer has
+    /// processed, or clear it with `None`.
+    pub fn set_stacks_checkpoint(&self, checkpoint: Option<StacksBlockId>) {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        let mut current = self
+            .stacks_checkpoint
+            .write()
+            .expect("BUG: Failed to acquire write lock");
+        *current = checkpoint;
+    }
+
+    /// Get the minimum number of bitcoin confirmations a deposit must
+    /// have before the block observer loads it, as last read from Emily.
+    pub fn deposit_min_confirmations(&self) -> Option<u64> {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        *self
+            .deposit_min_confirmations
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+    }
+
+    /// Set the minimum number of bitcoin confirmations a deposit must
+    /// have, or clear it with `None`.
+    pub fn set_deposit_min_confirmations(&self, min_confirmations: Option<u64>) {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        let mut current = self
+            .deposit_min_confirmations
+            .write()
+            .expect("BUG: Failed to acquire write lock");
+        *current = min_confirmations;
+    }
 }
 
 /// Represents the current sBTC limits.
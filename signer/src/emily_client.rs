@@ -15,7 +15,11 @@ use emily_client::apis::withdrawal_api;
 use emily_client::apis::Error as EmilyError;
 use emily_client::apis::ResponseContent;
 use emily_client::models::Chainstate;
+use emily_client::models::CreateDepositRequestBody;
+use emily_client::models::CreateDepositsRequestBody;
+use emily_client::models::CreateDepositsResponse;
 use emily_client::models::CreateWithdrawalRequestBody;
+use emily_client::models::Deposit;
 use emily_client::models::DepositUpdate;
 use emily_client::models::Status;
 use emily_client::models::UpdateDepositsRequestBody;
@@ -24,6 +28,8 @@ use emily_client::models::UpdateWithdrawalsRequestBody;
 use emily_client::models::UpdateWithdrawalsResponse;
 use emily_client::models::Withdrawal;
 use emily_client::models::WithdrawalUpdate;
+use futures::StreamExt as _;
+use futures::TryStreamExt as _;
 use sbtc::deposits::CreateDepositRequest;
 use url::Url;
 
@@ -36,6 +42,10 @@ use crate::storage::model::BitcoinTxId;
 use crate::storage::model::StacksBlock;
 use crate::util::ApiFallbackClient;
 
+/// The maximum number of withdrawals that are fetched from Emily at the
+/// same time, when fetching each of the pending withdrawals.
+const MAX_CONCURRENT_WITHDRAWAL_FETCHES: usize = 10;
+
 /// Emily client error variants.
 #[derive(Debug, thiserror::Error)]
 pub enum EmilyClientError {
@@ -47,6 +57,10 @@ pub enum EmilyClientError {
     #[error("invalid URL: host is required: {0}")]
     InvalidUrlHostRequired(String),
 
+    /// An error occurred while building the HTTP client
+    #[error("error building the HTTP client: {0}")]
+    BuildHttpClient(#[source] emily_reqwest::Error),
+
     /// An error occurred while getting a deposit request
     #[error("error getting a deposit: {0}")]
     GetDeposit(EmilyError<deposit_api::GetDepositError>),
@@ -55,10 +69,22 @@ pub enum EmilyClientError {
     #[error("error getting deposits: {0}")]
     GetDeposits(EmilyError<deposit_api::GetDepositsError>),
 
+    /// An error occurred while getting a withdrawal request
+    #[error("error getting a withdrawal: {0}")]
+    GetWithdrawal(EmilyError<withdrawal_api::GetWithdrawalError>),
+
+    /// An error occurred while getting withdrawals
+    #[error("error getting withdrawals: {0}")]
+    GetWithdrawals(EmilyError<withdrawal_api::GetWithdrawalsError>),
+
     /// An error occurred while updating deposits
     #[error("error updating deposits: {0}")]
     UpdateDeposits(EmilyError<deposit_api::UpdateDepositsError>),
 
+    /// An error occurred while creating deposits
+    #[error("error creating deposits: {0}")]
+    CreateDeposits(EmilyError<deposit_api::CreateDepositsError>),
+
     /// An error occurred while creating withdrawals
     #[error("error creating withdrawals: {0}")]
     CreateWithdrawal(EmilyError<withdrawal_api::CreateWithdrawalError>),
@@ -74,6 +100,51 @@ pub enum EmilyClientError {
     /// An error occurred while getting limits
     #[error("error getting limits: {0}")]
     GetLimits(EmilyError<limits_api::GetLimitsError>),
+
+    /// An error occurred while getting the deposit minimum-confirmation
+    /// override
+    #[error("error getting the deposit minimum confirmations: {0}")]
+    GetMinConfirmations(EmilyError<chainstate_api::GetMinConfirmationsError>),
+}
+
+impl EmilyClientError {
+    /// Whether the call that returned this error may succeed if it is made
+    /// again, because the Emily API could not be reached or responded with
+    /// a server error.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            EmilyClientError::InvalidUrlScheme(_)
+            | EmilyClientError::InvalidUrlHostRequired(_)
+            | EmilyClientError::BuildHttpClient(_) => false,
+            EmilyClientError::GetDeposit(error) => is_transient_api_error(error),
+            EmilyClientError::GetDeposits(error) => is_transient_api_error(error),
+            EmilyClientError::GetWithdrawal(error) => is_transient_api_error(error),
+            EmilyClientError::GetWithdrawals(error) => is_transient_api_error(error),
+            EmilyClientError::UpdateDeposits(error) => is_transient_api_error(error),
+            EmilyClientError::CreateDeposits(error) => is_transient_api_error(error),
+            EmilyClientError::CreateWithdrawal(error) => is_transient_api_error(error),
+            EmilyClientError::UpdateWithdrawals(error) => is_transient_api_error(error),
+            EmilyClientError::AddChainstateEntry(error) => is_transient_api_error(error),
+            EmilyClientError::GetLimits(error) => is_transient_api_error(error),
+            EmilyClientError::GetMinConfirmations(error) => is_transient_api_error(error),
+        }
+    }
+}
+
+/// Whether the given Emily API error is a transport error or a server
+/// error response.
+fn is_transient_api_error<T>(error: &EmilyError<T>) -> bool {
+    match error {
+        EmilyError::Reqwest(error) => {
+            error.is_connect()
+                || error.is_timeout()
+                || error
+                    .status()
+                    .is_some_and(|status| status.is_server_error())
+        }
+        EmilyError::ResponseError(response) => response.status.is_server_error(),
+        EmilyError::Serde(_) | EmilyError::Io(_) => false,
+    }
 }
 
 /// Trait describing the interactions with Emily API.
@@ -91,6 +162,11 @@ pub trait EmilyInteract: Sync + Send {
         &self,
     ) -> impl std::future::Future<Output = Result<Vec<CreateDepositRequest>, Error>> + Send;
 
+    /// Get pending withdrawals from Emily.
+    fn get_withdrawals(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Vec<CreateWithdrawalRequestBody>, Error>> + Send;
+
     /// Update accepted deposits after their sweep bitcoin transaction has been
     /// confirmed (but before being finalized -- the stacks transaction minting
     /// sBTC has not been confirmed yet).
@@ -106,6 +182,12 @@ pub trait EmilyInteract: Sync + Send {
         update_deposits: Vec<DepositUpdate>,
     ) -> impl std::future::Future<Output = Result<UpdateDepositsResponse, Error>> + Send;
 
+    /// Create deposits in Emily.
+    fn create_deposits(
+        &self,
+        create_deposits: Vec<CreateDepositRequestBody>,
+    ) -> impl std::future::Future<Output = Result<CreateDepositsResponse, Error>> + Send;
+
     /// Create withdrawals in Emily.
     fn create_withdrawals(
         &self,
@@ -126,6 +208,43 @@ pub trait EmilyInteract: Sync + Send {
 
     /// Gets the current sBTC-cap limits from Emily.
     fn get_limits(&self) -> impl std::future::Future<Output = Result<SbtcLimits, Error>> + Send;
+
+    /// Gets the minimum number of bitcoin confirmations a deposit must
+    /// have before the signers consider it, if one has been set in Emily.
+    fn get_min_confirmations(
+        &self,
+    ) -> impl std::future::Future<Output = Result<Option<u64>, Error>> + Send;
+}
+
+/// Convert a deposit returned by Emily into the deposit request that the
+/// signer validates.
+///
+/// The generated Emily client models and the sbtc types are both foreign
+/// to this crate, so this cannot be a `TryFrom` implementation.
+fn create_deposit_request(deposit: &Deposit) -> Result<CreateDepositRequest, Error> {
+    Ok(CreateDepositRequest {
+        outpoint: OutPoint {
+            txid: Txid::from_str(&deposit.bitcoin_txid).map_err(Error::DecodeHexTxid)?,
+            vout: deposit.bitcoin_tx_output_index,
+        },
+        reclaim_script: ScriptBuf::from_hex(&deposit.reclaim_script)
+            .map_err(Error::DecodeHexScript)?,
+        deposit_script: ScriptBuf::from_hex(&deposit.deposit_script)
+            .map_err(Error::DecodeHexScript)?,
+    })
+}
+
+/// Convert a withdrawal returned by Emily into the request that created
+/// it.
+fn create_withdrawal_request(withdrawal: Withdrawal) -> CreateWithdrawalRequestBody {
+    CreateWithdrawalRequestBody {
+        amount: withdrawal.amount,
+        parameters: withdrawal.parameters,
+        recipient: withdrawal.recipient,
+        request_id: withdrawal.request_id,
+        stacks_block_hash: withdrawal.stacks_block_hash,
+        stacks_block_height: withdrawal.stacks_block_height,
+    }
 }
 
 /// Emily API client.
@@ -173,6 +292,13 @@ impl TryFrom<&Url> for EmilyClient {
         let _ = url.set_username("");
 
         let mut config = EmilyApiConfig::new();
+        // Emily may compress its responses, and the generated client reads
+        // the response bodies as text, so they need to be decompressed
+        // transparently.
+        config.client = emily_reqwest::Client::builder()
+            .gzip(true)
+            .build()
+            .map_err(EmilyClientError::BuildHttpClient)?;
         // Url::parse defaults `path` to `/` even if the parsed url was without the trailing `/`
         // causing the api calls to have two leading slashes in the path (getting a 404)
         config.base_path = url.to_string().trim_end_matches("/").to_string();
@@ -203,41 +329,55 @@ impl EmilyInteract for EmilyClient {
             error => error.map_err(EmilyClientError::GetDeposit)?,
         };
 
-        Ok(Some(CreateDepositRequest {
-            outpoint: OutPoint {
-                txid: Txid::from_str(&deposit.bitcoin_txid).map_err(Error::DecodeHexTxid)?,
-                vout: deposit.bitcoin_tx_output_index,
-            },
-            reclaim_script: ScriptBuf::from_hex(&deposit.reclaim_script)
-                .map_err(Error::DecodeHexScript)?,
-            deposit_script: ScriptBuf::from_hex(&deposit.deposit_script)
-                .map_err(Error::DecodeHexScript)?,
-        }))
+        create_deposit_request(&deposit).map(Some)
     }
     async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
         // TODO: hanlde pagination -- if the queried data is over 1MB DynamoDB will
         // paginate the results even if we pass `None` as page limit.
-        let resp = deposit_api::get_deposits(&self.config, Status::Pending, None, None)
+        let resp = deposit_api::get_deposits(&self.config, Status::Pending, None, None, None, None)
             .await
             .map_err(EmilyClientError::GetDeposits)
             .map_err(Error::EmilyApi)?;
 
-        resp.deposits
-            .iter()
-            .map(|deposit| {
-                Ok(CreateDepositRequest {
-                    outpoint: OutPoint {
-                        txid: Txid::from_str(&deposit.bitcoin_txid)
-                            .map_err(Error::DecodeHexTxid)?,
-                        vout: deposit.bitcoin_tx_output_index,
-                    },
-                    reclaim_script: ScriptBuf::from_hex(&deposit.reclaim_script)
-                        .map_err(Error::DecodeHexScript)?,
-                    deposit_script: ScriptBuf::from_hex(&deposit.deposit_script)
-                        .map_err(Error::DecodeHexScript)?,
-                })
-            })
-            .collect()
+        resp.deposits.iter().map(create_deposit_request).collect()
+    }
+
+    async fn get_withdrawals(&self) -> Result<Vec<CreateWithdrawalRequestBody>, Error> {
+        let mut infos = Vec::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let resp = withdrawal_api::get_withdrawals(
+                &self.config,
+                Status::Pending,
+                next_token.as_deref(),
+                None,
+                None,
+            )
+            .await
+            .map_err(EmilyClientError::GetWithdrawals)
+            .map_err(Error::EmilyApi)?;
+
+            infos.extend(resp.withdrawals);
+            next_token = resp.next_token.flatten();
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        // The listed withdrawals do not include their parameters, so each
+        // withdrawal is fetched on its own, a few at a time.
+        let withdrawals: Vec<Withdrawal> = futures::stream::iter(infos)
+            .map(|info| withdrawal_api::get_withdrawal(&self.config, info.request_id))
+            .buffer_unordered(MAX_CONCURRENT_WITHDRAWAL_FETCHES)
+            .try_collect()
+            .await
+            .map_err(EmilyClientError::GetWithdrawal)
+            .map_err(Error::EmilyApi)?;
+
+        Ok(withdrawals
+            .into_iter()
+            .map(create_withdrawal_request)
+            .collect())
     }
 
     async fn update_deposits(
@@ -245,13 +385,45 @@ impl EmilyInteract for EmilyClient {
         update_deposits: Vec<DepositUpdate>,
     ) -> Result<UpdateDepositsResponse, Error> {
         if update_deposits.is_empty() {
-            return Ok(UpdateDepositsResponse { deposits: vec![] });
+            return Ok(UpdateDepositsResponse { deposits: vec![], errors: None });
         }
 
         let update_request = UpdateDepositsRequestBody { deposits: update_deposits };
-        deposit_api::update_deposits(&self.config, update_request)
+        let response = deposit_api::update_deposits(&self.config, update_request)
             .await
             .map_err(EmilyClientError::UpdateDeposits)
+            .map_err(Error::EmilyApi)?;
+
+        // Emily applies the updates that it can and reports the rest one
+        // by one, so a successful response may still carry rejections.
+        for error in response.errors.iter().flatten() {
+            tracing::warn!(
+                bitcoin_txid = %error.bitcoin_txid,
+                bitcoin_tx_output_index = error.bitcoin_tx_output_index,
+                status_code = error.status_code,
+                message = %error.message,
+                "Emily rejected a deposit update"
+            );
+        }
+
+        Ok(response)
+    }
+
+    async fn create_deposits(
+        &self,
+        create_deposits: Vec<CreateDepositRequestBody>,
+    ) -> Result<CreateDepositsResponse, Error> {
+        if create_deposits.is_empty() {
+            return Ok(CreateDepositsResponse {
+                deposits: vec![],
+                errors: vec![],
+            });
+        }
+
+        let create_request = CreateDepositsRequestBody { deposits: create_deposits };
+        deposit_api::create_deposits(&self.config, create_request)
+            .await
+            .map_err(EmilyClientError::CreateDeposits)
             .map_err(Error::EmilyApi)
     }
 
@@ -270,6 +442,7 @@ impl EmilyInteract for EmilyClient {
                 bitcoin_tx_output_index: deposit.outpoint.vout,
                 bitcoin_txid: deposit.outpoint.txid.to_string(),
                 status: Status::Accepted,
+                failure_reason: None,
                 fulfillment: None,
                 status_message: "".to_string(),
                 last_update_block_hash: stacks_chain_tip.block_hash.to_string(),
@@ -350,6 +523,15 @@ impl EmilyInteract for EmilyClient {
             None,
         ))
     }
+
+    async fn get_min_confirmations(&self) -> Result<Option<u64>, Error> {
+        let response = chainstate_api::get_min_confirmations(&self.config)
+            .await
+            .map_err(EmilyClientError::GetMinConfirmations)
+            .map_err(Error::EmilyApi)?;
+
+        Ok(response.min_confirmations.flatten())
+    }
 }
 
 impl EmilyInteract for ApiFallbackClient<EmilyClient> {
@@ -368,6 +550,10 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
         self.exec(|client, _| client.get_deposits())
     }
 
+    async fn get_withdrawals(&self) -> Result<Vec<CreateWithdrawalRequestBody>, Error> {
+        self.exec(|client, _| client.get_withdrawals()).await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<DepositUpdate>,
@@ -376,6 +562,14 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
             .await
     }
 
+    async fn create_deposits(
+        &self,
+        create_deposits: Vec<CreateDepositRequestBody>,
+    ) -> Result<CreateDepositsResponse, Error> {
+        self.exec(|client, _| client.create_deposits(create_deposits.clone()))
+            .await
+    }
+
     async fn accept_deposits<'a>(
         &'a self,
         transaction: &'a UnsignedTransaction<'a>,
@@ -413,6 +607,10 @@ impl EmilyInteract for ApiFallbackClient<EmilyClient> {
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
         self.exec(|client, _| client.get_limits()).await
     }
+
+    async fn get_min_confirmations(&self) -> Result<Option<u64>, Error> {
+        self.exec(|client, _| client.get_min_confirmations()).await
+    }
 }
 
 impl TryFrom<&EmilyClientConfig> for ApiFallbackClient<EmilyClient> {
@@ -454,4 +652,85 @@ mod tests {
         assert_eq!(client.config.base_path, "http://localhost:8080");
         assert!(client.config.api_key.is_none());
     }
+
+    /// The gzip encoding of the following JSON:
+    /// `{"accountCaps":{},"pegCap":1000000,"perDepositCap":50000,"perWithdrawalCap":20000}`
+    const GZIPPED_LIMITS: [u8; 79] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xab, 0x56, 0x4a, 0x4c, 0x4e,
+        0xce, 0x2f, 0xcd, 0x2b, 0x71, 0x4e, 0x2c, 0x28, 0x56, 0xb2, 0xaa, 0xae, 0xd5, 0x51, 0x2a,
+        0x48, 0x4d, 0x07, 0x72, 0x94, 0xac, 0x0c, 0x0d, 0xc0, 0x00, 0x24, 0x50, 0xe4, 0x92, 0x5a,
+        0x90, 0x5f, 0x9c, 0x59, 0x02, 0x16, 0x37, 0x85, 0x8b, 0x86, 0x67, 0x96, 0x64, 0xa4, 0x14,
+        0x25, 0x96, 0x27, 0xe6, 0x80, 0x25, 0x8c, 0x40, 0x12, 0xb5, 0x00, 0x13, 0x8c, 0x8b, 0x06,
+        0x52, 0x00, 0x00, 0x00,
+    ];
+
+    #[tokio::test]
+    async fn gzip_encoded_responses_are_decompressed() {
+        // Arrange.
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/limits")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_header("content-encoding", "gzip")
+            .with_body(GZIPPED_LIMITS)
+            .expect(1)
+            .create_async()
+            .await;
+        let url = Url::parse(&server.url()).unwrap();
+        let client = EmilyClient::try_from(&url).unwrap();
+        // Act.
+        let limits = client.get_limits().await.unwrap();
+        // Assert.
+        mock.assert_async().await;
+        assert_eq!(limits.total_cap(), Amount::from_sat(1_000_000));
+        assert_eq!(limits.per_deposit_cap(), Amount::from_sat(50_000));
+        assert_eq!(limits.per_withdrawal_cap(), Amount::from_sat(20_000));
+    }
+
+    #[tokio::test]
+    async fn get_withdrawals_fetches_each_pending_withdrawal() {
+        // Arrange.
+        let mut server = mockito::Server::new_async().await;
+        let list_mock = server
+            .mock(
+                "GET",
+                mockito::Matcher::Regex(r"^/withdrawal\?status=pending".into()),
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"withdrawals":[{"amount":1000,"lastUpdateBlockHash":"hash",
+                "lastUpdateHeight":2,"recipient":"recipient","requestId":7,
+                "stacksBlockHash":"hash","stacksBlockHeight":2,"status":"pending"}]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let get_mock = server
+            .mock("GET", "/withdrawal/7")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"amount":1000,"lastUpdateBlockHash":"hash","lastUpdateHeight":2,
+                "parameters":{"maxFee":50},"recipient":"recipient","requestId":7,
+                "signaturesCollected":0,"stacksBlockHash":"hash","stacksBlockHeight":2,
+                "status":"pending","statusMessage":"","threshold":0}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let url = Url::parse(&server.url()).unwrap();
+        let client = EmilyClient::try_from(&url).unwrap();
+        // Act.
+        let withdrawals = client.get_withdrawals().await.unwrap();
+        // Assert.
+        list_mock.assert_async().await;
+        get_mock.assert_async().await;
+        assert_eq!(withdrawals.len(), 1);
+        assert_eq!(withdrawals[0].request_id, 7);
+        assert_eq!(withdrawals[0].amount, 1000);
+        assert_eq!(withdrawals[0].parameters.max_fee, 50);
+        assert_eq!(withdrawals[0].recipient, "recipient");
+    }
 }
@@ -8,6 +8,7 @@ use crate::emily_client::EmilyClientError;
 use crate::stacks::contracts::DepositValidationError;
 use crate::stacks::contracts::RotateKeysValidationError;
 use crate::stacks::contracts::WithdrawalAcceptValidationError;
+use crate::stacks::contracts::WithdrawalRejectValidationError;
 use crate::storage::model::SigHash;
 
 /// Top-level signer error
@@ -58,10 +59,33 @@ pub enum Error {
     #[error("bitcoin-core getblock RPC error for hash {1}: {0}")]
     BitcoinCoreGetBlock(#[source] bitcoincore_rpc::Error, bitcoin::BlockHash),
 
+    /// Attempt to fetch a bitcoin block header ended in an unexpected
+    /// error. This is not triggered if the block is missing.
+    #[error("bitcoin-core getblockheader RPC error for hash {1}: {0}")]
+    BitcoinCoreGetBlockHeader(#[source] bitcoincore_rpc::Error, bitcoin::BlockHash),
+
     /// Received an error in response to getrawtransaction RPC call
     #[error("failed to retrieve the raw transaction for txid {1} from bitcoin-core. {0}")]
     BitcoinCoreGetTransaction(#[source] bitcoincore_rpc::Error, bitcoin::Txid),
 
+    /// Received an error in response to getblockchaininfo RPC call
+    #[error("bitcoin-core getblockchaininfo error: {0}")]
+    BitcoinCoreGetBlockchainInfo(#[source] bitcoincore_rpc::Error),
+
+    /// The connected bitcoin-core node reported that it is running on a
+    /// different network than the one the signer expects.
+    #[error(
+        "bitcoin-core node is on the {actual:?} chain, but the {expected:?} chain was expected"
+    )]
+    BitcoinNetworkMismatch {
+        /// The chain that the signer expected the node to be on, in the
+        /// format used by bitcoin-core.
+        expected: String,
+        /// The chain reported by the node in the `getblockchaininfo`
+        /// response.
+        actual: String,
+    },
+
     /// Error when creating an RPC client to bitcoin-core
     #[error("could not create RPC client to {1}: {0}")]
     BitcoinCoreRpcClient(#[source] bitcoincore_rpc::Error, String),
@@ -72,6 +96,29 @@ pub enum Error {
     #[error("transaction is missing, txid: {0}, block hash {1:?}")]
     BitcoinTxMissing(bitcoin::Txid, Option<bitcoin::BlockHash>),
 
+    /// The funding inputs of an unconfirmed transaction were spent, but
+    /// not by a replacement of the transaction that could be found, so the
+    /// transaction can never be confirmed.
+    #[error("the funding inputs of transaction {0} were spent without a replacement being found")]
+    FundingInputsSpent(bitcoin::Txid),
+
+    /// The lock time in the reclaim script of a deposit has not elapsed
+    /// yet, so the deposit cannot be reclaimed.
+    #[error("deposit {outpoint} cannot be reclaimed until it has {lock_time} confirmations, it has {confirmations}")]
+    ReclaimLockTimeNotElapsed {
+        /// The outpoint of the deposit UTXO being reclaimed.
+        outpoint: bitcoin::OutPoint,
+        /// The number of confirmations of the funding transaction.
+        confirmations: u32,
+        /// The lock time in the reclaim script, in bitcoin blocks.
+        lock_time: u32,
+    },
+
+    /// The reclaim script of a deposit does not follow any of the
+    /// templates in the configured allowlist.
+    #[error("the reclaim script of deposit {0} does not follow an allowlisted template")]
+    ReclaimNotAllowlisted(bitcoin::OutPoint),
+
     /// This is the error that is returned when validating a bitcoin
     /// trasnaction.
     #[error("bitcoin validation error: {0}")]
@@ -120,6 +167,10 @@ pub enum Error {
     #[error("an error occurred running the libp2p swarm: {0}")]
     SignerSwarm(#[from] crate::network::libp2p::SignerSwarmError),
 
+    /// The block observer task panicked or was cancelled.
+    #[error("the block observer task failed to complete: {0}")]
+    BlockObserverTask(#[source] tokio::task::JoinError),
+
     /// The requested operation is not allowed in the current state as the
     /// signer is being shut down.
     #[error("the signer is shutting down")]
@@ -151,6 +202,11 @@ pub enum Error {
     #[error("the change amounts for the transaction is negative: {0}")]
     InvalidAmount(i64),
 
+    /// The sum of the amounts in a batch of requests does not fit in a
+    /// u64.
+    #[error("the total amount of the requests overflows a u64")]
+    AmountOverflow,
+
     /// Old fee estimate
     #[error("got an old fee estimate")]
     OldFeeEstimate,
@@ -270,6 +326,16 @@ pub enum Error {
     #[error("invalid wallet definition, signatures required: {0}, number of keys: {1}")]
     InvalidWalletDefinition(u16, usize),
 
+    /// The signer set does not have the number of signers that was
+    /// configured for this signer.
+    #[error("signer set has {actual} signers, but {expected} were expected")]
+    SignerSetSizeMismatch {
+        /// The configured size of the signer set.
+        expected: u16,
+        /// The number of public keys in the signer set.
+        actual: usize,
+    },
+
     /// Error when parsing a URL
     #[error("could not parse the provided URL: {0}")]
     InvalidUrl(#[source] url::ParseError),
@@ -365,6 +431,11 @@ pub enum Error {
     #[error("invalid stacks response: {0}")]
     InvalidStacksResponse(&'static str),
 
+    /// The stacks node kept returning tenure info whose tip block is not
+    /// part of the reported tenure.
+    #[error("the stacks node returned inconsistent tenure info for tip block {0}")]
+    InconsistentTenureInfo(StacksBlockId),
+
     /// Taproot error
     #[error("an error occurred when constructing the taproot signing digest: {0}")]
     Taproot(#[from] bitcoin::sighash::TaprootError),
@@ -373,6 +444,18 @@ pub enum Error {
     #[error("key error: {0}")]
     KeyError(#[from] p256k1::keys::Error),
 
+    /// The block that a catch up should start from is not an ancestor of
+    /// the block that it should end at, or is too far behind it.
+    #[error(
+        "bitcoin block {from} is not an ancestor of bitcoin block {to} within the context window"
+    )]
+    CatchUpRangeDisconnected {
+        /// The block that the catch up should start from.
+        from: bitcoin::BlockHash,
+        /// The block that the catch up should end at.
+        to: bitcoin::BlockHash,
+    },
+
     /// Missing bitcoin block
     #[error("the database is missing bitcoin block {0}")]
     MissingBitcoinBlock(crate::storage::model::BitcoinBlockHash),
@@ -401,6 +484,18 @@ pub enum Error {
     #[error("missing signer utxo")]
     MissingSignerUtxo,
 
+    /// The estimated market fee rate is above the configured maximum fee
+    /// rate for sweep transactions.
+    #[error(
+        "estimated fee rate of {rate} sats/vbyte exceeds the configured cap of {cap} sats/vbyte"
+    )]
+    FeeRateTooHigh {
+        /// The estimated market fee rate, in sats per virtual byte.
+        rate: f64,
+        /// The configured maximum sweep fee rate, in sats per virtual byte.
+        cap: f64,
+    },
+
     /// This should never happen. It arises when a signer gets a message
     /// that requires DKG to have been run at some point, but it hasn't
     /// been.
@@ -463,6 +558,11 @@ pub enum Error {
     #[error("withdrawal accept validation error: {0}")]
     WithdrawalAcceptValidation(#[source] Box<WithdrawalAcceptValidationError>),
 
+    /// The error for when the request to sign a withdrawal-reject
+    /// transaction fails at the validation step.
+    #[error("withdrawal reject validation error: {0}")]
+    WithdrawalRejectValidation(#[source] Box<WithdrawalRejectValidationError>),
+
     /// WSTS error.
     #[error("WSTS error: {0}")]
     Wsts(#[source] wsts::state_machine::signer::Error),
@@ -556,4 +656,48 @@ impl Error {
     pub fn wsts_coordinator(err: wsts::state_machine::coordinator::Error) -> Self {
         Error::WstsCoordinator(Box::new(err))
     }
+
+    /// Whether the call that returned this error may succeed if it is made
+    /// again with the same client. Only transport errors, like a refused
+    /// connection, and HTTP 5xx responses are transient. Errors returned
+    /// by the bitcoin-core RPC itself, like a missing block, are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::BitcoinCoreGetTxOut(error, ..)
+            | Error::BitcoinCoreGetMempoolDescendants(error, _)
+            | Error::BitcoinCoreGetTxSpendingPrevout(error, _)
+            | Error::BitcoinCoreGetBlock(error, _)
+            | Error::BitcoinCoreGetBlockHeader(error, _)
+            | Error::BitcoinCoreGetTransaction(error, _)
+            | Error::BitcoinCoreGetBlockchainInfo(error)
+            | Error::BitcoinCoreRpcClient(error, _)
+            | Error::EstimateSmartFee(error, _)
+            | Error::BitcoinCoreRpc(error) => is_transient_rpc_error(error),
+            Error::Reqwest(error) => {
+                error.is_connect()
+                    || error.is_timeout()
+                    || error
+                        .status()
+                        .is_some_and(|status| status.is_server_error())
+            }
+            Error::EmilyApi(error) => error.is_transient(),
+            _ => false,
+        }
+    }
+}
+
+/// Whether the given bitcoin-core RPC error is a transport error. The
+/// transport reports an HTTP error code only when the response does not
+/// carry a JSON-RPC error, so client errors like a failed authentication
+/// are not transient.
+fn is_transient_rpc_error(error: &bitcoincore_rpc::Error) -> bool {
+    use bitcoincore_rpc::jsonrpc;
+
+    let bitcoincore_rpc::Error::JsonRpc(jsonrpc::Error::Transport(error)) = error else {
+        return false;
+    };
+    match error.downcast_ref::<jsonrpc::simple_http::Error>() {
+        Some(jsonrpc::simple_http::Error::HttpErrorCode(code)) => *code >= 500,
+        _ => true,
+    }
 }
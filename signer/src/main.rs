@@ -90,10 +90,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ApiFallbackClient<EmilyClient>,
     >::init(settings, db)?;
 
+    // Make sure that the bitcoin-core node that we are connected to is on
+    // the network that we expect before we start doing anything with it.
+    let settings = context.config();
+    let expected_network = settings.bitcoin.expected_network(settings.signer.network);
+    context
+        .get_bitcoin_client()
+        .check_network(expected_network.into())
+        .await?;
+
     // TODO: We should first check "another source of truth" for the current
     // signing set, and only assume we are bootstrapping if that source is
     // empty.
-    let settings = context.config();
     for signer in settings.signer.bootstrap_signing_set() {
         context.state().current_signer_set().add_signer(signer);
     }
@@ -304,6 +312,13 @@ async fn run_block_observer(ctx: impl Context) -> Result<(), Error> {
         horizon: config.signer.bitcoin_block_horizon,
     };
 
+    // A checkpoint that is no longer canonical would have us skip the
+    // stacks blocks that replaced it, but the observer can still make
+    // progress without the checkpoint, so we only log the error.
+    if let Err(error) = block_observer.reconcile_on_start().await {
+        tracing::warn!(%error, "could not reconcile the stacks checkpoint on startup");
+    }
+
     block_observer.run().await
 }
 
@@ -4,6 +4,7 @@ use secp256k1::ecdsa::RecoverableSignature;
 
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::validation::TxRequestIds;
+use crate::config::OutputOrdering;
 use crate::keys::PublicKey;
 use crate::keys::SignerScriptPubKey as _;
 use crate::stacks::contracts::StacksTx;
@@ -348,6 +349,10 @@ pub struct BitcoinPreSignRequest {
     /// The total fee amount and the fee rate for the last transaction that
     /// used this UTXO as an input.
     pub last_fees: Option<Fees>,
+    /// The order of the withdrawal outputs of the transactions. This is
+    /// set by the coordinator, so that every signer constructs the same
+    /// transactions regardless of their own configuration.
+    pub output_ordering: OutputOrdering,
 }
 
 /// An acknowledgment of a [`BitcoinPreSignRequest`].
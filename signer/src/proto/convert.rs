@@ -44,6 +44,7 @@ use wsts::traits::SignerState;
 use crate::bitcoin::utxo::Fees;
 use crate::bitcoin::validation::TxRequestIds;
 use crate::codec;
+use crate::config::OutputOrdering;
 use crate::ecdsa::Signed;
 use crate::error::Error;
 use crate::keys::PublicKey;
@@ -1383,6 +1384,7 @@ impl From<BitcoinPreSignRequest> for proto::BitcoinPreSignRequest {
                 .collect(),
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            output_ordering: proto::OutputOrdering::from(value.output_ordering).into(),
         }
     }
 }
@@ -1398,6 +1400,31 @@ impl TryFrom<proto::BitcoinPreSignRequest> for BitcoinPreSignRequest {
                 .collect::<Result<Vec<_>, _>>()?,
             fee_rate: value.fee_rate,
             last_fees: value.last_fees.map(|v| v.into()),
+            output_ordering: proto::OutputOrdering::try_from(value.output_ordering)
+                .map_err(|_| Error::TypeConversion)?
+                .try_into()?,
+        })
+    }
+}
+
+impl From<OutputOrdering> for proto::OutputOrdering {
+    fn from(value: OutputOrdering) -> Self {
+        match value {
+            OutputOrdering::RequestOrder => proto::OutputOrdering::RequestOrder,
+            OutputOrdering::Bip69 => proto::OutputOrdering::Bip69,
+            OutputOrdering::Seeded => proto::OutputOrdering::Seeded,
+        }
+    }
+}
+
+impl TryFrom<proto::OutputOrdering> for OutputOrdering {
+    type Error = Error;
+    fn try_from(value: proto::OutputOrdering) -> Result<Self, Self::Error> {
+        Ok(match value {
+            proto::OutputOrdering::RequestOrder => OutputOrdering::RequestOrder,
+            proto::OutputOrdering::Bip69 => OutputOrdering::Bip69,
+            proto::OutputOrdering::Seeded => OutputOrdering::Seeded,
+            proto::OutputOrdering::Unspecified => return Err(Error::TypeConversion),
         })
     }
 }
@@ -2261,6 +2288,7 @@ mod tests {
     #[test_case(PhantomData::<(Fees, proto::Fees)>; "Fees")]
     #[test_case(PhantomData::<(BitcoinPreSignRequest, proto::BitcoinPreSignRequest)>; "BitcoinPreSignRequest")]
     #[test_case(PhantomData::<(BitcoinPreSignAck, proto::BitcoinPreSignAck)>; "BitcoinPreSignAck")]
+    #[test_case(PhantomData::<(OutputOrdering, proto::OutputOrdering)>; "OutputOrdering")]
     fn convert_protobuf_type<T, U, E>(_: PhantomData<(T, U)>)
     where
         // `.unwrap()` requires that `E` implement `std::fmt::Debug` and
@@ -492,6 +492,9 @@ pub struct BitcoinPreSignRequest {
     /// used this UTXO as an input.
     #[prost(message, optional, tag = "3")]
     pub last_fees: ::core::option::Option<Fees>,
+    /// The order of the withdrawal outputs of the transactions.
+    #[prost(enumeration = "OutputOrdering", tag = "4")]
+    pub output_ordering: i32,
 }
 /// Represents an acknowledgment of a BitcoinPreSignRequest.
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -540,3 +543,42 @@ pub struct Fees {
     #[prost(double, tag = "2")]
     pub rate: f64,
 }
+/// The order of the withdrawal outputs of sweep transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OutputOrdering {
+    Unspecified = 0,
+    /// The withdrawal outputs follow the order of the identifiers of their
+    /// withdrawal requests.
+    RequestOrder = 1,
+    /// The withdrawal outputs are sorted by amount and then by
+    /// scriptPubKey, following BIP-69.
+    Bip69 = 2,
+    /// The withdrawal outputs are shuffled using the signers' UTXO that the
+    /// transaction spends as the seed.
+    Seeded = 3,
+}
+impl OutputOrdering {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            OutputOrdering::Unspecified => "OUTPUT_ORDERING_UNSPECIFIED",
+            OutputOrdering::RequestOrder => "OUTPUT_ORDERING_REQUEST_ORDER",
+            OutputOrdering::Bip69 => "OUTPUT_ORDERING_BIP69",
+            OutputOrdering::Seeded => "OUTPUT_ORDERING_SEEDED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OUTPUT_ORDERING_UNSPECIFIED" => Some(Self::Unspecified),
+            "OUTPUT_ORDERING_REQUEST_ORDER" => Some(Self::RequestOrder),
+            "OUTPUT_ORDERING_BIP69" => Some(Self::Bip69),
+            "OUTPUT_ORDERING_SEEDED" => Some(Self::Seeded),
+            _ => None,
+        }
+    }
+}
@@ -3,12 +3,14 @@
 use std::borrow::Cow;
 use std::future::Future;
 use std::time::Duration;
+use std::time::Instant;
 
 use bitcoin::Amount;
 use blockstack_lib::burnchains::Txid;
 use blockstack_lib::chainstate::burn::ConsensusHash;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::stacks::db::blocks::MINIMUM_TX_FEE_RATE_PER_BYTE;
+use blockstack_lib::chainstate::stacks::db::StacksBlockHeaderTypes;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
 use blockstack_lib::chainstate::stacks::TokenTransferMemo;
 use blockstack_lib::chainstate::stacks::TransactionPayload;
@@ -27,6 +29,7 @@ use blockstack_lib::net::api::postfeerate::RPCFeeEstimateResponse;
 use blockstack_lib::types::chainstate::StacksAddress;
 use blockstack_lib::types::chainstate::StacksBlockId;
 use clarity::types::StacksEpochId;
+use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::{BuffData, ListData, SequenceData};
 use clarity::vm::{ClarityName, ContractName, Value};
 use reqwest::header::CONTENT_LENGTH;
@@ -35,6 +38,7 @@ use serde::{Deserialize, Deserializer};
 use url::Url;
 
 use crate::config::Settings;
+use crate::config::SignerConfig;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::storage::model::BitcoinBlockHash;
@@ -140,12 +144,35 @@ pub trait StacksInteract: Send + Sync {
         contract_principal: &StacksAddress,
     ) -> impl Future<Output = Result<Option<PublicKey>, Error>> + Send;
 
+    /// Check whether the given key is, or was, one of the signers'
+    /// aggregate keys, using the `aggregate-pubkeys` map of the
+    /// `sbtc-registry` contract.
+    ///
+    /// This is done by making a `POST /v2/map_entry/<contract-principal>/sbtc-registry/aggregate-pubkeys`
+    /// request.
+    fn is_registered_aggregate_key(
+        &self,
+        contract_principal: &StacksAddress,
+        aggregate_key: &PublicKey,
+    ) -> impl Future<Output = Result<bool, Error>> + Send;
+
     /// Get the latest account info for the given address.
     fn get_account(
         &self,
         address: &StacksAddress,
     ) -> impl Future<Output = Result<AccountInfo, Error>> + Send;
 
+    /// Get the total balance, in micro-STX, of the given address.
+    ///
+    /// The default implementation reads the balance from the response of
+    /// [`StacksInteract::get_account`].
+    fn get_balance(
+        &self,
+        address: &StacksAddress,
+    ) -> impl Future<Output = Result<u128, Error>> + Send {
+        async move { Ok(self.get_account(address).await?.balance) }
+    }
+
     /// Submit a transaction to a Stacks node.
     fn submit_tx(
         &self,
@@ -158,6 +185,12 @@ pub trait StacksInteract: Send + Sync {
         &self,
         block_id: StacksBlockId,
     ) -> impl Future<Output = Result<NakamotoBlock, Error>> + Send;
+    /// Fetch the transactions in the stacks nakamoto block with the given
+    /// Stacks block ID, in the order that they appear in the block.
+    fn get_block_transactions(
+        &self,
+        block_id: StacksBlockId,
+    ) -> impl Future<Output = Result<Vec<StacksTransaction>, Error>> + Send;
     /// Fetch all Nakamoto ancestor blocks within the same tenure as the
     /// given block ID from a Stacks node.
     ///
@@ -171,6 +204,15 @@ pub trait StacksInteract: Send + Sync {
         &self,
         block_id: StacksBlockId,
     ) -> impl Future<Output = Result<TenureBlocks, Error>> + Send;
+    /// Fetch the Nakamoto blocks of the tenure with the given consensus
+    /// hash, in ascending order.
+    ///
+    /// The blocks are those of the tenure up to the tip of the tenure
+    /// known to the Stacks node.
+    fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> impl Future<Output = Result<Vec<NakamotoBlock>, Error>> + Send;
     /// Get information about the current tenure.
     ///
     /// This function is analogous to the GET /v3/tenures/info stacks node
@@ -200,9 +242,31 @@ pub trait StacksInteract: Send + Sync {
     /// Get information about the current PoX state.
     fn get_pox_info(&self) -> impl Future<Output = Result<RPCPoxInfoData, Error>> + Send;
 
+    /// Get the number of reward slots in the current PoX reward cycle.
+    ///
+    /// The default implementation reads the `reward_slots` field of the
+    /// response from [`StacksInteract::get_pox_info`].
+    fn get_reward_slots(&self) -> impl Future<Output = Result<u64, Error>> + Send {
+        async move {
+            let pox_info = self.get_pox_info().await?;
+            Ok(u64::from(pox_info.reward_slots))
+        }
+    }
+
     /// Get information about the current node.
     fn get_node_info(&self) -> impl Future<Output = Result<RPCPeerInfoData, Error>> + Send;
 
+    /// Get the affirmation maps reported by the current node.
+    ///
+    /// The default implementation parses the `affirmations` field of the
+    /// response from [`StacksInteract::get_node_info`].
+    fn get_affirmations(&self) -> impl Future<Output = Result<Affirmations, Error>> + Send {
+        async move {
+            let node_info = self.get_node_info().await?;
+            Affirmations::try_from(&node_info)
+        }
+    }
+
     /// Get the source of a deployed smart contract.
     ///
     /// # Notes
@@ -221,6 +285,15 @@ pub trait StacksInteract: Send + Sync {
         &self,
         sender: &StacksAddress,
     ) -> impl Future<Output = Result<Amount, Error>> + Send;
+
+    /// Get the sBTC balance of the given principal from the SIP-010
+    /// `get-balance` function of the `sbtc-token` smart contract deployed
+    /// by the given token principal.
+    fn get_sbtc_balance(
+        &self,
+        token_principal: &StacksAddress,
+        address: &PrincipalData,
+    ) -> impl Future<Output = Result<u128, Error>> + Send;
 }
 
 /// A trait for getting the start height of the first EPOCH 3.0 block on the
@@ -243,6 +316,192 @@ impl GetNakamotoStartHeight for RPCPoxInfoData {
     }
 }
 
+/// Return the block limit of the epoch that contains the given bitcoin
+/// block height, or `None` if no epoch in the PoX info contains it.
+///
+/// Each epoch covers the bitcoin block heights from its start height up
+/// to, but not including, its end height.
+pub fn current_block_limit(pox_info: &RPCPoxInfoData, burn_height: u64) -> Option<ExecutionCost> {
+    pox_info
+        .epochs
+        .iter()
+        .find(|epoch| epoch.start_height <= burn_height && burn_height < epoch.end_height)
+        .map(|epoch| epoch.block_limit.clone())
+}
+
+/// A single entry in an affirmation map. Each entry describes what the
+/// stacks node believes about the PoX anchor block of one reward cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affirmation {
+    /// The anchor block of the reward cycle is affirmed as present. This
+    /// is encoded as `p` in the affirmation map.
+    Present,
+    /// The anchor block of the reward cycle is affirmed as absent. This
+    /// is encoded as `a` in the affirmation map.
+    Absent,
+    /// Nothing is affirmed about the anchor block of the reward cycle.
+    /// This is encoded as `n` in the affirmation map.
+    Nothing,
+}
+
+impl TryFrom<char> for Affirmation {
+    type Error = Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'p' => Ok(Affirmation::Present),
+            'a' => Ok(Affirmation::Absent),
+            'n' => Ok(Affirmation::Nothing),
+            _ => Err(Error::InvalidStacksResponse(
+                "invalid entry in an affirmation map",
+            )),
+        }
+    }
+}
+
+/// The affirmation maps returned in the `affirmations` field of the
+/// GET /v2/info response, with one entry per reward cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Affirmations {
+    /// The affirmation map of the heaviest chain.
+    pub heaviest: Vec<Affirmation>,
+    /// The affirmation map of the current stacks chain tip.
+    pub stacks_tip: Vec<Affirmation>,
+    /// The affirmation map of the current sortition tip.
+    pub sortition_tip: Vec<Affirmation>,
+    /// The tentative best affirmation map.
+    pub tentative_best: Vec<Affirmation>,
+}
+
+impl Affirmations {
+    fn parse_map(map: &str) -> Result<Vec<Affirmation>, Error> {
+        map.chars().map(Affirmation::try_from).collect()
+    }
+}
+
+impl TryFrom<&RPCPeerInfoData> for Affirmations {
+    type Error = Error;
+
+    fn try_from(node_info: &RPCPeerInfoData) -> Result<Self, Self::Error> {
+        /// The raw form of the affirmation maps, as returned by the node.
+        #[derive(Deserialize)]
+        struct RawAffirmations {
+            heaviest: String,
+            stacks_tip: String,
+            sortition_tip: String,
+            tentative_best: String,
+        }
+
+        let value = serde_json::to_value(&node_info.affirmations)
+            .map_err(|_| Error::InvalidStacksResponse("could not read the affirmation maps"))?;
+        let raw: Option<RawAffirmations> = serde_json::from_value(value)
+            .map_err(|_| Error::InvalidStacksResponse("malformed affirmation maps"))?;
+        let raw = raw.ok_or(Error::InvalidStacksResponse(
+            "node info is missing the affirmation maps",
+        ))?;
+
+        Ok(Affirmations {
+            heaviest: Self::parse_map(&raw.heaviest)?,
+            stacks_tip: Self::parse_map(&raw.stacks_tip)?,
+            sortition_tip: Self::parse_map(&raw.sortition_tip)?,
+            tentative_best: Self::parse_map(&raw.tentative_best)?,
+        })
+    }
+}
+
+/// The default amount of time between reads of the current signer set
+/// from the `sbtc-registry` contract.
+pub const DEFAULT_SIGNER_SET_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A cached copy of the current signer set in the `sbtc-registry`
+/// contract.
+///
+/// The signer set is only re-read from the stacks node once the refresh
+/// interval has elapsed since it was last read, which keeps the number of
+/// requests to the stacks node down. Callers pass in the current time, so
+/// they control the clock. The signer keeps one in its state, and reads
+/// the signer set through [`Context::get_current_signer_set`].
+///
+/// [`Context::get_current_signer_set`]: crate::context::Context::get_current_signer_set
+#[derive(Debug)]
+pub struct SignerSetCache {
+    /// How long a signer set that was read from the stacks node is used
+    /// before it is read again.
+    refresh_interval: Duration,
+    /// The last signer set read from the stacks node, along with when it
+    /// was read.
+    cached: tokio::sync::Mutex<Option<(Instant, Vec<PublicKey>)>>,
+}
+
+impl Default for SignerSetCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIGNER_SET_REFRESH_INTERVAL)
+    }
+}
+
+impl SignerSetCache {
+    /// Create a new, empty, cache with the given refresh interval.
+    pub fn new(refresh_interval: Duration) -> Self {
+        Self {
+            refresh_interval,
+            cached: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Create a new, empty, cache using the refresh interval in the given
+    /// config.
+    pub fn from_config(config: &SignerConfig) -> Self {
+        let refresh_interval = config
+            .signer_set_refresh_interval
+            .map_or(DEFAULT_SIGNER_SET_REFRESH_INTERVAL, Duration::from_secs);
+        Self::new(refresh_interval)
+    }
+
+    /// Return the current signer set, reading it from the stacks node if
+    /// it has not been read within the refresh interval before `now`.
+    pub async fn get<S>(
+        &self,
+        stacks_client: &S,
+        contract_principal: &StacksAddress,
+        now: Instant,
+    ) -> Result<Vec<PublicKey>, Error>
+    where
+        S: StacksInteract,
+    {
+        let mut cached = self.cached.lock().await;
+        if let Some((read_at, signer_set)) = cached.as_ref() {
+            if now.saturating_duration_since(*read_at) < self.refresh_interval {
+                return Ok(signer_set.clone());
+            }
+        }
+
+        let signer_set = stacks_client
+            .get_current_signer_set(contract_principal)
+            .await?;
+        *cached = Some((now, signer_set.clone()));
+        Ok(signer_set)
+    }
+
+    /// Read the current signer set from the stacks node, regardless of
+    /// when it was last read, and cache it as of `now`.
+    pub async fn force_refresh<S>(
+        &self,
+        stacks_client: &S,
+        contract_principal: &StacksAddress,
+        now: Instant,
+    ) -> Result<Vec<PublicKey>, Error>
+    where
+        S: StacksInteract,
+    {
+        let mut cached = self.cached.lock().await;
+        let signer_set = stacks_client
+            .get_current_signer_set(contract_principal)
+            .await?;
+        *cached = Some((now, signer_set.clone()));
+        Ok(signer_set)
+    }
+}
+
 /// This struct represents a non-empty subset of the Stacks blocks that
 /// were created during a tenure.
 #[derive(Debug)]
@@ -363,7 +622,7 @@ pub enum RejectionReason {
 /// The official documentation specifies what to expect when there is a
 /// rejection, and that documentation can be found here:
 /// https://github.com/stacks-network/stacks-core/blob/2.5.0.0.5/docs/rpc-endpoints.md
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 #[cfg_attr(feature = "testing", derive(serde::Serialize))]
 pub struct TxRejection {
     /// The error message. It should always be the string "transaction
@@ -398,7 +657,7 @@ impl std::error::Error for TxRejection {}
 /// could be due to the fee being too low or because of a bad nonce. These
 /// are retryable "error", so we distinguish them from the third kinds of
 /// errors, which are likely not retryable.
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 #[serde(untagged)]
 pub enum SubmitTxResponse {
     /// The transaction ID for the submitted transaction.
@@ -408,6 +667,7 @@ pub enum SubmitTxResponse {
 }
 
 /// The account info for a stacks address.
+#[derive(Debug, Clone)]
 pub struct AccountInfo {
     /// The total balance of the account in micro-STX. This amount includes
     /// the amount locked.
@@ -429,6 +689,15 @@ pub struct DataVarResponse {
     pub data: Value,
 }
 
+/// The response from a POST /v2/map_entry/<contract-principal>/<contract-name>/<map-name> request.
+#[derive(Debug, Deserialize)]
+pub struct MapEntryResponse {
+    /// The value of the map entry, wrapped in an optional that is none
+    /// when the map has no entry for the given key.
+    #[serde(deserialize_with = "clarity_value_deserializer")]
+    pub data: Value,
+}
+
 /// The request body for a POST /v2/contracts/call-read/<contract-principal>/<contract-name>/<fn-name> request.
 #[derive(Debug, serde::Serialize)]
 pub struct CallReadRequest {
@@ -492,7 +761,8 @@ impl StacksClient {
         })
     }
 
-    /// Calls a read-only public function on a given smart contract.
+    /// Calls a read-only public function on a given smart contract with
+    /// the given arguments.
     #[tracing::instrument(skip_all)]
     pub async fn call_read(
         &self,
@@ -500,6 +770,7 @@ impl StacksClient {
         contract_name: &ContractName,
         fn_name: &ClarityName,
         sender: &StacksAddress,
+        arguments: &[Value],
     ) -> Result<Value, Error> {
         let path = format!(
             "/v2/contracts/call-read/{}/{}/{}?tip=latest",
@@ -513,7 +784,10 @@ impl StacksClient {
 
         let body = CallReadRequest {
             sender: sender.to_string(),
-            arguments: vec![], // TODO: Add when needed
+            arguments: arguments
+                .iter()
+                .map(|arg| format!("0x{}", hex::encode(arg.serialize_to_vec())))
+                .collect(),
         };
 
         tracing::debug!(
@@ -588,6 +862,53 @@ impl StacksClient {
             .map(|x| x.data)
     }
 
+    /// Fetch the entry of the given key in the given map of the given
+    /// contract. The returned value is an optional, which is none when
+    /// the map has no entry for the key.
+    pub async fn get_map_entry(
+        &self,
+        contract_principal: &StacksAddress,
+        contract_name: &ContractName,
+        map_name: &ClarityName,
+        key: &Value,
+    ) -> Result<Value, Error> {
+        let path = format!(
+            "/v2/map_entry/{}/{}/{}?proof=0",
+            contract_principal, contract_name, map_name
+        );
+
+        let url = self
+            .endpoint
+            .join(&path)
+            .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
+
+        let body = format!("0x{}", hex::encode(key.serialize_to_vec()));
+
+        tracing::debug!(
+            %contract_principal,
+            %contract_name,
+            %map_name,
+            "fetching contract map entry"
+        );
+
+        let response = self
+            .client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(Error::StacksNodeRequest)?;
+
+        response
+            .error_for_status()
+            .map_err(Error::StacksNodeResponse)?
+            .json::<MapEntryResponse>()
+            .await
+            .map_err(Error::UnexpectedStacksResponse)
+            .map(|x| x.data)
+    }
+
     /// Get the latest account info for the given address.
     ///
     /// This is done by making a GET /v2/accounts/<principal> request. In
@@ -887,6 +1208,62 @@ impl StacksClient {
         Ok(blocks)
     }
 
+    /// Get the header of the highest block known to the Stacks node in
+    /// the tenure with the given consensus hash.
+    ///
+    /// Uses the GET /v3/tenures/tip/<consensus-hash> stacks node endpoint.
+    #[tracing::instrument(skip(self))]
+    async fn get_tenure_tip(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<StacksBlockHeaderTypes, Error> {
+        let path = format!("/v3/tenures/tip/{}", consensus_hash);
+        let url = self
+            .endpoint
+            .join(&path)
+            .map_err(|err| Error::PathJoin(err, self.endpoint.clone(), Cow::Owned(path)))?;
+
+        tracing::debug!("making request to the stacks node for the tenure tip");
+        let response = self
+            .client
+            .get(url)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(Error::StacksNodeRequest)?;
+
+        response
+            .error_for_status()
+            .map_err(Error::StacksNodeResponse)?
+            .json()
+            .await
+            .map_err(Error::UnexpectedStacksResponse)
+    }
+
+    /// Fetch the Nakamoto blocks of the tenure with the given consensus
+    /// hash, in ascending order.
+    ///
+    /// The tip of the tenure is fetched first, and then the tenure that
+    /// ends with it, see [`StacksClient::get_tenure`].
+    #[tracing::instrument(skip(self))]
+    pub async fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        let StacksBlockHeaderTypes::Nakamoto(tip) = self.get_tenure_tip(consensus_hash).await?
+        else {
+            return Err(Error::InvalidStacksResponse(
+                "the tenure tip is not a Nakamoto block",
+            ));
+        };
+
+        let mut blocks = self.get_tenure(tip.block_id()).await?.into_blocks();
+        // The GET /v3/tenures/<block-id> responses list the blocks from
+        // the given block back to the start of the tenure.
+        blocks.sort_by_key(|block| block.header.chain_length);
+        Ok(blocks)
+    }
+
     /// Get information about the current tenure.
     ///
     /// Uses the GET /v3/tenures/info stacks node endpoint for retrieving
@@ -1136,6 +1513,30 @@ impl StacksInteract for StacksClient {
         }
     }
 
+    async fn is_registered_aggregate_key(
+        &self,
+        contract_principal: &StacksAddress,
+        aggregate_key: &PublicKey,
+    ) -> Result<bool, Error> {
+        let key = Value::buff_from(aggregate_key.serialize().to_vec())
+            .map_err(|_| Error::InvalidStacksResponse("could not encode the aggregate key"))?;
+        let result = self
+            .get_map_entry(
+                contract_principal,
+                &ContractName::from("sbtc-registry"),
+                &ClarityName::from("aggregate-pubkeys"),
+                &key,
+            )
+            .await?;
+
+        match result {
+            Value::Optional(entry) => Ok(entry.data.is_some()),
+            _ => Err(Error::InvalidStacksResponse(
+                "expected an optional but got something else",
+            )),
+        }
+    }
+
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
         self.get_account(address).await
     }
@@ -1148,10 +1549,24 @@ impl StacksInteract for StacksClient {
         self.get_block(block_id).await
     }
 
+    async fn get_block_transactions(
+        &self,
+        block_id: StacksBlockId,
+    ) -> Result<Vec<StacksTransaction>, Error> {
+        self.get_block(block_id).await.map(|block| block.txs)
+    }
+
     async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
         self.get_tenure(block_id).await
     }
 
+    async fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        self.get_blocks_by_consensus_hash(consensus_hash).await
+    }
+
     async fn get_tenure_info(&self) -> Result<RPCGetTenureInfo, Error> {
         self.get_tenure_info().await
     }
@@ -1284,6 +1699,7 @@ impl StacksInteract for StacksClient {
                 &ContractName::from("sbtc-token"),
                 &ClarityName::from("get-total-supply"),
                 deployer,
+                &[],
             )
             .await?;
 
@@ -1302,6 +1718,34 @@ impl StacksInteract for StacksClient {
             )),
         }
     }
+
+    async fn get_sbtc_balance(
+        &self,
+        token_principal: &StacksAddress,
+        address: &PrincipalData,
+    ) -> Result<u128, Error> {
+        let result = self
+            .call_read(
+                token_principal,
+                &ContractName::from("sbtc-token"),
+                &ClarityName::from("get-balance"),
+                token_principal,
+                &[Value::Principal(address.clone())],
+            )
+            .await?;
+
+        match result {
+            Value::Response(response) => match *response.data {
+                Value::UInt(balance) => Ok(balance),
+                _ => Err(Error::InvalidStacksResponse(
+                    "expected a uint but got something else",
+                )),
+            },
+            _ => Err(Error::InvalidStacksResponse(
+                "expected a response but got something else",
+            )),
+        }
+    }
 }
 
 impl StacksInteract for ApiFallbackClient<StacksClient> {
@@ -1331,6 +1775,21 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         .await
     }
 
+    async fn is_registered_aggregate_key(
+        &self,
+        contract_principal: &StacksAddress,
+        aggregate_key: &PublicKey,
+    ) -> Result<bool, Error> {
+        self.exec(|client, retry| async move {
+            let result = client
+                .is_registered_aggregate_key(contract_principal, aggregate_key)
+                .await;
+            retry.abort_if(|| matches!(result, Err(Error::InvalidStacksResponse(_))));
+            result
+        })
+        .await
+    }
+
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
         self.exec(|client, _| client.get_account(address)).await
     }
@@ -1343,10 +1802,26 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         self.exec(|client, _| client.get_block(block_id)).await
     }
 
+    async fn get_block_transactions(
+        &self,
+        block_id: StacksBlockId,
+    ) -> Result<Vec<StacksTransaction>, Error> {
+        self.exec(|client, _| StacksInteract::get_block_transactions(client, block_id))
+            .await
+    }
+
     async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
         self.exec(|client, _| client.get_tenure(block_id)).await
     }
 
+    async fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        self.exec(|client, _| client.get_blocks_by_consensus_hash(consensus_hash))
+            .await
+    }
+
     async fn get_tenure_info(&self) -> Result<RPCGetTenureInfo, Error> {
         self.exec(|client, _| client.get_tenure_info()).await
     }
@@ -1400,6 +1875,15 @@ impl StacksInteract for ApiFallbackClient<StacksClient> {
         self.exec(|client, _| client.get_sbtc_total_supply(deployer))
             .await
     }
+
+    async fn get_sbtc_balance(
+        &self,
+        token_principal: &StacksAddress,
+        address: &PrincipalData,
+    ) -> Result<u128, Error> {
+        self.exec(|client, _| client.get_sbtc_balance(token_principal, address))
+            .await
+    }
 }
 
 impl TryFrom<&Settings> for ApiFallbackClient<StacksClient> {
@@ -1425,14 +1909,17 @@ mod tests {
     use crate::stacks::wallet::get_full_tx_size;
     use crate::storage::in_memory::Store;
     use crate::storage::DbWrite;
+    use crate::testing::dummy;
     use crate::testing::storage::DATABASE_NUM;
 
+    use blockstack_lib::net::api::getpoxinfo::RPCPoxEpoch;
     use clarity::types::Address;
     use clarity::vm::types::{
         BuffData, BufferLength, ListData, ListTypeData, SequenceData, SequenceSubtype,
         TypeSignature,
     };
     use rand::rngs::OsRng;
+    use rand::SeedableRng as _;
     use secp256k1::Keypair;
     use test_case::test_case;
     use test_log::test;
@@ -2044,6 +2531,43 @@ mod tests {
         assert_eq!(nakamoto_start_height.unwrap(), 232);
     }
 
+    #[test]
+    fn current_block_limit_picks_the_epoch_containing_the_height() {
+        let raw_json_response =
+            include_str!("../../tests/fixtures/stacksapi-get-pox-info-test-data.json");
+        let mut pox_info: RPCPoxInfoData = serde_json::from_str(raw_json_response).unwrap();
+
+        let limit = |runtime: u64| ExecutionCost {
+            write_length: 15_000_000,
+            write_count: 15_000,
+            read_length: 100_000_000,
+            read_count: 15_000,
+            runtime,
+        };
+        pox_info.epochs = vec![
+            RPCPoxEpoch {
+                epoch_id: StacksEpochId::Epoch25,
+                start_height: 100,
+                end_height: 200,
+                block_limit: limit(1_000),
+                network_epoch: 10,
+            },
+            RPCPoxEpoch {
+                epoch_id: StacksEpochId::Epoch30,
+                start_height: 200,
+                end_height: u64::MAX,
+                block_limit: limit(2_000),
+                network_epoch: 11,
+            },
+        ];
+
+        assert_eq!(current_block_limit(&pox_info, 99), None);
+        assert_eq!(current_block_limit(&pox_info, 100), Some(limit(1_000)));
+        assert_eq!(current_block_limit(&pox_info, 199), Some(limit(1_000)));
+        assert_eq!(current_block_limit(&pox_info, 200), Some(limit(2_000)));
+        assert_eq!(current_block_limit(&pox_info, 5_000), Some(limit(2_000)));
+    }
+
     #[tokio::test]
     async fn get_node_info_works() {
         let raw_json_response =
@@ -2072,6 +2596,103 @@ mod tests {
         mock.assert();
     }
 
+    #[tokio::test]
+    async fn signer_set_is_read_once_per_refresh_interval() {
+        let mut client = MockStacksInteract::new();
+        client
+            .expect_get_current_signer_set()
+            .times(2)
+            .returning(|_| Box::pin(async { Ok(Vec::new()) }));
+
+        let cache = SignerSetCache::new(Duration::from_secs(60));
+        let contract_principal = StacksAddress::burn_address(false);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        // The first read goes to the node, and the next reads within the
+        // interval use the cached set. This is two reads over two
+        // intervals.
+        for secs in [0, 1, 30, 59, 60, 61, 119] {
+            cache
+                .get(&client, &contract_principal, at(secs))
+                .await
+                .unwrap();
+        }
+        client.checkpoint();
+
+        // Forcing a refresh always reads the set, and restarts the
+        // interval.
+        client
+            .expect_get_current_signer_set()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Vec::new()) }));
+        cache
+            .force_refresh(&client, &contract_principal, at(100))
+            .await
+            .unwrap();
+        cache
+            .get(&client, &contract_principal, at(159))
+            .await
+            .unwrap();
+        client.checkpoint();
+    }
+
+    #[test]
+    fn affirmations_parse_into_typed_form() {
+        let raw_json_response =
+            include_str!("../../tests/fixtures/stacksapi-get-node-info-test-data.json");
+        let mut node_info: RPCPeerInfoData = serde_json::from_str(raw_json_response).unwrap();
+
+        // The fixture has "nnnnnnnnnn" for the heaviest map and
+        // "nnnnnnnnnnp" for the others.
+        let affirmations = Affirmations::try_from(&node_info).unwrap();
+        let mut expected = vec![Affirmation::Nothing; 10];
+        assert_eq!(affirmations.heaviest, expected);
+        expected.push(Affirmation::Present);
+        assert_eq!(affirmations.stacks_tip, expected);
+        assert_eq!(affirmations.sortition_tip, expected);
+        assert_eq!(affirmations.tentative_best, expected);
+
+        assert_eq!(Affirmation::try_from('a').unwrap(), Affirmation::Absent);
+        assert!(Affirmation::try_from('x').is_err());
+
+        node_info.affirmations = None;
+        assert!(Affirmations::try_from(&node_info).is_err());
+    }
+
+    #[test_case(|url| StacksClient::new(url, 20).unwrap(); "stacks-client")]
+    #[test_case(|url| ApiFallbackClient::new(vec![StacksClient::new(url, 20).unwrap()]).unwrap(); "fallback-client")]
+    #[tokio::test]
+    async fn get_block_transactions_works<F, C>(client: F)
+    where
+        C: StacksInteract,
+        F: Fn(Url) -> C,
+    {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut block = dummy::stacks_block(&fake::Faker, &mut rng);
+        block.txs = std::iter::repeat_with(|| dummy::stacks_tx(&fake::Faker, &mut rng))
+            .take(5)
+            .collect();
+        let block_id = block.block_id();
+
+        let mut stacks_node_server = mockito::Server::new_async().await;
+        let endpoint_path = format!("/v3/blocks/{}", block_id.to_hex());
+        let mock = stacks_node_server
+            .mock("GET", endpoint_path.as_str())
+            .with_status(200)
+            .with_header("content-type", "application/octet-stream")
+            .with_body(block.serialize_to_vec())
+            .expect(1)
+            .create();
+
+        let client = client(url::Url::parse(stacks_node_server.url().as_str()).unwrap());
+        let txs = client.get_block_transactions(block_id).await.unwrap();
+
+        assert_eq!(txs.len(), block.txs.len());
+        assert_eq!(txs, block.txs);
+        mock.assert();
+    }
+
     #[tokio::test]
     #[ignore = "This is an integration test that hasn't been setup for CI yet"]
     async fn fetching_last_tenure_blocks_works() {
@@ -2117,4 +2738,193 @@ mod tests {
         let account = client.get_account(&address).await.unwrap();
         assert_eq!(account.nonce, 0);
     }
+
+    #[test(tokio::test)]
+    async fn get_reward_slots_returns_pox_reward_slots() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        harness.set_reward_slots(4000);
+
+        let reward_slots = harness.get_reward_slots().await.unwrap();
+        assert_eq!(reward_slots, 4000);
+    }
+
+    #[test(tokio::test)]
+    async fn get_balance_returns_account_balance() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let address = StacksAddress::burn_address(false);
+        let account = AccountInfo {
+            balance: 1_234_567,
+            locked: 1_000,
+            unlock_height: 42,
+            nonce: 7,
+        };
+        let expected_balance = account.balance;
+        harness.set_account(address, account);
+
+        let balance = harness.get_balance(&address).await.unwrap();
+        assert_eq!(balance, expected_balance);
+    }
+
+    #[test(tokio::test)]
+    async fn get_sbtc_balance_returns_the_configured_balance() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let deployer = StacksAddress::burn_address(false);
+        let holder = PrincipalData::from(StacksAddress::burn_address(true));
+        harness.set_sbtc_balance(deployer, holder.clone(), 1_234_567);
+
+        let balance = harness.get_sbtc_balance(&deployer, &holder).await.unwrap();
+        assert_eq!(balance, 1_234_567);
+
+        // Principals without a configured balance have not received any
+        // sBTC.
+        let unknown = PrincipalData::from(deployer);
+        let balance = harness.get_sbtc_balance(&deployer, &unknown).await.unwrap();
+        assert_eq!(balance, 0);
+    }
+
+    #[test(tokio::test)]
+    async fn harness_get_account_defaults_for_unknown_addresses() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let known = StacksAddress::burn_address(false);
+        let unknown = StacksAddress::burn_address(true);
+        let account = AccountInfo {
+            balance: 1_234_567,
+            locked: 0,
+            unlock_height: 0,
+            nonce: 7,
+        };
+        harness.set_account(known, account);
+
+        let account = harness.get_account(&known).await.unwrap();
+        assert_eq!(account.nonce, 7);
+        assert_eq!(account.balance, 1_234_567);
+
+        let account = harness.get_account(&unknown).await.unwrap();
+        let default = crate::testing::block_observer::DEFAULT_ACCOUNT;
+        assert_eq!(account.nonce, 0);
+        assert_eq!(account.balance, default.balance);
+
+        // A forced failure only affects the next call.
+        harness.fail_next_get_account();
+        assert!(harness.get_account(&known).await.is_err());
+        assert!(harness.get_account(&known).await.is_ok());
+    }
+
+    #[test(tokio::test)]
+    async fn harness_returns_tenure_blocks_in_ascending_order() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 3, 4..5);
+
+        // Each bitcoin block anchors a tenure of four stacks blocks.
+        let tenure: Vec<_> = harness
+            .stacks_blocks()
+            .iter()
+            .filter(|(_, _, btc_block_id)| btc_block_id == &harness.stacks_blocks()[4].2)
+            .map(|(block_id, _, _)| *block_id)
+            .collect();
+        assert_eq!(tenure.len(), 4);
+
+        let tenure_blocks = harness.get_tenure(tenure[3]).await.unwrap();
+        let block_ids: Vec<_> = tenure_blocks
+            .blocks()
+            .iter()
+            .map(NakamotoBlock::block_id)
+            .collect();
+        assert_eq!(block_ids, tenure);
+        assert!(tenure_blocks
+            .blocks()
+            .windows(2)
+            .all(|pair| pair[0].header.chain_length < pair[1].header.chain_length));
+
+        // Asking for the first block of the tenure returns only that block.
+        let tenure_blocks = harness.get_tenure(tenure[0]).await.unwrap();
+        assert_eq!(tenure_blocks.blocks().len(), 1);
+        assert_eq!(tenure_blocks.blocks()[0].block_id(), tenure[0]);
+    }
+
+    #[test(tokio::test)]
+    async fn harness_returns_the_signer_set_of_each_contract() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let deployer = StacksAddress::burn_address(false);
+        let other_deployer = StacksAddress::burn_address(true);
+        let signers = generate_pubkeys(3);
+        harness.set_signer_set(deployer, signers.clone());
+
+        let signer_set = harness.get_current_signer_set(&deployer).await.unwrap();
+        assert_eq!(signer_set, signers);
+
+        let signer_set = harness
+            .get_current_signer_set(&other_deployer)
+            .await
+            .unwrap();
+        assert!(signer_set.is_empty());
+    }
+
+    #[test(tokio::test)]
+    async fn harness_records_submitted_stacks_transactions() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let tx = dummy::stacks_tx(&fake::Faker, &mut rng);
+
+        // By default the transaction is accepted.
+        let response = harness.clone().submit_tx(&tx).await.unwrap();
+        assert!(matches!(response, SubmitTxResponse::Acceptance(txid) if txid == tx.txid()));
+
+        let rejection = TxRejection {
+            error: "transaction rejection".to_string(),
+            reason: RejectionReason::FeeTooLow,
+            reason_data: None,
+            txid: tx.txid(),
+        };
+        harness.set_submit_tx_response(SubmitTxResponse::Rejection(rejection));
+        let response = harness.submit_tx(&tx).await.unwrap();
+        assert!(matches!(response, SubmitTxResponse::Rejection(_)));
+
+        // Submissions through clones end up in the same buffer.
+        let submitted: Vec<_> = harness
+            .submitted_stacks_txs()
+            .iter()
+            .map(StacksTransaction::txid)
+            .collect();
+        assert_eq!(submitted, vec![tx.txid(), tx.txid()]);
+    }
+
+    #[test(tokio::test)]
+    async fn concurrent_nonce_reservations_are_distinct() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut harness = crate::testing::block_observer::TestHarness::generate(&mut rng, 2, 0..5);
+        let address = StacksAddress::burn_address(false);
+        let account = AccountInfo {
+            balance: 0,
+            locked: 0,
+            unlock_height: 0,
+            nonce: 7,
+        };
+        harness.set_account(address, account);
+
+        let reserve = |harness: crate::testing::block_observer::TestHarness| {
+            tokio::spawn(async move {
+                let mut nonces = Vec::new();
+                for _ in 0..50 {
+                    nonces.push(harness.reserve_nonce(&address).unwrap());
+                    tokio::task::yield_now().await;
+                }
+                nonces
+            })
+        };
+        let task1 = reserve(harness.clone());
+        let task2 = reserve(harness.clone());
+
+        let mut nonces = task1.await.unwrap();
+        nonces.extend(task2.await.unwrap());
+        nonces.sort_unstable();
+
+        let expected: Vec<u64> = (7..107).collect();
+        assert_eq!(nonces, expected);
+    }
 }
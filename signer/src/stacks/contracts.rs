@@ -989,19 +989,109 @@ impl AsContractCall for RejectWithdrawalV1 {
     /// Validates that the reject-withdrawal-request satisfies the
     /// following criteria:
     ///
-    /// 1. That the transaction with the associated request_id is stored as
-    ///    an event on the canonical Stacks blockchain.
-    /// 2. That the signer bitmap matches the signer decisions stored in
+    /// 1. That the smart contract deployer matches the deployer in our
+    ///    context.
+    /// 2. That the withdrawal request with the associated request_id is
+    ///    stored as an event on the canonical Stacks blockchain, and that
+    ///    it is still pending at the end of our withdrawal expiry window.
+    /// 3. That the signer bitmap matches the signer decisions stored in
     ///    this signer's database.
-    async fn validate<C>(&self, _ctx: &C, _req_ctx: &ReqContext) -> Result<(), Error>
+    async fn validate<C>(&self, ctx: &C, req_ctx: &ReqContext) -> Result<(), Error>
     where
         C: Context + Send + Sync,
     {
-        // TODO(255): Add validation implementation
+        let db = ctx.get_storage();
+
+        // 1. That the smart contract deployer matches the deployer in our
+        //    context.
+        if self.deployer != req_ctx.deployer {
+            return Err(WithdrawalRejectErrorMsg::DeployerMismatch.into_error(req_ctx, self));
+        }
+
+        // 2. That the withdrawal request is on the canonical Stacks
+        //    blockchain and has expired. Withdrawal requests do not expire
+        //    at all when we have no expiry window configured.
+        let Some(expiry_window) = ctx.config().signer.withdrawal_expiry_window else {
+            return Err(WithdrawalRejectErrorMsg::RequestNotExpired.into_error(req_ctx, self));
+        };
+        let request = db
+            .get_expired_withdrawal_requests(
+                &req_ctx.chain_tip.block_hash,
+                req_ctx.context_window,
+                expiry_window,
+            )
+            .await?
+            .into_iter()
+            .find(|req| req.request_id == self.request_id)
+            .ok_or_else(|| WithdrawalRejectErrorMsg::RequestNotExpired.into_error(req_ctx, self))?;
+
+        // 3. That the signer bitmap matches the bitmap formed from our
+        //    records.
+        let votes = db
+            .get_withdrawal_request_signer_votes(&request.qualified_id(), &req_ctx.aggregate_key)
+            .await?;
+        if self.signer_bitmap != BitArray::from(votes) {
+            return Err(WithdrawalRejectErrorMsg::BitmapMismatch.into_error(req_ctx, self));
+        }
+
         Ok(())
     }
 }
 
+/// A struct for a validation error containing all the necessary context.
+#[derive(Debug)]
+pub struct WithdrawalRejectValidationError {
+    /// The specific error that happened during validation.
+    pub error: WithdrawalRejectErrorMsg,
+    /// The additional information that was used when trying to validate
+    /// the `reject-withdrawal-request` contract call. This includes the
+    /// public key of the signer that was attempting to generate the
+    /// `reject-withdrawal-request` transaction.
+    pub context: ReqContext,
+    /// The specific transaction that was being validated.
+    pub tx: RejectWithdrawalV1,
+}
+
+impl std::fmt::Display for WithdrawalRejectValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // TODO(191): Add the other variables to the error message.
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for WithdrawalRejectValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// The responses for validation of a reject-withdrawal-request smart
+/// contract call transaction.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum WithdrawalRejectErrorMsg {
+    /// The bitmap set in the transaction object should match the one in
+    /// our database.
+    #[error("bitmap does not match expected bitmap from our records")]
+    BitmapMismatch,
+    /// The smart contract deployer is fixed, so this should always match.
+    #[error("the deployer in the transaction does not match the expected deployer")]
+    DeployerMismatch,
+    /// We do not have a record of the withdrawal request in our list of
+    /// expired withdrawal requests.
+    #[error("no record of withdrawal request in expired withdrawal requests")]
+    RequestNotExpired,
+}
+
+impl WithdrawalRejectErrorMsg {
+    fn into_error(self, ctx: &ReqContext, tx: &RejectWithdrawalV1) -> Error {
+        Error::WithdrawalRejectValidation(Box::new(WithdrawalRejectValidationError {
+            error: self,
+            context: *ctx,
+            tx: *tx,
+        }))
+    }
+}
+
 /// This struct is used to generate a properly formatted Stacks transaction
 /// for calling the rotate-keys-wrapper function in the
 /// sbtc-bootstrap-signers smart contract.
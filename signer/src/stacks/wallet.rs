@@ -180,8 +180,9 @@ impl SignerWallet {
 
         match last_key_rotation {
             Some(keys) => {
-                let public_keys = keys.signer_set;
+                let public_keys: BTreeSet<PublicKey> = keys.signer_set.into_iter().collect();
                 let signatures_required = keys.signatures_required;
+                Self::check_signer_set_size(config, &public_keys)?;
                 SignerWallet::new(&public_keys, signatures_required, network_kind, 0)
             }
             None => Self::load_boostrap_wallet(config),
@@ -194,9 +195,27 @@ impl SignerWallet {
         let public_keys = config.bootstrap_signing_set();
         let signatures_required = config.bootstrap_signatures_required;
 
+        Self::check_signer_set_size(config, &public_keys)?;
         SignerWallet::new(&public_keys, signatures_required, network_kind, 0)
     }
 
+    /// Check that the given signer set has the number of signers set in
+    /// the `signer_set_size` field of the config, if it is set.
+    fn check_signer_set_size(
+        config: &SignerConfig,
+        public_keys: &BTreeSet<PublicKey>,
+    ) -> Result<(), Error> {
+        match config.signer_set_size {
+            Some(expected) if public_keys.len() != expected as usize => {
+                Err(Error::SignerSetSizeMismatch {
+                    expected,
+                    actual: public_keys.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn hash_mode() -> OrderIndependentMultisigHashMode {
         MULTISIG_ADDRESS_HASH_MODE
     }
@@ -457,6 +476,7 @@ mod tests {
     use clarity::codec::StacksMessageCodec as _;
     use test_case::test_case;
 
+    use crate::config::Settings;
     use crate::context::Context;
     use crate::signature::sign_stacks_tx;
     use crate::stacks::contracts::ReqContext;
@@ -468,6 +488,7 @@ mod tests {
     use crate::testing::context::TestContext;
     use crate::testing::context::*;
     use crate::testing::storage::model::TestData;
+    use crate::testing::wsts::generate_signer_set_public_keys;
 
     use super::*;
 
@@ -749,6 +770,37 @@ mod tests {
         SignerWallet::load_boostrap_wallet(&ctx.config().signer).unwrap();
     }
 
+    #[test_case(4, 4; "correct size")]
+    #[test_case(4, 5; "too many signers")]
+    #[test_case(4, 3; "too few signers")]
+    fn signer_set_size_is_enforced(expected: u16, num_signers: usize) {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut settings = Settings::new_from_default_config().unwrap();
+
+        // The signer's own public key is always included in the bootstrap
+        // signing set, so we add it to the generated keys ourselves.
+        let self_public_key = PublicKey::from_private_key(&settings.signer.private_key);
+        let mut public_keys = generate_signer_set_public_keys(&mut rng, num_signers - 1);
+        public_keys.push(self_public_key);
+
+        settings.signer.bootstrap_signing_set = public_keys;
+        settings.signer.bootstrap_signatures_required = 2;
+        settings.signer.signer_set_size = Some(expected);
+
+        let result = SignerWallet::load_boostrap_wallet(&settings.signer);
+        if num_signers == expected as usize {
+            assert_eq!(result.unwrap().num_signers(), expected);
+        } else {
+            match result.unwrap_err() {
+                Error::SignerSetSizeMismatch { expected: e, actual } => {
+                    assert_eq!(e, expected);
+                    assert_eq!(actual, num_signers);
+                }
+                err => panic!("unexpected error: {err}"),
+            }
+        }
+    }
+
     #[test_case(1, 1)]
     #[test_case(2, 3)]
     #[test_case(11, 15)]
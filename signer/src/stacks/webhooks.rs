@@ -32,6 +32,9 @@ use stacks_common::types::chainstate::BurnchainHeaderHash;
 use stacks_common::types::chainstate::StacksBlockId;
 
 use crate::error::Error;
+use crate::stacks::events::EventError;
+use crate::stacks::events::RegistryEvent;
+use crate::stacks::events::TxInfo;
 
 /// This struct represents the body of POST /new_block events from a stacks
 /// node.
@@ -83,6 +86,34 @@ pub struct NewBlockEvent {
     pub parent_burn_block_timestamp: u64,
 }
 
+impl NewBlockEvent {
+    /// Scan the events in this block for `print` events emitted by the
+    /// given sbtc-registry contract and decode them into
+    /// [`RegistryEvent`]s. Each decoded event is returned alongside the
+    /// [`TxInfo`] of the transaction that emitted it.
+    ///
+    /// Events from any other contract, events with a different topic, and
+    /// events from transactions that were not committed are skipped.
+    /// Although transactions can fail, only successful transactions emit
+    /// sBTC print events, since those events are emitted at the very end
+    /// of the contract call.
+    pub fn registry_events<'a>(
+        &'a self,
+        registry_id: &'a QualifiedContractIdentifier,
+    ) -> impl Iterator<Item = (TxInfo, Result<RegistryEvent, EventError>)> + 'a {
+        let block_id = self.index_block_hash;
+        self.events
+            .iter()
+            .filter(|x| x.committed)
+            .filter_map(|x| x.contract_event.as_ref().map(|ev| (ev, x.txid)))
+            .filter(move |(ev, _)| &ev.contract_identifier == registry_id && ev.topic == "print")
+            .map(move |(ev, txid)| {
+                let tx_info = TxInfo { txid, block_id };
+                (tx_info, RegistryEvent::try_new(ev.value.clone(), tx_info))
+            })
+    }
+}
+
 /// This matches the json value that is defined in stacks-core[^1]. It
 /// contains the raw transaction and the result of the transaction.
 ///
@@ -358,4 +389,43 @@ mod tests {
         assert_eq!(event.block_hash, expected_block_hash);
         assert_eq!(event.transactions.first().unwrap().txid, expected_txid);
     }
+
+    const WITHDRAWAL_CREATE_WEBHOOK: &str =
+        include_str!("../../tests/fixtures/withdrawal-create-event.json");
+
+    #[test]
+    fn registry_events_decodes_withdrawal_create() {
+        let event: NewBlockEvent = serde_json::from_str(WITHDRAWAL_CREATE_WEBHOOK).unwrap();
+        let registry_id = QualifiedContractIdentifier::parse(
+            "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.sbtc-registry",
+        )
+        .unwrap();
+
+        let events: Vec<_> = event.registry_events(&registry_id).collect();
+        assert_eq!(events.len(), 1);
+
+        let (tx_info, decoded) = events.into_iter().next().unwrap();
+        let RegistryEvent::WithdrawalCreate(withdrawal) = decoded.unwrap() else {
+            panic!("expected a withdrawal-create event");
+        };
+
+        assert_eq!(withdrawal.txid, event.events[0].txid);
+        assert_eq!(withdrawal.txid, tx_info.txid);
+        assert_eq!(withdrawal.block_id, event.index_block_hash);
+        assert_eq!(withdrawal.request_id, 1);
+        assert_eq!(withdrawal.amount, 22500);
+        assert_eq!(withdrawal.max_fee, 3000);
+        assert_eq!(withdrawal.block_height, 137);
+    }
+
+    #[test]
+    fn registry_events_skips_other_contracts() {
+        let event: NewBlockEvent = serde_json::from_str(WITHDRAWAL_CREATE_WEBHOOK).unwrap();
+        let other_id = QualifiedContractIdentifier::parse(
+            "SN3R84XZYA63QS28932XQF3G1J8R9PC3W76P9CSQS.not-sbtc",
+        )
+        .unwrap();
+
+        assert_eq!(event.registry_events(&other_id).count(), 0);
+    }
 }
@@ -126,6 +126,13 @@ pub struct Store {
     /// Bitcoin withdrawal outputs
     pub bitcoin_withdrawal_outputs:
         HashMap<(u64, model::StacksBlockHash), model::BitcoinWithdrawalOutput>,
+
+    /// The last stacks block that the block observer has processed
+    pub stacks_checkpoint: Option<model::StacksBlockHash>,
+
+    /// The withdrawal accept events that are held back until their sweep
+    /// is deep enough, keyed by request ID
+    pub held_withdrawals: BTreeMap<u64, WithdrawalAcceptEvent>,
 }
 
 impl Store {
@@ -365,22 +372,28 @@ impl super::DbRead for SharedStore {
         .take(context_window as usize)
         .collect::<HashSet<_>>();
 
-        Ok(deposit_requests
+        // The height of the canonical block that confirmed the given
+        // transaction, if there is one in the context window.
+        let confirmed_height = |txid: &model::BitcoinTxId| {
+            store
+                .bitcoin_transactions_to_blocks
+                .get(txid)
+                .unwrap_or(&Vec::new())
+                .iter()
+                .filter(|block_hash| canonical_bitcoin_blocks.contains(block_hash))
+                .filter_map(|block_hash| store.bitcoin_blocks.get(block_hash))
+                .map(|block_included: &model::BitcoinBlock| block_included.block_height)
+                .next()
+        };
+
+        let mut pending_accepted: Vec<model::DepositRequest> = deposit_requests
             .into_iter()
             .filter(|deposit_request| {
-                store
-                    .bitcoin_transactions_to_blocks
-                    .get(&deposit_request.txid)
-                    .unwrap_or(&Vec::new())
-                    .iter()
-                    .filter(|block_hash| canonical_bitcoin_blocks.contains(block_hash))
-                    .filter_map(|block_hash| store.bitcoin_blocks.get(block_hash))
-                    .map(|block_included: &model::BitcoinBlock| {
-                        let unlock_height =
-                            block_included.block_height as u32 + deposit_request.lock_time;
+                confirmed_height(&deposit_request.txid)
+                    .map(|block_height| {
+                        let unlock_height = block_height as u32 + deposit_request.lock_time;
                         unlock_height >= minimum_acceptable_unlock_height
                     })
-                    .next()
                     .unwrap_or(false)
             })
             .filter(|deposit_request| {
@@ -396,7 +409,18 @@ impl super::DbRead for SharedStore {
                     })
                     .unwrap_or_default()
             })
-            .collect())
+            .collect();
+
+        pending_accepted.sort_by_cached_key(|deposit_request| {
+            let block_height = confirmed_height(&deposit_request.txid);
+            (
+                block_height,
+                deposit_request.txid,
+                deposit_request.output_index,
+            )
+        });
+
+        Ok(pending_accepted)
     }
 
     async fn get_accepted_deposit_requests(
@@ -548,6 +572,50 @@ impl super::DbRead for SharedStore {
             .collect())
     }
 
+    async fn get_expired_withdrawal_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        expiry_window: u16,
+    ) -> Result<Vec<model::WithdrawalRequest>, Error> {
+        let store = self.lock().await;
+        let Some(chain_tip_height) = store
+            .bitcoin_blocks
+            .get(chain_tip)
+            .map(|block| block.block_height)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let anchor_height = |request: &model::WithdrawalRequest| {
+            store
+                .stacks_blocks
+                .get(&request.block_hash)
+                .and_then(|block| store.bitcoin_blocks.get(&block.bitcoin_anchor))
+                .map(|block| block.block_height)
+        };
+
+        Ok(store
+            .get_withdrawal_requests(chain_tip, context_window)
+            .into_iter()
+            .filter(|request| {
+                anchor_height(request).is_some_and(|height| {
+                    chain_tip_height.saturating_sub(height) > u64::from(expiry_window)
+                })
+            })
+            .filter(|request| {
+                let key = (request.request_id, request.block_hash);
+                !store.bitcoin_withdrawal_outputs.contains_key(&key)
+                    && !store
+                        .withdrawal_accept_events
+                        .contains_key(&request.request_id)
+                    && !store
+                        .withdrawal_reject_events
+                        .contains_key(&request.request_id)
+            })
+            .collect())
+    }
+
     async fn get_withdrawal_request_report(
         &self,
         _chain_tip: &model::BitcoinBlockHash,
@@ -934,6 +1002,20 @@ impl super::DbRead for SharedStore {
             .get(sighash)
             .map(|s| s.will_sign))
     }
+
+    async fn get_stacks_checkpoint(&self) -> Result<Option<model::StacksBlockHash>, Error> {
+        Ok(self.lock().await.stacks_checkpoint)
+    }
+
+    async fn get_held_withdrawals(&self) -> Result<Vec<WithdrawalAcceptEvent>, Error> {
+        Ok(self
+            .lock()
+            .await
+            .held_withdrawals
+            .values()
+            .cloned()
+            .collect())
+    }
 }
 
 impl super::DbWrite for SharedStore {
@@ -1260,4 +1342,25 @@ impl super::DbWrite for SharedStore {
         });
         Ok(())
     }
+
+    async fn write_stacks_checkpoint(
+        &self,
+        checkpoint: Option<&model::StacksBlockHash>,
+    ) -> Result<(), Error> {
+        self.lock().await.stacks_checkpoint = checkpoint.copied();
+        Ok(())
+    }
+
+    async fn write_held_withdrawal(&self, event: &WithdrawalAcceptEvent) -> Result<(), Error> {
+        self.lock()
+            .await
+            .held_withdrawals
+            .insert(event.request_id, event.clone());
+        Ok(())
+    }
+
+    async fn delete_held_withdrawal(&self, request_id: u64) -> Result<(), Error> {
+        self.lock().await.held_withdrawals.remove(&request_id);
+        Ok(())
+    }
 }
@@ -71,6 +71,9 @@ pub trait DbRead {
     /// For an individual signer, 'accepted' means their blocklist client
     /// hasn't blocked the request and they are part of the signing set
     /// that generated the aggregate key locking the deposit.
+    ///
+    /// The deposit requests are returned oldest first, that is, by the
+    /// height of the block that confirmed them, then by their outpoint.
     fn get_pending_accepted_deposit_requests(
         &self,
         chain_tip: &model::BitcoinBlockHash,
@@ -167,6 +170,21 @@ pub trait DbRead {
         threshold: u16,
     ) -> impl Future<Output = Result<Vec<model::WithdrawalRequest>, Error>> + Send;
 
+    /// Get the withdrawal requests that have expired
+    ///
+    /// These are the withdrawal requests, on the blockchain identified by
+    /// the given chain tip, that were created in a stacks block anchored
+    /// more than `expiry_window` bitcoin blocks before the chain tip, and
+    /// that have not been included in a sweep transaction, accepted or
+    /// rejected since. Only requests within the context window are
+    /// returned.
+    fn get_expired_withdrawal_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        expiry_window: u16,
+    ) -> impl Future<Output = Result<Vec<model::WithdrawalRequest>, Error>> + Send;
+
     /// This function returns a withdrawal request report that does the
     /// following:
     ///
@@ -360,6 +378,18 @@ pub trait DbRead {
         &self,
         sighash: &model::SigHash,
     ) -> impl Future<Output = Result<Option<bool>, Error>> + Send;
+
+    /// Get the last stacks block that the block observer has processed,
+    /// if any.
+    fn get_stacks_checkpoint(
+        &self,
+    ) -> impl Future<Output = Result<Option<model::StacksBlockHash>, Error>> + Send;
+
+    /// Get the withdrawal accept events that are held back until their
+    /// sweep transaction has enough confirmations, ordered by request ID.
+    fn get_held_withdrawals(
+        &self,
+    ) -> impl Future<Output = Result<Vec<WithdrawalAcceptEvent>, Error>> + Send;
 }
 
 /// Represents the ability to write data to the signer storage.
@@ -507,4 +537,26 @@ pub trait DbWrite {
         &self,
         withdrawals_outputs: &[model::BitcoinWithdrawalOutput],
     ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Write the last stacks block that the block observer has processed,
+    /// or clear it with `None`.
+    fn write_stacks_checkpoint(
+        &self,
+        checkpoint: Option<&model::StacksBlockHash>,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Hold back the withdrawal in the given accept event until its sweep
+    /// transaction has enough confirmations. The event must have already
+    /// been written with [`DbWrite::write_withdrawal_accept_event`]. This
+    /// replaces any event that is already held for the same request.
+    fn write_held_withdrawal(
+        &self,
+        event: &WithdrawalAcceptEvent,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Stop holding back the withdrawal with the given request ID.
+    fn delete_held_withdrawal(
+        &self,
+        request_id: u64,
+    ) -> impl Future<Output = Result<(), Error>> + Send;
 }
@@ -872,6 +872,7 @@ impl super::DbRead for PgStore {
                   , deposit_requests.lock_time
                   , deposit_requests.signers_public_key
                   , deposit_requests.sender_script_pub_keys
+                  , MIN(transactions.block_height) AS block_height
                 FROM transactions_in_window transactions
                 JOIN sbtc_signer.deposit_requests deposit_requests USING(txid)
                 JOIN sbtc_signer.deposit_signers signers USING(txid, output_index)
@@ -903,8 +904,13 @@ impl super::DbRead for PgStore {
               , accepted_deposits.lock_time
               , accepted_deposits.signers_public_key
               , accepted_deposits.sender_script_pub_keys
+              , accepted_deposits.block_height
             HAVING
                 COUNT(transactions_in_window.txid) = 0
+            ORDER BY
+                accepted_deposits.block_height ASC
+              , accepted_deposits.txid ASC
+              , accepted_deposits.output_index ASC
             "#,
         )
         .bind(chain_tip)
@@ -1341,6 +1347,94 @@ impl super::DbRead for PgStore {
         .map_err(Error::SqlxQuery)
     }
 
+    async fn get_expired_withdrawal_requests(
+        &self,
+        chain_tip: &model::BitcoinBlockHash,
+        context_window: u16,
+        expiry_window: u16,
+    ) -> Result<Vec<model::WithdrawalRequest>, Error> {
+        let Some(stacks_chain_tip) = self.get_stacks_chain_tip(chain_tip).await? else {
+            return Ok(Vec::new());
+        };
+        sqlx::query_as::<_, model::WithdrawalRequest>(
+            r#"
+            WITH RECURSIVE extended_context_window AS (
+                SELECT
+                    block_hash
+                  , parent_hash
+                  , 1 AS depth
+                FROM sbtc_signer.bitcoin_blocks
+                WHERE block_hash = $1
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , last.depth + 1
+                FROM sbtc_signer.bitcoin_blocks parent
+                JOIN extended_context_window last ON parent.block_hash = last.parent_hash
+                WHERE last.depth <= $3
+            ),
+            stacks_context_window AS (
+                SELECT
+                    stacks_blocks.block_hash
+                  , stacks_blocks.parent_hash
+                  , stacks_blocks.bitcoin_anchor
+                FROM sbtc_signer.stacks_blocks stacks_blocks
+                WHERE stacks_blocks.block_hash = $2
+
+                UNION ALL
+
+                SELECT
+                    parent.block_hash
+                  , parent.parent_hash
+                  , parent.bitcoin_anchor
+                FROM sbtc_signer.stacks_blocks parent
+                JOIN stacks_context_window last
+                        ON parent.block_hash = last.parent_hash
+                JOIN extended_context_window block
+                        ON block.block_hash = parent.bitcoin_anchor
+            )
+            SELECT
+                wr.request_id
+              , wr.txid
+              , wr.block_hash
+              , wr.recipient
+              , wr.amount
+              , wr.max_fee
+              , wr.sender_address
+            FROM sbtc_signer.withdrawal_requests wr
+            JOIN stacks_context_window sc ON wr.block_hash = sc.block_hash
+            JOIN extended_context_window anchor ON anchor.block_hash = sc.bitcoin_anchor
+            WHERE anchor.depth > $4 + 1
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.bitcoin_withdrawals_outputs bwo
+                WHERE bwo.request_id = wr.request_id
+                  AND bwo.stacks_block_hash = wr.block_hash
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.withdrawal_accept_events wae
+                WHERE wae.request_id = wr.request_id
+              )
+              AND NOT EXISTS (
+                SELECT 1
+                FROM sbtc_signer.withdrawal_reject_events wre
+                WHERE wre.request_id = wr.request_id
+              )
+            "#,
+        )
+        .bind(chain_tip)
+        .bind(stacks_chain_tip.block_hash)
+        .bind(i32::from(context_window))
+        .bind(i32::from(expiry_window))
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
     async fn get_withdrawal_request_report(
         &self,
         _chain_tip: &model::BitcoinBlockHash,
@@ -1904,6 +1998,92 @@ impl super::DbRead for PgStore {
         .await
         .map_err(Error::SqlxQuery)
     }
+
+    async fn get_stacks_checkpoint(&self) -> Result<Option<model::StacksBlockHash>, Error> {
+        sqlx::query_scalar::<_, model::StacksBlockHash>(
+            r#"
+            SELECT block_hash
+            FROM sbtc_signer.stacks_checkpoint
+            "#,
+        )
+        .fetch_optional(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)
+    }
+
+    async fn get_held_withdrawals(&self) -> Result<Vec<WithdrawalAcceptEvent>, Error> {
+        #[allow(clippy::type_complexity)]
+        let rows = sqlx::query_as::<
+            _,
+            (
+                [u8; 32],
+                [u8; 32],
+                i64,
+                [u8; 16],
+                [u8; 32],
+                i64,
+                i64,
+                [u8; 32],
+                i64,
+                [u8; 32],
+            ),
+        >(
+            r#"
+            SELECT DISTINCT ON (held.request_id)
+                wae.txid
+              , wae.block_hash
+              , wae.request_id
+              , wae.signer_bitmap
+              , wae.bitcoin_txid
+              , wae.output_index
+              , wae.fee
+              , wae.sweep_block_hash
+              , wae.sweep_block_height
+              , wae.sweep_txid
+            FROM sbtc_signer.held_withdrawals held
+            JOIN sbtc_signer.withdrawal_accept_events wae
+              ON wae.request_id = held.request_id
+             AND wae.txid = held.txid
+             AND wae.block_hash = held.block_hash
+            ORDER BY held.request_id
+            "#,
+        )
+        .fetch_all(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        rows.into_iter()
+            .map(|row| {
+                let (
+                    txid,
+                    block_id,
+                    request_id,
+                    signer_bitmap,
+                    bitcoin_txid,
+                    vout,
+                    fee,
+                    sweep_block_hash,
+                    sweep_block_height,
+                    sweep_txid,
+                ) = row;
+                Ok(WithdrawalAcceptEvent {
+                    txid: blockstack_lib::burnchains::Txid(txid),
+                    block_id: StacksBlockId(block_id),
+                    request_id: u64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?,
+                    signer_bitmap: bitvec::array::BitArray::new(signer_bitmap),
+                    outpoint: OutPoint {
+                        txid: bitcoin::Txid::from_byte_array(bitcoin_txid),
+                        vout: u32::try_from(vout).map_err(Error::ConversionDatabaseInt)?,
+                    },
+                    fee: u64::try_from(fee).map_err(Error::ConversionDatabaseInt)?,
+                    sweep_block_hash: bitcoin::BlockHash::from_byte_array(sweep_block_hash),
+                    sweep_block_height: u64::try_from(sweep_block_height)
+                        .map_err(Error::ConversionDatabaseInt)?,
+                    sweep_txid: bitcoin::Txid::from_byte_array(sweep_txid),
+                })
+            })
+            .collect()
+    }
 }
 
 impl super::DbWrite for PgStore {
@@ -2880,6 +3060,65 @@ impl super::DbWrite for PgStore {
 
         Ok(())
     }
+
+    async fn write_stacks_checkpoint(
+        &self,
+        checkpoint: Option<&model::StacksBlockHash>,
+    ) -> Result<(), Error> {
+        let Some(block_hash) = checkpoint else {
+            sqlx::query("DELETE FROM sbtc_signer.stacks_checkpoint")
+                .execute(&self.0)
+                .await
+                .map_err(Error::SqlxQuery)?;
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.stacks_checkpoint (block_hash)
+            VALUES ($1)
+            ON CONFLICT (id) DO UPDATE
+            SET block_hash = EXCLUDED.block_hash
+              , updated_at = NOW()
+            "#,
+        )
+        .bind(block_hash)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn write_held_withdrawal(&self, event: &WithdrawalAcceptEvent) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO sbtc_signer.held_withdrawals (request_id, txid, block_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (request_id) DO UPDATE
+            SET txid = EXCLUDED.txid
+              , block_hash = EXCLUDED.block_hash
+            "#,
+        )
+        .bind(i64::try_from(event.request_id).map_err(Error::ConversionDatabaseInt)?)
+        .bind(event.txid.0)
+        .bind(event.block_id.0)
+        .execute(&self.0)
+        .await
+        .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
+
+    async fn delete_held_withdrawal(&self, request_id: u64) -> Result<(), Error> {
+        sqlx::query("DELETE FROM sbtc_signer.held_withdrawals WHERE request_id = $1")
+            .bind(i64::try_from(request_id).map_err(Error::ConversionDatabaseInt)?)
+            .execute(&self.0)
+            .await
+            .map_err(Error::SqlxQuery)?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
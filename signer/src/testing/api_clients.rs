@@ -1,16 +1,15 @@
 //! Testing helpers for api clients
 
-use url::Url;
-
 use crate::bitcoin::MockBitcoinInteract;
+use crate::config::BitcoinConfig;
 use crate::config::Settings;
 use crate::error::Error;
 use crate::stacks::api::MockStacksInteract;
 
-impl TryFrom<&[Url]> for MockBitcoinInteract {
+impl TryFrom<&BitcoinConfig> for MockBitcoinInteract {
     type Error = Error;
 
-    fn try_from(_: &[Url]) -> Result<Self, Self::Error> {
+    fn try_from(_: &BitcoinConfig) -> Result<Self, Self::Error> {
         Ok(Self::default())
     }
 }
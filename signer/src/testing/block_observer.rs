@@ -1,17 +1,28 @@
 //! Test utilities for the block observer
 
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::ops::Deref;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use bitcoin::hashes::Hash;
 use bitcoin::Amount;
 use bitcoin::BlockHash;
+use bitcoin::OutPoint;
+use bitcoin::ScriptBuf;
 use bitcoin::Txid;
 use bitcoincore_rpc_json::GetTxOutResult;
 use blockstack_lib::chainstate::burn::ConsensusHash;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
 use blockstack_lib::chainstate::nakamoto::NakamotoBlockHeader;
 use blockstack_lib::chainstate::stacks::StacksTransaction;
+use blockstack_lib::codec::StacksMessageCodec as _;
 use blockstack_lib::net::api::getcontractsrc::ContractSrcResponse;
 use blockstack_lib::net::api::getinfo::RPCPeerInfoData;
 use blockstack_lib::net::api::getpoxinfo::RPCPoxEpoch;
@@ -23,10 +34,18 @@ use blockstack_lib::types::chainstate::StacksBlockId;
 use clarity::types::chainstate::BurnchainHeaderHash;
 use clarity::types::chainstate::SortitionId;
 use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::PrincipalData;
+use emily_client::apis::Error as EmilyError;
+use emily_client::apis::ResponseContent;
 use emily_client::models::Chainstate;
 use emily_client::models::CreateWithdrawalRequestBody;
+use emily_client::models::Deposit;
+use emily_client::models::DepositParameters;
+use emily_client::models::DepositUpdate;
+use emily_client::models::Status;
 use emily_client::models::Withdrawal;
 use rand::seq::IteratorRandom;
+use rand::SeedableRng as _;
 use sbtc::deposits::CreateDepositRequest;
 
 use crate::bitcoin::rpc::BitcoinTxInfo;
@@ -35,11 +54,14 @@ use crate::bitcoin::utxo;
 use crate::bitcoin::BitcoinInteract;
 use crate::bitcoin::GetTransactionFeeResult;
 use crate::bitcoin::TransactionLookupHint;
+use crate::bitcoin::Utxo;
 use crate::context::SbtcLimits;
+use crate::emily_client::EmilyClientError;
 use crate::emily_client::EmilyInteract;
 use crate::error::Error;
 use crate::keys::PublicKey;
 use crate::stacks::api::AccountInfo;
+use crate::stacks::api::Affirmations;
 use crate::stacks::api::FeePriority;
 use crate::stacks::api::StacksInteract;
 use crate::stacks::api::SubmitTxResponse;
@@ -49,6 +71,25 @@ use crate::storage::model;
 use crate::testing::dummy;
 use crate::util::ApiFallbackClient;
 
+/// The account info returned by the test harness for stacks addresses
+/// that have not been set with [`TestHarness::set_account`].
+pub const DEFAULT_ACCOUNT: AccountInfo = AccountInfo {
+    balance: 1_000_000_000_000,
+    locked: 0,
+    unlock_height: 0,
+    nonce: 0,
+};
+
+/// The error returned when adding a pending deposit request to a test
+/// harness that already holds as many pending deposit requests as it is
+/// allowed to.
+#[derive(Debug, thiserror::Error)]
+#[error("the test harness already holds the maximum of {max_pending_deposits} pending deposits")]
+pub struct PendingDepositsFull {
+    /// The maximum number of pending deposit requests in the harness.
+    pub max_pending_deposits: usize,
+}
+
 /// A test harness for the block observer.
 #[derive(Debug, Clone)]
 pub struct TestHarness {
@@ -59,12 +100,187 @@ pub struct TestHarness {
     stacks_blocks: Vec<(StacksBlockId, NakamotoBlock, BlockHash)>,
     /// This represents deposit transactions
     deposits: HashMap<Txid, (GetTxResponse, BitcoinTxInfo)>,
+    /// This represents the confirmed transactions that can be looked up
+    /// using `get_tx_info`, keyed by their txid and the hash of the block
+    /// that confirmed them.
+    tx_infos: HashMap<(Txid, BlockHash), BitcoinTxInfo>,
     /// This represents deposit requests that have not been processed, i.e.
-    /// they are received from the Emily API.
-    pending_deposits: Vec<CreateDepositRequest>,
+    /// they are received from the Emily API. Like the broadcast
+    /// transactions, these are shared between clones of the harness.
+    pending_deposits: Arc<Mutex<Vec<CreateDepositRequest>>>,
+    /// The maximum number of pending deposit requests that the harness
+    /// holds. When this is `None` there is no limit.
+    max_pending_deposits: Option<usize>,
+    /// This represents withdrawal requests that have not been processed,
+    /// i.e. they are received from the Emily API. Like the pending
+    /// deposits, these are shared between clones of the harness.
+    pending_withdrawals: Arc<Mutex<Vec<CreateWithdrawalRequestBody>>>,
+    /// This represents deposit requests that were evicted from the
+    /// pending deposits because they were marked as failed, along with
+    /// the reason given for the failure.
+    evicted_deposits: Arc<Mutex<HashMap<OutPoint, String>>>,
+    /// This represents deposit requests that were removed from the
+    /// pending deposits because they were marked as reclaimed.
+    reclaimed_deposits: Arc<Mutex<Vec<OutPoint>>>,
+    /// This represents deposit requests that were removed from the
+    /// pending deposits because they are locked to a key that the signers
+    /// do not know about.
+    rejected_wrong_key_deposits: Arc<Mutex<Vec<OutPoint>>>,
+    /// The latest status reported to Emily for each deposit, through
+    /// `update_deposits`. Like the pending deposits, these are shared
+    /// between clones of the harness.
+    deposit_statuses: Arc<Mutex<HashMap<OutPoint, Status>>>,
+    /// The transactions that have been broadcast through this harness.
+    /// This is shared between clones of the harness, so that the
+    /// transactions broadcast by a clone handed to a component under test
+    /// can be inspected by the test.
+    broadcast_transactions: Arc<Mutex<Vec<bitcoin::Transaction>>>,
+    /// The stacks transactions that have been submitted through this
+    /// harness. Like the broadcast transactions, these are shared between
+    /// clones of the harness.
+    submitted_stacks_txs: Arc<Mutex<Vec<StacksTransaction>>>,
+    /// The response returned by `submit_tx`. When this is `None` every
+    /// submitted transaction is accepted.
+    submit_tx_response: Option<SubmitTxResponse>,
+    /// The number of reward slots reported in the PoX info.
+    reward_slots: u32,
+    /// The affirmation maps reported by the node. When this is `None`
+    /// the affirmation maps are parsed from the node info.
+    affirmations: Option<Affirmations>,
+    /// The current signer set stored in the `sbtc-registry` contract
+    /// deployed by each contract principal.
+    signer_sets: HashMap<StacksAddress, Vec<PublicKey>>,
+    /// The aggregate keys recorded in the `aggregate-pubkeys` map of the
+    /// `sbtc-registry` contract.
+    registered_aggregate_keys: HashSet<PublicKey>,
+    /// The account info of stacks addresses.
+    accounts: HashMap<StacksAddress, AccountInfo>,
+    /// The sBTC balances of principals, keyed by the principal that
+    /// deployed the `sbtc-token` contract and the principal holding the
+    /// balance.
+    sbtc_balances: HashMap<(StacksAddress, PrincipalData), u128>,
+    /// The next nonce that has not been reserved for each stacks address
+    /// that has had a nonce reserved. This is shared between clones of the
+    /// harness.
+    reserved_nonces: Arc<Mutex<HashMap<StacksAddress, u64>>>,
+    /// This maps the txids of transactions that were replaced using
+    /// replace-by-fee to the txids of their replacements.
+    replacements: HashMap<Txid, Txid>,
+    /// The txids of the transactions whose funding inputs were spent by
+    /// a transaction that is not a replacement that can be found.
+    abandoned_txids: HashSet<Txid>,
+    /// This represents the unspent transaction outputs known to the
+    /// bitcoin node.
+    utxos: HashMap<OutPoint, Utxo>,
+    /// Whether the next call to `get_block` should fail. This is shared
+    /// between clones of the harness.
+    fail_next_get_block: Arc<AtomicBool>,
+    /// Whether the next call to `get_account` should fail. This is shared
+    /// between clones of the harness.
+    fail_next_get_account: Arc<AtomicBool>,
+    /// The number of upcoming calls to `get_deposits` that should fail.
+    /// This is shared between clones of the harness.
+    failing_get_deposits: Arc<AtomicUsize>,
+    /// The number of upcoming calls to `update_deposits` that should
+    /// fail. This is shared between clones of the harness.
+    failing_update_deposits: Arc<AtomicUsize>,
+    /// Whether the next call to `get_tenure_info` should return a tenure
+    /// that does not contain the tip block. This is shared between clones
+    /// of the harness.
+    inconsistent_next_tenure_info: Arc<AtomicBool>,
+    /// How long each call to `get_tx_info` takes to return.
+    tx_info_delay: Duration,
+    /// The number of calls to `get_tx_info` that are in progress. This is
+    /// shared between clones of the harness.
+    tx_info_calls_in_flight: Arc<AtomicUsize>,
+    /// The largest number of calls to `get_tx_info` that have been in
+    /// progress at the same time. This is shared between clones of the
+    /// harness.
+    max_tx_info_calls_in_flight: Arc<AtomicUsize>,
+    /// The fee rate returned by `estimate_fee_rate` once the queued fee
+    /// rates have been used up.
+    fee_rate: f64,
+    /// The fee rates returned by the next calls to `estimate_fee_rate`,
+    /// in order, simulating a changing mempool. This is shared between
+    /// clones of the harness.
+    queued_fee_rates: Arc<Mutex<VecDeque<f64>>>,
+    /// The minimum number of confirmations a deposit must have, as set in
+    /// Emily. This is shared between clones of the harness.
+    min_confirmations: Arc<Mutex<Option<u64>>>,
+}
+
+/// A builder for a [`TestHarness`] filled with random data. Harnesses
+/// built with the same settings are identical.
+#[derive(Debug, Clone)]
+pub struct TestHarnessBuilder {
+    /// The number of bitcoin blocks in the harness.
+    num_bitcoin_blocks: usize,
+    /// The range of the number of stacks blocks anchored to each bitcoin
+    /// block.
+    num_stacks_blocks_per_bitcoin_block: std::ops::Range<usize>,
+    /// The deposit transactions added to the harness.
+    deposits: Vec<(Txid, GetTxResponse)>,
+    /// The seed of the source of randomness used to generate the harness.
+    seed: u64,
+}
+
+impl Default for TestHarnessBuilder {
+    fn default() -> Self {
+        Self {
+            num_bitcoin_blocks: 5,
+            num_stacks_blocks_per_bitcoin_block: 0..5,
+            deposits: Vec::new(),
+            seed: 46,
+        }
+    }
+}
+
+impl TestHarnessBuilder {
+    /// Set the number of bitcoin blocks in the harness.
+    pub fn bitcoin_blocks(mut self, num_bitcoin_blocks: usize) -> Self {
+        self.num_bitcoin_blocks = num_bitcoin_blocks;
+        self
+    }
+
+    /// Set the range of the number of stacks blocks anchored to each
+    /// bitcoin block.
+    pub fn stacks_per_block(mut self, range: std::ops::Range<usize>) -> Self {
+        self.num_stacks_blocks_per_bitcoin_block = range;
+        self
+    }
+
+    /// Add the given deposit transactions to the harness.
+    pub fn with_deposits(mut self, deposits: Vec<(Txid, GetTxResponse)>) -> Self {
+        self.deposits.extend(deposits);
+        self
+    }
+
+    /// Set the seed of the source of randomness used to generate the
+    /// harness.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Build the test harness.
+    pub fn build(self) -> TestHarness {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(self.seed);
+        let mut harness = TestHarness::generate(
+            &mut rng,
+            self.num_bitcoin_blocks,
+            self.num_stacks_blocks_per_bitcoin_block,
+        );
+        harness.add_deposits(&self.deposits);
+        harness
+    }
 }
 
 impl TestHarness {
+    /// Create a builder for a test harness filled with random data.
+    pub fn builder() -> TestHarnessBuilder {
+        TestHarnessBuilder::default()
+    }
+
     /// Get the Bitcoin blocks in the test harness.
     pub fn bitcoin_blocks(&self) -> &[bitcoin::Block] {
         &self.bitcoin_blocks
@@ -75,6 +291,33 @@ impl TestHarness {
         &self.stacks_blocks
     }
 
+    /// Get the Stacks chain tip as of the bitcoin block at the given burn
+    /// height. This is the highest Stacks block whose tenure is anchored
+    /// to a bitcoin block at or below the burn height, returned with its
+    /// height.
+    ///
+    /// The first bitcoin block in the test harness has a burn height of
+    /// zero, and every other block is one higher than its parent.
+    pub fn stacks_tip_at_burn_height(&self, burn_height: usize) -> Option<(StacksBlockId, u64)> {
+        let mut burn_heights: HashMap<BlockHash, usize> = HashMap::new();
+        for block in self.bitcoin_blocks.iter() {
+            let height = burn_heights
+                .get(&block.header.prev_blockhash)
+                .map_or(0, |parent_height| parent_height + 1);
+            burn_heights.insert(block.block_hash(), height);
+        }
+
+        self.stacks_blocks
+            .iter()
+            .filter(|(_, _, btc_block_hash)| {
+                burn_heights
+                    .get(btc_block_hash)
+                    .is_some_and(|height| *height <= burn_height)
+            })
+            .map(|(block_id, block, _)| (*block_id, block.header.chain_length))
+            .max_by_key(|(_, chain_length)| *chain_length)
+    }
+
     /// Get the deposit transactions in the test harness.
     pub fn deposits(&self) -> &HashMap<Txid, (GetTxResponse, BitcoinTxInfo)> {
         &self.deposits
@@ -95,7 +338,7 @@ impl TestHarness {
             block_hash: response
                 .block_hash
                 .unwrap_or(bitcoin::BlockHash::all_zeros()),
-            confirmations: 0,
+            confirmations: response.confirmations.unwrap_or(0),
             block_time: 0,
         };
         self.deposits.insert(txid, (response, tx_info));
@@ -108,22 +351,220 @@ impl TestHarness {
         }
     }
 
+    /// Add the info of a transaction confirmed in the given block to the
+    /// test harness.
+    pub fn add_tx_info(&mut self, txid: Txid, block_hash: BlockHash, tx_info: BitcoinTxInfo) {
+        self.tx_infos.insert((txid, block_hash), tx_info);
+    }
+
+    /// Add the info of multiple confirmed transactions to the test
+    /// harness.
+    pub fn add_tx_infos(&mut self, tx_infos: &[(Txid, BlockHash, BitcoinTxInfo)]) {
+        for (txid, block_hash, tx_info) in tx_infos {
+            self.add_tx_info(*txid, *block_hash, tx_info.clone());
+        }
+    }
+
+    /// Look up the info of the transaction confirmed in the given block.
+    /// Transactions added using `add_tx_info` take precedence over the
+    /// deposit transactions, which are found regardless of the block.
+    fn tx_info(&self, txid: &Txid, block_hash: &BlockHash) -> Option<BitcoinTxInfo> {
+        self.tx_infos
+            .get(&(*txid, *block_hash))
+            .or_else(|| self.deposits.get(txid).map(|(_, tx_info)| tx_info))
+            .cloned()
+    }
+
     /// Get the pending deposit requests in the test harness.
-    pub fn pending_deposits(&self) -> &[CreateDepositRequest] {
-        &self.pending_deposits
+    pub fn pending_deposits(&self) -> Vec<CreateDepositRequest> {
+        self.pending_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// Set the maximum number of pending deposit requests that the test
+    /// harness holds, modeling an Emily that returns a capped set.
+    pub fn set_max_pending_deposits(&mut self, max_pending_deposits: usize) {
+        self.max_pending_deposits = Some(max_pending_deposits);
     }
 
     /// Add a single pending deposit request to the test harness.
-    pub fn add_pending_deposit(&mut self, deposit: CreateDepositRequest) {
-        self.pending_deposits.push(deposit);
+    ///
+    /// The request is ignored if there is already a pending request for
+    /// the same outpoint, so the first request added wins. Otherwise the
+    /// request is rejected if the harness already holds the maximum number
+    /// of pending deposit requests.
+    pub fn add_pending_deposit(
+        &mut self,
+        deposit: CreateDepositRequest,
+    ) -> Result<(), PendingDepositsFull> {
+        let mut pending_deposits = self
+            .pending_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        if pending_deposits
+            .iter()
+            .any(|pending| pending.outpoint == deposit.outpoint)
+        {
+            return Ok(());
+        }
+        if let Some(max_pending_deposits) = self.max_pending_deposits {
+            if pending_deposits.len() >= max_pending_deposits {
+                return Err(PendingDepositsFull { max_pending_deposits });
+            }
+        }
+        pending_deposits.push(deposit);
+        Ok(())
+    }
+
+    /// Add multiple pending deposit requests to the test harness, stopping
+    /// at the first request that is rejected.
+    pub fn add_pending_deposits(
+        &mut self,
+        deposits: &[CreateDepositRequest],
+    ) -> Result<(), PendingDepositsFull> {
+        for deposit in deposits {
+            self.add_pending_deposit(deposit.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Get the pending withdrawal requests in the test harness.
+    pub fn pending_withdrawals(&self) -> Vec<CreateWithdrawalRequestBody> {
+        self.pending_withdrawals
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
     }
 
-    /// Add multiple pending deposit requests to the test harness.
-    pub fn add_pending_deposits(&mut self, deposits: &[CreateDepositRequest]) {
-        self.pending_deposits.extend(deposits.iter().cloned());
+    /// Add a single pending withdrawal request to the test harness.
+    ///
+    /// The request is ignored if there is already a pending request with
+    /// the same request id, so the first request added wins.
+    pub fn add_pending_withdrawal(&mut self, withdrawal: CreateWithdrawalRequestBody) {
+        let mut pending_withdrawals = self
+            .pending_withdrawals
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        if pending_withdrawals
+            .iter()
+            .all(|pending| pending.request_id != withdrawal.request_id)
+        {
+            pending_withdrawals.push(withdrawal);
+        }
+    }
+
+    /// Add multiple pending withdrawal requests to the test harness.
+    pub fn add_pending_withdrawals(&mut self, withdrawals: &[CreateWithdrawalRequestBody]) {
+        for withdrawal in withdrawals {
+            self.add_pending_withdrawal(withdrawal.clone());
+        }
+    }
+
+    /// Get the deposit requests that have been evicted from the pending
+    /// deposits in the test harness, along with the reason for their
+    /// eviction.
+    pub fn evicted_deposits(&self) -> HashMap<OutPoint, String> {
+        self.evicted_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// Get the deposit requests that have been marked as reclaimed in the
+    /// test harness, in the order that they were marked.
+    pub fn reclaimed_deposits(&self) -> Vec<OutPoint> {
+        self.reclaimed_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// Get the deposit requests that have been rejected in the test
+    /// harness for being locked to a key that the signers do not know
+    /// about, in the order that they were rejected.
+    pub fn rejected_wrong_key_deposits(&self) -> Vec<OutPoint> {
+        self.rejected_wrong_key_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// The latest status reported to Emily for each deposit through
+    /// `update_deposits`.
+    pub fn deposit_statuses(&self) -> HashMap<OutPoint, Status> {
+        self.deposit_statuses
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// The record that Emily returns for the given deposit request after
+    /// the given update has been applied to it.
+    fn deposit_record(&self, request: &CreateDepositRequest, update: &DepositUpdate) -> Deposit {
+        let amount = self
+            .deposits
+            .get(&request.outpoint.txid)
+            .and_then(|(response, _)| response.tx.output.get(request.outpoint.vout as usize))
+            .map_or(0, |tx_out| tx_out.value.to_sat());
+        let (parameters, recipient) = match request.parse_scripts() {
+            Ok(scripts) => (
+                DepositParameters {
+                    lock_time: scripts.reclaim.lock_time(),
+                    max_fee: scripts.deposit.max_fee,
+                },
+                hex::encode(scripts.deposit.recipient.serialize_to_vec()),
+            ),
+            Err(_) => (DepositParameters::default(), String::new()),
+        };
+        Deposit {
+            amount,
+            bitcoin_tx_output_index: request.outpoint.vout,
+            bitcoin_txid: request.outpoint.txid.to_string(),
+            deposit_script: request.deposit_script.to_hex_string(),
+            failure_reason: update.failure_reason.clone(),
+            fulfillment: update.fulfillment.clone(),
+            last_update_block_hash: update.last_update_block_hash.clone(),
+            last_update_height: update.last_update_height,
+            parameters: Box::new(parameters),
+            recipient,
+            reclaim_script: request.reclaim_script.to_hex_string(),
+            status: update.status.clone(),
+            status_message: update.status_message.clone(),
+        }
+    }
+
+    /// Get the transactions that have been broadcast through the test
+    /// harness, in the order that they were broadcast.
+    pub fn broadcast_transactions(&self) -> Vec<bitcoin::Transaction> {
+        self.broadcast_transactions
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// Get the stacks transactions that have been submitted through the
+    /// test harness, in the order that they were submitted.
+    pub fn submitted_stacks_txs(&self) -> Vec<StacksTransaction> {
+        self.submitted_stacks_txs
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .clone()
+    }
+
+    /// Set the response returned by `submit_tx` for every transaction
+    /// submitted from now on, instead of accepting them.
+    pub fn set_submit_tx_response(&mut self, response: SubmitTxResponse) {
+        self.submit_tx_response = Some(response);
     }
 
     /// Generate a new test harness with random data.
+    ///
+    /// See [`TestHarness::builder`] for a more readable way to set up a
+    /// test harness.
     pub fn generate(
         rng: &mut impl rand::RngCore,
         num_bitcoin_blocks: usize,
@@ -152,6 +593,8 @@ impl TestHarness {
                         .scan(initial_state, |last_stx_block_header, mut stx_block| {
                             stx_block.header.parent_block_id = last_stx_block_header.block_id();
                             stx_block.header.chain_length = last_stx_block_header.chain_length + 1;
+                            stx_block.header.consensus_hash =
+                                tenure_consensus_hash(&btc_block.block_hash());
                             *last_stx_block_header = stx_block.header.clone();
                             Some((stx_block.block_id(), stx_block, btc_block.block_hash()))
                         })
@@ -170,10 +613,334 @@ impl TestHarness {
             bitcoin_blocks,
             stacks_blocks,
             deposits: HashMap::new(),
-            pending_deposits: Vec::new(),
+            tx_infos: HashMap::new(),
+            pending_deposits: Arc::new(Mutex::new(Vec::new())),
+            max_pending_deposits: None,
+            pending_withdrawals: Arc::new(Mutex::new(Vec::new())),
+            evicted_deposits: Arc::new(Mutex::new(HashMap::new())),
+            reclaimed_deposits: Arc::new(Mutex::new(Vec::new())),
+            rejected_wrong_key_deposits: Arc::new(Mutex::new(Vec::new())),
+            deposit_statuses: Arc::new(Mutex::new(HashMap::new())),
+            broadcast_transactions: Arc::new(Mutex::new(Vec::new())),
+            submitted_stacks_txs: Arc::new(Mutex::new(Vec::new())),
+            submit_tx_response: None,
+            reward_slots: get_pox_info_data().reward_slots,
+            affirmations: None,
+            signer_sets: HashMap::new(),
+            registered_aggregate_keys: HashSet::new(),
+            accounts: HashMap::new(),
+            sbtc_balances: HashMap::new(),
+            reserved_nonces: Arc::new(Mutex::new(HashMap::new())),
+            replacements: HashMap::new(),
+            abandoned_txids: HashSet::new(),
+            utxos: HashMap::new(),
+            fail_next_get_block: Arc::new(AtomicBool::new(false)),
+            fail_next_get_account: Arc::new(AtomicBool::new(false)),
+            failing_get_deposits: Arc::new(AtomicUsize::new(0)),
+            failing_update_deposits: Arc::new(AtomicUsize::new(0)),
+            inconsistent_next_tenure_info: Arc::new(AtomicBool::new(false)),
+            tx_info_delay: Duration::ZERO,
+            tx_info_calls_in_flight: Arc::new(AtomicUsize::new(0)),
+            max_tx_info_calls_in_flight: Arc::new(AtomicUsize::new(0)),
+            fee_rate: 1.0,
+            queued_fee_rates: Arc::new(Mutex::new(VecDeque::new())),
+            min_confirmations: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Replace the transaction with the given txid with the transaction
+    /// in the given response, as if the replacement had been broadcast
+    /// using replace-by-fee. The replaced transaction is forgotten, like
+    /// it would be when evicted from the mempool.
+    pub fn add_replacement(&mut self, txid: Txid, response: GetTxResponse) {
+        let replacement_txid = response.tx.compute_txid();
+        self.deposits.remove(&txid);
+        self.add_deposit(replacement_txid, response);
+        self.replacements.insert(txid, replacement_txid);
+    }
+
+    /// Spend the funding inputs of the transaction with the given txid
+    /// with a transaction that is not a replacement that can be found, as
+    /// if it was confirmed deeper than the replacement search goes. The
+    /// transaction is forgotten, like it would be when evicted from the
+    /// mempool.
+    pub fn abandon_transaction(&mut self, txid: Txid) {
+        self.deposits.remove(&txid);
+        self.abandoned_txids.insert(txid);
+    }
+
+    /// Make the next call to `get_block` on the test harness, or any of
+    /// its clones, return an error.
+    pub fn fail_next_get_block(&self) {
+        self.fail_next_get_block.store(true, Ordering::SeqCst);
+    }
+
+    /// Make the next call to `get_account` on the test harness, or any
+    /// of its clones, return an error.
+    pub fn fail_next_get_account(&self) {
+        self.fail_next_get_account.store(true, Ordering::SeqCst);
+    }
+
+    /// Make the next `count` calls to `get_deposits` on the test harness,
+    /// or any of its clones, return an error, like an unavailable Emily.
+    pub fn fail_next_get_deposits(&self, count: usize) {
+        self.failing_get_deposits.store(count, Ordering::SeqCst);
+    }
+
+    /// Make the next `count` calls to `update_deposits` on the test
+    /// harness, or any of its clones, return an error, like an unavailable
+    /// Emily. Failed calls leave the state of the harness unchanged.
+    pub fn fail_next_update_deposits(&self, count: usize) {
+        self.failing_update_deposits.store(count, Ordering::SeqCst);
+    }
+
+    /// Set the deposit minimum-confirmation override returned by
+    /// `get_min_confirmations` on the test harness and all of its clones,
+    /// like an update through the Emily API.
+    pub fn set_min_confirmations(&self, min_confirmations: Option<u64>) {
+        *self
+            .min_confirmations
+            .lock()
+            .expect("BUG: Failed to acquire lock") = min_confirmations;
+    }
+
+    /// Make the next call to `get_tenure_info` on the test harness, or
+    /// any of its clones, return a tenure that does not contain the tip
+    /// block, like a stacks node in the middle of a tenure change.
+    pub fn make_next_tenure_info_inconsistent(&self) {
+        self.inconsistent_next_tenure_info
+            .store(true, Ordering::SeqCst);
+    }
+
+    /// Make each call to `get_tx_info` take the given amount of time to
+    /// return, so that concurrent calls overlap.
+    pub fn set_tx_info_delay(&mut self, delay: Duration) {
+        self.tx_info_delay = delay;
+    }
+
+    /// The largest number of calls to `get_tx_info` that have been in
+    /// progress at the same time.
+    pub fn max_tx_info_calls_in_flight(&self) -> usize {
+        self.max_tx_info_calls_in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Set the fee rate returned by `estimate_fee_rate` once the queued
+    /// fee rates have been used up.
+    pub fn set_fee_rate(&mut self, fee_rate: f64) {
+        self.fee_rate = fee_rate;
+    }
+
+    /// Queue fee rates to be returned by the next calls to
+    /// `estimate_fee_rate`, one per call, before falling back to the fee
+    /// rate set with `set_fee_rate`.
+    pub fn queue_fee_rates(&self, fee_rates: &[f64]) {
+        self.queued_fee_rates
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .extend(fee_rates);
+    }
+
+    /// Add an unspent transaction output to the test harness.
+    pub fn add_utxo(&mut self, utxo: Utxo) {
+        self.utxos.insert(utxo.outpoint, utxo);
+    }
+
+    /// Set the current signer set returned by `get_current_signer_set`
+    /// for the given contract principal.
+    pub fn set_signer_set(&mut self, contract_principal: StacksAddress, signers: Vec<PublicKey>) {
+        self.signer_sets.insert(contract_principal, signers);
+    }
+
+    /// Record the given key in the `aggregate-pubkeys` map of the
+    /// `sbtc-registry` contract, as if the signers had rotated to it.
+    pub fn register_aggregate_key(&mut self, aggregate_key: PublicKey) {
+        self.registered_aggregate_keys.insert(aggregate_key);
+    }
+
+    /// Set the account info for the given stacks address.
+    pub fn set_account(&mut self, address: StacksAddress, account: AccountInfo) {
+        self.accounts.insert(address, account);
+    }
+
+    /// Set the sBTC balance returned by `get_sbtc_balance` for the given
+    /// principal in the `sbtc-token` contract deployed by the given token
+    /// principal.
+    pub fn set_sbtc_balance(
+        &mut self,
+        token_principal: StacksAddress,
+        address: PrincipalData,
+        balance: u128,
+    ) {
+        self.sbtc_balances
+            .insert((token_principal, address), balance);
+    }
+
+    /// Reserve the next usable nonce of the given stacks address, so that
+    /// callers submitting transactions for the same account concurrently,
+    /// through this harness or any of its clones, each use a different
+    /// nonce. The first reservation for an address returns the nonce of
+    /// its account info, or zero if the address is unknown.
+    pub fn reserve_nonce(&self, address: &StacksAddress) -> Result<u64, Error> {
+        let mut reserved_nonces = self
+            .reserved_nonces
+            .lock()
+            .expect("BUG: Failed to acquire reserved nonces lock");
+        let next_nonce = reserved_nonces.entry(*address).or_insert_with(|| {
+            self.accounts
+                .get(address)
+                .map(|account| account.nonce)
+                .unwrap_or_default()
+        });
+        let nonce = *next_nonce;
+        *next_nonce += 1;
+        Ok(nonce)
+    }
+
+    /// Set the number of reward slots reported in the PoX info.
+    pub fn set_reward_slots(&mut self, reward_slots: u32) {
+        self.reward_slots = reward_slots;
+    }
+
+    /// Set the affirmation maps reported by the node.
+    pub fn set_affirmations(&mut self, affirmations: Affirmations) {
+        self.affirmations = Some(affirmations);
+    }
+
+    /// Add a fork to the bitcoin blockchain in the test harness. The fork
+    /// is made up of `num_blocks` new blocks where the first one builds on
+    /// the block with the given parent hash. The new blocks are returned
+    /// in chronological order.
+    pub fn add_bitcoin_fork<R>(
+        &mut self,
+        rng: &mut R,
+        parent: BlockHash,
+        num_blocks: usize,
+    ) -> Vec<bitcoin::Block>
+    where
+        R: rand::RngCore,
+    {
+        let mut prev_blockhash = parent;
+        let fork: Vec<bitcoin::Block> = std::iter::repeat_with(|| dummy::block(&fake::Faker, rng))
+            .take(num_blocks)
+            .map(|mut block| {
+                block.header.prev_blockhash = prev_blockhash;
+                prev_blockhash = block.block_hash();
+                block
+            })
+            .collect();
+
+        self.bitcoin_blocks.extend(fork.iter().cloned());
+        fork
+    }
+
+    /// Start a new tenure in the test harness. A new bitcoin block is
+    /// added on top of the last bitcoin block, along with
+    /// `num_stacks_blocks` new stacks blocks that build on the last stacks
+    /// block and are anchored to the new bitcoin block. The new bitcoin
+    /// block is returned.
+    pub fn add_stacks_tenure<R>(&mut self, rng: &mut R, num_stacks_blocks: usize) -> bitcoin::Block
+    where
+        R: rand::RngCore,
+    {
+        let parent = self
+            .bitcoin_blocks
+            .last()
+            .map(|block| block.block_hash())
+            .unwrap_or_else(BlockHash::all_zeros);
+        let bitcoin_block = self.add_bitcoin_fork(rng, parent, 1).remove(0);
+
+        let mut last_header = self
+            .stacks_tip()
+            .map(|(_, block, _)| block.header.clone())
+            .unwrap_or_else(NakamotoBlockHeader::empty);
+        for _ in 0..num_stacks_blocks {
+            let mut stx_block = dummy::stacks_block(&fake::Faker, rng);
+            stx_block.header.parent_block_id = last_header.block_id();
+            stx_block.header.chain_length = last_header.chain_length + 1;
+            stx_block.header.consensus_hash = tenure_consensus_hash(&bitcoin_block.block_hash());
+            last_header = stx_block.header.clone();
+            self.stacks_blocks
+                .push((stx_block.block_id(), stx_block, bitcoin_block.block_hash()));
+        }
+
+        bitcoin_block
+    }
+
+    /// Add a fork to the stacks blockchain in the test harness. The fork
+    /// is made up of `num_blocks` new stacks blocks where the first one
+    /// builds on the stacks block with the given ID, and all of them are
+    /// in the same tenure as that block. The IDs of the new blocks are
+    /// returned in ascending order.
+    ///
+    /// The stacks chain tip of the test harness is the tip of the
+    /// heaviest chain, so a fork that is longer than the chain it
+    /// competes with replaces it.
+    pub fn add_stacks_fork<R>(
+        &mut self,
+        rng: &mut R,
+        from_block_id: StacksBlockId,
+        num_blocks: usize,
+    ) -> Vec<StacksBlockId>
+    where
+        R: rand::RngCore,
+    {
+        let (_, from_block, bitcoin_block_hash) = self
+            .stacks_blocks
+            .iter()
+            .find(|(block_id, _, _)| block_id == &from_block_id)
+            .cloned()
+            .expect("BUG: the fork must build on a known stacks block");
+
+        let mut last_header = from_block.header;
+        let mut fork = Vec::with_capacity(num_blocks);
+        for _ in 0..num_blocks {
+            let mut stx_block = dummy::stacks_block(&fake::Faker, rng);
+            stx_block.header.parent_block_id = last_header.block_id();
+            stx_block.header.chain_length = last_header.chain_length + 1;
+            stx_block.header.consensus_hash = last_header.consensus_hash;
+            last_header = stx_block.header.clone();
+            fork.push(stx_block.block_id());
+            self.stacks_blocks
+                .push((stx_block.block_id(), stx_block, bitcoin_block_hash));
+        }
+
+        fork
+    }
+
+    /// The tip of the heaviest stacks chain in the test harness, which is
+    /// the stacks block with the greatest chain length. Ties go to the
+    /// block that was added last.
+    fn stacks_tip(&self) -> Option<&(StacksBlockId, NakamotoBlock, BlockHash)> {
+        self.stacks_blocks
+            .iter()
+            .max_by_key(|(_, block, _)| block.header.chain_length)
+    }
+
+    /// The blocks of the tenure of the stacks block with the given ID, up
+    /// to and including that block, in ascending order.
+    ///
+    /// We follow the parents of the block rather than the order of the
+    /// blocks in the test harness, so only the blocks on the same fork as
+    /// the given block are returned.
+    fn tenure_blocks(&self, block_id: &StacksBlockId) -> Option<Vec<&NakamotoBlock>> {
+        let find =
+            |block_id: &StacksBlockId| self.stacks_blocks.iter().find(|(id, _, _)| id == block_id);
+        let (_, block, bitcoin_block_hash) = find(block_id)?;
+
+        let mut blocks = vec![block];
+        while let Some((_, parent, parent_bitcoin_block_hash)) =
+            find(&blocks[blocks.len() - 1].header.parent_block_id)
+        {
+            if parent_bitcoin_block_hash != bitcoin_block_hash {
+                break;
+            }
+            blocks.push(parent);
+        }
+        // Callers expect the blocks of a tenure in ascending order.
+        blocks.reverse();
+        Some(blocks)
+    }
+
     /// Spawn a Bitcoin block hash stream for testing.
     pub fn spawn_block_hash_stream(
         &self,
@@ -196,6 +963,15 @@ impl TestHarness {
     }
 }
 
+/// The consensus hash of the tenure anchored to the bitcoin block with
+/// the given hash. Each tenure in the test harness is anchored to a
+/// different bitcoin block, so this is unique for each tenure.
+fn tenure_consensus_hash(block_hash: &BlockHash) -> ConsensusHash {
+    let mut consensus_hash = [0; 20];
+    consensus_hash.copy_from_slice(&block_hash.as_byte_array()[..20]);
+    ConsensusHash(consensus_hash)
+}
+
 impl TryFrom<TestHarness> for ApiFallbackClient<TestHarness> {
     type Error = Error;
     fn try_from(value: TestHarness) -> Result<Self, Error> {
@@ -211,15 +987,36 @@ impl BitcoinInteract for TestHarness {
     async fn get_tx_info(
         &self,
         txid: &Txid,
-        _: &BlockHash,
+        block_hash: &BlockHash,
     ) -> Result<Option<BitcoinTxInfo>, Error> {
-        Ok(self.deposits.get(txid).cloned().map(|(_, tx_info)| tx_info))
+        let in_flight = self.tx_info_calls_in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_tx_info_calls_in_flight
+            .fetch_max(in_flight, Ordering::SeqCst);
+        if !self.tx_info_delay.is_zero() {
+            tokio::time::sleep(self.tx_info_delay).await;
+        }
+        self.tx_info_calls_in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        Ok(self.tx_info(txid, block_hash))
+    }
+
+    async fn get_txs_info(
+        &self,
+        queries: &[(Txid, BlockHash)],
+    ) -> Result<Vec<Option<BitcoinTxInfo>>, Error> {
+        Ok(queries
+            .iter()
+            .map(|(txid, block_hash)| self.tx_info(txid, block_hash))
+            .collect())
     }
 
     async fn get_block(
         &self,
         block_hash: &bitcoin::BlockHash,
     ) -> Result<Option<bitcoin::Block>, Error> {
+        if self.fail_next_get_block.swap(false, Ordering::SeqCst) {
+            return Err(Error::MissingBlock);
+        }
         Ok(self
             .bitcoin_blocks
             .iter()
@@ -227,12 +1024,33 @@ impl BitcoinInteract for TestHarness {
             .cloned())
     }
 
+    async fn get_block_header(
+        &self,
+        block_hash: &bitcoin::BlockHash,
+    ) -> Result<Option<bitcoin::block::Header>, Error> {
+        Ok(self
+            .bitcoin_blocks
+            .iter()
+            .find(|block| &block.block_hash() == block_hash)
+            .map(|block| block.header))
+    }
+
     async fn estimate_fee_rate(&self) -> Result<f64, Error> {
-        unimplemented!()
+        let fee_rate = self
+            .queued_fee_rates
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .pop_front()
+            .unwrap_or(self.fee_rate);
+        Ok(fee_rate)
     }
 
-    async fn broadcast_transaction(&self, _tx: &bitcoin::Transaction) -> Result<(), Error> {
-        unimplemented!()
+    async fn broadcast_transaction(&self, tx: &bitcoin::Transaction) -> Result<(), Error> {
+        self.broadcast_transactions
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .push(tx.clone());
+        Ok(())
     }
 
     async fn find_mempool_transactions_spending_output(
@@ -242,6 +1060,17 @@ impl BitcoinInteract for TestHarness {
         unimplemented!()
     }
 
+    async fn find_replacement_tx(
+        &self,
+        txid: &Txid,
+        _funding_inputs: &[bitcoin::OutPoint],
+    ) -> Result<Option<Txid>, Error> {
+        if self.abandoned_txids.contains(txid) {
+            return Err(Error::FundingInputsSpent(*txid));
+        }
+        Ok(self.replacements.get(txid).copied())
+    }
+
     async fn find_mempool_descendants(&self, _txid: &Txid) -> Result<Vec<Txid>, Error> {
         unimplemented!()
     }
@@ -254,6 +1083,10 @@ impl BitcoinInteract for TestHarness {
         unimplemented!()
     }
 
+    async fn get_utxo(&self, outpoint: &bitcoin::OutPoint) -> Result<Option<Utxo>, Error> {
+        Ok(self.utxos.get(outpoint).cloned())
+    }
+
     async fn get_transaction_fee(
         &self,
         _txid: &bitcoin::Txid,
@@ -262,6 +1095,25 @@ impl BitcoinInteract for TestHarness {
         unimplemented!()
     }
 
+    async fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        // Transactions broadcast through the harness stay in the mempool
+        // until they are included in one of the harness' bitcoin blocks.
+        let confirmed: HashSet<Txid> = self
+            .bitcoin_blocks
+            .iter()
+            .flat_map(|block| block.txdata.iter().map(|tx| tx.compute_txid()))
+            .collect();
+
+        Ok(self
+            .broadcast_transactions
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .iter()
+            .map(|tx| tx.compute_txid())
+            .filter(|txid| !confirmed.contains(txid))
+            .collect())
+    }
+
     async fn get_mempool_entry(
         &self,
         _txid: &Txid,
@@ -273,10 +1125,15 @@ impl BitcoinInteract for TestHarness {
 impl StacksInteract for TestHarness {
     async fn get_current_signer_set(
         &self,
-        _contract_principal: &StacksAddress,
+        contract_principal: &StacksAddress,
     ) -> Result<Vec<PublicKey>, Error> {
-        // issue #118
-        todo!()
+        // Contracts without a signer set report no signers, which is
+        // what the registry returns before the first key rotation.
+        Ok(self
+            .signer_sets
+            .get(contract_principal)
+            .cloned()
+            .unwrap_or_default())
     }
     async fn get_current_signers_aggregate_key(
         &self,
@@ -285,14 +1142,46 @@ impl StacksInteract for TestHarness {
         // issue #118
         todo!()
     }
-    async fn get_account(&self, _address: &StacksAddress) -> Result<AccountInfo, Error> {
-        // issue #118
-        todo!()
+    async fn is_registered_aggregate_key(
+        &self,
+        _contract_principal: &StacksAddress,
+        aggregate_key: &PublicKey,
+    ) -> Result<bool, Error> {
+        Ok(self.registered_aggregate_keys.contains(aggregate_key))
+    }
+    async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
+        if self.fail_next_get_account.swap(false, Ordering::SeqCst) {
+            return Err(Error::InvalidStacksResponse("failed to get account"));
+        }
+        // Addresses that we know nothing about get a fresh account with
+        // enough funds to pay for any transaction, so that tests that
+        // submit transactions do not need to set up every account.
+        Ok(self
+            .accounts
+            .get(address)
+            .cloned()
+            .unwrap_or(DEFAULT_ACCOUNT))
     }
 
-    async fn submit_tx(&self, _tx: &StacksTransaction) -> Result<SubmitTxResponse, Error> {
-        // issue #118
-        todo!()
+    async fn get_balance(&self, address: &StacksAddress) -> Result<u128, Error> {
+        // Like a stacks node, addresses that we know nothing about have
+        // a zero balance.
+        Ok(self
+            .accounts
+            .get(address)
+            .map(|account| account.balance)
+            .unwrap_or_default())
+    }
+
+    async fn submit_tx(&self, tx: &StacksTransaction) -> Result<SubmitTxResponse, Error> {
+        self.submitted_stacks_txs
+            .lock()
+            .expect("BUG: Failed to acquire lock")
+            .push(tx.clone());
+        Ok(self
+            .submit_tx_response
+            .clone()
+            .unwrap_or_else(|| SubmitTxResponse::Acceptance(tx.txid())))
     }
 
     async fn get_block(&self, block_id: StacksBlockId) -> Result<NakamotoBlock, Error> {
@@ -304,48 +1193,76 @@ impl StacksInteract for TestHarness {
             .cloned()
             .ok_or(Error::MissingBlock)
     }
-    async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
-        let (stx_block_id, stx_block, btc_block_id) = self
-            .stacks_blocks
+    async fn get_block_transactions(
+        &self,
+        block_id: StacksBlockId,
+    ) -> Result<Vec<StacksTransaction>, Error> {
+        self.stacks_blocks
             .iter()
             .find(|(id, _, _)| &block_id == id)
-            .ok_or(Error::MissingBlock)?;
-
-        let blocks: Vec<NakamotoBlock> = self
-            .stacks_blocks
-            .iter()
-            .skip_while(|(_, _, block_id)| block_id != btc_block_id)
-            .take_while(|(block_id, _, _)| block_id != stx_block_id)
-            .map(|(_, block, _)| block)
-            .chain(std::iter::once(stx_block))
+            .map(|(_, block, _)| block.txs.clone())
+            .ok_or(Error::MissingBlock)
+    }
+    async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
+        let blocks = self
+            .tenure_blocks(&block_id)
+            .ok_or(Error::MissingBlock)?
+            .into_iter()
             .cloned()
             .collect();
 
         TenureBlocks::from_blocks(blocks)
     }
     async fn get_tenure_info(&self) -> Result<RPCGetTenureInfo, Error> {
-        let (_, _, btc_block_id) = self.stacks_blocks.last().unwrap();
+        let (tip_block_id, tip_block, btc_block_id) = self.stacks_tip().unwrap();
+        let consensus_hash = if self
+            .inconsistent_next_tenure_info
+            .swap(false, Ordering::SeqCst)
+        {
+            tenure_consensus_hash(&BlockHash::all_zeros())
+        } else {
+            tenure_consensus_hash(btc_block_id)
+        };
 
         Ok(RPCGetTenureInfo {
-            consensus_hash: ConsensusHash([0; 20]),
+            consensus_hash,
             tenure_start_block_id: self
-                .stacks_blocks
-                .iter()
-                .find(|(_, _, block_id)| block_id == btc_block_id)
-                .map(|(stx_block_id, _, _)| *stx_block_id)
+                .tenure_blocks(tip_block_id)
+                .and_then(|blocks| blocks.first().map(|block| block.block_id()))
                 .unwrap(),
             parent_consensus_hash: ConsensusHash([0; 20]),
             parent_tenure_start_block_id: StacksBlockId::first_mined(),
-            tip_block_id: self
-                .stacks_blocks
-                .last()
-                .map(|(block_id, _, _)| *block_id)
-                .unwrap(),
-            tip_height: self.stacks_blocks.len() as u64,
+            tip_block_id: *tip_block_id,
+            tip_height: tip_block.header.chain_length,
             reward_cycle: 0,
         })
     }
 
+    async fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        // Tenures are keyed by the bitcoin block that they are anchored
+        // to, so we need to know that block to find the tenure.
+        let Some(bitcoin_block_hash) = self
+            .bitcoin_blocks
+            .iter()
+            .map(|block| block.block_hash())
+            .find(|block_hash| &tenure_consensus_hash(block_hash) == consensus_hash)
+        else {
+            return Err(Error::MissingBlock);
+        };
+
+        let mut blocks: Vec<NakamotoBlock> = self
+            .stacks_blocks
+            .iter()
+            .filter(|(_, _, block_hash)| block_hash == &bitcoin_block_hash)
+            .map(|(_, block, _)| block.clone())
+            .collect();
+        blocks.sort_by_key(|block| block.header.chain_length);
+        Ok(blocks)
+    }
+
     async fn get_sortition_info(
         &self,
         _consensus_hash: &ConsensusHash,
@@ -398,6 +1315,7 @@ impl StacksInteract for TestHarness {
                     runtime: 5_000_000_000,
                 },
             }],
+            reward_slots: self.reward_slots,
             ..data
         };
 
@@ -409,13 +1327,23 @@ impl StacksInteract for TestHarness {
 
         let result = RPCPeerInfoData {
             burn_block_height: self.bitcoin_blocks.len() as u64,
-            stacks_tip_height: self.stacks_blocks.len() as u64,
+            stacks_tip_height: self
+                .stacks_tip()
+                .map(|(_, block, _)| block.header.chain_length)
+                .unwrap_or_default(),
             ..data
         };
 
         Ok(result)
     }
 
+    async fn get_affirmations(&self) -> Result<Affirmations, Error> {
+        match &self.affirmations {
+            Some(affirmations) => Ok(affirmations.clone()),
+            None => Affirmations::try_from(&self.get_node_info().await?),
+        }
+    }
+
     async fn get_contract_source(
         &self,
         _address: &StacksAddress,
@@ -431,6 +1359,17 @@ impl StacksInteract for TestHarness {
     async fn get_sbtc_total_supply(&self, _: &StacksAddress) -> Result<Amount, Error> {
         Ok(Amount::from_sat(u64::MAX))
     }
+
+    async fn get_sbtc_balance(
+        &self,
+        token_principal: &StacksAddress,
+        address: &PrincipalData,
+    ) -> Result<u128, Error> {
+        // Principals that have never held sBTC have a zero balance, which
+        // is what the token contract returns for them.
+        let key = (*token_principal, address.clone());
+        Ok(self.sbtc_balances.get(&key).copied().unwrap_or_default())
+    }
 }
 
 impl EmilyInteract for TestHarness {
@@ -439,24 +1378,108 @@ impl EmilyInteract for TestHarness {
         txid: &model::BitcoinTxId,
         output_index: u32,
     ) -> Result<Option<CreateDepositRequest>, Error> {
-        let deposit = self
-            .pending_deposits
-            .iter()
-            .find(|request| {
-                &request.outpoint.txid == txid.deref() && request.outpoint.vout == output_index
-            })
-            .cloned();
+        let deposit = self.pending_deposits().into_iter().find(|request| {
+            &request.outpoint.txid == txid.deref() && request.outpoint.vout == output_index
+        });
         Ok(deposit)
     }
     async fn get_deposits(&self) -> Result<Vec<CreateDepositRequest>, Error> {
-        Ok(self.pending_deposits.clone())
+        let failing = self
+            .failing_get_deposits
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if failing {
+            let response = ResponseContent {
+                status: emily_reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                content: "Emily is unavailable".to_string(),
+                entity: None,
+            };
+            let error = EmilyClientError::GetDeposits(EmilyError::ResponseError(response));
+            return Err(Error::EmilyApi(error));
+        }
+        Ok(self.pending_deposits())
+    }
+
+    async fn get_withdrawals(&self) -> Result<Vec<CreateWithdrawalRequestBody>, Error> {
+        Ok(self.pending_withdrawals())
     }
 
     async fn update_deposits(
         &self,
-        _update_deposits: Vec<emily_client::models::DepositUpdate>,
+        update_deposits: Vec<emily_client::models::DepositUpdate>,
     ) -> Result<emily_client::models::UpdateDepositsResponse, Error> {
-        unimplemented!()
+        let failing = self
+            .failing_update_deposits
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+                count.checked_sub(1)
+            })
+            .is_ok();
+        if failing {
+            let response = ResponseContent {
+                status: emily_reqwest::StatusCode::SERVICE_UNAVAILABLE,
+                content: "Emily is unavailable".to_string(),
+                entity: None,
+            };
+            let error = EmilyClientError::UpdateDeposits(EmilyError::ResponseError(response));
+            return Err(Error::EmilyApi(error));
+        }
+
+        let mut pending_deposits = self
+            .pending_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+        let mut evicted_deposits = self
+            .evicted_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+        let mut reclaimed_deposits = self
+            .reclaimed_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        let mut rejected_deposits = self
+            .rejected_wrong_key_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        let mut deposit_statuses = self
+            .deposit_statuses
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        let mut deposits = Vec::new();
+        for update in update_deposits {
+            let outpoint = OutPoint {
+                txid: update.bitcoin_txid.parse().map_err(Error::DecodeHexTxid)?,
+                vout: update.bitcoin_tx_output_index,
+            };
+            // Emily only knows about the deposits that have been
+            // submitted to it, so only those are returned as updated.
+            if let Some(request) = pending_deposits
+                .iter()
+                .find(|deposit| deposit.outpoint == outpoint)
+            {
+                deposits.push(self.deposit_record(request, &update));
+            }
+            deposit_statuses.insert(outpoint, update.status.clone());
+
+            // Only failed, reclaimed and rejected deposits are removed
+            // from the pending deposits, since those will never be
+            // processed by the signers.
+            match update.status {
+                Status::Reclaimed => reclaimed_deposits.push(outpoint),
+                Status::RejectedWrongKey => rejected_deposits.push(outpoint),
+                Status::Failed => {
+                    evicted_deposits.insert(outpoint, update.status_message);
+                }
+                _ => continue,
+            }
+            pending_deposits.retain(|deposit| deposit.outpoint != outpoint);
+        }
+
+        Ok(emily_client::models::UpdateDepositsResponse { deposits, errors: None })
     }
 
     async fn accept_deposits<'a>(
@@ -467,6 +1490,41 @@ impl EmilyInteract for TestHarness {
         unimplemented!()
     }
 
+    async fn create_deposits(
+        &self,
+        create_deposits: Vec<emily_client::models::CreateDepositRequestBody>,
+    ) -> Result<emily_client::models::CreateDepositsResponse, Error> {
+        let mut pending_deposits = self
+            .pending_deposits
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+
+        for body in create_deposits {
+            let request = CreateDepositRequest {
+                outpoint: OutPoint {
+                    txid: body.bitcoin_txid.parse().map_err(Error::DecodeHexTxid)?,
+                    vout: body.bitcoin_tx_output_index,
+                },
+                deposit_script: ScriptBuf::from_hex(&body.deposit_script)
+                    .map_err(Error::DecodeHexScript)?,
+                reclaim_script: ScriptBuf::from_hex(&body.reclaim_script)
+                    .map_err(Error::DecodeHexScript)?,
+            };
+            // Like Emily, the first request for an outpoint wins.
+            if !pending_deposits
+                .iter()
+                .any(|pending| pending.outpoint == request.outpoint)
+            {
+                pending_deposits.push(request);
+            }
+        }
+
+        Ok(emily_client::models::CreateDepositsResponse {
+            deposits: vec![],
+            errors: vec![],
+        })
+    }
+
     async fn create_withdrawals(
         &self,
         _create_withdrawals: Vec<CreateWithdrawalRequestBody>,
@@ -488,6 +1546,14 @@ impl EmilyInteract for TestHarness {
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
         Ok(SbtcLimits::default())
     }
+
+    async fn get_min_confirmations(&self) -> Result<Option<u64>, Error> {
+        let min_confirmations = self
+            .min_confirmations
+            .lock()
+            .expect("BUG: Failed to acquire lock");
+        Ok(*min_confirmations)
+    }
 }
 
 fn get_pox_info_data() -> RPCPoxInfoData {
@@ -568,3 +1634,356 @@ fn get_node_info_data() -> RPCPeerInfoData {
 
     serde_json::from_str::<RPCPeerInfoData>(raw_json_response).unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng as _;
+
+    use super::*;
+
+    #[test]
+    fn stacks_tip_at_burn_height_maps_to_tenure() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 10, 1..5);
+
+        let burn_height = 5;
+        let anchor = test_harness.bitcoin_blocks()[burn_height].block_hash();
+        // Every bitcoin block has at least one stacks block, so the tip is
+        // the last stacks block in the tenure of the block at that height.
+        let (expected_id, expected_block, _) = test_harness
+            .stacks_blocks()
+            .iter()
+            .filter(|(_, _, btc_block_hash)| btc_block_hash == &anchor)
+            .last()
+            .unwrap();
+
+        let (block_id, height) = test_harness.stacks_tip_at_burn_height(burn_height).unwrap();
+        assert_eq!(&block_id, expected_id);
+        assert_eq!(height, expected_block.header.chain_length);
+
+        // Tenures of later bitcoin blocks are ignored.
+        let (_, tip_height) = test_harness.stacks_tip_at_burn_height(9).unwrap();
+        assert!(tip_height > height);
+    }
+
+    #[test]
+    fn duplicate_pending_deposits_are_ignored() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 1, 0..1);
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let outpoint = bitcoin::OutPoint {
+            txid: tx_setup.tx.compute_txid(),
+            vout: 0,
+        };
+        let deposit_request = CreateDepositRequest {
+            outpoint,
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        // Same outpoint, different scripts.
+        let duplicate_request = CreateDepositRequest {
+            outpoint,
+            deposit_script: bitcoin::ScriptBuf::new(),
+            reclaim_script: bitcoin::ScriptBuf::new(),
+        };
+
+        test_harness
+            .add_pending_deposit(deposit_request.clone())
+            .unwrap();
+        test_harness
+            .add_pending_deposits(&[deposit_request.clone(), duplicate_request])
+            .unwrap();
+
+        let pending_deposits = test_harness.pending_deposits();
+        assert_eq!(pending_deposits.len(), 1);
+        assert_eq!(
+            pending_deposits[0].deposit_script,
+            deposit_request.deposit_script
+        );
+    }
+
+    #[tokio::test]
+    async fn test_harness_returns_tx_info_for_the_confirming_block() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 2, 0..1);
+        let block = test_harness.bitcoin_blocks()[1].clone();
+        let other_block_hash = test_harness.bitcoin_blocks()[0].block_hash();
+
+        let tx = block.txdata[0].clone();
+        let txid = tx.compute_txid();
+        let block_hash = block.block_hash();
+        let tx_info = BitcoinTxInfo {
+            in_active_chain: true,
+            fee: Amount::from_sat(1000),
+            txid,
+            hash: tx.compute_wtxid(),
+            size: tx.total_size() as u64,
+            vsize: tx.vsize() as u64,
+            tx,
+            vin: Vec::new(),
+            vout: Vec::new(),
+            block_hash,
+            confirmations: 1,
+            block_time: 0,
+        };
+        test_harness.add_tx_info(txid, block_hash, tx_info.clone());
+
+        let found = test_harness.get_tx_info(&txid, &block_hash).await.unwrap();
+        assert_eq!(found, Some(tx_info));
+
+        let missing = test_harness
+            .get_tx_info(&txid, &other_block_hash)
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[tokio::test]
+    async fn test_harness_builder_is_deterministic() {
+        let tx = crate::testing::dummy::tx(&fake::Faker, &mut rand::rngs::OsRng);
+        let txid = tx.compute_txid();
+        let response = GetTxResponse {
+            tx,
+            block_hash: None,
+            confirmations: None,
+            block_time: None,
+        };
+
+        let builder = TestHarness::builder()
+            .bitcoin_blocks(3)
+            .stacks_per_block(1..4)
+            .seed(7);
+        let test_harness = builder
+            .clone()
+            .with_deposits(vec![(txid, response)])
+            .build();
+        assert_eq!(test_harness.bitcoin_blocks().len(), 3);
+        assert!(test_harness.get_tx(&txid).await.unwrap().is_some());
+
+        // The same seed gives the same chains.
+        let other = builder.build();
+        assert_eq!(other.bitcoin_blocks(), test_harness.bitcoin_blocks());
+        assert_eq!(other.stacks_blocks(), test_harness.stacks_blocks());
+        assert!(other.get_tx(&txid).await.unwrap().is_none());
+    }
+
+    /// Test that the data generated for a test harness only depends on
+    /// the given rng, so that a failing test can be reproduced from its
+    /// seed.
+    #[test]
+    fn test_harness_generated_from_a_seed_is_reproducible() {
+        use clarity::codec::StacksMessageCodec as _;
+
+        let serialize = |test_harness: &TestHarness| {
+            let bitcoin_blocks: Vec<Vec<u8>> = test_harness
+                .bitcoin_blocks()
+                .iter()
+                .map(bitcoin::consensus::encode::serialize)
+                .collect();
+            let stacks_blocks: Vec<Vec<u8>> = test_harness
+                .stacks_blocks()
+                .iter()
+                .map(|(_, block, _)| block.serialize_to_vec())
+                .collect();
+            (bitcoin_blocks, stacks_blocks)
+        };
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let other = TestHarness::generate(&mut rng, 10, 0..5);
+
+        assert_eq!(serialize(&test_harness), serialize(&other));
+    }
+
+    #[tokio::test]
+    async fn test_harness_returns_queued_fee_rates_in_order() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 1, 0..1);
+        assert_eq!(test_harness.estimate_fee_rate().await.unwrap(), 1.0);
+
+        test_harness.set_fee_rate(5.0);
+        test_harness.queue_fee_rates(&[10.0, 20.0]);
+
+        // Clones share the queue, so the rates are used up in order
+        // regardless of which clone asks for them.
+        let clone = test_harness.clone();
+        assert_eq!(clone.estimate_fee_rate().await.unwrap(), 10.0);
+        assert_eq!(test_harness.estimate_fee_rate().await.unwrap(), 20.0);
+        assert_eq!(clone.estimate_fee_rate().await.unwrap(), 5.0);
+        assert_eq!(test_harness.estimate_fee_rate().await.unwrap(), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_harness_returns_pending_withdrawals() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+
+        let withdrawal = |request_id: u64, amount: u64| CreateWithdrawalRequestBody {
+            amount,
+            request_id,
+            ..Default::default()
+        };
+        test_harness.add_pending_withdrawals(&[withdrawal(1, 1_000), withdrawal(2, 2_000)]);
+        // A request with the same request id as a pending one is ignored.
+        test_harness.add_pending_withdrawal(withdrawal(1, 3_000));
+
+        let withdrawals = test_harness.get_withdrawals().await.unwrap();
+        let ids_and_amounts: Vec<(u64, u64)> = withdrawals
+            .iter()
+            .map(|withdrawal| (withdrawal.request_id, withdrawal.amount))
+            .collect();
+        assert_eq!(ids_and_amounts, vec![(1, 1_000), (2, 2_000)]);
+    }
+
+    #[tokio::test]
+    async fn test_harness_rejects_pending_deposits_past_the_cap() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+        test_harness.set_max_pending_deposits(2);
+
+        let requests: Vec<CreateDepositRequest> = (0..3)
+            .map(|_| {
+                let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+                CreateDepositRequest {
+                    outpoint: bitcoin::OutPoint::new(tx_setup.tx.compute_txid(), 0),
+                    deposit_script: tx_setup.deposit.deposit_script(),
+                    reclaim_script: tx_setup.reclaim.reclaim_script(),
+                }
+            })
+            .collect();
+
+        test_harness.add_pending_deposits(&requests[..2]).unwrap();
+        // Adding a request that is already pending leaves the harness
+        // unchanged, even when it is full.
+        test_harness
+            .add_pending_deposit(requests[0].clone())
+            .unwrap();
+
+        let error = test_harness
+            .add_pending_deposit(requests[2].clone())
+            .unwrap_err();
+        assert_eq!(error.max_pending_deposits, 2);
+
+        let pending = test_harness.get_deposits().await.unwrap();
+        let outpoints: Vec<OutPoint> = pending.iter().map(|request| request.outpoint).collect();
+        assert_eq!(outpoints, vec![requests[0].outpoint, requests[1].outpoint]);
+    }
+
+    #[tokio::test]
+    async fn test_harness_applies_deposit_updates_after_injected_failures() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 5, 1..5);
+
+        let tx_setup = sbtc::testing::deposits::tx_setup(150, 32000, 500_000);
+        let txid = tx_setup.tx.compute_txid();
+        let response = GetTxResponse {
+            tx: tx_setup.tx.clone(),
+            block_hash: Some(test_harness.bitcoin_blocks()[1].block_hash()),
+            confirmations: None,
+            block_time: None,
+        };
+        test_harness.add_deposit(txid, response);
+        let request = CreateDepositRequest {
+            outpoint: bitcoin::OutPoint::new(txid, 0),
+            deposit_script: tx_setup.deposit.deposit_script(),
+            reclaim_script: tx_setup.reclaim.reclaim_script(),
+        };
+        test_harness.add_pending_deposit(request.clone()).unwrap();
+
+        let update = DepositUpdate {
+            bitcoin_tx_output_index: request.outpoint.vout,
+            bitcoin_txid: txid.to_string(),
+            status: Status::Accepted,
+            failure_reason: None,
+            fulfillment: None,
+            status_message: "accepted".to_string(),
+            last_update_block_hash: "stacks_block_hash".to_string(),
+            last_update_height: 3,
+        };
+
+        // A failed call leaves Emily's view of the deposit unchanged.
+        test_harness.fail_next_update_deposits(1);
+        let result = test_harness.update_deposits(vec![update.clone()]).await;
+        assert!(result.is_err());
+        assert!(test_harness.deposit_statuses().is_empty());
+
+        let response = test_harness.update_deposits(vec![update]).await.unwrap();
+        assert_eq!(response.deposits.len(), 1);
+        let deposit = &response.deposits[0];
+        assert_eq!(deposit.status, Status::Accepted);
+        assert_eq!(deposit.amount, 500_000);
+        assert_eq!(deposit.parameters.max_fee, 32000);
+        assert_eq!(deposit.parameters.lock_time, 150);
+
+        let statuses = test_harness.deposit_statuses();
+        assert_eq!(statuses.get(&request.outpoint), Some(&Status::Accepted));
+        // Accepted deposits are still pending until they are confirmed.
+        assert_eq!(test_harness.pending_deposits().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_harness_returns_the_blocks_of_a_tenure_by_consensus_hash() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let mut test_harness = TestHarness::generate(&mut rng, 10, 1..5);
+        let bitcoin_block = test_harness.add_stacks_tenure(&mut rng, 3);
+
+        let (_, tip, _) = test_harness.stacks_blocks().last().unwrap().clone();
+        let consensus_hash = tip.header.consensus_hash;
+        let blocks = test_harness
+            .get_blocks_by_consensus_hash(&consensus_hash)
+            .await
+            .unwrap();
+
+        let expected: Vec<StacksBlockId> = test_harness
+            .stacks_blocks()
+            .iter()
+            .filter(|(_, _, block_hash)| block_hash == &bitcoin_block.block_hash())
+            .map(|(block_id, _, _)| *block_id)
+            .collect();
+        let block_ids: Vec<StacksBlockId> = blocks.iter().map(|block| block.block_id()).collect();
+        assert_eq!(block_ids.len(), 3);
+        assert_eq!(block_ids, expected);
+        assert!(blocks
+            .iter()
+            .all(|block| block.header.consensus_hash == consensus_hash));
+
+        // Every generated tenure is returned in full as well.
+        for (_, block, bitcoin_block_hash) in test_harness.stacks_blocks() {
+            let tenure_size = test_harness
+                .stacks_blocks()
+                .iter()
+                .filter(|(_, _, block_hash)| block_hash == bitcoin_block_hash)
+                .count();
+            let blocks = test_harness
+                .get_blocks_by_consensus_hash(&block.header.consensus_hash)
+                .await
+                .unwrap();
+            assert_eq!(blocks.len(), tenure_size);
+        }
+
+        let unknown = ConsensusHash([1; 20]);
+        let result = test_harness.get_blocks_by_consensus_hash(&unknown).await;
+        assert!(matches!(result, Err(Error::MissingBlock)));
+    }
+
+    #[tokio::test]
+    async fn test_harness_returns_the_headers_of_its_blocks() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let test_harness = TestHarness::generate(&mut rng, 10, 0..5);
+
+        for block in test_harness.bitcoin_blocks() {
+            let header = test_harness
+                .get_block_header(&block.block_hash())
+                .await
+                .unwrap();
+            assert_eq!(header, Some(block.header));
+        }
+
+        let unknown = BlockHash::all_zeros();
+        let header = test_harness.get_block_header(&unknown).await.unwrap();
+        assert_eq!(header, None);
+    }
+}
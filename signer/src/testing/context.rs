@@ -14,6 +14,7 @@ use blockstack_lib::{
     },
 };
 use clarity::types::chainstate::{StacksAddress, StacksBlockId};
+use clarity::vm::types::PrincipalData;
 use tokio::sync::{broadcast, Mutex};
 use tokio::time::error::Elapsed;
 
@@ -304,10 +305,21 @@ impl BitcoinInteract for WrappedMock<MockBitcoinInteract> {
         self.inner.lock().await.get_block(block_hash).await
     }
 
+    async fn get_block_header(
+        &self,
+        block_hash: &bitcoin::BlockHash,
+    ) -> Result<Option<bitcoin::block::Header>, Error> {
+        self.inner.lock().await.get_block_header(block_hash).await
+    }
+
     async fn get_tx(&self, txid: &Txid) -> Result<Option<GetTxResponse>, Error> {
         self.inner.lock().await.get_tx(txid).await
     }
 
+    async fn get_tx_confirmations(&self, txid: &Txid) -> Result<Option<u32>, Error> {
+        self.inner.lock().await.get_tx_confirmations(txid).await
+    }
+
     async fn get_tx_info(
         &self,
         txid: &bitcoin::Txid,
@@ -353,6 +365,10 @@ impl BitcoinInteract for WrappedMock<MockBitcoinInteract> {
         unimplemented!()
     }
 
+    async fn get_raw_mempool(&self) -> Result<Vec<Txid>, Error> {
+        self.inner.lock().await.get_raw_mempool().await
+    }
+
     async fn get_mempool_entry(
         &self,
         _txid: &Txid,
@@ -384,6 +400,18 @@ impl StacksInteract for WrappedMock<MockStacksInteract> {
             .await
     }
 
+    async fn is_registered_aggregate_key(
+        &self,
+        contract_principal: &StacksAddress,
+        aggregate_key: &PublicKey,
+    ) -> Result<bool, Error> {
+        self.inner
+            .lock()
+            .await
+            .is_registered_aggregate_key(contract_principal, aggregate_key)
+            .await
+    }
+
     async fn get_account(&self, address: &StacksAddress) -> Result<AccountInfo, Error> {
         self.inner.lock().await.get_account(address).await
     }
@@ -396,6 +424,17 @@ impl StacksInteract for WrappedMock<MockStacksInteract> {
         self.inner.lock().await.get_block(block_id).await
     }
 
+    async fn get_block_transactions(
+        &self,
+        block_id: StacksBlockId,
+    ) -> Result<Vec<StacksTransaction>, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_block_transactions(block_id)
+            .await
+    }
+
     async fn get_tenure(&self, block_id: StacksBlockId) -> Result<TenureBlocks, Error> {
         self.inner.lock().await.get_tenure(block_id).await
     }
@@ -404,6 +443,17 @@ impl StacksInteract for WrappedMock<MockStacksInteract> {
         self.inner.lock().await.get_tenure_info().await
     }
 
+    async fn get_blocks_by_consensus_hash(
+        &self,
+        consensus_hash: &ConsensusHash,
+    ) -> Result<Vec<NakamotoBlock>, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_blocks_by_consensus_hash(consensus_hash)
+            .await
+    }
+
     async fn get_sortition_info(
         &self,
         consensus_hash: &ConsensusHash,
@@ -454,6 +504,18 @@ impl StacksInteract for WrappedMock<MockStacksInteract> {
     async fn get_sbtc_total_supply(&self, sender: &StacksAddress) -> Result<Amount, Error> {
         self.inner.lock().await.get_sbtc_total_supply(sender).await
     }
+
+    async fn get_sbtc_balance(
+        &self,
+        token_principal: &StacksAddress,
+        address: &PrincipalData,
+    ) -> Result<u128, Error> {
+        self.inner
+            .lock()
+            .await
+            .get_sbtc_balance(token_principal, address)
+            .await
+    }
 }
 
 impl EmilyInteract for WrappedMock<MockEmilyInteract> {
@@ -472,6 +534,12 @@ impl EmilyInteract for WrappedMock<MockEmilyInteract> {
         self.inner.lock().await.get_deposits().await
     }
 
+    async fn get_withdrawals(
+        &self,
+    ) -> Result<Vec<emily_client::models::CreateWithdrawalRequestBody>, Error> {
+        self.inner.lock().await.get_withdrawals().await
+    }
+
     async fn update_deposits(
         &self,
         update_deposits: Vec<emily_client::models::DepositUpdate>,
@@ -495,6 +563,17 @@ impl EmilyInteract for WrappedMock<MockEmilyInteract> {
             .await
     }
 
+    async fn create_deposits(
+        &self,
+        create_deposits: Vec<emily_client::models::CreateDepositRequestBody>,
+    ) -> Result<emily_client::models::CreateDepositsResponse, Error> {
+        self.inner
+            .lock()
+            .await
+            .create_deposits(create_deposits)
+            .await
+    }
+
     async fn create_withdrawals(
         &self,
         create_withdrawals: Vec<emily_client::models::CreateWithdrawalRequestBody>,
@@ -527,6 +606,10 @@ impl EmilyInteract for WrappedMock<MockEmilyInteract> {
     async fn get_limits(&self) -> Result<SbtcLimits, Error> {
         self.inner.lock().await.get_limits().await
     }
+
+    async fn get_min_confirmations(&self) -> Result<Option<u64>, Error> {
+        self.inner.lock().await.get_min_confirmations().await
+    }
 }
 
 /// Struct which holds the current configuration of the context builder.
@@ -790,6 +790,7 @@ impl fake::Dummy<fake::Faker> for BitcoinPreSignRequest {
             request_package: fake::vec![TxRequestIds; 0..20],
             fee_rate: config.fake_with_rng(rng),
             last_fees: config.fake_with_rng(rng),
+            output_ordering: config.fake_with_rng(rng),
         }
     }
 }
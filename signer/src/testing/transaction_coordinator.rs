@@ -297,7 +297,10 @@ where
                     .times(1..)
                     .returning(|_, _| {
                         Box::pin(async {
-                            Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
+                            Ok(emily_client::models::UpdateDepositsResponse {
+                                deposits: vec![],
+                                errors: None,
+                            })
                         })
                     });
             })
@@ -447,7 +450,10 @@ where
                     .times(1..)
                     .returning(|_, _| {
                         Box::pin(async {
-                            Ok(emily_client::models::UpdateDepositsResponse { deposits: vec![] })
+                            Ok(emily_client::models::UpdateDepositsResponse {
+                                deposits: vec![],
+                                errors: None,
+                            })
                         })
                     });
             })
@@ -48,6 +48,7 @@ use crate::stacks::api::SubmitTxResponse;
 use crate::stacks::contracts::AsTxPayload;
 use crate::stacks::contracts::CompleteDepositV1;
 use crate::stacks::contracts::ContractCall;
+use crate::stacks::contracts::RejectWithdrawalV1;
 use crate::stacks::contracts::RotateKeysV1;
 use crate::stacks::contracts::SmartContract;
 use crate::stacks::contracts::SMART_CONTRACTS;
@@ -59,6 +60,7 @@ use crate::storage::DbRead as _;
 use crate::wsts_state_machine::CoordinatorStateMachine;
 
 use bitcoin::hashes::Hash as _;
+use bitvec::array::BitArray;
 use wsts::net::SignatureType;
 use wsts::state_machine::coordinator::Coordinator as _;
 use wsts::state_machine::coordinator::State as WstsCoordinatorState;
@@ -413,6 +415,7 @@ where
                 .collect(),
             fee_rate: signer_btc_state.fee_rate,
             last_fees: signer_btc_state.last_fees.map(Into::into),
+            output_ordering: signer_btc_state.output_ordering,
         };
 
         let presign_ack_filter = |event: &SignerSignal| {
@@ -520,7 +523,15 @@ where
             "fetched requests"
         );
         // Construct the transaction package and store it in the database.
-        let transaction_package = pending_requests.construct_transactions()?;
+        let selection_strategy = self
+            .context
+            .config()
+            .signer
+            .deposit_processing_order
+            .unwrap_or_default()
+            .selection_strategy();
+        let transaction_package =
+            pending_requests.construct_transactions_with(selection_strategy)?;
 
         self.construct_and_send_bitcoin_presign_request(
             bitcoin_chain_tip,
@@ -550,6 +561,27 @@ where
         Ok(())
     }
 
+    /// Preview the sweep transactions that
+    /// [`TxCoordinatorEventLoop::construct_and_sign_bitcoin_sbtc_transactions`]
+    /// would construct for the pending requests. Nothing is signed,
+    /// stored or broadcast.
+    #[tracing::instrument(skip_all)]
+    pub async fn preview_sweep(
+        &mut self,
+        bitcoin_chain_tip: &model::BitcoinBlockHash,
+        aggregate_key: &PublicKey,
+        signer_public_keys: &BTreeSet<PublicKey>,
+    ) -> Result<Vec<utxo::SweepPreview>, Error> {
+        let pending_requests = self
+            .get_pending_requests(bitcoin_chain_tip, aggregate_key, signer_public_keys)
+            .await?;
+
+        match pending_requests {
+            Some(requests) => requests.preview_sweep(),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Construct and coordinate signing rounds for `deposit-accept`,
     /// `withdraw-accept` and `withdraw-reject` transactions.
     ///
@@ -595,14 +627,28 @@ where
             .get_swept_deposit_requests(chain_tip, self.context_window)
             .await?;
 
-        if deposit_requests.is_empty() {
+        // Withdrawal requests that are still pending at the end of the
+        // expiry window are rejected, which returns the locked sBTC to the
+        // requester.
+        let expired_withdrawals = match self.context.config().signer.withdrawal_expiry_window {
+            Some(expiry_window) => {
+                self.context
+                    .get_storage()
+                    .get_expired_withdrawal_requests(chain_tip, self.context_window, expiry_window)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+
+        if deposit_requests.is_empty() && expired_withdrawals.is_empty() {
             tracing::debug!("no stacks transactions to create, exiting");
             return Ok(());
         }
 
         tracing::debug!(
             num_deposits = %deposit_requests.len(),
-            "we have deposit requests that have been swept that may need minting"
+            num_expired_withdrawals = %expired_withdrawals.len(),
+            "we have swept deposit requests that may need minting or expired withdrawal requests"
         );
         // We need to know the nonce to use, so we reach out to our stacks
         // node for the account information for our multi-sig address.
@@ -649,6 +695,40 @@ where
             }
         }
 
+        for req in expired_withdrawals {
+            let request_id = req.request_id;
+            let sign_request_fut = self.construct_withdrawal_reject_stacks_sign_request(
+                req,
+                bitcoin_aggregate_key,
+                &wallet,
+            );
+
+            let (sign_request, multi_tx) = match sign_request_fut.await {
+                Ok(res) => res,
+                Err(error) => {
+                    tracing::error!(%error, request_id, "could not construct a transaction rejecting the withdrawal request");
+                    continue;
+                }
+            };
+
+            let process_request_fut =
+                self.process_sign_request(sign_request, chain_tip, multi_tx, &wallet);
+
+            match process_request_fut.await {
+                Ok(txid) => {
+                    tracing::info!(%txid, request_id, "successfully submitted reject-withdrawal transaction")
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        %error,
+                        request_id,
+                        "could not process the stacks sign request for an expired withdrawal"
+                    );
+                    wallet.set_nonce(wallet.get_nonce().saturating_sub(1));
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -705,8 +785,21 @@ where
             .sign_stacks_transaction(sign_request, multi_tx, chain_tip, wallet)
             .await?;
 
+        let stacks_tip_height = self
+            .context
+            .get_storage()
+            .get_stacks_chain_tip(chain_tip)
+            .await?
+            .map_or(0, |block| block.block_height);
+
         match self.context.get_stacks_client().submit_tx(&tx).await {
-            Ok(SubmitTxResponse::Acceptance(txid)) => Ok(txid.into()),
+            Ok(SubmitTxResponse::Acceptance(txid)) => {
+                self.context
+                    .state()
+                    .pending_submissions()
+                    .insert(txid.into(), stacks_tip_height);
+                Ok(txid.into())
+            }
             Ok(SubmitTxResponse::Rejection(err)) => Err(err.into()),
             Err(err) => Err(err),
         }
@@ -776,6 +869,49 @@ where
         Ok((sign_request, multi_tx))
     }
 
+    /// Construct a sign request for a `reject-withdrawal-request`
+    /// transaction for the given expired withdrawal request.
+    #[tracing::instrument(skip_all)]
+    async fn construct_withdrawal_reject_stacks_sign_request(
+        &self,
+        req: model::WithdrawalRequest,
+        bitcoin_aggregate_key: &PublicKey,
+        wallet: &SignerWallet,
+    ) -> Result<(StacksTransactionSignRequest, MultisigTx), Error> {
+        let votes = self
+            .context
+            .get_storage()
+            .get_withdrawal_request_signer_votes(&req.qualified_id(), bitcoin_aggregate_key)
+            .await?;
+
+        let contract_call = ContractCall::RejectWithdrawalV1(RejectWithdrawalV1 {
+            request_id: req.request_id,
+            signer_bitmap: BitArray::from(votes),
+            deployer: self.context.config().signer.deployer,
+        });
+
+        // The requester has waited for the whole expiry window already, so
+        // there is no rush, and we use the medium priority fee.
+        let tx_fee = self
+            .context
+            .get_stacks_client()
+            .estimate_fees(wallet, &contract_call, FeePriority::Medium)
+            .await?;
+
+        let multi_tx = MultisigTx::new_tx(&contract_call, wallet, tx_fee);
+        let tx = multi_tx.tx();
+
+        let sign_request = StacksTransactionSignRequest {
+            aggregate_key: *bitcoin_aggregate_key,
+            contract_tx: contract_call.into(),
+            nonce: tx.get_origin_nonce(),
+            tx_fee: tx.get_tx_fee(),
+            txid: tx.txid(),
+        };
+
+        Ok((sign_request, multi_tx))
+    }
+
     /// Attempt to sign the stacks transaction.
     #[tracing::instrument(skip_all)]
     async fn sign_stacks_transaction(
@@ -1229,6 +1365,13 @@ where
         let bitcoin_client = self.context.get_bitcoin_client();
         let fee_rate = bitcoin_client.estimate_fee_rate().await?;
 
+        // Refuse to sweep during fee spikes rather than overpay.
+        if let Some(cap) = self.context.config().signer.max_sweep_fee_rate {
+            if fee_rate > cap {
+                return Err(Error::FeeRateTooHigh { rate: fee_rate, cap });
+            }
+        }
+
         // Retrieve the signer's current UTXO.
         let utxo = self
             .context
@@ -1245,6 +1388,12 @@ where
             public_key: bitcoin::XOnlyPublicKey::from(aggregate_key),
             last_fees,
             magic_bytes: [b'T', b'3'], //TODO(#472): Use the correct magic bytes.
+            output_ordering: self
+                .context
+                .config()
+                .signer
+                .sweep_output_ordering
+                .unwrap_or_default(),
         })
     }
 
@@ -1299,6 +1448,12 @@ where
             accept_threshold: threshold,
             num_signers,
             sbtc_limits: self.context.state().get_current_limits(),
+            min_sweep_value: self
+                .context
+                .config()
+                .signer
+                .min_sweep_value
+                .unwrap_or_default(),
         }))
     }
 
@@ -1635,10 +1790,35 @@ pub fn coordinator_public_key(
     bitcoin_chain_tip: &model::BitcoinBlockHash,
     signer_public_keys: &BTreeSet<PublicKey>,
 ) -> Option<PublicKey> {
-    // Create a hash of the bitcoin chain tip. SHA256 will always result in
-    // a 32 byte digest.
+    select_coordinator(&bitcoin_chain_tip.into_bytes(), signer_public_keys)
+}
+
+/// Find the coordinator for the signing session with the given id.
+///
+/// The signer set is sorted before selecting the coordinator, so all
+/// signers compute the same coordinator for the same session regardless
+/// of the order of the keys that they were given.
+///
+/// # Panics
+///
+/// Panics if the signer set is empty.
+pub fn coordinator_for(signer_set: &[PublicKey], session_id: &[u8; 32]) -> PublicKey {
+    let signer_public_keys: BTreeSet<PublicKey> = signer_set.iter().copied().collect();
+    #[allow(clippy::expect_used)]
+    select_coordinator(session_id, &signer_public_keys)
+        .expect("BUG: cannot select a coordinator from an empty signer set")
+}
+
+/// Select a signer from the sorted signer set using the hash of the given
+/// seed.
+fn select_coordinator(
+    seed: &[u8; 32],
+    signer_public_keys: &BTreeSet<PublicKey>,
+) -> Option<PublicKey> {
+    // Create a hash of the seed. SHA256 will always result in a 32 byte
+    // digest.
     let mut hasher = sha2::Sha256::new();
-    hasher.update(bitcoin_chain_tip.into_bytes());
+    hasher.update(seed);
     let digest: [u8; 32] = hasher.finalize().into();
 
     // Use the first 4 bytes of the digest to create a u32 index. Since `digest`
@@ -1652,6 +1832,9 @@ pub fn coordinator_public_key(
     let index = u32::from_be_bytes(u32_bytes);
 
     let num_signers = signer_public_keys.len();
+    if num_signers == 0 {
+        return None;
+    }
 
     signer_public_keys
         .iter()
@@ -1663,14 +1846,29 @@ pub fn coordinator_public_key(
 mod tests {
     use crate::bitcoin::MockBitcoinInteract;
     use crate::emily_client::MockEmilyInteract;
+    use crate::error::Error;
     use crate::stacks::api::MockStacksInteract;
     use crate::storage::in_memory::SharedStore;
+    use crate::storage::model;
     use crate::testing;
     use crate::testing::context::*;
     use crate::testing::transaction_coordinator::TestEnvironment;
 
+    use std::collections::BTreeSet;
+    use std::time::Duration;
+
+    use fake::Fake as _;
+    use fake::Faker;
+
+    use crate::keys::PrivateKey;
+    use crate::keys::PublicKey;
+    use crate::network::in_memory2::SignerNetwork;
+    use rand::SeedableRng as _;
     use test_log::test;
 
+    use super::coordinator_for;
+    use super::TxCoordinatorEventLoop;
+
     fn test_environment() -> TestEnvironment<
         TestContext<
             SharedStore,
@@ -1770,4 +1968,82 @@ mod tests {
     async fn should_ignore_withdrawals() {
         test_environment().assert_ignore_withdrawals().await;
     }
+
+    #[test(tokio::test)]
+    async fn sweeps_are_refused_when_the_fee_rate_exceeds_the_cap() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+        let mut settings = crate::config::Settings::new_from_default_config().unwrap();
+        settings.signer.max_sweep_fee_rate = Some(100.0);
+
+        let mut context = TestContext::builder()
+            .with_settings(settings)
+            .with_in_memory_storage()
+            .with_mocked_clients()
+            .build();
+        let network = SignerNetwork::single(&context);
+
+        // The estimator reports a fee spike above the configured cap.
+        context
+            .with_bitcoin_client(|client| {
+                client
+                    .expect_estimate_fee_rate()
+                    .times(1)
+                    .returning(|| Box::pin(async { Ok(250.0) }));
+            })
+            .await;
+
+        let mut coord = TxCoordinatorEventLoop {
+            context,
+            private_key: PrivateKey::new(&mut rng),
+            network: network.spawn(),
+            threshold: 5,
+            context_window: 5,
+            signing_round_max_duration: Duration::from_secs(5),
+            bitcoin_presign_request_max_duration: Duration::from_secs(5),
+            dkg_max_duration: Duration::from_secs(5),
+            sbtc_contracts_deployed: false,
+            is_epoch3: true,
+        };
+
+        let aggregate_key = PublicKey::from_private_key(&PrivateKey::new(&mut rng));
+        let chain_tip: model::BitcoinBlockHash = Faker.fake_with_rng(&mut rng);
+
+        let result = coord.get_btc_state(&chain_tip, &aggregate_key).await;
+        match result {
+            Err(Error::FeeRateTooHigh { rate, cap }) => {
+                assert_eq!(rate, 250.0);
+                assert_eq!(cap, 100.0);
+            }
+            other => panic!("expected the sweep to be refused, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn coordinator_for_is_deterministic_across_signers() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let signer_set: Vec<PublicKey> = std::iter::repeat_with(|| PrivateKey::new(&mut rng))
+            .map(|private_key| PublicKey::from_private_key(&private_key))
+            .take(7)
+            .collect();
+
+        let session_id = [1; 32];
+        let coordinator = coordinator_for(&signer_set, &session_id);
+        assert!(signer_set.contains(&coordinator));
+
+        // Each signer may have learned about the signer set in a
+        // different order, but they all agree on the coordinator.
+        for shift in 0..signer_set.len() {
+            let mut shuffled = signer_set.clone();
+            shuffled.rotate_left(shift);
+            shuffled.swap(0, shift);
+            assert_eq!(coordinator_for(&shuffled, &session_id), coordinator);
+        }
+
+        // Different sessions end up with different coordinators.
+        let coordinators: BTreeSet<PublicKey> = (0..32u8)
+            .map(|byte| coordinator_for(&signer_set, &[byte; 32]))
+            .collect();
+        assert!(coordinators.len() > 1);
+    }
 }
@@ -268,6 +268,10 @@ where
                     .get_storage_mut()
                     .write_sweep_transaction(&sweep_tx.into())
                     .await?;
+                self.context
+                    .state()
+                    .known_sweeps()
+                    .insert(sweep_tx.txid, sweep_tx.created_at_block_hash.into());
             }
 
             (message::Payload::BitcoinPreSignRequest(requests), _, _) => {
@@ -5,13 +5,16 @@ use std::{
     future::Future,
     ops::Deref,
     sync::{
-        atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
+        Arc, RwLock,
     },
+    time::{Duration, SystemTime},
 };
 
+use rand::Rng as _;
 use thiserror::Error;
 
+use crate::config::BitcoinConfig;
 use crate::error::Error;
 
 /// Extension trait for `Vec`.
@@ -32,6 +35,10 @@ impl<T> CollectionExt for Vec<T> {
 /// plus two retries).
 const DEFAULT_MINIMUM_RETRY_COUNT: usize = 2;
 
+/// How long a client that failed a call is passed over in round-robin
+/// mode, unless every client failed within that time.
+const RECENT_ERROR_WINDOW: Duration = Duration::from_secs(30);
+
 /// Error variants for the fallback client.
 #[derive(Debug, Error)]
 pub enum FallbackClientError {
@@ -46,6 +53,166 @@ pub enum FallbackClientError {
     NoEndpoints,
 }
 
+/// The policy that the fallback client uses to retry idempotent calls on
+/// the same client before failing over to the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts made with a client before failing
+    /// over to the next one. At least one attempt is always made.
+    pub max_attempts: u8,
+    /// The delay before the first retry with the same client. The delay
+    /// doubles with each retry after that.
+    pub base_delay: Duration,
+    /// The upper bound of the random amount of time that is added to each
+    /// delay, so that callers do not retry in lockstep.
+    pub jitter: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// The default policy makes a single attempt with each client, so
+    /// that calls fail over right away.
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_delay: Duration::ZERO,
+            jitter: Duration::ZERO,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create the retry policy for reads with the bitcoin-core nodes from
+    /// the bitcoin configuration.
+    pub fn from_config(config: &BitcoinConfig) -> Self {
+        let default = Self::default();
+        Self {
+            max_attempts: config.rpc_max_attempts.unwrap_or(default.max_attempts),
+            base_delay: config
+                .rpc_retry_base_delay
+                .map(Duration::from_millis)
+                .unwrap_or(default.base_delay),
+            jitter: config
+                .rpc_retry_jitter
+                .map(Duration::from_millis)
+                .unwrap_or(default.jitter),
+        }
+    }
+
+    /// The delay before the given retry with the same client, where the
+    /// first retry is `1`.
+    pub fn delay(&self, retry: u8) -> Duration {
+        let exponent = u32::from(retry.saturating_sub(1));
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(exponent));
+        let jitter = if self.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            rand::thread_rng().gen_range(Duration::ZERO..=self.jitter)
+        };
+        delay.saturating_add(jitter)
+    }
+}
+
+/// How the fallback client picks the client that a call is made with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchStrategy {
+    /// Every call is made with the current client, and the fallback
+    /// client only moves on to the next client when a call fails.
+    #[default]
+    Failover,
+    /// Successive calls are made with each of the clients in turn,
+    /// spreading the load across them. A call that fails falls back to
+    /// the next client, and clients that failed recently are passed over.
+    ///
+    /// Successive calls may be made with nodes that are at different
+    /// chain tips, so this is not suitable for callers that rely on
+    /// consecutive calls seeing the same chain.
+    RoundRobin,
+}
+
+/// The outcomes of the calls made with one of the clients of the fallback
+/// client.
+#[derive(Debug, Default)]
+struct ClientHealth {
+    /// The number of calls that succeeded.
+    successes: AtomicU64,
+    /// The number of calls that failed.
+    failures: AtomicU64,
+    /// When the last call failed, in milliseconds since the unix epoch,
+    /// or zero if no call has failed.
+    last_error_at: AtomicU64,
+}
+
+impl ClientHealth {
+    /// Record the outcome of a call made with the client.
+    fn record<R, E>(&self, result: &Result<R, E>) {
+        if result.is_ok() {
+            self.successes.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let millis = u64::try_from(now.as_millis()).unwrap_or(u64::MAX);
+        self.last_error_at.store(millis.max(1), Ordering::Relaxed);
+    }
+
+    /// Whether the last failed call with the client was within the given
+    /// amount of time.
+    fn failed_within(&self, window: Duration) -> bool {
+        match self.last_error_at.load(Ordering::Relaxed) {
+            0 => false,
+            millis => {
+                let failed_at = SystemTime::UNIX_EPOCH + Duration::from_millis(millis);
+                // If the clock went backwards then the failure is as
+                // recent as it gets.
+                failed_at.elapsed().map_or(true, |elapsed| elapsed < window)
+            }
+        }
+    }
+
+    fn snapshot(&self) -> ClientHealthSnapshot {
+        let last_error_at = match self.last_error_at.load(Ordering::Relaxed) {
+            0 => None,
+            millis => Some(SystemTime::UNIX_EPOCH + Duration::from_millis(millis)),
+        };
+        ClientHealthSnapshot {
+            successes: self.successes.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            last_error_at,
+        }
+    }
+}
+
+/// The outcomes of the calls made with one of the clients of the fallback
+/// client, at the time of the snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientHealthSnapshot {
+    /// The number of calls that succeeded.
+    pub successes: u64,
+    /// The number of calls that failed, including those that were retried
+    /// on the same client.
+    pub failures: u64,
+    /// When the last call failed, if any call has failed.
+    pub last_error_at: Option<SystemTime>,
+}
+
+/// The health of the clients of the fallback client, at the time of the
+/// snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthSnapshot {
+    /// The index of the client that the next call will be made with.
+    pub current_client: usize,
+    /// The number of times that the fallback client has failed over to
+    /// another client.
+    pub failovers: u64,
+    /// The health of each client, in the order that the clients were
+    /// given to the fallback client.
+    pub clients: Vec<ClientHealthSnapshot>,
+}
+
 /// A fallback-wrapper that can failover to other clients if the current client fails.
 pub struct ApiFallbackClient<T> {
     inner: Arc<InnerApiFallbackClient<T>>,
@@ -68,8 +235,12 @@ impl<T> Clone for ApiFallbackClient<T> {
 /// Inner implementation of the fallback client.
 pub struct InnerApiFallbackClient<T> {
     inner_clients: Vec<T>,
+    dispatch_strategy: DispatchStrategy,
     last_client_index: AtomicUsize,
     retry_count: AtomicU8,
+    retry_policy: RwLock<RetryPolicy>,
+    health: Vec<ClientHealth>,
+    failovers: AtomicU64,
 }
 
 /// A context that provides information about the current retry attempt and
@@ -153,11 +324,36 @@ impl<T> InnerApiFallbackClient<T> {
         self.retry_count.store(retry_count, Ordering::Relaxed);
     }
 
+    /// Get a snapshot of the health of the clients, including the client
+    /// that is currently in use.
+    pub fn health(&self) -> HealthSnapshot {
+        HealthSnapshot {
+            current_client: self.last_client_index.load(Ordering::Relaxed),
+            failovers: self.failovers.load(Ordering::Relaxed),
+            clients: self.health.iter().map(ClientHealth::snapshot).collect(),
+        }
+    }
+
+    /// Get the strategy used to pick the client that each call is made
+    /// with.
+    pub fn dispatch_strategy(&self) -> DispatchStrategy {
+        self.dispatch_strategy
+    }
+
     /// Get a reference to the current inner API client.
     pub fn get_client(&self) -> &T {
         &self.inner_clients[self.last_client_index.load(Ordering::Relaxed)]
     }
 
+    /// Get the policy used to retry idempotent calls on the same client.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        *self
+            .retry_policy
+            .read()
+            .expect("BUG: Failed to acquire read lock")
+    }
+
     /// Execute a closure on the current client, falling back to remaining clients
     /// if the closure returns an error.
     ///
@@ -166,42 +362,139 @@ impl<T> InnerApiFallbackClient<T> {
         &'a self,
         f: impl Fn(&'a T, RetryContext) -> F,
     ) -> Result<R, Error>
+    where
+        E: std::error::Error + std::fmt::Debug,
+        E: Into<Error>,
+        F: Future<Output = Result<R, E>> + 'a,
+    {
+        self.exec_with_policy(RetryPolicy::default(), f).await
+    }
+
+    /// Execute an idempotent closure on the current client, retrying it on
+    /// the same client according to the [`RetryPolicy`] before falling
+    /// back to the remaining clients. Only errors that are transient, see
+    /// [`Error::is_transient`], are retried on the same client.
+    ///
+    /// Only use this for calls that can safely be made more than once,
+    /// like reads, since a call that fails with a transient error may
+    /// still have taken effect.
+    pub async fn exec_idempotent<'a, R, E, F>(
+        &'a self,
+        f: impl Fn(&'a T, RetryContext) -> F,
+    ) -> Result<R, Error>
+    where
+        E: std::error::Error + std::fmt::Debug,
+        E: Into<Error>,
+        F: Future<Output = Result<R, E>> + 'a,
+    {
+        self.exec_with_policy(self.retry_policy(), f).await
+    }
+
+    async fn exec_with_policy<'a, R, E, F>(
+        &'a self,
+        policy: RetryPolicy,
+        f: impl Fn(&'a T, RetryContext) -> F,
+    ) -> Result<R, Error>
     where
         E: std::error::Error + std::fmt::Debug,
         E: Into<Error>,
         F: Future<Output = Result<R, E>> + 'a,
     {
         let retry_count = self.retry_count.load(Ordering::Relaxed);
+        let mut client_index = self.first_client_index();
         for i in 0..=retry_count {
             let retry_ctx = RetryContext::new(retry_count, i);
-            let client_index = self.last_client_index.load(Ordering::Relaxed);
-            let result = f(&self.inner_clients[client_index], retry_ctx.clone()).await;
+            let client = &self.inner_clients[client_index];
+            let mut result: Result<R, Error> =
+                f(client, retry_ctx.clone()).await.map_err(Into::into);
+            self.health[client_index].record(&result);
+
+            // Transient errors, like a network blip, are retried on the
+            // same client before we fail over to the next one. Other
+            // errors would only fail the same way again.
+            for attempt in 1..policy.max_attempts {
+                let Err(error) = &result else {
+                    break;
+                };
+                if retry_ctx.is_aborted() || !error.is_transient() {
+                    break;
+                }
+                tracing::debug!(%error, attempt, max_attempts=policy.max_attempts, "retrying call on the same client");
+                tokio::time::sleep(policy.delay(attempt)).await;
+                result = f(client, retry_ctx.clone()).await.map_err(Into::into);
+                self.health[client_index].record(&result);
+            }
 
             if let Err(error) = result {
                 tracing::warn!(%error, retry_num=i, max_retries=retry_count, "failover client call failed");
 
                 if retry_ctx.is_aborted() {
-                    return Err(error.into());
+                    return Err(error);
                 }
 
-                self.last_client_index.store(
-                    (client_index + 1) % self.inner_clients.len(),
-                    Ordering::Relaxed,
-                );
+                let next_index = (client_index + 1) % self.inner_clients.len();
+                if next_index != client_index {
+                    self.failovers.fetch_add(1, Ordering::Relaxed);
+                }
+                // In round-robin mode the next call moves on to the next
+                // client regardless, so only this call falls back.
+                if self.dispatch_strategy == DispatchStrategy::Failover {
+                    self.last_client_index.store(next_index, Ordering::Relaxed);
+                }
+                client_index = next_index;
 
                 continue;
             }
 
-            return result.map_err(Into::into);
+            return result;
         }
 
         Err(FallbackClientError::AllClientsFailed.into())
     }
+
+    /// Get the index of the client that a new call is first made with.
+    /// In round-robin mode this also moves the current client on to the
+    /// next one, for the next call, and passes over the clients whose last
+    /// failure was within [`RECENT_ERROR_WINDOW`], unless all of them
+    /// failed recently.
+    ///
+    /// In round-robin mode consecutive calls are made with different
+    /// nodes, which may be at different chain tips. A caller that needs
+    /// several calls to see the same chain should not rely on this.
+    fn first_client_index(&self) -> usize {
+        match self.dispatch_strategy {
+            DispatchStrategy::Failover => self.last_client_index.load(Ordering::Relaxed),
+            DispatchStrategy::RoundRobin => {
+                let len = self.inner_clients.len();
+                let index = self
+                    .last_client_index
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |index| {
+                        Some((index + 1) % len)
+                    })
+                    .unwrap_or_else(|index| index);
+
+                (0..len)
+                    .map(|offset| (index + offset) % len)
+                    .find(|&index| !self.health[index].failed_within(RECENT_ERROR_WINDOW))
+                    .unwrap_or(index)
+            }
+        }
+    }
 }
 
 impl<T> ApiFallbackClient<T> {
-    /// Create a new fallback client from a list of clients.
+    /// Create a new fallback client from a list of clients, using the
+    /// [`DispatchStrategy::Failover`] strategy.
     pub fn new(clients: Vec<T>) -> Result<Self, FallbackClientError> {
+        Self::new_with_strategy(clients, DispatchStrategy::default())
+    }
+
+    /// Create a new fallback client from a list of clients, using the
+    /// given strategy to pick the client that each call is made with.
+    pub fn new_with_strategy(
+        clients: Vec<T>,
+        dispatch_strategy: DispatchStrategy,
+    ) -> Result<Self, FallbackClientError> {
         if clients.is_empty() {
             return Err(FallbackClientError::NoEndpoints);
         }
@@ -209,19 +502,38 @@ impl<T> ApiFallbackClient<T> {
         let retry_count = min(DEFAULT_MINIMUM_RETRY_COUNT, clients.len());
 
         let inner = InnerApiFallbackClient {
+            health: clients.iter().map(|_| ClientHealth::default()).collect(),
             inner_clients: clients,
+            dispatch_strategy,
             last_client_index: AtomicUsize::new(0),
             retry_count: AtomicU8::new(retry_count as u8),
+            retry_policy: RwLock::new(RetryPolicy::default()),
+            failovers: AtomicU64::new(0),
         };
 
         Ok(Self { inner: Arc::new(inner) })
     }
+
+    /// Set the policy used to retry idempotent calls on the same client
+    /// before failing over. See [`InnerApiFallbackClient::exec_idempotent`].
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        // We should never fail to acquire a lock from the RwLock so that it panics.
+        *self
+            .retry_policy
+            .write()
+            .expect("BUG: Failed to acquire write lock") = policy;
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use rand::SeedableRng as _;
     use url::Url;
 
+    use crate::emily_client::EmilyInteract as _;
+    use crate::testing::block_observer::TestHarness;
+
     use super::*;
 
     #[derive(Debug, PartialEq, Eq)]
@@ -404,6 +716,189 @@ mod tests {
         ));
     }
 
+    fn retry_policy(max_attempts: u8) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            jitter: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn idempotent_calls_are_retried_on_the_same_client() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let harness = TestHarness::generate(&mut rng, 2, 0..1);
+        let client = ApiFallbackClient::new(vec![harness.clone(), harness.clone()])
+            .unwrap()
+            .with_retry_policy(retry_policy(3));
+
+        // The first client returns an error twice and then succeeds, so
+        // the third attempt with it succeeds without failing over.
+        harness.fail_next_get_deposits(2);
+        let call_count = AtomicUsize::new(0);
+        let result = client
+            .exec_idempotent(|client, _| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                client.get_deposits()
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+        assert_eq!(client.last_client_index.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn idempotent_calls_fail_over_once_retries_are_exhausted() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let harness = TestHarness::generate(&mut rng, 2, 0..1);
+        let client = ApiFallbackClient::new(vec![harness.clone(), harness.clone()])
+            .unwrap()
+            .with_retry_policy(retry_policy(2));
+
+        // Both attempts with the first client fail, so the call fails
+        // over to the second client, where it succeeds.
+        harness.fail_next_get_deposits(2);
+        let call_count = AtomicUsize::new(0);
+        let result = client
+            .exec_idempotent(|client, _| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                client.get_deposits()
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+        assert_eq!(client.last_client_index.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn idempotent_calls_are_not_retried_on_errors_that_are_not_transient() {
+        let client = ApiFallbackClient::<MockClient>::from(
+            &[
+                Url::parse("http://fail/1").unwrap(),
+                Url::parse("http://ok/2").unwrap(),
+            ][..],
+        )
+        .with_retry_policy(retry_policy(3));
+
+        // The first client fails with an error that would only happen
+        // again, so the call fails over right away.
+        let call_count = AtomicUsize::new(0);
+        let result = client
+            .exec_idempotent(|client, _| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                client.call()
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+        assert_eq!(client.last_client_index.load(Ordering::Relaxed), 1);
+    }
+
+    fn transport_error(error: bitcoincore_rpc::jsonrpc::simple_http::Error) -> Error {
+        let error = bitcoincore_rpc::jsonrpc::Error::Transport(Box::new(error));
+        Error::BitcoinCoreRpc(bitcoincore_rpc::Error::JsonRpc(error))
+    }
+
+    #[test]
+    fn only_transport_and_server_errors_are_transient() {
+        use bitcoincore_rpc::jsonrpc;
+
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(transport_error(jsonrpc::simple_http::Error::SocketError(refused)).is_transient());
+        assert!(transport_error(jsonrpc::simple_http::Error::HttpErrorCode(503)).is_transient());
+        assert!(!transport_error(jsonrpc::simple_http::Error::HttpErrorCode(401)).is_transient());
+
+        let not_found = jsonrpc::error::RpcError {
+            code: -5,
+            message: "Block not found".to_string(),
+            data: None,
+        };
+        let error = bitcoincore_rpc::Error::JsonRpc(jsonrpc::Error::Rpc(not_found));
+        assert!(!Error::BitcoinCoreRpc(error).is_transient());
+        assert!(!Error::Encryption.is_transient());
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_calls_are_not_retried_on_the_same_client() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let harness = TestHarness::generate(&mut rng, 2, 0..1);
+        let client = ApiFallbackClient::new(vec![harness.clone(), harness.clone()])
+            .unwrap()
+            .with_retry_policy(retry_policy(3));
+
+        harness.fail_next_get_deposits(1);
+        let call_count = AtomicUsize::new(0);
+        let result = client
+            .exec(|client, _| {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                client.get_deposits()
+            })
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+        assert_eq!(client.last_client_index.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn retry_delays_double_with_each_retry() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            jitter: Duration::from_millis(10),
+        };
+
+        for (retry, delay) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = Duration::from_millis(delay);
+            let actual = policy.delay(retry);
+            assert!(actual >= delay && actual <= delay + policy.jitter);
+        }
+    }
+
+    #[tokio::test]
+    async fn health_tracks_the_outcomes_of_calls_and_failovers() {
+        let client = ApiFallbackClient::<MockClient>::from(
+            &[
+                Url::parse("http://fail/1").unwrap(),
+                Url::parse("http://ok/2").unwrap(),
+            ][..],
+        );
+
+        let health = client.health();
+        assert_eq!(health.current_client, 0);
+        assert_eq!(health.failovers, 0);
+        assert!(health
+            .clients
+            .iter()
+            .all(|client| client.successes == 0 && client.failures == 0));
+
+        // The first client fails, so the call fails over to the second
+        // client, where it succeeds. Later calls stay with the second
+        // client.
+        let before = SystemTime::now();
+        client.exec(|client, _| client.call()).await.unwrap();
+        client.exec(|client, _| client.call()).await.unwrap();
+
+        let health = client.health();
+        assert_eq!(health.current_client, 1);
+        assert_eq!(health.failovers, 1);
+
+        let failing = health.clients[0];
+        assert_eq!(failing.successes, 0);
+        assert_eq!(failing.failures, 1);
+        // The timestamp is stored with millisecond precision.
+        let last_error_at = failing.last_error_at.unwrap();
+        assert!(last_error_at + Duration::from_millis(1) >= before);
+
+        let healthy = health.clients[1];
+        assert_eq!(healthy.successes, 2);
+        assert_eq!(healthy.failures, 0);
+        assert_eq!(healthy.last_error_at, None);
+    }
+
     #[tokio::test]
     async fn returns_err_early_when_abort_called() {
         let client = ApiFallbackClient::<MockClient>::from(
@@ -433,4 +928,107 @@ mod tests {
         // (which was just randomly chosen, it has no significance)
         assert!(matches!(result.unwrap_err(), Error::Encryption));
     }
+
+    fn round_robin_client(rng: &mut rand::rngs::StdRng) -> ApiFallbackClient<TestHarness> {
+        let harnesses = (0..3)
+            .map(|_| TestHarness::generate(rng, 2, 0..1))
+            .collect();
+        ApiFallbackClient::new_with_strategy(harnesses, DispatchStrategy::RoundRobin).unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_robin_spreads_calls_evenly_across_clients() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let client = round_robin_client(&mut rng);
+
+        for _ in 0..30 {
+            client
+                .exec(|client, _| client.get_deposits())
+                .await
+                .unwrap();
+        }
+
+        let health = client.health();
+        assert_eq!(health.current_client, 0);
+        assert_eq!(health.failovers, 0);
+        for client in health.clients {
+            assert_eq!(client.successes, 10);
+            assert_eq!(client.failures, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn round_robin_passes_over_clients_that_failed_recently() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let client = round_robin_client(&mut rng);
+
+        // The second call is made with the second client, which fails, so
+        // that call falls back to the third client. After that, the calls
+        // that would go to the second client go to the third client, as
+        // the second client failed too recently.
+        client.inner_clients[1].fail_next_get_deposits(1);
+        for _ in 0..30 {
+            client
+                .exec(|client, _| client.get_deposits())
+                .await
+                .unwrap();
+        }
+
+        let health = client.health();
+        assert_eq!(health.failovers, 1);
+        let successes: Vec<u64> = health
+            .clients
+            .iter()
+            .map(|client| client.successes)
+            .collect();
+        assert_eq!(successes, vec![10, 0, 20]);
+        assert_eq!(health.clients[1].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn round_robin_uses_its_turn_when_every_client_failed_recently() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let client = round_robin_client(&mut rng);
+        for health in &client.health {
+            health.record::<(), _>(&Err(Error::Encryption));
+        }
+
+        for _ in 0..3 {
+            client
+                .exec(|client, _| client.get_deposits())
+                .await
+                .unwrap();
+        }
+
+        let successes: Vec<u64> = client
+            .health()
+            .clients
+            .iter()
+            .map(|client| client.successes)
+            .collect();
+        assert_eq!(successes, vec![1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn failover_is_the_default_dispatch_strategy() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(46);
+        let harness = TestHarness::generate(&mut rng, 2, 0..1);
+        let client = ApiFallbackClient::new(vec![harness.clone(), harness.clone()]).unwrap();
+        assert_eq!(client.dispatch_strategy(), DispatchStrategy::Failover);
+
+        for _ in 0..10 {
+            client
+                .exec(|client, _| client.get_deposits())
+                .await
+                .unwrap();
+        }
+
+        let successes: Vec<u64> = client
+            .health()
+            .clients
+            .iter()
+            .map(|client| client.successes)
+            .collect();
+        assert_eq!(successes, vec![10, 0]);
+    }
 }
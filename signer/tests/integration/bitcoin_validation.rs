@@ -14,6 +14,7 @@ use signer::bitcoin::validation::BitcoinTxContext;
 use signer::bitcoin::validation::BitcoinTxValidationData;
 use signer::bitcoin::validation::InputValidationResult;
 use signer::bitcoin::validation::TxRequestIds;
+use signer::config::OutputOrdering;
 use signer::context::Context;
 use signer::context::SbtcLimits;
 use signer::message::BitcoinPreSignRequest;
@@ -51,6 +52,7 @@ where
         public_key: btc_ctx.aggregate_key.into(),
         last_fees: request.last_fees,
         magic_bytes: [b'T', b'3'],
+        output_ordering: request.output_ordering,
     }
 }
 
@@ -132,6 +134,7 @@ async fn one_tx_per_request_set() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -234,6 +237,7 @@ async fn one_invalid_deposit_invalidates_tx() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -351,6 +355,7 @@ async fn one_withdrawal_errors_validation() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -444,6 +449,7 @@ async fn cannot_sign_deposit_is_ok() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -516,6 +522,7 @@ async fn cannot_sign_deposit_is_ok() {
         accept_threshold: 2,
         num_signers: 3,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -578,6 +585,7 @@ async fn sighashes_match_from_sbtc_requests_object() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
@@ -646,6 +654,7 @@ async fn sighashes_match_from_sbtc_requests_object() {
         accept_threshold: 2,
         num_signers: 3,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
     let txs = sbtc_requests.construct_transactions().unwrap();
     assert_eq!(txs.len(), 1);
@@ -716,6 +725,7 @@ async fn outcome_is_independent_of_input_order() {
         }],
         fee_rate: TEST_FEE_RATE,
         last_fees: None,
+        output_ordering: OutputOrdering::default(),
     };
 
     let btc_ctx = BitcoinTxContext {
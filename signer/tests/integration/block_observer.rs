@@ -25,6 +25,7 @@ use sbtc::testing::regtest;
 use sbtc::testing::regtest::Recipient;
 use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
+use signer::config::OutputOrdering;
 use signer::context::SbtcLimits;
 use signer::emily_client::EmilyClient;
 use signer::error::Error;
@@ -108,6 +109,10 @@ async fn load_latest_deposit_requests_persists_requests_from_past(blocks_ago: u6
             .expect_get_limits()
             .times(1..)
             .returning(|| Box::pin(async { Ok(SbtcLimits::default()) }));
+
+        client
+            .expect_get_min_confirmations()
+            .returning(|| Box::pin(async { Ok(None) }));
     })
     .await;
 
@@ -294,6 +299,10 @@ async fn link_blocks() {
         client
             .expect_get_deposits()
             .returning(move || Box::pin(std::future::ready(Ok(vec![]))));
+
+        client
+            .expect_get_min_confirmations()
+            .returning(|| Box::pin(async { Ok(None) }));
     })
     .await;
 
@@ -452,6 +461,14 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
             Box::pin(std::future::ready(response))
         });
 
+        client.expect_get_block().returning(|_| {
+            let response = Ok(NakamotoBlock {
+                header: NakamotoBlockHeader::empty(),
+                txs: Vec::new(),
+            });
+            Box::pin(std::future::ready(response))
+        });
+
         let chain_tip = BitcoinBlockHash::from(chain_tip_info.hash);
         client.expect_get_tenure().returning(move |_| {
             let mut tenure = TenureBlocks::nearly_empty().unwrap();
@@ -617,10 +634,12 @@ async fn block_observer_stores_donation_and_sbtc_utxos() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            output_ordering: OutputOrdering::default(),
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
 
     let mut transactions = requests.construct_transactions().unwrap();
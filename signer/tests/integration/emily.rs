@@ -14,6 +14,8 @@ use bitcoin::TxMerkleNode;
 use bitcoin::Txid;
 use bitcoincore_rpc_json::Utxo;
 
+use blockstack_lib::chainstate::nakamoto::NakamotoBlock;
+use blockstack_lib::chainstate::nakamoto::NakamotoBlockHeader;
 use blockstack_lib::net::api::getpoxinfo::RPCPoxInfoData;
 use blockstack_lib::net::api::getsortition::SortitionInfo;
 use blockstack_lib::net::api::gettenureinfo::RPCGetTenureInfo;
@@ -368,6 +370,16 @@ async fn deposit_flow() {
                 .once()
                 .returning(move || Box::pin(async move { Ok(DUMMY_TENURE_INFO.clone()) }));
 
+            // The block observer checks that the tip block is in the
+            // tenure above.
+            client.expect_get_block().returning(|_| {
+                let response = Ok(NakamotoBlock {
+                    header: NakamotoBlockHeader::empty(),
+                    txs: Vec::new(),
+                });
+                Box::pin(std::future::ready(response))
+            });
+
             client
                 .expect_get_tenure()
                 .once()
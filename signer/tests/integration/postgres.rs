@@ -609,22 +609,20 @@ async fn should_return_the_same_pending_accepted_deposit_requests_as_in_memory_s
         chain_tip
     );
 
-    let mut pending_accepted_deposit_requests = in_memory_store
+    let pending_accepted_deposit_requests = in_memory_store
         .get_pending_accepted_deposit_requests(&chain_tip, context_window, threshold)
         .await
         .expect("failed to get pending deposit requests");
 
-    pending_accepted_deposit_requests.sort();
-
     assert!(!pending_accepted_deposit_requests.is_empty());
 
-    let mut pg_pending_accepted_deposit_requests = pg_store
+    // Both stores return the deposit requests oldest first, so they
+    // should come back in the same order.
+    let pg_pending_accepted_deposit_requests = pg_store
         .get_pending_accepted_deposit_requests(&chain_tip, context_window, threshold)
         .await
         .expect("failed to get pending deposit requests");
 
-    pg_pending_accepted_deposit_requests.sort();
-
     assert_eq!(
         pending_accepted_deposit_requests,
         pg_pending_accepted_deposit_requests
@@ -701,6 +699,82 @@ async fn should_return_the_same_pending_accepted_withdraw_requests_as_in_memory_
     signer::testing::storage::drop_db(pg_store).await;
 }
 
+/// This ensures that the postgres store and the in memory stores returns
+/// equivalent results when fetching expired withdrawal requests, and that
+/// withdrawal requests only expire once the chain tip has moved past the
+/// expiry window.
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn should_return_the_same_expired_withdraw_requests_as_in_memory_store() {
+    let db_num = DATABASE_NUM.fetch_add(1, Ordering::SeqCst);
+    let mut pg_store = testing::storage::new_test_database(db_num, true).await;
+    let mut in_memory_store = storage::in_memory::Store::new_shared();
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let num_signers = 7;
+    let context_window = 7;
+    let test_model_params = testing::storage::model::Params {
+        num_bitcoin_blocks: 20,
+        num_stacks_blocks_per_bitcoin_block: 3,
+        num_deposit_requests_per_block: 5,
+        num_withdraw_requests_per_block: 1,
+        num_signers_per_request: 0,
+    };
+
+    let signer_set = testing::wsts::generate_signer_set_public_keys(&mut rng, num_signers);
+    let test_data = TestData::generate(&mut rng, &signer_set, &test_model_params);
+
+    test_data.write_to(&mut in_memory_store).await;
+    test_data.write_to(&mut pg_store).await;
+
+    let chain_tip = in_memory_store
+        .get_bitcoin_canonical_chain_tip()
+        .await
+        .expect("failed to get canonical chain tip")
+        .expect("no chain tip");
+
+    // The requests in the last few blocks have not expired yet, so there
+    // are fewer expired requests than pending ones.
+    let pending_withdraw_requests = in_memory_store
+        .get_pending_withdrawal_requests(&chain_tip, context_window, &signer_set[0])
+        .await
+        .expect("failed to get pending withdrawal requests");
+
+    let mut expired_withdraw_requests = in_memory_store
+        .get_expired_withdrawal_requests(&chain_tip, context_window, 2)
+        .await
+        .expect("failed to get expired withdrawal requests");
+
+    expired_withdraw_requests.sort();
+
+    assert!(!expired_withdraw_requests.is_empty());
+    more_asserts::assert_lt!(
+        expired_withdraw_requests.len(),
+        pending_withdraw_requests.len()
+    );
+
+    let mut pg_expired_withdraw_requests = pg_store
+        .get_expired_withdrawal_requests(&chain_tip, context_window, 2)
+        .await
+        .expect("failed to get expired withdrawal requests");
+
+    pg_expired_withdraw_requests.sort();
+
+    assert_eq!(expired_withdraw_requests, pg_expired_withdraw_requests);
+
+    // Nothing within the context window expires when the expiry window is
+    // as long as the context window.
+    let expired_withdraw_requests = pg_store
+        .get_expired_withdrawal_requests(&chain_tip, context_window, context_window)
+        .await
+        .expect("failed to get expired withdrawal requests");
+
+    assert!(expired_withdraw_requests.is_empty());
+
+    signer::testing::storage::drop_db(pg_store).await;
+}
+
 /// This tests that when fetching pending accepted deposits we ingore swept ones.
 #[cfg_attr(not(feature = "integration-tests"), ignore)]
 #[tokio::test]
@@ -3328,3 +3402,77 @@ async fn get_deposit_request_returns_returns_inserted_deposit_request() {
 
     signer::testing::storage::drop_db(db).await;
 }
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn stacks_checkpoint_is_overwritten_and_cleared() {
+    let db_num = testing::storage::DATABASE_NUM.fetch_add(1, Ordering::SeqCst);
+    let db = testing::storage::new_test_database(db_num, true).await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    assert_eq!(db.get_stacks_checkpoint().await.unwrap(), None);
+
+    let checkpoint1: model::StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+    let checkpoint2: model::StacksBlockHash = fake::Faker.fake_with_rng(&mut rng);
+
+    db.write_stacks_checkpoint(Some(&checkpoint1))
+        .await
+        .unwrap();
+    assert_eq!(db.get_stacks_checkpoint().await.unwrap(), Some(checkpoint1));
+
+    // There is only ever one checkpoint, so writing another one replaces
+    // the first.
+    db.write_stacks_checkpoint(Some(&checkpoint2))
+        .await
+        .unwrap();
+    assert_eq!(db.get_stacks_checkpoint().await.unwrap(), Some(checkpoint2));
+
+    db.write_stacks_checkpoint(None).await.unwrap();
+    assert_eq!(db.get_stacks_checkpoint().await.unwrap(), None);
+
+    signer::testing::storage::drop_db(db).await;
+}
+
+#[cfg_attr(not(feature = "integration-tests"), ignore)]
+#[tokio::test]
+async fn held_withdrawals_are_read_back_from_their_accept_events() {
+    let db_num = testing::storage::DATABASE_NUM.fetch_add(1, Ordering::SeqCst);
+    let db = testing::storage::new_test_database(db_num, true).await;
+    let mut rng = rand::rngs::StdRng::seed_from_u64(51);
+
+    let event: WithdrawalAcceptEvent = fake::Faker.fake_with_rng(&mut rng);
+    db.write_withdrawal_accept_event(&event).await.unwrap();
+    db.write_held_withdrawal(&event).await.unwrap();
+
+    let held = db.get_held_withdrawals().await.unwrap();
+    assert_eq!(held.len(), 1);
+    assert_eq!(held[0].txid, event.txid);
+    assert_eq!(held[0].block_id, event.block_id);
+    assert_eq!(held[0].request_id, event.request_id);
+    assert_eq!(held[0].signer_bitmap, event.signer_bitmap);
+    assert_eq!(held[0].outpoint, event.outpoint);
+    assert_eq!(held[0].fee, event.fee);
+    assert_eq!(held[0].sweep_block_hash, event.sweep_block_hash);
+    assert_eq!(held[0].sweep_block_height, event.sweep_block_height);
+    assert_eq!(held[0].sweep_txid, event.sweep_txid);
+
+    // Holding back another accept event for the same request replaces
+    // the first one.
+    let replacement = WithdrawalAcceptEvent {
+        request_id: event.request_id,
+        ..fake::Faker.fake_with_rng(&mut rng)
+    };
+    db.write_withdrawal_accept_event(&replacement)
+        .await
+        .unwrap();
+    db.write_held_withdrawal(&replacement).await.unwrap();
+
+    let held = db.get_held_withdrawals().await.unwrap();
+    assert_eq!(held.len(), 1);
+    assert_eq!(held[0].txid, replacement.txid);
+
+    db.delete_held_withdrawal(event.request_id).await.unwrap();
+    assert!(db.get_held_withdrawals().await.unwrap().is_empty());
+
+    signer::testing::storage::drop_db(db).await;
+}
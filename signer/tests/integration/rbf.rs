@@ -20,6 +20,7 @@ use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::utxo::UnsignedTransaction;
 use signer::bitcoin::utxo::WithdrawalRequest;
+use signer::config::OutputOrdering;
 use signer::context::SbtcLimits;
 use signer::message::SweepTransactionInfo;
 use signer::storage::model;
@@ -229,10 +230,12 @@ pub fn transaction_with_rbf(
             // The value here isn't important, but it matches what happens
             // in Nakamoto testnet.
             magic_bytes: [b'T', b'3'],
+            output_ordering: OutputOrdering::default(),
         },
         accept_threshold: failure_threshold,
         num_signers: 2 * failure_threshold,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
 
     // Okay, lets submit the transaction. We also do a sanity check where
@@ -24,6 +24,7 @@ use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::block_observer::BlockObserver;
 use signer::block_observer::Deposit;
+use signer::config::OutputOrdering;
 use signer::config::Settings;
 use signer::context::SbtcLimits;
 use signer::keys::PublicKey;
@@ -147,10 +148,12 @@ impl TestSweepSetup {
                 public_key: signers_public_key,
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                output_ordering: OutputOrdering::default(),
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // There should only be one transaction here since there is only
@@ -276,6 +279,7 @@ impl TestSweepSetup {
         let deposit = Deposit {
             tx_info: self.deposit_tx_info.clone(),
             info: self.deposit_info.clone(),
+            tx_vsize: self.deposit_tx_info.tx.vsize() as u64,
         };
         let deposit_request = model::DepositRequest::from(deposit);
         db.write_deposit_request(&deposit_request).await.unwrap();
@@ -740,10 +744,12 @@ impl TestSweepSetup2 {
                 public_key: aggregated_signer.keypair.x_only_public_key().0,
                 last_fees: None,
                 magic_bytes: [b'T', b'3'],
+                output_ordering: OutputOrdering::default(),
             },
             accept_threshold: 4,
             num_signers: 7,
             sbtc_limits: SbtcLimits::default(),
+            min_sweep_value: 0,
         };
 
         // There should only be one transaction here since there is only
@@ -821,6 +827,7 @@ impl TestSweepSetup2 {
             let deposit = Deposit {
                 tx_info: tx_info.clone(),
                 info: info.clone(),
+                tx_vsize: tx_info.tx.vsize() as u64,
             };
             let deposit_request = model::DepositRequest::from(deposit);
             db.write_deposit_request(&deposit_request).await.unwrap();
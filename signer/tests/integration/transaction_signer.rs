@@ -574,6 +574,7 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
         request_package: vec![sbtc_requests],
         fee_rate,
         last_fees: None,
+        output_ordering: signer::config::OutputOrdering::default(),
     };
 
     let sbtc_state = signer::bitcoin::utxo::SignerBtcState {
@@ -587,6 +588,7 @@ pub async fn assert_should_be_able_to_handle_sbtc_requests() {
         last_fees: None,
         public_key: setup.aggregated_signer.keypair.public_key().into(),
         magic_bytes: [b'T', b'3'],
+        output_ordering: signer::config::OutputOrdering::default(),
     };
 
     // Create an unsigned transaction with the deposit request
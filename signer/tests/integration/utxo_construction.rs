@@ -29,6 +29,7 @@ use signer::bitcoin::utxo::SbtcRequests;
 use signer::bitcoin::utxo::SignerBtcState;
 use signer::bitcoin::utxo::SignerUtxo;
 use signer::bitcoin::utxo::WithdrawalRequest;
+use signer::config::OutputOrdering;
 use signer::context::SbtcLimits;
 use stacks_common::types::chainstate::StacksAddress;
 
@@ -221,10 +222,12 @@ fn deposits_add_to_controlled_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            output_ordering: OutputOrdering::default(),
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
 
     // There should only be one transaction here since there is only one
@@ -286,10 +289,12 @@ fn withdrawals_reduce_to_signers_amounts() {
             public_key: signers_public_key,
             last_fees: None,
             magic_bytes: [b'T', b'3'],
+            output_ordering: OutputOrdering::default(),
         },
         accept_threshold: 4,
         num_signers: 7,
         sbtc_limits: SbtcLimits::default(),
+        min_sweep_value: 0,
     };
 
     // There should only be one transaction here since there is only one